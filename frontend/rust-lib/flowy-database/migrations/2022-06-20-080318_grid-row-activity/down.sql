@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE grid_row_comment_table;
+DROP TABLE grid_row_change_table;
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+CREATE TABLE grid_row_comment_table (
+    id TEXT NOT NULL PRIMARY KEY,
+    grid_id TEXT NOT NULL,
+    row_id TEXT NOT NULL,
+    author TEXT NOT NULL,
+    content TEXT NOT NULL,
+    create_time BIGINT NOT NULL,
+    modified_time BIGINT NOT NULL
+);
+
+CREATE TABLE grid_row_change_table (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    grid_id TEXT NOT NULL,
+    row_id TEXT NOT NULL,
+    field_id TEXT NOT NULL,
+    field_name TEXT NOT NULL,
+    old_value TEXT NOT NULL,
+    new_value TEXT NOT NULL,
+    author TEXT NOT NULL,
+    create_time BIGINT NOT NULL
+);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE text_rev_checkpoint_table;
+DROP TABLE text_rev_history_table;
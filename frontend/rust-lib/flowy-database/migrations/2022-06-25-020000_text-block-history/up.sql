@@ -0,0 +1,18 @@
+-- Your SQL goes here
+CREATE TABLE text_rev_history_table (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    doc_id TEXT NOT NULL,
+    rev_id BIGINT NOT NULL,
+    author TEXT NOT NULL,
+    create_time BIGINT NOT NULL,
+    UNIQUE(doc_id, rev_id)
+);
+
+CREATE TABLE text_rev_checkpoint_table (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    doc_id TEXT NOT NULL,
+    rev_id BIGINT NOT NULL,
+    rev_md5 TEXT NOT NULL,
+    data BLOB NOT NULL,
+    UNIQUE(doc_id, rev_id)
+);
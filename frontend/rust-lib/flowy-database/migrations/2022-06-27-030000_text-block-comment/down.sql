@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE text_comment_thread_table;
+DROP TABLE text_comment_table;
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+CREATE TABLE text_comment_thread_table (
+    id TEXT NOT NULL PRIMARY KEY,
+    doc_id TEXT NOT NULL,
+    anchor_start BIGINT NOT NULL,
+    anchor_end BIGINT NOT NULL,
+    quote TEXT NOT NULL,
+    resolved Boolean NOT NULL DEFAULT false,
+    author TEXT NOT NULL,
+    create_time BIGINT NOT NULL,
+    modified_time BIGINT NOT NULL
+);
+
+CREATE TABLE text_comment_table (
+    id TEXT NOT NULL PRIMARY KEY,
+    thread_id TEXT NOT NULL,
+    author TEXT NOT NULL,
+    content TEXT NOT NULL,
+    create_time BIGINT NOT NULL
+);
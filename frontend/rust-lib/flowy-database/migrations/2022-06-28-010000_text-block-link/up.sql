@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE text_link_table (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    source_id TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    broken Boolean NOT NULL DEFAULT false,
+    UNIQUE(source_id, target_id)
+);
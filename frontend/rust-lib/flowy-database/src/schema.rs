@@ -42,6 +42,32 @@ table! {
     }
 }
 
+table! {
+    grid_row_change_table (id) {
+        id -> Integer,
+        grid_id -> Text,
+        row_id -> Text,
+        field_id -> Text,
+        field_name -> Text,
+        old_value -> Text,
+        new_value -> Text,
+        author -> Text,
+        create_time -> BigInt,
+    }
+}
+
+table! {
+    grid_row_comment_table (id) {
+        id -> Text,
+        grid_id -> Text,
+        row_id -> Text,
+        author -> Text,
+        content -> Text,
+        create_time -> BigInt,
+        modified_time -> BigInt,
+    }
+}
+
 table! {
     kv_table (key) {
         key -> Text,
@@ -61,6 +87,60 @@ table! {
     }
 }
 
+table! {
+    text_comment_table (id) {
+        id -> Text,
+        thread_id -> Text,
+        author -> Text,
+        content -> Text,
+        create_time -> BigInt,
+    }
+}
+
+table! {
+    text_comment_thread_table (id) {
+        id -> Text,
+        doc_id -> Text,
+        anchor_start -> BigInt,
+        anchor_end -> BigInt,
+        quote -> Text,
+        resolved -> Bool,
+        author -> Text,
+        create_time -> BigInt,
+        modified_time -> BigInt,
+    }
+}
+
+table! {
+    text_link_table (id) {
+        id -> Integer,
+        source_id -> Text,
+        target_id -> Text,
+        title -> Text,
+        broken -> Bool,
+    }
+}
+
+table! {
+    text_rev_checkpoint_table (id) {
+        id -> Integer,
+        doc_id -> Text,
+        rev_id -> BigInt,
+        rev_md5 -> Text,
+        data -> Binary,
+    }
+}
+
+table! {
+    text_rev_history_table (id) {
+        id -> Integer,
+        doc_id -> Text,
+        rev_id -> BigInt,
+        author -> Text,
+        create_time -> BigInt,
+    }
+}
+
 table! {
     trash_table (id) {
         id -> Text,
@@ -115,8 +195,15 @@ allow_tables_to_appear_in_same_query!(
     grid_block_index_table,
     grid_meta_rev_table,
     grid_rev_table,
+    grid_row_change_table,
+    grid_row_comment_table,
     kv_table,
     rev_table,
+    text_comment_table,
+    text_comment_thread_table,
+    text_link_table,
+    text_rev_checkpoint_table,
+    text_rev_history_table,
     trash_table,
     user_table,
     view_table,
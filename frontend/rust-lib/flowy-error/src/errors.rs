@@ -66,6 +66,7 @@ impl FlowyError {
     static_flowy_error!(user_not_exist, ErrorCode::UserNotExist);
     static_flowy_error!(text_too_long, ErrorCode::TextTooLong);
     static_flowy_error!(invalid_data, ErrorCode::InvalidData);
+    static_flowy_error!(phone_format, ErrorCode::PhoneNumberFormatInvalid);
 }
 
 impl std::convert::From<ErrorCode> for FlowyError {
@@ -0,0 +1,30 @@
+use crate::impl_def_and_def_mut;
+use flowy_derive::ProtoBuf;
+
+/// The link from the document to the view.
+#[derive(Eq, PartialEq, ProtoBuf, Debug, Default, Clone)]
+pub struct Backlink {
+    /// The id of the view that the link is in.
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    /// The name of the view that the link is in.
+    #[pb(index = 2)]
+    pub name: String,
+
+    /// The text of the link, it's empty if the link is a mention.
+    #[pb(index = 3)]
+    pub title: String,
+
+    /// The view that the link refers to is in the trash.
+    #[pb(index = 4)]
+    pub broken: bool,
+}
+
+#[derive(Eq, PartialEq, Debug, Default, ProtoBuf, Clone)]
+pub struct RepeatedBacklink {
+    #[pb(index = 1)]
+    pub items: Vec<Backlink>,
+}
+
+impl_def_and_def_mut!(RepeatedBacklink, Backlink);
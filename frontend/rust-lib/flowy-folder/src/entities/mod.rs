@@ -1,4 +1,5 @@
 pub mod app;
+mod backlink;
 mod parser;
 pub mod trash;
 pub mod view;
@@ -6,6 +7,7 @@ mod view_info;
 pub mod workspace;
 
 pub use app::*;
+pub use backlink::*;
 pub use trash::*;
 pub use view::*;
 pub use view_info::*;
@@ -78,6 +78,21 @@ impl std::convert::From<ViewDataType> for ViewDataTypeRevision {
     }
 }
 
+/// The format of the data that is used to create the view. The data in the markdown or html format
+/// is converted into the delta of the view, it's only supported by the view of the text block.
+#[derive(Eq, PartialEq, Hash, Debug, ProtoBuf_Enum, Clone)]
+pub enum ViewDataFormat {
+    Delta = 0,
+    Markdown = 1,
+    Html = 2,
+}
+
+impl std::default::Default for ViewDataFormat {
+    fn default() -> Self {
+        ViewDataFormat::Delta
+    }
+}
+
 #[derive(Eq, PartialEq, Debug, Default, ProtoBuf, Clone)]
 pub struct RepeatedView {
     #[pb(index = 1)]
@@ -120,6 +135,9 @@ pub struct CreateViewPayload {
 
     #[pb(index = 7)]
     pub data: Vec<u8>,
+
+    #[pb(index = 8)]
+    pub data_format: ViewDataFormat,
 }
 
 #[derive(Default, ProtoBuf, Debug, Clone)]
@@ -147,6 +165,9 @@ pub struct CreateViewParams {
 
     #[pb(index = 8)]
     pub plugin_type: i32,
+
+    #[pb(index = 9)]
+    pub data_format: ViewDataFormat,
 }
 
 impl TryInto<CreateViewParams> for CreateViewPayload {
@@ -169,6 +190,7 @@ impl TryInto<CreateViewParams> for CreateViewPayload {
             thumbnail,
             view_id,
             data: self.data,
+            data_format: self.data_format,
             plugin_type: self.plugin_type,
         })
     }
@@ -64,6 +64,7 @@ pub fn create(folder: Arc<FolderManager>) -> Module {
         .event(FolderEvent::ReadView, read_view_handler)
         .event(FolderEvent::UpdateView, update_view_handler)
         .event(FolderEvent::ReadViewInfo, read_view_info_handler)
+        .event(FolderEvent::ReadBacklinks, read_backlinks_handler)
         .event(FolderEvent::DeleteView, delete_view_handler)
         .event(FolderEvent::DuplicateView, duplicate_view_handler)
         .event(FolderEvent::SetLatestView, set_latest_view_handler)
@@ -135,6 +136,9 @@ pub enum FolderEvent {
     #[event(input = "ViewId", output = "ViewInfo")]
     ReadViewInfo = 207,
 
+    #[event(input = "ViewId", output = "RepeatedBacklink")]
+    ReadBacklinks = 208,
+
     #[event()]
     CopyLink = 220,
 
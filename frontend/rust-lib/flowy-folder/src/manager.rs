@@ -1,4 +1,4 @@
-use crate::entities::view::ViewDataType;
+use crate::entities::view::{ViewDataFormat, ViewDataType};
 use crate::{
     dart_notification::{send_dart_notification, FolderNotification},
     entities::workspace::RepeatedWorkspace,
@@ -242,6 +242,9 @@ pub trait ViewDataProcessor {
 
     fn close_container(&self, view_id: &str) -> FutureResult<(), FlowyError>;
 
+    /// Updates the links to the view after the view was renamed.
+    fn did_rename_view(&self, view_id: &str, name: &str) -> FutureResult<(), FlowyError>;
+
     fn get_delta_data(&self, view_id: &str) -> FutureResult<Bytes, FlowyError>;
 
     fn create_default_view(&self, user_id: &str, view_id: &str) -> FutureResult<Bytes, FlowyError>;
@@ -251,6 +254,7 @@ pub trait ViewDataProcessor {
         user_id: &str,
         view_id: &str,
         data: Vec<u8>,
+        data_format: ViewDataFormat,
     ) -> FutureResult<Bytes, FlowyError>;
 
     fn data_type(&self) -> ViewDataType;
@@ -14,6 +14,7 @@ use std::sync::Arc;
 
 const V1_MIGRATION: &str = "FOLDER_V1_MIGRATION";
 const V2_MIGRATION: &str = "FOLDER_V2_MIGRATION";
+const V3_MIGRATION: &str = "FOLDER_V3_MIGRATION";
 
 pub(crate) struct FolderMigration {
     user_id: String,
@@ -84,6 +85,28 @@ impl FolderMigration {
         if KV::get_bool(&key) {
             return Ok(None);
         }
+
+        let pad = self.load_folder(user_id, folder_id).await?;
+        KV::set_bool(&key, true);
+        tracing::trace!("Run folder v2 migration");
+        Ok(pad)
+    }
+
+    /// Rewrites the folder revisions, which contain the whole folder in JSON format, into the
+    /// [FolderOperation](flowy_sync::client_folder::FolderOperation) log.
+    pub async fn run_v3_migration(&self, user_id: &str, folder_id: &FolderId) -> FlowyResult<Option<FolderPad>> {
+        let key = md5(format!("{}{}", self.user_id, V3_MIGRATION));
+        if KV::get_bool(&key) {
+            return Ok(None);
+        }
+
+        let pad = self.load_folder(user_id, folder_id).await?;
+        KV::set_bool(&key, true);
+        tracing::trace!("Run folder v3 migration");
+        Ok(pad)
+    }
+
+    async fn load_folder(&self, user_id: &str, folder_id: &FolderId) -> FlowyResult<Option<FolderPad>> {
         let pool = self.database.db_pool()?;
         let disk_cache = Arc::new(SQLiteTextBlockRevisionPersistence::new(user_id, pool));
         let rev_persistence = Arc::new(RevisionPersistence::new(user_id, folder_id.as_ref(), disk_cache));
@@ -97,14 +120,11 @@ impl FolderMigration {
         .await?;
 
         if revisions.is_empty() {
-            tracing::trace!("Run folder v2 migration, but revision is empty");
-            KV::set_bool(&key, true);
+            tracing::trace!("Load folder for migration, but revision is empty");
             return Ok(None);
         }
 
         let pad = FolderPad::from_revisions(revisions)?;
-        KV::set_bool(&key, true);
-        tracing::trace!("Run folder v2 migration");
         Ok(Some(pad))
     }
 }
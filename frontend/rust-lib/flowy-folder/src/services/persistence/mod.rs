@@ -13,7 +13,10 @@ use flowy_folder_data_model::revision::{AppRevision, TrashRevision, ViewRevision
 use flowy_revision::disk::{RevisionRecord, RevisionState};
 use flowy_revision::mk_revision_disk_cache;
 use flowy_sync::client_folder::initial_folder_delta;
-use flowy_sync::{client_folder::FolderPad, entities::revision::Revision};
+use flowy_sync::{
+    client_folder::FolderPad,
+    entities::revision::{md5, Revision},
+};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 pub use version_1::{app_sql::*, trash_sql::*, v1_impl::V1Transaction, view_sql::*, workspace_sql::*};
@@ -104,13 +107,18 @@ impl FolderPersistence {
             self.save_folder(user_id, folder_id, migrated_folder).await?;
         }
 
+        if let Some(migrated_folder) = migrations.run_v3_migration(user_id, folder_id).await? {
+            self.save_folder(user_id, folder_id, migrated_folder).await?;
+        }
+
         Ok(())
     }
 
     pub async fn save_folder(&self, user_id: &str, folder_id: &FolderId, folder: FolderPad) -> FlowyResult<()> {
         let pool = self.database.db_pool()?;
-        let delta_data = initial_folder_delta(&folder)?.to_delta_bytes();
-        let md5 = folder.md5();
+        let delta = initial_folder_delta(&folder)?;
+        let md5 = md5(&delta.to_delta_bytes());
+        let delta_data = delta.to_compact_bytes();
         let revision = Revision::new(folder_id.as_ref(), 0, 0, delta_data, user_id, md5);
         let record = RevisionRecord {
             revision,
@@ -1,11 +1,11 @@
 pub use crate::entities::view::ViewDataType;
-use crate::entities::ViewInfo;
+use crate::entities::{Backlink, RepeatedBacklink, ViewInfo};
 use crate::manager::{ViewDataProcessor, ViewDataProcessorMap};
 use crate::{
     dart_notification::{send_dart_notification, FolderNotification},
     entities::{
         trash::{RepeatedTrashId, TrashType},
-        view::{CreateViewParams, RepeatedView, UpdateViewParams, View, ViewId},
+        view::{CreateViewParams, RepeatedView, UpdateViewParams, View, ViewDataFormat, ViewId},
     },
     errors::{FlowyError, FlowyResult},
     event_map::{FolderCouldServiceV1, WorkspaceUser},
@@ -18,6 +18,7 @@ use bytes::Bytes;
 use flowy_database::kv::KV;
 use flowy_folder_data_model::revision::{gen_view_id, ViewRevision};
 use flowy_sync::entities::text_block::TextBlockId;
+use flowy_text_block::link::TextBlockLinkPersistence;
 use futures::{FutureExt, StreamExt};
 use std::{collections::HashSet, sync::Arc};
 
@@ -65,7 +66,12 @@ impl ViewController {
             params.data = view_data.to_vec();
         } else {
             let delta_data = processor
-                .create_view_from_delta_data(&user_id, &params.view_id, params.data.clone())
+                .create_view_from_delta_data(
+                    &user_id,
+                    &params.view_id,
+                    params.data.clone(),
+                    params.data_format.clone(),
+                )
                 .await?;
             let _ = self
                 .create_view(&params.view_id, params.data_type.clone(), delta_data)
@@ -218,6 +224,7 @@ impl ViewController {
             thumbnail: view_rev.thumbnail,
             data_type: view_rev.data_type.into(),
             data: delta_bytes.to_vec(),
+            data_format: ViewDataFormat::Delta,
             view_id: gen_view_id(),
             plugin_type: view_rev.plugin_type,
         };
@@ -254,10 +261,46 @@ impl ViewController {
             })
             .await?;
 
+        if let Some(name) = &params.name {
+            for processor in self.data_processors.values() {
+                if let Err(e) = processor.did_rename_view(&view_id, name).await {
+                    tracing::error!("Update the links to {} failed: {}", view_id, e);
+                }
+            }
+        }
+
         let _ = self.update_view_on_server(params);
         Ok(view_rev)
     }
 
+    /// Returns the links to the view, the links in the views that are in the trash are skipped.
+    pub(crate) async fn read_backlinks(&self, view_id: &str) -> Result<RepeatedBacklink, FlowyError> {
+        let links = TextBlockLinkPersistence::new(self.persistence.db_pool()?).get_backlinks(view_id)?;
+        let items = self
+            .persistence
+            .begin_transaction(|transaction| {
+                let trash_ids = self.trash_controller.read_trash_ids(&transaction)?;
+                let mut items = vec![];
+                for link in links {
+                    if trash_ids.contains(&link.source_id) {
+                        continue;
+                    }
+                    // The link may be indexed before the view of the document was synced.
+                    if let Ok(view_rev) = transaction.read_view(&link.source_id) {
+                        items.push(Backlink {
+                            view_id: link.source_id,
+                            name: view_rev.name,
+                            title: link.title,
+                            broken: link.broken,
+                        });
+                    }
+                }
+                Ok(items)
+            })
+            .await?;
+        Ok(RepeatedBacklink { items })
+    }
+
     pub(crate) async fn latest_visit_view(&self) -> FlowyResult<Option<ViewRevision>> {
         match KV::get_str(LATEST_VIEW_ID) {
             None => Ok(None),
@@ -388,6 +431,7 @@ async fn handle_trash_event(
 ) {
     match event {
         TrashEvent::NewTrash(identifiers, ret) => {
+            let view_ids = trash_ids(&identifiers);
             let result = persistence
                 .begin_transaction(|transaction| {
                     let view_revs = read_local_views_with_transaction(identifiers, &transaction)?;
@@ -397,10 +441,12 @@ async fn handle_trash_event(
                     }
                     Ok(())
                 })
-                .await;
+                .await
+                .and_then(|_| set_links_broken(&persistence, &view_ids, true));
             let _ = ret.send(result).await;
         }
         TrashEvent::Putback(identifiers, ret) => {
+            let view_ids = trash_ids(&identifiers);
             let result = persistence
                 .begin_transaction(|transaction| {
                     let view_revs = read_local_views_with_transaction(identifiers, &transaction)?;
@@ -410,7 +456,8 @@ async fn handle_trash_event(
                     }
                     Ok(())
                 })
-                .await;
+                .await
+                .and_then(|_| set_links_broken(&persistence, &view_ids, false));
             let _ = ret.send(result).await;
         }
         TrashEvent::Delete(identifiers, ret) => {
@@ -450,6 +497,19 @@ async fn handle_trash_event(
     }
 }
 
+fn trash_ids(identifiers: &RepeatedTrashId) -> Vec<String> {
+    identifiers
+        .items
+        .iter()
+        .map(|identifier| identifier.id.clone())
+        .collect()
+}
+
+// The links to the views in the trash are broken, they are restored when the views are put back.
+fn set_links_broken(persistence: &FolderPersistence, view_ids: &[String], broken: bool) -> FlowyResult<()> {
+    TextBlockLinkPersistence::new(persistence.db_pool()?).set_broken(view_ids, broken)
+}
+
 fn get_data_processor(
     data_processors: ViewDataProcessorMap,
     data_type: &ViewDataType,
@@ -1,5 +1,5 @@
 use crate::entities::view::{MoveFolderItemParams, MoveFolderItemPayload, MoveFolderItemType};
-use crate::entities::ViewInfo;
+use crate::entities::{RepeatedBacklink, ViewInfo};
 use crate::manager::FolderManager;
 use crate::services::{notify_workspace_setting_did_change, AppController};
 use crate::{
@@ -43,6 +43,15 @@ pub(crate) async fn read_view_info_handler(
     data_result(view_info)
 }
 
+pub(crate) async fn read_backlinks_handler(
+    data: Data<ViewId>,
+    controller: AppData<Arc<ViewController>>,
+) -> DataResult<RepeatedBacklink, FlowyError> {
+    let view_id: ViewId = data.into_inner();
+    let backlinks = controller.read_backlinks(&view_id.value).await?;
+    data_result(backlinks)
+}
+
 #[tracing::instrument(level = "debug", skip(data, controller), err)]
 pub(crate) async fn update_view_handler(
     data: Data<UpdateViewPayload>,
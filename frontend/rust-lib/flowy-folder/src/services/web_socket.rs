@@ -130,4 +130,9 @@ impl RevisionWSDataStream for FolderRevisionWSDataStream {
         let resolver = self.conflict_controller.clone();
         Box::pin(async move { resolver.send_revisions(range).await })
     }
+
+    fn receive_transformed_revision(&self, bytes: Bytes) -> BoxResultFuture<(), FlowyError> {
+        let resolver = self.conflict_controller.clone();
+        Box::pin(async move { resolver.receive_transformed_bytes(bytes).await })
+    }
 }
@@ -190,6 +190,28 @@ async fn view_delete_then_restore() {
     .await;
 }
 
+#[tokio::test]
+async fn view_backlinks_follow_rename_and_trash() {
+    let mut test = FolderTest::new().await;
+    test.run_scripts(vec![
+        LinkToView {
+            name: "View A".to_owned(),
+            title: "Folder View".to_owned(),
+        },
+        AssertBacklinks(vec![("View A", "Folder View", false)]),
+        UpdateView {
+            name: Some("Renamed View".to_owned()),
+            desc: None,
+        },
+        AssertBacklinks(vec![("View A", "Renamed View", false)]),
+        DeleteView,
+        AssertBacklinks(vec![("View A", "Renamed View", true)]),
+        RestoreViewFromTrash,
+        AssertBacklinks(vec![("View A", "Renamed View", false)]),
+    ])
+    .await;
+}
+
 #[tokio::test]
 async fn view_delete_all() {
     let mut test = FolderTest::new().await;
@@ -1,9 +1,10 @@
 use flowy_folder::entities::view::{RepeatedViewId, ViewId};
 use flowy_folder::entities::workspace::WorkspaceId;
+use flowy_folder::entities::RepeatedBacklink;
 use flowy_folder::entities::{
     app::{App, RepeatedApp},
     trash::Trash,
-    view::{RepeatedView, View, ViewDataType},
+    view::{RepeatedView, View, ViewDataFormat, ViewDataType},
     workspace::Workspace,
 };
 use flowy_folder::entities::{
@@ -17,8 +18,9 @@ use flowy_folder::{errors::ErrorCode, services::folder_editor::FolderEditor};
 
 use flowy_revision::disk::RevisionState;
 use flowy_revision::REVISION_WRITE_INTERVAL_IN_MILLIS;
-use flowy_sync::entities::text_block::TextBlockInfo;
+use flowy_sync::entities::text_block::{TextBlockDelta, TextBlockInfo};
 use flowy_test::{event_builder::*, FlowySDKTest};
+use flowy_text_block::event_map::TextBlockEvent;
 use std::{sync::Arc, time::Duration};
 use tokio::time::sleep;
 
@@ -61,6 +63,13 @@ pub enum FolderScript {
     },
     DeleteView,
     DeleteViews(Vec<String>),
+    // Creates the text block view that links to the current view.
+    LinkToView {
+        name: String,
+        title: String,
+    },
+    // The name of the linking view, the title of the link and whether it's broken.
+    AssertBacklinks(Vec<(&'static str, &'static str, bool)>),
 
     // Trash
     RestoreAppFromTrash,
@@ -198,6 +207,27 @@ impl FolderTest {
             FolderScript::DeleteViews(view_ids) => {
                 delete_view(sdk, view_ids).await;
             }
+            FolderScript::LinkToView { name, title } => {
+                let view = create_view(sdk, &self.app.id, &name, "", ViewDataType::TextBlock).await;
+                let delta_str = format!(
+                    r#"[{{"insert":"{}","attributes":{{"page_link":"{}"}}}}]"#,
+                    title, self.view.id
+                );
+                apply_text_block_delta(sdk, &view.id, delta_str).await;
+            }
+            FolderScript::AssertBacklinks(expected) => {
+                let backlinks = read_backlinks(sdk, &self.view.id)
+                    .await
+                    .items
+                    .into_iter()
+                    .map(|backlink| (backlink.name, backlink.title, backlink.broken))
+                    .collect::<Vec<(String, String, bool)>>();
+                let expected = expected
+                    .into_iter()
+                    .map(|(name, title, broken)| (name.to_owned(), title.to_owned(), broken))
+                    .collect::<Vec<(String, String, bool)>>();
+                assert_eq!(backlinks, expected);
+            }
             FolderScript::RestoreAppFromTrash => {
                 restore_app_from_trash(sdk, &self.app.id).await;
             }
@@ -355,6 +385,7 @@ pub async fn create_view(sdk: &FlowySDKTest, app_id: &str, name: &str, desc: &st
         data_type,
         plugin_type: 0,
         data: vec![],
+        data_format: ViewDataFormat::Delta,
     };
     let view = FolderEventBuilder::new(sdk.clone())
         .event(CreateView)
@@ -389,6 +420,28 @@ pub async fn update_view(sdk: &FlowySDKTest, view_id: &str, name: Option<String>
         .await;
 }
 
+pub async fn read_backlinks(sdk: &FlowySDKTest, view_id: &str) -> RepeatedBacklink {
+    let view_id: ViewId = view_id.into();
+    FolderEventBuilder::new(sdk.clone())
+        .event(ReadBacklinks)
+        .payload(view_id)
+        .async_send()
+        .await
+        .parse::<RepeatedBacklink>()
+}
+
+pub async fn apply_text_block_delta(sdk: &FlowySDKTest, view_id: &str, delta_str: String) {
+    let delta = TextBlockDelta {
+        block_id: view_id.to_owned(),
+        delta_str,
+    };
+    FolderEventBuilder::new(sdk.clone())
+        .event(TextBlockEvent::ApplyDelta)
+        .payload(delta)
+        .async_send()
+        .await;
+}
+
 pub async fn delete_view(sdk: &FlowySDKTest, view_ids: Vec<String>) {
     let request = RepeatedViewId { items: view_ids };
     FolderEventBuilder::new(sdk.clone())
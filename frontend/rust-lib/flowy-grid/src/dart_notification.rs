@@ -8,9 +8,13 @@ pub enum GridNotification {
     DidCreateBlock = 11,
     DidUpdateGridBlock = 20,
     DidUpdateGridField = 21,
+    DidUpdateFieldSettings = 22,
+    DidUpdateRowTemplates = 23,
     DidUpdateRow = 30,
+    DidUpdateRowActivity = 31,
     DidUpdateCell = 40,
     DidUpdateField = 50,
+    DidUpdateCellPresences = 60,
 }
 
 impl std::default::Default for GridNotification {
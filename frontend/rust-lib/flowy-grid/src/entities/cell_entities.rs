@@ -150,6 +150,7 @@ impl std::convert::From<CellChangeset> for RowMetaChangeset {
             row_id: changeset.row_id,
             height: None,
             visibility: None,
+            document_id: None,
             cell_by_field_id,
         }
     }
@@ -33,6 +33,9 @@ pub struct Field {
 
     #[pb(index = 8)]
     pub is_primary: bool,
+
+    #[pb(index = 9, one_of)]
+    pub default_value: Option<String>,
 }
 
 impl std::convert::From<FieldRevision> for Field {
@@ -46,6 +49,7 @@ impl std::convert::From<FieldRevision> for Field {
             visibility: field_rev.visibility,
             width: field_rev.width,
             is_primary: field_rev.is_primary,
+            default_value: field_rev.default_value,
         }
     }
 }
@@ -415,6 +419,10 @@ pub struct FieldChangesetPayload {
 
     #[pb(index = 9, one_of)]
     pub type_option_data: Option<Vec<u8>>,
+
+    /// An empty string means removing the default value of the field.
+    #[pb(index = 10, one_of)]
+    pub default_value: Option<String>,
 }
 
 impl TryInto<FieldChangesetParams> for FieldChangesetPayload {
@@ -440,6 +448,7 @@ impl TryInto<FieldChangesetParams> for FieldChangesetPayload {
             visibility: self.visibility,
             width: self.width,
             type_option_data: self.type_option_data,
+            default_value: self.default_value,
         })
     }
 }
@@ -469,6 +478,8 @@ pub enum FieldType {
     MultiSelect = 4,
     Checkbox = 5,
     URL = 6,
+    Email = 7,
+    Phone = 8,
 }
 
 impl std::default::Default for FieldType {
@@ -529,6 +540,14 @@ impl FieldType {
         self == &FieldType::URL
     }
 
+    pub fn is_email(&self) -> bool {
+        self == &FieldType::Email
+    }
+
+    pub fn is_phone(&self) -> bool {
+        self == &FieldType::Phone
+    }
+
     pub fn is_select_option(&self) -> bool {
         self == &FieldType::MultiSelect || self == &FieldType::SingleSelect
     }
@@ -561,6 +580,8 @@ impl std::convert::From<FieldTypeRevision> for FieldType {
             4 => FieldType::MultiSelect,
             5 => FieldType::Checkbox,
             6 => FieldType::URL,
+            7 => FieldType::Email,
+            8 => FieldType::Phone,
             _ => {
                 tracing::error!("Can't parser FieldTypeRevision: {} to FieldType", ty);
                 FieldType::RichText
@@ -108,7 +108,7 @@ impl TryInto<CreateGridFilterParams> for CreateGridFilterPayload {
             .0;
         let condition = self.condition as u8;
         match self.field_type {
-            FieldType::RichText | FieldType::URL => {
+            FieldType::RichText | FieldType::URL | FieldType::Email | FieldType::Phone => {
                 let _ = TextFilterCondition::try_from(condition)?;
             }
             FieldType::Checkbox => {
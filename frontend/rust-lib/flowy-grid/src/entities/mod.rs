@@ -4,7 +4,10 @@ mod field_entities;
 mod filter_entities;
 mod grid_entities;
 mod group_entities;
+mod presence_entities;
+mod row_activity_entities;
 mod row_entities;
+mod row_template_entities;
 mod setting_entities;
 mod sort_entities;
 
@@ -14,6 +17,9 @@ pub use field_entities::*;
 pub use filter_entities::*;
 pub use grid_entities::*;
 pub use group_entities::*;
+pub use presence_entities::*;
+pub use row_activity_entities::*;
 pub use row_entities::*;
+pub use row_template_entities::*;
 pub use setting_entities::*;
 pub use sort_entities::*;
@@ -0,0 +1,26 @@
+use flowy_derive::ProtoBuf;
+
+/// The cell that another user focused in the grid.
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct GridCellPresence {
+    #[pb(index = 1)]
+    pub user_id: String,
+
+    #[pb(index = 2)]
+    pub row_id: String,
+
+    #[pb(index = 3)]
+    pub field_id: String,
+}
+
+#[derive(Debug, Default, ProtoBuf)]
+pub struct RepeatedGridCellPresence {
+    #[pb(index = 1)]
+    pub items: Vec<GridCellPresence>,
+}
+
+impl std::convert::From<Vec<GridCellPresence>> for RepeatedGridCellPresence {
+    fn from(items: Vec<GridCellPresence>) -> Self {
+        Self { items }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::services::persistence::row_activity::{RowChangeTable, RowCommentTable};
+use flowy_derive::ProtoBuf;
+use flowy_error::ErrorCode;
+use flowy_grid_data_model::parser::NotEmptyStr;
+
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct RowComment {
+    #[pb(index = 1)]
+    pub id: String,
+
+    #[pb(index = 2)]
+    pub row_id: String,
+
+    #[pb(index = 3)]
+    pub author: String,
+
+    #[pb(index = 4)]
+    pub content: String,
+
+    #[pb(index = 5)]
+    pub create_time: i64,
+
+    #[pb(index = 6)]
+    pub modified_time: i64,
+}
+
+impl std::convert::From<RowCommentTable> for RowComment {
+    fn from(table: RowCommentTable) -> Self {
+        Self {
+            id: table.id,
+            row_id: table.row_id,
+            author: table.author,
+            content: table.content,
+            create_time: table.create_time,
+            modified_time: table.modified_time,
+        }
+    }
+}
+
+/// A change of the cell that belongs to the row. The `old_value` and the `new_value` are the
+/// text that was displayed in the cell when the change happened. The `desc` describes the change,
+/// for example: Status: Open → Done
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct RowChange {
+    #[pb(index = 1)]
+    pub field_id: String,
+
+    #[pb(index = 2)]
+    pub field_name: String,
+
+    #[pb(index = 3)]
+    pub old_value: String,
+
+    #[pb(index = 4)]
+    pub new_value: String,
+
+    #[pb(index = 5)]
+    pub author: String,
+
+    #[pb(index = 6)]
+    pub timestamp: i64,
+
+    #[pb(index = 7)]
+    pub desc: String,
+}
+
+impl std::convert::From<RowChangeTable> for RowChange {
+    fn from(table: RowChangeTable) -> Self {
+        let desc = format!("{}: {} → {}", table.field_name, table.old_value, table.new_value);
+        Self {
+            field_id: table.field_id,
+            field_name: table.field_name,
+            old_value: table.old_value,
+            new_value: table.new_value,
+            author: table.author,
+            timestamp: table.create_time,
+            desc,
+        }
+    }
+}
+
+#[derive(Debug, Default, ProtoBuf)]
+pub struct RowActivity {
+    #[pb(index = 1)]
+    pub row_id: String,
+
+    #[pb(index = 2)]
+    pub comments: Vec<RowComment>,
+
+    #[pb(index = 3)]
+    pub changes: Vec<RowChange>,
+}
+
+#[derive(ProtoBuf, Default)]
+pub struct CreateRowCommentPayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub row_id: String,
+
+    #[pb(index = 3)]
+    pub content: String,
+}
+
+pub struct CreateRowCommentParams {
+    pub grid_id: String,
+    pub row_id: String,
+    pub content: String,
+}
+
+impl TryInto<CreateRowCommentParams> for CreateRowCommentPayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<CreateRowCommentParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let row_id = NotEmptyStr::parse(self.row_id).map_err(|_| ErrorCode::RowIdIsEmpty)?;
+        let content = NotEmptyStr::parse(self.content).map_err(|_| ErrorCode::RowCommentIsEmpty)?;
+        Ok(CreateRowCommentParams {
+            grid_id: grid_id.0,
+            row_id: row_id.0,
+            content: content.0,
+        })
+    }
+}
+
+#[derive(ProtoBuf, Default)]
+pub struct UpdateRowCommentPayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub comment_id: String,
+
+    #[pb(index = 3)]
+    pub content: String,
+}
+
+pub struct UpdateRowCommentParams {
+    pub grid_id: String,
+    pub comment_id: String,
+    pub content: String,
+}
+
+impl TryInto<UpdateRowCommentParams> for UpdateRowCommentPayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<UpdateRowCommentParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let comment_id = NotEmptyStr::parse(self.comment_id).map_err(|_| ErrorCode::RowCommentIdIsEmpty)?;
+        let content = NotEmptyStr::parse(self.content).map_err(|_| ErrorCode::RowCommentIsEmpty)?;
+        Ok(UpdateRowCommentParams {
+            grid_id: grid_id.0,
+            comment_id: comment_id.0,
+            content: content.0,
+        })
+    }
+}
+
+#[derive(ProtoBuf, Default)]
+pub struct RowCommentIdPayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub comment_id: String,
+}
+
+pub struct RowCommentId {
+    pub grid_id: String,
+    pub comment_id: String,
+}
+
+impl TryInto<RowCommentId> for RowCommentIdPayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<RowCommentId, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let comment_id = NotEmptyStr::parse(self.comment_id).map_err(|_| ErrorCode::RowCommentIdIsEmpty)?;
+        Ok(RowCommentId {
+            grid_id: grid_id.0,
+            comment_id: comment_id.0,
+        })
+    }
+}
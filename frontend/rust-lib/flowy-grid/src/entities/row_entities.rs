@@ -42,6 +42,17 @@ impl TryInto<GridRowId> for GridRowIdPayload {
     }
 }
 
+/// The document that holds the body of the row's detail page. Open it with the text block
+/// events using the `document_id`.
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct RowDocument {
+    #[pb(index = 1)]
+    pub row_id: String,
+
+    #[pb(index = 2)]
+    pub document_id: String,
+}
+
 #[derive(Debug, Default, Clone, ProtoBuf)]
 pub struct BlockRowId {
     #[pb(index = 1)]
@@ -58,12 +69,17 @@ pub struct CreateRowPayload {
 
     #[pb(index = 2, one_of)]
     pub start_row_id: Option<String>,
+
+    /// The id of the row template that is used to fill in the cells of the new row.
+    #[pb(index = 3, one_of)]
+    pub template_id: Option<String>,
 }
 
 #[derive(Default)]
 pub struct CreateRowParams {
     pub grid_id: String,
     pub start_row_id: Option<String>,
+    pub template_id: Option<String>,
 }
 
 impl TryInto<CreateRowParams> for CreateRowPayload {
@@ -74,6 +90,7 @@ impl TryInto<CreateRowParams> for CreateRowPayload {
         Ok(CreateRowParams {
             grid_id: grid_id.0,
             start_row_id: self.start_row_id,
+            template_id: self.template_id,
         })
     }
 }
@@ -0,0 +1,190 @@
+use flowy_derive::ProtoBuf;
+use flowy_error::ErrorCode;
+use flowy_grid_data_model::parser::NotEmptyStr;
+use flowy_grid_data_model::revision::RowTemplateRevision;
+use std::sync::Arc;
+
+/// The content of the cell that will be filled in when creating a row from the template. The
+/// content could be one of the cell placeholders, for example, `{{today}}` or `{{current_user}}`.
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct RowTemplateCell {
+    #[pb(index = 1)]
+    pub field_id: String,
+
+    #[pb(index = 2)]
+    pub content: String,
+}
+
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct RowTemplate {
+    #[pb(index = 1)]
+    pub id: String,
+
+    #[pb(index = 2)]
+    pub name: String,
+
+    #[pb(index = 3)]
+    pub cells: Vec<RowTemplateCell>,
+
+    #[pb(index = 4, one_of)]
+    pub document: Option<String>,
+}
+
+impl std::convert::From<&RowTemplateRevision> for RowTemplate {
+    fn from(template_rev: &RowTemplateRevision) -> Self {
+        let cells = template_rev
+            .cells
+            .iter()
+            .map(|(field_id, content)| RowTemplateCell {
+                field_id: field_id.clone(),
+                content: content.clone(),
+            })
+            .collect();
+
+        Self {
+            id: template_rev.id.clone(),
+            name: template_rev.name.clone(),
+            cells,
+            document: template_rev.document.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default, ProtoBuf)]
+pub struct RepeatedRowTemplate {
+    #[pb(index = 1)]
+    pub items: Vec<RowTemplate>,
+}
+
+impl std::convert::From<Vec<Arc<RowTemplateRevision>>> for RepeatedRowTemplate {
+    fn from(template_revs: Vec<Arc<RowTemplateRevision>>) -> Self {
+        let items = template_revs
+            .iter()
+            .map(|template_rev| RowTemplate::from(template_rev.as_ref()))
+            .collect();
+        Self { items }
+    }
+}
+
+#[derive(ProtoBuf, Default)]
+pub struct CreateRowTemplatePayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub name: String,
+
+    #[pb(index = 3)]
+    pub cells: Vec<RowTemplateCell>,
+
+    #[pb(index = 4, one_of)]
+    pub document: Option<String>,
+}
+
+pub struct CreateRowTemplateParams {
+    pub grid_id: String,
+    pub name: String,
+    pub cells: Vec<RowTemplateCell>,
+    pub document: Option<String>,
+}
+
+impl TryInto<CreateRowTemplateParams> for CreateRowTemplatePayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<CreateRowTemplateParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let name = NotEmptyStr::parse(self.name).map_err(|_| ErrorCode::RowTemplateNameIsEmpty)?;
+        let cells = parse_template_cells(self.cells)?;
+        Ok(CreateRowTemplateParams {
+            grid_id: grid_id.0,
+            name: name.0,
+            cells,
+            document: self.document,
+        })
+    }
+}
+
+/// Replace the name, the cells and the document of the row template.
+#[derive(ProtoBuf, Default)]
+pub struct UpdateRowTemplatePayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub template_id: String,
+
+    #[pb(index = 3)]
+    pub name: String,
+
+    #[pb(index = 4)]
+    pub cells: Vec<RowTemplateCell>,
+
+    #[pb(index = 5, one_of)]
+    pub document: Option<String>,
+}
+
+pub struct UpdateRowTemplateParams {
+    pub grid_id: String,
+    pub template_id: String,
+    pub name: String,
+    pub cells: Vec<RowTemplateCell>,
+    pub document: Option<String>,
+}
+
+impl TryInto<UpdateRowTemplateParams> for UpdateRowTemplatePayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<UpdateRowTemplateParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let template_id = NotEmptyStr::parse(self.template_id).map_err(|_| ErrorCode::RowTemplateIdIsEmpty)?;
+        let name = NotEmptyStr::parse(self.name).map_err(|_| ErrorCode::RowTemplateNameIsEmpty)?;
+        let cells = parse_template_cells(self.cells)?;
+        Ok(UpdateRowTemplateParams {
+            grid_id: grid_id.0,
+            template_id: template_id.0,
+            name: name.0,
+            cells,
+            document: self.document,
+        })
+    }
+}
+
+#[derive(ProtoBuf, Default)]
+pub struct RowTemplateIdPayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub template_id: String,
+}
+
+pub struct RowTemplateIdParams {
+    pub grid_id: String,
+    pub template_id: String,
+}
+
+impl TryInto<RowTemplateIdParams> for RowTemplateIdPayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<RowTemplateIdParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let template_id = NotEmptyStr::parse(self.template_id).map_err(|_| ErrorCode::RowTemplateIdIsEmpty)?;
+        Ok(RowTemplateIdParams {
+            grid_id: grid_id.0,
+            template_id: template_id.0,
+        })
+    }
+}
+
+fn parse_template_cells(cells: Vec<RowTemplateCell>) -> Result<Vec<RowTemplateCell>, ErrorCode> {
+    cells
+        .into_iter()
+        .map(|cell| {
+            let field_id = NotEmptyStr::parse(cell.field_id).map_err(|_| ErrorCode::FieldIdIsEmpty)?;
+            Ok(RowTemplateCell {
+                field_id: field_id.0,
+                content: cell.content,
+            })
+        })
+        .collect()
+}
@@ -6,7 +6,7 @@ use flowy_derive::{ProtoBuf, ProtoBuf_Enum};
 use flowy_error::ErrorCode;
 use flowy_grid_data_model::parser::NotEmptyStr;
 use flowy_grid_data_model::revision::GridLayoutRevision;
-use flowy_sync::entities::grid::GridSettingChangesetParams;
+use flowy_sync::entities::grid::{FieldSettingChangesetParams, GridSettingChangesetParams};
 use std::collections::HashMap;
 use std::convert::TryInto;
 use strum::IntoEnumIterator;
@@ -28,6 +28,32 @@ pub struct GridSetting {
 
     #[pb(index = 5)]
     pub sorts_by_field_id: HashMap<String, RepeatedGridSort>,
+
+    #[pb(index = 6)]
+    pub field_settings: Vec<FieldSetting>,
+}
+
+/// The visibility and width of the field in a specific layout.
+#[derive(Eq, PartialEq, ProtoBuf, Debug, Default, Clone)]
+pub struct FieldSetting {
+    #[pb(index = 1)]
+    pub field_id: String,
+
+    #[pb(index = 2)]
+    pub visibility: bool,
+
+    #[pb(index = 3)]
+    pub width: i32,
+}
+
+/// The settings of the fields in the order they are displayed in the layout.
+#[derive(Eq, PartialEq, ProtoBuf, Debug, Default, Clone)]
+pub struct LayoutFieldSettings {
+    #[pb(index = 1)]
+    pub layout_type: GridLayoutType,
+
+    #[pb(index = 2)]
+    pub items: Vec<FieldSetting>,
 }
 
 #[derive(Eq, PartialEq, ProtoBuf, Debug, Default, Clone)]
@@ -155,3 +181,109 @@ impl TryInto<GridSettingChangesetParams> for GridSettingChangesetPayload {
         })
     }
 }
+
+#[derive(ProtoBuf, Default)]
+pub struct GridLayoutPayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub layout_type: GridLayoutType,
+}
+
+pub struct GridLayoutParams {
+    pub grid_id: String,
+    pub layout_type: GridLayoutType,
+}
+
+impl TryInto<GridLayoutParams> for GridLayoutPayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<GridLayoutParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        Ok(GridLayoutParams {
+            grid_id: grid_id.0,
+            layout_type: self.layout_type,
+        })
+    }
+}
+
+#[derive(ProtoBuf, Default)]
+pub struct FieldSettingChangesetPayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub layout_type: GridLayoutType,
+
+    #[pb(index = 3)]
+    pub field_id: String,
+
+    #[pb(index = 4, one_of)]
+    pub visibility: Option<bool>,
+
+    #[pb(index = 5, one_of)]
+    pub width: Option<i32>,
+}
+
+impl TryInto<FieldSettingChangesetParams> for FieldSettingChangesetPayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<FieldSettingChangesetParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let field_id = NotEmptyStr::parse(self.field_id).map_err(|_| ErrorCode::FieldIdIsEmpty)?;
+        Ok(FieldSettingChangesetParams {
+            grid_id: grid_id.0,
+            layout_type: self.layout_type.into(),
+            field_id: field_id.0,
+            visibility: self.visibility,
+            width: self.width,
+        })
+    }
+}
+
+#[derive(ProtoBuf, Default)]
+pub struct MoveLayoutFieldPayload {
+    #[pb(index = 1)]
+    pub grid_id: String,
+
+    #[pb(index = 2)]
+    pub layout_type: GridLayoutType,
+
+    #[pb(index = 3)]
+    pub field_id: String,
+
+    #[pb(index = 4)]
+    pub from_index: i32,
+
+    #[pb(index = 5)]
+    pub to_index: i32,
+}
+
+pub struct MoveLayoutFieldParams {
+    pub grid_id: String,
+    pub layout_type: GridLayoutType,
+    pub field_id: String,
+    pub from_index: i32,
+    pub to_index: i32,
+}
+
+impl TryInto<MoveLayoutFieldParams> for MoveLayoutFieldPayload {
+    type Error = ErrorCode;
+
+    fn try_into(self) -> Result<MoveLayoutFieldParams, Self::Error> {
+        let grid_id = NotEmptyStr::parse(self.grid_id).map_err(|_| ErrorCode::GridIdIsEmpty)?;
+        let field_id = NotEmptyStr::parse(self.field_id).map_err(|_| ErrorCode::FieldIdIsEmpty)?;
+        if self.from_index < 0 || self.to_index < 0 {
+            return Err(ErrorCode::InvalidData);
+        }
+
+        Ok(MoveLayoutFieldParams {
+            grid_id: grid_id.0,
+            layout_type: self.layout_type,
+            field_id: field_id.0,
+            from_index: self.from_index,
+            to_index: self.to_index,
+        })
+    }
+}
@@ -7,7 +7,7 @@ use crate::services::field::{
 };
 use flowy_error::{ErrorCode, FlowyError, FlowyResult};
 use flowy_grid_data_model::revision::FieldRevision;
-use flowy_sync::entities::grid::{FieldChangesetParams, GridSettingChangesetParams};
+use flowy_sync::entities::grid::{FieldChangesetParams, FieldSettingChangesetParams, GridSettingChangesetParams};
 use lib_dispatch::prelude::{data_result, AppData, Data, DataResult};
 use std::sync::Arc;
 
@@ -210,6 +210,39 @@ pub(crate) async fn move_item_handler(
     Ok(())
 }
 
+#[tracing::instrument(level = "trace", skip(data, manager), err)]
+pub(crate) async fn get_field_settings_handler(
+    data: Data<GridLayoutPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> DataResult<LayoutFieldSettings, FlowyError> {
+    let params: GridLayoutParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let field_settings = editor.get_field_settings(&params.layout_type).await?;
+    data_result(field_settings)
+}
+
+#[tracing::instrument(level = "trace", skip(data, manager), err)]
+pub(crate) async fn update_field_setting_handler(
+    data: Data<FieldSettingChangesetPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> Result<(), FlowyError> {
+    let params: FieldSettingChangesetParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let _ = editor.update_field_setting(params).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(data, manager), err)]
+pub(crate) async fn move_layout_field_handler(
+    data: Data<MoveLayoutFieldPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> Result<(), FlowyError> {
+    let params: MoveLayoutFieldParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let _ = editor.move_layout_field(params).await?;
+    Ok(())
+}
+
 /// The FieldMeta contains multiple data, each of them belongs to a specific FieldType.
 async fn get_type_option_data(field_rev: &FieldRevision, field_type: &FieldType) -> FlowyResult<Vec<u8>> {
     let s = field_rev
@@ -257,6 +290,105 @@ pub(crate) async fn duplicate_row_handler(
     Ok(())
 }
 
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn get_row_document_handler(
+    data: Data<GridRowIdPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> DataResult<RowDocument, FlowyError> {
+    let params: GridRowId = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let row_document = editor.get_row_document(&params.row_id).await?;
+    data_result(row_document)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn get_row_templates_handler(
+    data: Data<GridId>,
+    manager: AppData<Arc<GridManager>>,
+) -> DataResult<RepeatedRowTemplate, FlowyError> {
+    let grid_id: GridId = data.into_inner();
+    let editor = manager.open_grid(grid_id).await?;
+    let row_templates = editor.get_row_templates().await?;
+    data_result(row_templates)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn create_row_template_handler(
+    data: Data<CreateRowTemplatePayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> DataResult<RowTemplate, FlowyError> {
+    let params: CreateRowTemplateParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let row_template = editor.create_row_template(params).await?;
+    data_result(row_template)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn update_row_template_handler(
+    data: Data<UpdateRowTemplatePayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> Result<(), FlowyError> {
+    let params: UpdateRowTemplateParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let _ = editor.update_row_template(params).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn delete_row_template_handler(
+    data: Data<RowTemplateIdPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> Result<(), FlowyError> {
+    let params: RowTemplateIdParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let _ = editor.delete_row_template(&params.template_id).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn get_row_activity_handler(
+    data: Data<GridRowIdPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> DataResult<RowActivity, FlowyError> {
+    let params: GridRowId = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let row_activity = editor.get_row_activity(&params.row_id).await?;
+    data_result(row_activity)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn create_row_comment_handler(
+    data: Data<CreateRowCommentPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> DataResult<RowComment, FlowyError> {
+    let params: CreateRowCommentParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let comment = editor.create_row_comment(params).await?;
+    data_result(comment)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn update_row_comment_handler(
+    data: Data<UpdateRowCommentPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> Result<(), FlowyError> {
+    let params: UpdateRowCommentParams = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let _ = editor.update_row_comment(params).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn delete_row_comment_handler(
+    data: Data<RowCommentIdPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> Result<(), FlowyError> {
+    let params: RowCommentId = data.into_inner().try_into()?;
+    let editor = manager.get_grid_editor(&params.grid_id)?;
+    let _ = editor.delete_row_comment(&params.comment_id).await?;
+    Ok(())
+}
+
 #[tracing::instrument(level = "debug", skip(data, manager), err)]
 pub(crate) async fn create_row_handler(
     data: Data<CreateRowPayload>,
@@ -264,7 +396,7 @@ pub(crate) async fn create_row_handler(
 ) -> Result<(), FlowyError> {
     let params: CreateRowParams = data.into_inner().try_into()?;
     let editor = manager.get_grid_editor(params.grid_id.as_ref())?;
-    let _ = editor.create_row(params.start_row_id).await?;
+    let _ = editor.create_row(params).await?;
     Ok(())
 }
 
@@ -281,6 +413,26 @@ pub(crate) async fn get_cell_handler(
     }
 }
 
+#[tracing::instrument(level = "trace", skip_all, err)]
+pub(crate) async fn update_focused_cell_handler(
+    data: Data<CellIdentifierPayload>,
+    manager: AppData<Arc<GridManager>>,
+) -> Result<(), FlowyError> {
+    let params: CellIdentifier = data.into_inner().try_into()?;
+    let _ = manager.update_focused_cell(&params).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "trace", skip(data, manager), err)]
+pub(crate) async fn get_cell_presences_handler(
+    data: Data<GridId>,
+    manager: AppData<Arc<GridManager>>,
+) -> DataResult<RepeatedGridCellPresence, FlowyError> {
+    let grid_id: GridId = data.into_inner();
+    let presences = manager.get_cell_presences(grid_id.as_ref());
+    data_result(presences)
+}
+
 #[tracing::instrument(level = "trace", skip_all, err)]
 pub(crate) async fn update_cell_handler(
     data: Data<CellChangeset>,
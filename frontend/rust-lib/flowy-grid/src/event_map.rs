@@ -23,14 +23,30 @@ pub fn create(grid_manager: Arc<GridManager>) -> Module {
         .event(GridEvent::MoveItem, move_item_handler)
         .event(GridEvent::GetFieldTypeOption, get_field_type_option_data_handler)
         .event(GridEvent::CreateFieldTypeOption, create_field_type_option_data_handler)
+        .event(GridEvent::GetFieldSettings, get_field_settings_handler)
+        .event(GridEvent::UpdateFieldSetting, update_field_setting_handler)
+        .event(GridEvent::MoveLayoutField, move_layout_field_handler)
         // Row
         .event(GridEvent::CreateRow, create_row_handler)
         .event(GridEvent::GetRow, get_row_handler)
         .event(GridEvent::DeleteRow, delete_row_handler)
         .event(GridEvent::DuplicateRow, duplicate_row_handler)
+        .event(GridEvent::GetRowDocument, get_row_document_handler)
+        // Row template
+        .event(GridEvent::GetRowTemplates, get_row_templates_handler)
+        .event(GridEvent::CreateRowTemplate, create_row_template_handler)
+        .event(GridEvent::UpdateRowTemplate, update_row_template_handler)
+        .event(GridEvent::DeleteRowTemplate, delete_row_template_handler)
+        // Row activity
+        .event(GridEvent::GetRowActivity, get_row_activity_handler)
+        .event(GridEvent::CreateRowComment, create_row_comment_handler)
+        .event(GridEvent::UpdateRowComment, update_row_comment_handler)
+        .event(GridEvent::DeleteRowComment, delete_row_comment_handler)
         // Cell
         .event(GridEvent::GetCell, get_cell_handler)
         .event(GridEvent::UpdateCell, update_cell_handler)
+        .event(GridEvent::UpdateFocusedCell, update_focused_cell_handler)
+        .event(GridEvent::GetCellPresences, get_cell_presences_handler)
         // SelectOption
         .event(GridEvent::NewSelectOption, new_select_option_handler)
         .event(GridEvent::UpdateSelectOption, update_select_option_handler)
@@ -87,6 +103,15 @@ pub enum GridEvent {
     #[event(input = "EditFieldPayload", output = "FieldTypeOptionData")]
     CreateFieldTypeOption = 24,
 
+    #[event(input = "GridLayoutPayload", output = "LayoutFieldSettings")]
+    GetFieldSettings = 25,
+
+    #[event(input = "FieldSettingChangesetPayload")]
+    UpdateFieldSetting = 26,
+
+    #[event(input = "MoveLayoutFieldPayload")]
+    MoveLayoutField = 27,
+
     #[event(input = "CreateSelectOptionPayload", output = "SelectOption")]
     NewSelectOption = 30,
 
@@ -108,6 +133,33 @@ pub enum GridEvent {
     #[event(input = "GridRowIdPayload")]
     DuplicateRow = 53,
 
+    #[event(input = "GridRowIdPayload", output = "RowDocument")]
+    GetRowDocument = 54,
+
+    #[event(input = "GridId", output = "RepeatedRowTemplate")]
+    GetRowTemplates = 55,
+
+    #[event(input = "CreateRowTemplatePayload", output = "RowTemplate")]
+    CreateRowTemplate = 56,
+
+    #[event(input = "UpdateRowTemplatePayload")]
+    UpdateRowTemplate = 57,
+
+    #[event(input = "RowTemplateIdPayload")]
+    DeleteRowTemplate = 58,
+
+    #[event(input = "GridRowIdPayload", output = "RowActivity")]
+    GetRowActivity = 60,
+
+    #[event(input = "CreateRowCommentPayload", output = "RowComment")]
+    CreateRowComment = 61,
+
+    #[event(input = "UpdateRowCommentPayload")]
+    UpdateRowComment = 62,
+
+    #[event(input = "RowCommentIdPayload")]
+    DeleteRowComment = 63,
+
     #[event(input = "CellIdentifierPayload", output = "Cell")]
     GetCell = 70,
 
@@ -117,6 +169,12 @@ pub enum GridEvent {
     #[event(input = "SelectOptionCellChangesetPayload")]
     UpdateSelectOptionCell = 72,
 
+    #[event(input = "CellIdentifierPayload")]
+    UpdateFocusedCell = 73,
+
+    #[event(input = "GridId", output = "RepeatedGridCellPresence")]
+    GetCellPresences = 74,
+
     #[event(input = "DateChangesetPayload")]
     UpdateDateCell = 80,
 }
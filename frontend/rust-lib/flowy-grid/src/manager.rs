@@ -1,6 +1,10 @@
+use crate::dart_notification::{send_dart_notification, GridNotification};
+use crate::entities::{CellIdentifier, GridCellPresence, RepeatedGridCellPresence};
 use crate::services::grid_editor::GridRevisionEditor;
 use crate::services::persistence::block_index::BlockIndexCache;
 use crate::services::persistence::kv::GridKVPersistence;
+use crate::services::persistence::migration::{run_v1_migration, skip_v1_migration};
+use crate::services::persistence::row_activity::RowActivityPersistence;
 use crate::services::persistence::GridDatabase;
 use crate::services::tasks::GridTaskScheduler;
 use bytes::Bytes;
@@ -9,9 +13,12 @@ use flowy_database::ConnectionPool;
 use flowy_error::{FlowyError, FlowyResult};
 use flowy_grid_data_model::revision::{BuildGridContext, GridRevision};
 use flowy_revision::disk::{SQLiteGridBlockMetaRevisionPersistence, SQLiteGridRevisionPersistence};
-use flowy_revision::{RevisionManager, RevisionPersistence, RevisionWebSocket};
+use flowy_revision::{PresenceWebSocket, RevisionManager, RevisionPersistence, RevisionWebSocket};
 use flowy_sync::client_grid::{make_block_meta_delta, make_grid_delta};
+use flowy_sync::entities::presence::{PresenceWSData, PresenceWSDataType};
 use flowy_sync::entities::revision::{RepeatedRevision, Revision};
+use lib_infra::future::FutureResult;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -21,12 +28,26 @@ pub trait GridUser: Send + Sync {
     fn db_pool(&self) -> Result<Arc<ConnectionPool>, FlowyError>;
 }
 
+/// The documents of the rows' detail pages are text blocks. They are created, read and deleted
+/// by the text block module, so their revisions are synchronized the same way as the normal
+/// documents.
+pub trait GridDocumentService: Send + Sync {
+    fn create_document(&self, document_id: &str, delta_str: String) -> FutureResult<(), FlowyError>;
+    fn read_document(&self, document_id: &str) -> FutureResult<String, FlowyError>;
+    fn delete_document(&self, document_id: &str) -> FutureResult<(), FlowyError>;
+}
+
 pub type GridTaskSchedulerRwLock = Arc<RwLock<GridTaskScheduler>>;
 
 pub struct GridManager {
     grid_editors: Arc<DashMap<String, Arc<GridRevisionEditor>>>,
     grid_user: Arc<dyn GridUser>,
+    document_service: Arc<dyn GridDocumentService>,
+    presence_web_socket: Arc<dyn PresenceWebSocket>,
+    /// The cells that the other users focused, keyed by the grid id and then the user id.
+    cell_presences: Arc<DashMap<String, HashMap<String, GridCellPresence>>>,
     block_index_cache: Arc<BlockIndexCache>,
+    row_activity_persistence: Arc<RowActivityPersistence>,
     #[allow(dead_code)]
     kv_persistence: Arc<GridKVPersistence>,
     task_scheduler: GridTaskSchedulerRwLock,
@@ -36,17 +57,24 @@ impl GridManager {
     pub fn new(
         grid_user: Arc<dyn GridUser>,
         _rev_web_socket: Arc<dyn RevisionWebSocket>,
+        presence_web_socket: Arc<dyn PresenceWebSocket>,
         database: Arc<dyn GridDatabase>,
+        document_service: Arc<dyn GridDocumentService>,
     ) -> Self {
         let grid_editors = Arc::new(DashMap::new());
         let kv_persistence = Arc::new(GridKVPersistence::new(database.clone()));
+        let row_activity_persistence = Arc::new(RowActivityPersistence::new(database.clone()));
         let block_index_cache = Arc::new(BlockIndexCache::new(database));
         let task_scheduler = GridTaskScheduler::new();
         Self {
             grid_editors,
             grid_user,
+            document_service,
+            presence_web_socket,
+            cell_presences: Arc::new(DashMap::new()),
             kv_persistence,
             block_index_cache,
+            row_activity_persistence,
             task_scheduler,
         }
     }
@@ -77,13 +105,16 @@ impl GridManager {
     pub async fn open_grid<T: AsRef<str>>(&self, grid_id: T) -> FlowyResult<Arc<GridRevisionEditor>> {
         let grid_id = grid_id.as_ref();
         tracing::Span::current().record("grid_id", &grid_id);
-        self.get_or_create_grid_editor(grid_id).await
+        let editor = self.get_or_create_grid_editor(grid_id).await?;
+        self.join_grid(grid_id);
+        Ok(editor)
     }
 
     #[tracing::instrument(level = "debug", skip_all, fields(grid_id), err)]
     pub async fn close_grid<T: AsRef<str>>(&self, grid_id: T) -> FlowyResult<()> {
         let grid_id = grid_id.as_ref();
         tracing::Span::current().record("grid_id", &grid_id);
+        self.leave_grid(grid_id);
         self.grid_editors.remove(grid_id);
         self.task_scheduler.write().await.unregister_handler(grid_id);
         Ok(())
@@ -93,6 +124,7 @@ impl GridManager {
     pub async fn delete_grid<T: AsRef<str>>(&self, grid_id: T) -> FlowyResult<()> {
         let grid_id = grid_id.as_ref();
         tracing::Span::current().record("grid_id", &grid_id);
+        self.leave_grid(grid_id);
         self.grid_editors.remove(grid_id);
         self.task_scheduler.write().await.unregister_handler(grid_id);
         Ok(())
@@ -108,6 +140,90 @@ impl GridManager {
         }
     }
 
+    /// Sends the cell that the current user focused to the other users viewing the grid.
+    pub async fn update_focused_cell(&self, params: &CellIdentifier) -> FlowyResult<()> {
+        let user_id = self.grid_user.user_id()?;
+        let data = PresenceWSData::cell(&params.grid_id, &user_id, &params.row_id, &params.field_id);
+        let _ = self.presence_web_socket.send(data).await?;
+        Ok(())
+    }
+
+    /// Receives the focused cell of another user. The presences are only kept for the grids that
+    /// are opened.
+    pub fn receive_presence(&self, data: PresenceWSData) {
+        if !self.grid_editors.contains_key(&data.object_id) {
+            return;
+        }
+
+        let mut presences = self.cell_presences.entry(data.object_id.clone()).or_default();
+        match data.ty {
+            PresenceWSDataType::Heartbeat => return,
+            PresenceWSDataType::Leave => {
+                if presences.remove(&data.user_id).is_none() {
+                    return;
+                }
+            }
+            PresenceWSDataType::Update => match data.cell {
+                None => return,
+                Some(cell) => {
+                    let presence = GridCellPresence {
+                        user_id: data.user_id.clone(),
+                        row_id: cell.row_id,
+                        field_id: cell.field_id,
+                    };
+                    presences.insert(data.user_id, presence);
+                }
+            },
+        }
+        drop(presences);
+
+        send_dart_notification(&data.object_id, GridNotification::DidUpdateCellPresences)
+            .payload(self.get_cell_presences(&data.object_id))
+            .send();
+    }
+
+    /// Returns the cells that the other users focused, ordered by the id of the user.
+    pub fn get_cell_presences(&self, grid_id: &str) -> RepeatedGridCellPresence {
+        let mut presences = self
+            .cell_presences
+            .get(grid_id)
+            .map(|presences| presences.values().cloned().collect::<Vec<GridCellPresence>>())
+            .unwrap_or_default();
+        presences.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        presences.into()
+    }
+
+    /// Sends the heartbeat of the grid, so the other users see the current user before any cell
+    /// is focused. The heartbeats keep being sent until the user leaves the grid.
+    fn join_grid(&self, grid_id: &str) {
+        let user_id = match self.grid_user.user_id() {
+            Ok(user_id) => user_id,
+            Err(_) => return,
+        };
+        self.send_presence(PresenceWSData::heartbeat(grid_id, &user_id));
+    }
+
+    fn leave_grid(&self, grid_id: &str) {
+        self.cell_presences.remove(grid_id);
+        if !self.grid_editors.contains_key(grid_id) {
+            return;
+        }
+        let user_id = match self.grid_user.user_id() {
+            Ok(user_id) => user_id,
+            Err(_) => return,
+        };
+        self.send_presence(PresenceWSData::leave(grid_id, &user_id));
+    }
+
+    fn send_presence(&self, data: PresenceWSData) {
+        let presence_web_socket = self.presence_web_socket.clone();
+        tokio::spawn(async move {
+            if let Err(e) = presence_web_socket.send(data).await {
+                tracing::error!("Send the presence failed: {}", e);
+            }
+        });
+    }
+
     async fn get_or_create_grid_editor(&self, grid_id: &str) -> FlowyResult<Arc<GridRevisionEditor>> {
         match self.grid_editors.get(grid_id) {
             None => {
@@ -138,10 +254,14 @@ impl GridManager {
             grid_id,
             user,
             rev_manager,
+            self.document_service.clone(),
             self.block_index_cache.clone(),
+            self.row_activity_persistence.clone(),
             self.task_scheduler.clone(),
         )
         .await?;
+
+        let _ = run_v1_migration(grid_id, &grid_editor).await?;
         Ok(grid_editor)
     }
 
@@ -182,7 +302,7 @@ pub async fn make_grid_view_data(
 
         // Create grid's block
         let grid_block_meta_delta = make_block_meta_delta(block_meta_data);
-        let block_meta_delta_data = grid_block_meta_delta.to_delta_bytes();
+        let block_meta_delta_data = grid_block_meta_delta.to_compact_bytes();
         let repeated_revision: RepeatedRevision =
             Revision::initial_revision(user_id, block_id, block_meta_delta_data).into();
         let _ = grid_manager
@@ -190,14 +310,22 @@ pub async fn make_grid_view_data(
             .await?;
     }
 
+    // Create the documents of the rows
+    for (document_id, delta_str) in &build_context.row_documents {
+        let _ = grid_manager
+            .document_service
+            .create_document(document_id, delta_str.clone())
+            .await?;
+    }
+
     let grid_rev = GridRevision::from_build_context(view_id, build_context);
 
     // Create grid
     let grid_meta_delta = make_grid_delta(&grid_rev);
-    let grid_delta_data = grid_meta_delta.to_delta_bytes();
     let repeated_revision: RepeatedRevision =
-        Revision::initial_revision(user_id, view_id, grid_delta_data.clone()).into();
+        Revision::initial_revision(user_id, view_id, grid_meta_delta.to_compact_bytes()).into();
     let _ = grid_manager.create_grid(view_id, repeated_revision).await?;
+    skip_v1_migration(view_id);
 
-    Ok(grid_delta_data)
+    Ok(grid_meta_delta.to_delta_bytes())
 }
@@ -1,16 +1,23 @@
 use crate::dart_notification::{send_dart_notification, GridNotification};
-use crate::entities::{CellChangeset, GridBlockChangeset, InsertedRow, Row, RowInfo, UpdatedRow};
+use crate::entities::{
+    CellChangeset, CreateRowCommentParams, GridBlockChangeset, InsertedRow, Row, RowActivity, RowChange, RowComment,
+    RowInfo, UpdateRowCommentParams, UpdatedRow,
+};
 use crate::manager::GridUser;
 use crate::services::block_revision_editor::GridBlockRevisionEditor;
+use crate::services::cell::decode_cell_data_to_string;
 use crate::services::persistence::block_index::BlockIndexCache;
+use crate::services::persistence::row_activity::{NewRowChange, RowActivityPersistence, RowCommentTable};
 use crate::services::row::{block_from_row_orders, GridBlockSnapshot};
 use dashmap::DashMap;
-use flowy_error::FlowyResult;
+use flowy_error::{FlowyError, FlowyResult};
 use flowy_grid_data_model::revision::{
-    GridBlockMetaRevision, GridBlockMetaRevisionChangeset, RowMetaChangeset, RowRevision,
+    FieldRevision, GridBlockMetaRevision, GridBlockMetaRevisionChangeset, RowMetaChangeset, RowRevision,
 };
 use flowy_revision::disk::SQLiteGridBlockMetaRevisionPersistence;
 use flowy_revision::{RevisionManager, RevisionPersistence};
+use lib_infra::util::timestamp;
+use nanoid::nanoid;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -21,6 +28,7 @@ pub(crate) struct GridBlockManager {
     grid_id: String,
     user: Arc<dyn GridUser>,
     persistence: Arc<BlockIndexCache>,
+    activity_persistence: Arc<RowActivityPersistence>,
     block_editors: DashMap<BlockId, Arc<GridBlockRevisionEditor>>,
 }
 
@@ -30,6 +38,7 @@ impl GridBlockManager {
         user: &Arc<dyn GridUser>,
         block_meta_revs: Vec<Arc<GridBlockMetaRevision>>,
         persistence: Arc<BlockIndexCache>,
+        activity_persistence: Arc<RowActivityPersistence>,
     ) -> FlowyResult<Self> {
         let block_editors = make_block_editors(user, block_meta_revs).await?;
         let user = user.clone();
@@ -39,6 +48,7 @@ impl GridBlockManager {
             user,
             block_editors,
             persistence,
+            activity_persistence,
         };
         Ok(manager)
     }
@@ -196,16 +206,118 @@ impl GridBlockManager {
         Ok(())
     }
 
-    pub async fn update_cell<F>(&self, changeset: CellChangeset, row_builder: F) -> FlowyResult<()>
+    pub async fn update_cell<F>(
+        &self,
+        changeset: CellChangeset,
+        field_rev: &FieldRevision,
+        row_builder: F,
+    ) -> FlowyResult<()>
     where
         F: FnOnce(Arc<RowRevision>) -> Option<Row>,
     {
+        let old_value = self.get_cell_display_str(&changeset.row_id, field_rev).await?;
         let row_changeset: RowMetaChangeset = changeset.clone().into();
         let _ = self.update_row(row_changeset, row_builder).await?;
+        let new_value = self.get_cell_display_str(&changeset.row_id, field_rev).await?;
+        if old_value != new_value {
+            // Failing to record the change should not fail the update of the cell.
+            if let Err(e) = self.record_row_change(&changeset.row_id, field_rev, old_value, new_value) {
+                tracing::error!("Record the change of the row:{} failed: {:?}", changeset.row_id, e);
+            }
+        }
         self.notify_did_update_cell(changeset).await?;
         Ok(())
     }
 
+    async fn get_cell_display_str(&self, row_id: &str, field_rev: &FieldRevision) -> FlowyResult<String> {
+        let row_rev = self.get_row_rev(row_id).await?;
+        let cell_rev = row_rev.as_ref().and_then(|row_rev| row_rev.cells.get(&field_rev.id));
+        Ok(decode_cell_data_to_string(cell_rev, field_rev))
+    }
+
+    fn record_row_change(
+        &self,
+        row_id: &str,
+        field_rev: &FieldRevision,
+        old_value: String,
+        new_value: String,
+    ) -> FlowyResult<()> {
+        let change = NewRowChange {
+            grid_id: self.grid_id.clone(),
+            row_id: row_id.to_owned(),
+            field_id: field_rev.id.clone(),
+            field_name: field_rev.name.clone(),
+            old_value,
+            new_value,
+            author: self.user.user_id()?,
+            create_time: timestamp(),
+        };
+        let _ = self.activity_persistence.insert_change(change)?;
+        self.notify_did_update_row_activity(row_id);
+        Ok(())
+    }
+
+    pub fn get_row_activity(&self, row_id: &str) -> FlowyResult<RowActivity> {
+        let comments = self
+            .activity_persistence
+            .get_comments(row_id)?
+            .into_iter()
+            .map(RowComment::from)
+            .collect();
+        let changes = self
+            .activity_persistence
+            .get_changes(row_id)?
+            .into_iter()
+            .map(RowChange::from)
+            .collect();
+        Ok(RowActivity {
+            row_id: row_id.to_owned(),
+            comments,
+            changes,
+        })
+    }
+
+    pub fn create_row_comment(&self, params: CreateRowCommentParams) -> FlowyResult<RowComment> {
+        let time = timestamp();
+        let comment = RowCommentTable {
+            id: nanoid!(10),
+            grid_id: params.grid_id,
+            row_id: params.row_id,
+            author: self.user.user_id()?,
+            content: params.content,
+            create_time: time,
+            modified_time: time,
+        };
+        let _ = self.activity_persistence.insert_comment(comment.clone())?;
+        self.notify_did_update_row_activity(&comment.row_id);
+        Ok(comment.into())
+    }
+
+    pub fn update_row_comment(&self, params: UpdateRowCommentParams) -> FlowyResult<()> {
+        let comment = self.get_own_comment(&params.comment_id)?;
+        let _ = self
+            .activity_persistence
+            .update_comment(&comment.id, &params.content, timestamp())?;
+        self.notify_did_update_row_activity(&comment.row_id);
+        Ok(())
+    }
+
+    pub fn delete_row_comment(&self, comment_id: &str) -> FlowyResult<()> {
+        let comment = self.get_own_comment(comment_id)?;
+        let _ = self.activity_persistence.delete_comment(&comment.id)?;
+        self.notify_did_update_row_activity(&comment.row_id);
+        Ok(())
+    }
+
+    /// Only the author of the comment can edit or delete it.
+    fn get_own_comment(&self, comment_id: &str) -> FlowyResult<RowCommentTable> {
+        let comment = self.activity_persistence.get_comment(comment_id)?;
+        if comment.author != self.user.user_id()? {
+            return Err(FlowyError::unauthorized().context("Only the author can modify the comment"));
+        }
+        Ok(comment)
+    }
+
     pub async fn get_row_rev(&self, row_id: &str) -> FlowyResult<Option<Arc<RowRevision>>> {
         let editor = self.get_editor_from_row_id(row_id).await?;
         let row_ids = vec![Cow::Borrowed(row_id)];
@@ -254,6 +366,10 @@ impl GridBlockManager {
         Ok(())
     }
 
+    fn notify_did_update_row_activity(&self, row_id: &str) {
+        send_dart_notification(row_id, GridNotification::DidUpdateRowActivity).send();
+    }
+
     async fn notify_did_update_cell(&self, changeset: CellChangeset) -> FlowyResult<()> {
         let id = format!("{}:{}", changeset.row_id, changeset.field_id);
         send_dart_notification(&id, GridNotification::DidUpdateCell).send();
@@ -1,4 +1,5 @@
 use crate::entities::RowInfo;
+use crate::services::persistence::migration::run_v2_migration;
 use bytes::Bytes;
 use flowy_error::{FlowyError, FlowyResult};
 use flowy_grid_data_model::revision::{CellRevision, GridBlockRevision, RowMetaChangeset, RowRevision};
@@ -29,7 +30,13 @@ impl GridBlockRevisionEditor {
         let cloud = Arc::new(GridBlockMetaRevisionCloudService {
             token: token.to_owned(),
         });
-        let block_meta_pad = rev_manager.load::<GridBlockMetaPadBuilder>(Some(cloud)).await?;
+        let _ = rev_manager
+            .run_format_migration(Box::new(GridBlockMetaRevisionCompactor()))
+            .await?;
+        let mut block_meta_pad = rev_manager.load::<GridBlockMetaPadBuilder>(Some(cloud)).await?;
+        if let Some(delta) = run_v2_migration(user_id, &rev_manager, || block_meta_pad.snapshot_delta()).await? {
+            block_meta_pad = GridBlockRevisionPad::from_delta(delta)?;
+        }
         let pad = Arc::new(RwLock::new(block_meta_pad));
         let rev_manager = Arc::new(rev_manager);
         let user_id = user_id.to_owned();
@@ -161,7 +168,7 @@ impl GridBlockRevisionEditor {
         let GridBlockMetaChange { delta, md5 } = change;
         let user_id = self.user_id.clone();
         let (base_rev_id, rev_id) = self.rev_manager.next_rev_id_pair();
-        let delta_data = delta.to_delta_bytes();
+        let delta_data = delta.to_compact_bytes();
         let revision = Revision::new(
             &self.rev_manager.object_id,
             base_rev_id,
@@ -204,6 +211,6 @@ struct GridBlockMetaRevisionCompactor();
 impl RevisionCompactor for GridBlockMetaRevisionCompactor {
     fn bytes_from_revisions(&self, revisions: Vec<Revision>) -> FlowyResult<Bytes> {
         let delta = make_delta_from_revisions::<PlainTextAttributes>(revisions)?;
-        Ok(delta.to_delta_bytes())
+        Ok(delta.to_compact_bytes())
     }
 }
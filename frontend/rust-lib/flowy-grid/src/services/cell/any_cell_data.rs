@@ -86,6 +86,14 @@ impl AnyCellData {
         self.field_type == FieldType::URL
     }
 
+    pub fn is_email(&self) -> bool {
+        self.field_type == FieldType::Email
+    }
+
+    pub fn is_phone(&self) -> bool {
+        self.field_type == FieldType::Phone
+    }
+
     pub fn is_select_option(&self) -> bool {
         self.field_type == FieldType::MultiSelect || self.field_type == FieldType::SingleSelect
     }
@@ -97,6 +105,7 @@ impl AnyCellData {
 ///
 /// * Use DateCellData to parse the data when the FieldType is Date.
 /// * Use URLCellData to parse the data when the FieldType is URL.
+/// * Use EmailCellData or PhoneCellData to parse the data when the FieldType is Email or Phone.
 /// * Use String to parse the data when the FieldType is RichText, Number, or Checkbox.
 /// * Check out the implementation of CellDataOperation trait for more information.
 #[derive(Default)]
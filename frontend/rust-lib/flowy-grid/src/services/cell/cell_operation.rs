@@ -3,6 +3,7 @@ use flowy_grid_data_model::revision::{CellRevision, FieldRevision, FieldTypeRevi
 
 use crate::entities::FieldType;
 use crate::services::cell::{AnyCellData, DecodedCellData};
+use crate::services::field::select_option::SelectOptionCellData;
 use crate::services::field::*;
 
 pub trait CellFilterOperation<T> {
@@ -44,6 +45,8 @@ pub fn apply_cell_data_changeset<C: ToString, T: AsRef<FieldRevision>>(
         FieldType::MultiSelect => MultiSelectTypeOption::from(field_rev).apply_changeset(changeset.into(), cell_rev),
         FieldType::Checkbox => CheckboxTypeOption::from(field_rev).apply_changeset(changeset.into(), cell_rev),
         FieldType::URL => URLTypeOption::from(field_rev).apply_changeset(changeset.into(), cell_rev),
+        FieldType::Email => EmailTypeOption::from(field_rev).apply_changeset(changeset.into(), cell_rev),
+        FieldType::Phone => PhoneTypeOption::from(field_rev).apply_changeset(changeset.into(), cell_rev),
     }?;
 
     Ok(AnyCellData::new(s, field_type).json())
@@ -100,6 +103,12 @@ pub fn try_decode_cell_data(
             FieldType::URL => field_rev
                 .get_type_option_entry::<URLTypeOption>(field_type)?
                 .decode_cell_data(cell_data.into(), s_field_type, field_rev),
+            FieldType::Email => field_rev
+                .get_type_option_entry::<EmailTypeOption>(field_type)?
+                .decode_cell_data(cell_data.into(), s_field_type, field_rev),
+            FieldType::Phone => field_rev
+                .get_type_option_entry::<PhoneTypeOption>(field_type)?
+                .decode_cell_data(cell_data.into(), s_field_type, field_rev),
         };
         Some(data)
     };
@@ -114,6 +123,48 @@ pub fn try_decode_cell_data(
     }
 }
 
+/// Return the text that the cell displays. The select option cell displays the names of the
+/// selected options, and the date cell displays the formatted date.
+pub fn decode_cell_data_to_string(cell_rev: Option<&CellRevision>, field_rev: &FieldRevision) -> String {
+    let cell_rev = match cell_rev {
+        None => return "".to_owned(),
+        Some(cell_rev) => cell_rev,
+    };
+
+    let decoded_cell_data = decode_any_cell_data(cell_rev.data.clone(), field_rev);
+    let field_type: FieldType = field_rev.field_type_rev.into();
+    match field_type {
+        FieldType::RichText | FieldType::Number | FieldType::Checkbox => decoded_cell_data.to_string(),
+        FieldType::DateTime => decoded_cell_data
+            .parse::<DateCellData>()
+            .map(|cell_data| format!("{} {}", cell_data.date, cell_data.time).trim().to_owned())
+            .unwrap_or_default(),
+        FieldType::SingleSelect | FieldType::MultiSelect => decoded_cell_data
+            .parse::<SelectOptionCellData>()
+            .map(|cell_data| {
+                cell_data
+                    .select_options
+                    .into_iter()
+                    .map(|option| option.name)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .unwrap_or_default(),
+        FieldType::URL => decoded_cell_data
+            .parse::<URLCellData>()
+            .map(|cell_data| cell_data.content)
+            .unwrap_or_default(),
+        FieldType::Email => decoded_cell_data
+            .parse::<EmailCellData>()
+            .map(|cell_data| cell_data.email)
+            .unwrap_or_default(),
+        FieldType::Phone => decoded_cell_data
+            .parse::<PhoneCellData>()
+            .map(|cell_data| cell_data.phone)
+            .unwrap_or_default(),
+    }
+}
+
 pub trait FromCellString {
     fn from_cell_str(s: &str) -> FlowyResult<Self>
     where
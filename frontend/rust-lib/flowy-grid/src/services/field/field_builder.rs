@@ -39,6 +39,7 @@ impl FieldBuilder {
             width: field.width,
             type_options: IndexMap::default(),
             is_primary: field.is_primary,
+            default_value: field.default_value,
         };
         Self {
             field_rev,
@@ -76,6 +77,11 @@ impl FieldBuilder {
         self
     }
 
+    pub fn default_value(mut self, default_value: &str) -> Self {
+        self.field_rev.default_value = Some(default_value.to_owned());
+        self
+    }
+
     pub fn build(self) -> FieldRevision {
         let mut field_rev = self.field_rev;
         field_rev.insert_type_option_entry(self.type_option_builder.entry());
@@ -97,6 +103,8 @@ pub fn default_type_option_builder_from_type(field_type: &FieldType) -> Box<dyn
         FieldType::MultiSelect => MultiSelectTypeOption::default().into(),
         FieldType::Checkbox => CheckboxTypeOption::default().into(),
         FieldType::URL => URLTypeOption::default().into(),
+        FieldType::Email => EmailTypeOption::default().into(),
+        FieldType::Phone => PhoneTypeOption::default().into(),
     };
 
     type_option_builder_from_json_str(&s, field_type)
@@ -111,6 +119,8 @@ pub fn type_option_builder_from_json_str(s: &str, field_type: &FieldType) -> Box
         FieldType::MultiSelect => Box::new(MultiSelectTypeOptionBuilder::from_json_str(s)),
         FieldType::Checkbox => Box::new(CheckboxTypeOptionBuilder::from_json_str(s)),
         FieldType::URL => Box::new(URLTypeOptionBuilder::from_json_str(s)),
+        FieldType::Email => Box::new(EmailTypeOptionBuilder::from_json_str(s)),
+        FieldType::Phone => Box::new(PhoneTypeOptionBuilder::from_json_str(s)),
     }
 }
 
@@ -124,5 +134,7 @@ pub fn type_option_builder_from_bytes<T: Into<Bytes>>(bytes: T, field_type: &Fie
         FieldType::MultiSelect => Box::new(MultiSelectTypeOptionBuilder::from_protobuf_bytes(bytes)),
         FieldType::Checkbox => Box::new(CheckboxTypeOptionBuilder::from_protobuf_bytes(bytes)),
         FieldType::URL => Box::new(URLTypeOptionBuilder::from_protobuf_bytes(bytes)),
+        FieldType::Email => Box::new(EmailTypeOptionBuilder::from_protobuf_bytes(bytes)),
+        FieldType::Phone => Box::new(PhoneTypeOptionBuilder::from_protobuf_bytes(bytes)),
     }
 }
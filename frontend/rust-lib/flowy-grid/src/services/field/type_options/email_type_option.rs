@@ -0,0 +1,197 @@
+use crate::entities::FieldType;
+use crate::impl_type_option;
+use crate::services::cell::{
+    AnyCellData, CellData, CellDataChangeset, CellDataOperation, DecodedCellData, FromCellString,
+};
+use crate::services::field::{BoxTypeOptionBuilder, TypeOptionBuilder};
+use bytes::Bytes;
+use fancy_regex::Regex;
+use flowy_derive::ProtoBuf;
+use flowy_error::{internal_error, FlowyError, FlowyResult};
+use flowy_grid_data_model::revision::{CellRevision, FieldRevision, TypeOptionDataDeserializer, TypeOptionDataEntry};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default)]
+pub struct EmailTypeOptionBuilder(EmailTypeOption);
+impl_into_box_type_option_builder!(EmailTypeOptionBuilder);
+impl_builder_from_json_str_and_from_bytes!(EmailTypeOptionBuilder, EmailTypeOption);
+
+impl TypeOptionBuilder for EmailTypeOptionBuilder {
+    fn field_type(&self) -> FieldType {
+        FieldType::Email
+    }
+
+    fn entry(&self) -> &dyn TypeOptionDataEntry {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ProtoBuf)]
+pub struct EmailTypeOption {
+    #[pb(index = 1)]
+    data: String, //It's not used yet.
+}
+impl_type_option!(EmailTypeOption, FieldType::Email);
+
+impl CellDataOperation<EmailCellData, String> for EmailTypeOption {
+    fn decode_cell_data(
+        &self,
+        cell_data: CellData<EmailCellData>,
+        decoded_field_type: &FieldType,
+        _field_rev: &FieldRevision,
+    ) -> FlowyResult<DecodedCellData> {
+        if !decoded_field_type.is_email() {
+            return Ok(DecodedCellData::default());
+        }
+        let cell_data: EmailCellData = cell_data.try_into_inner()?;
+        DecodedCellData::try_from_bytes(cell_data)
+    }
+
+    fn apply_changeset(
+        &self,
+        changeset: CellDataChangeset<String>,
+        _cell_rev: Option<CellRevision>,
+    ) -> Result<String, FlowyError> {
+        let changeset = changeset.try_into_inner()?;
+        let s = changeset.trim();
+        if s.is_empty() {
+            return EmailCellData::default().to_json();
+        }
+
+        let email = normalize_email(s).ok_or_else(|| FlowyError::email_format().context(s))?;
+        EmailCellData::new(&email).to_json()
+    }
+}
+
+/// Lowercase the domain of the email address and strip the `mailto:` scheme if the user pasted
+/// a link. The local part is kept as it is because it may be case-sensitive.
+fn normalize_email(s: &str) -> Option<String> {
+    let s = s.strip_prefix("mailto:").unwrap_or(s);
+    if !EMAIL_REGEX.is_match(s).unwrap_or(false) {
+        return None;
+    }
+
+    let (local, domain) = s.rsplit_once('@')?;
+    Some(format!("{}@{}", local, domain.to_lowercase()))
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ProtoBuf)]
+pub struct EmailCellData {
+    #[pb(index = 1)]
+    pub email: String,
+
+    #[pb(index = 2)]
+    pub link: String,
+}
+
+impl EmailCellData {
+    pub fn new(email: &str) -> Self {
+        Self {
+            email: email.to_string(),
+            link: format!("mailto:{}", email),
+        }
+    }
+
+    fn to_json(&self) -> FlowyResult<String> {
+        serde_json::to_string(self).map_err(internal_error)
+    }
+}
+
+impl AsRef<str> for EmailCellData {
+    fn as_ref(&self) -> &str {
+        &self.email
+    }
+}
+
+impl FromCellString for EmailCellData {
+    fn from_cell_str(s: &str) -> FlowyResult<Self> {
+        serde_json::from_str::<EmailCellData>(s).map_err(internal_error)
+    }
+}
+
+impl std::convert::TryFrom<AnyCellData> for EmailCellData {
+    type Error = FlowyError;
+
+    fn try_from(data: AnyCellData) -> Result<Self, Self::Error> {
+        serde_json::from_str::<EmailCellData>(&data.data).map_err(internal_error)
+    }
+}
+
+lazy_static! {
+    static ref EMAIL_REGEX: Regex = Regex::new(
+        "^[a-zA-Z0-9.!#$%&'*+/=?^_`{|}~-]+@[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?(?:\\.[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?)*\\.[a-zA-Z]{2,}$"
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::FieldType;
+    use crate::services::cell::{CellData, CellDataOperation};
+    use crate::services::field::FieldBuilder;
+    use crate::services::field::{EmailCellData, EmailTypeOption};
+    use flowy_grid_data_model::revision::FieldRevision;
+
+    #[test]
+    fn email_type_option_normalize_test() {
+        let type_option = EmailTypeOption::default();
+        let field_type = FieldType::Email;
+        let field_rev = FieldBuilder::from_field_type(&field_type).build();
+        assert_changeset(
+            &type_option,
+            " Nathan@AppFlowy.IO ",
+            &field_rev,
+            "Nathan@appflowy.io",
+            "mailto:Nathan@appflowy.io",
+        );
+
+        assert_changeset(
+            &type_option,
+            "mailto:annie@appflowy.io",
+            &field_rev,
+            "annie@appflowy.io",
+            "mailto:annie@appflowy.io",
+        );
+    }
+
+    #[test]
+    fn email_type_option_empty_test() {
+        let type_option = EmailTypeOption::default();
+        let field_rev = FieldBuilder::from_field_type(&FieldType::Email).build();
+        assert_changeset(&type_option, "  ", &field_rev, "", "");
+    }
+
+    #[test]
+    fn email_type_option_invalid_test() {
+        let type_option = EmailTypeOption::default();
+        for s in ["appflowy", "appflowy@", "@appflowy.io", "nathan@appflowy", "nathan appflowy@io.com"] {
+            assert!(type_option.apply_changeset(s.to_owned().into(), None).is_err());
+        }
+    }
+
+    fn assert_changeset(
+        type_option: &EmailTypeOption,
+        cell_data: &str,
+        field_rev: &FieldRevision,
+        expected_email: &str,
+        expected_link: &str,
+    ) {
+        let encoded_data = type_option.apply_changeset(cell_data.to_owned().into(), None).unwrap();
+        let decode_cell_data = decode_cell_data(encoded_data, type_option, field_rev);
+        assert_eq!(expected_email.to_owned(), decode_cell_data.email);
+        assert_eq!(expected_link.to_owned(), decode_cell_data.link);
+    }
+
+    fn decode_cell_data<T: Into<CellData<EmailCellData>>>(
+        encoded_data: T,
+        type_option: &EmailTypeOption,
+        field_rev: &FieldRevision,
+    ) -> EmailCellData {
+        type_option
+            .decode_cell_data(encoded_data.into(), &FieldType::Email, field_rev)
+            .unwrap()
+            .parse::<EmailCellData>()
+            .unwrap()
+    }
+}
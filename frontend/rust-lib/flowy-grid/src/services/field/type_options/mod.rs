@@ -1,7 +1,9 @@
 mod checkbox_type_option;
 mod date_type_option;
+mod email_type_option;
 mod multi_select_type_option;
 mod number_type_option;
+mod phone_type_option;
 mod single_select_type_option;
 mod text_type_option;
 mod url_type_option;
@@ -9,9 +11,11 @@ mod util;
 
 pub use checkbox_type_option::*;
 pub use date_type_option::*;
+pub use email_type_option::*;
 pub use multi_select_type_option::*;
 pub use multi_select_type_option::*;
 pub use number_type_option::*;
+pub use phone_type_option::*;
 pub use single_select_type_option::*;
 pub use text_type_option::*;
 pub use url_type_option::*;
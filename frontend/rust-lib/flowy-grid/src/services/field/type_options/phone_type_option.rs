@@ -0,0 +1,190 @@
+use crate::entities::FieldType;
+use crate::impl_type_option;
+use crate::services::cell::{
+    AnyCellData, CellData, CellDataChangeset, CellDataOperation, DecodedCellData, FromCellString,
+};
+use crate::services::field::{BoxTypeOptionBuilder, TypeOptionBuilder};
+use bytes::Bytes;
+use fancy_regex::Regex;
+use flowy_derive::ProtoBuf;
+use flowy_error::{internal_error, FlowyError, FlowyResult};
+use flowy_grid_data_model::revision::{CellRevision, FieldRevision, TypeOptionDataDeserializer, TypeOptionDataEntry};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default)]
+pub struct PhoneTypeOptionBuilder(PhoneTypeOption);
+impl_into_box_type_option_builder!(PhoneTypeOptionBuilder);
+impl_builder_from_json_str_and_from_bytes!(PhoneTypeOptionBuilder, PhoneTypeOption);
+
+impl TypeOptionBuilder for PhoneTypeOptionBuilder {
+    fn field_type(&self) -> FieldType {
+        FieldType::Phone
+    }
+
+    fn entry(&self) -> &dyn TypeOptionDataEntry {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ProtoBuf)]
+pub struct PhoneTypeOption {
+    #[pb(index = 1)]
+    data: String, //It's not used yet.
+}
+impl_type_option!(PhoneTypeOption, FieldType::Phone);
+
+impl CellDataOperation<PhoneCellData, String> for PhoneTypeOption {
+    fn decode_cell_data(
+        &self,
+        cell_data: CellData<PhoneCellData>,
+        decoded_field_type: &FieldType,
+        _field_rev: &FieldRevision,
+    ) -> FlowyResult<DecodedCellData> {
+        if !decoded_field_type.is_phone() {
+            return Ok(DecodedCellData::default());
+        }
+        let cell_data: PhoneCellData = cell_data.try_into_inner()?;
+        DecodedCellData::try_from_bytes(cell_data)
+    }
+
+    fn apply_changeset(
+        &self,
+        changeset: CellDataChangeset<String>,
+        _cell_rev: Option<CellRevision>,
+    ) -> Result<String, FlowyError> {
+        let changeset = changeset.try_into_inner()?;
+        let s = changeset.trim();
+        if s.is_empty() {
+            return PhoneCellData::default().to_json();
+        }
+
+        let phone = normalize_phone(s).ok_or_else(|| FlowyError::phone_format().context(s))?;
+        PhoneCellData::new(&phone).to_json()
+    }
+}
+
+/// Remove the separators, e.g. spaces, dashes, dots and parentheses, from the phone number and
+/// keep the leading `+` of the international format. The number should contain 7 to 15 digits,
+/// the maximum length of the E.164 format.
+fn normalize_phone(s: &str) -> Option<String> {
+    let s = s.strip_prefix("tel:").unwrap_or(s);
+    if !PHONE_REGEX.is_match(s).unwrap_or(false) {
+        return None;
+    }
+
+    let digits = s.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
+    if !(7..=15).contains(&digits.len()) {
+        return None;
+    }
+
+    if s.starts_with('+') {
+        Some(format!("+{}", digits))
+    } else {
+        Some(digits)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ProtoBuf)]
+pub struct PhoneCellData {
+    #[pb(index = 1)]
+    pub phone: String,
+
+    #[pb(index = 2)]
+    pub link: String,
+}
+
+impl PhoneCellData {
+    pub fn new(phone: &str) -> Self {
+        Self {
+            phone: phone.to_string(),
+            link: format!("tel:{}", phone),
+        }
+    }
+
+    fn to_json(&self) -> FlowyResult<String> {
+        serde_json::to_string(self).map_err(internal_error)
+    }
+}
+
+impl AsRef<str> for PhoneCellData {
+    fn as_ref(&self) -> &str {
+        &self.phone
+    }
+}
+
+impl FromCellString for PhoneCellData {
+    fn from_cell_str(s: &str) -> FlowyResult<Self> {
+        serde_json::from_str::<PhoneCellData>(s).map_err(internal_error)
+    }
+}
+
+impl std::convert::TryFrom<AnyCellData> for PhoneCellData {
+    type Error = FlowyError;
+
+    fn try_from(data: AnyCellData) -> Result<Self, Self::Error> {
+        serde_json::from_str::<PhoneCellData>(&data.data).map_err(internal_error)
+    }
+}
+
+lazy_static! {
+    static ref PHONE_REGEX: Regex = Regex::new("^\\+?[0-9()\\-. ]+$").unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::FieldType;
+    use crate::services::cell::{CellData, CellDataOperation};
+    use crate::services::field::FieldBuilder;
+    use crate::services::field::{PhoneCellData, PhoneTypeOption};
+    use flowy_grid_data_model::revision::FieldRevision;
+
+    #[test]
+    fn phone_type_option_normalize_test() {
+        let type_option = PhoneTypeOption::default();
+        let field_rev = FieldBuilder::from_field_type(&FieldType::Phone).build();
+        assert_changeset(&type_option, "+1 (555) 010-9999", &field_rev, "+15550109999", "tel:+15550109999");
+        assert_changeset(&type_option, "555.010.9999", &field_rev, "5550109999", "tel:5550109999");
+        assert_changeset(&type_option, "tel:+8613800138000", &field_rev, "+8613800138000", "tel:+8613800138000");
+    }
+
+    #[test]
+    fn phone_type_option_empty_test() {
+        let type_option = PhoneTypeOption::default();
+        let field_rev = FieldBuilder::from_field_type(&FieldType::Phone).build();
+        assert_changeset(&type_option, "", &field_rev, "", "");
+    }
+
+    #[test]
+    fn phone_type_option_invalid_test() {
+        let type_option = PhoneTypeOption::default();
+        for s in ["appflowy", "12345", "1234567890123456", "+1 555 abc 9999", "555+0109999"] {
+            assert!(type_option.apply_changeset(s.to_owned().into(), None).is_err());
+        }
+    }
+
+    fn assert_changeset(
+        type_option: &PhoneTypeOption,
+        cell_data: &str,
+        field_rev: &FieldRevision,
+        expected_phone: &str,
+        expected_link: &str,
+    ) {
+        let encoded_data = type_option.apply_changeset(cell_data.to_owned().into(), None).unwrap();
+        let decode_cell_data = decode_cell_data(encoded_data, type_option, field_rev);
+        assert_eq!(expected_phone.to_owned(), decode_cell_data.phone);
+        assert_eq!(expected_link.to_owned(), decode_cell_data.link);
+    }
+
+    fn decode_cell_data<T: Into<CellData<PhoneCellData>>>(
+        encoded_data: T,
+        type_option: &PhoneTypeOption,
+        field_rev: &FieldRevision,
+    ) -> PhoneCellData {
+        type_option
+            .decode_cell_data(encoded_data.into(), &FieldType::Phone, field_rev)
+            .unwrap()
+            .parse::<PhoneCellData>()
+            .unwrap()
+    }
+}
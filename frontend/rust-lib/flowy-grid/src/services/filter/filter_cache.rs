@@ -60,6 +60,8 @@ impl FilterResult {
 pub(crate) struct FilterCache {
     pub(crate) text_filter: DashMap<FilterId, GridTextFilter>,
     pub(crate) url_filter: DashMap<FilterId, GridTextFilter>,
+    pub(crate) email_filter: DashMap<FilterId, GridTextFilter>,
+    pub(crate) phone_filter: DashMap<FilterId, GridTextFilter>,
     pub(crate) number_filter: DashMap<FilterId, GridNumberFilter>,
     pub(crate) date_filter: DashMap<FilterId, GridDateFilter>,
     pub(crate) select_option_filter: DashMap<FilterId, GridSelectOptionFilter>,
@@ -96,6 +98,12 @@ impl FilterCache {
             FieldType::URL => {
                 let _ = self.url_filter.remove(filter_id);
             }
+            FieldType::Email => {
+                let _ = self.email_filter.remove(filter_id);
+            }
+            FieldType::Phone => {
+                let _ = self.phone_filter.remove(filter_id);
+            }
         };
     }
 }
@@ -140,6 +148,12 @@ pub(crate) async fn refresh_filter_cache(
                     FieldType::URL => {
                         let _ = cache.url_filter.insert(filter_id, GridTextFilter::from(filter_rev));
                     }
+                    FieldType::Email => {
+                        let _ = cache.email_filter.insert(filter_id, GridTextFilter::from(filter_rev));
+                    }
+                    FieldType::Phone => {
+                        let _ = cache.phone_filter.insert(filter_id, GridTextFilter::from(filter_rev));
+                    }
                 }
             }
         }
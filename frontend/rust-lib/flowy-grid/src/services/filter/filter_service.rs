@@ -3,8 +3,8 @@ use crate::entities::{FieldType, GridBlockChangeset};
 use crate::services::block_manager::GridBlockManager;
 use crate::services::cell::{AnyCellData, CellFilterOperation};
 use crate::services::field::{
-    CheckboxTypeOption, DateTypeOption, MultiSelectTypeOption, NumberTypeOption, RichTextTypeOption,
-    SingleSelectTypeOption, URLTypeOption,
+    CheckboxTypeOption, DateTypeOption, EmailTypeOption, MultiSelectTypeOption, NumberTypeOption, PhoneTypeOption,
+    RichTextTypeOption, SingleSelectTypeOption, URLTypeOption,
 };
 use crate::services::filter::filter_cache::{
     refresh_filter_cache, FilterCache, FilterId, FilterResult, FilterResultCache,
@@ -242,6 +242,22 @@ fn filter_cell(
                     .ok(),
             )
         }),
+        FieldType::Email => filter_cache.email_filter.get(&filter_id).and_then(|filter| {
+            Some(
+                field_rev
+                    .get_type_option_entry::<EmailTypeOption>(field_type_rev)?
+                    .apply_filter(any_cell_data, filter.value())
+                    .ok(),
+            )
+        }),
+        FieldType::Phone => filter_cache.phone_filter.get(&filter_id).and_then(|filter| {
+            Some(
+                field_rev
+                    .get_type_option_entry::<PhoneTypeOption>(field_type_rev)?
+                    .apply_filter(any_cell_data, filter.value())
+                    .ok(),
+            )
+        }),
     }?;
 
     let is_visible = !is_visible.unwrap_or(true);
@@ -0,0 +1,11 @@
+use crate::entities::{FieldType, GridTextFilter};
+use crate::services::cell::{AnyCellData, CellFilterOperation};
+use crate::services::field::{PhoneCellData, PhoneTypeOption};
+use crate::services::filter::impls::apply_text_filter;
+use flowy_error::FlowyResult;
+
+impl CellFilterOperation<GridTextFilter> for PhoneTypeOption {
+    fn apply_filter(&self, any_cell_data: AnyCellData, filter: &GridTextFilter) -> FlowyResult<bool> {
+        apply_text_filter::<PhoneCellData>(any_cell_data, &FieldType::Phone, filter)
+    }
+}
@@ -1,7 +1,7 @@
-use crate::entities::{GridTextFilter, TextFilterCondition};
+use crate::entities::{FieldType, GridTextFilter, TextFilterCondition};
 use crate::services::cell::{AnyCellData, CellFilterOperation};
 use crate::services::field::{RichTextTypeOption, TextCellData};
-use flowy_error::FlowyResult;
+use flowy_error::{FlowyError, FlowyResult};
 
 impl GridTextFilter {
     pub fn is_visible<T: AsRef<str>>(&self, cell_data: T) -> bool {
@@ -26,13 +26,26 @@ impl GridTextFilter {
 
 impl CellFilterOperation<GridTextFilter> for RichTextTypeOption {
     fn apply_filter(&self, any_cell_data: AnyCellData, filter: &GridTextFilter) -> FlowyResult<bool> {
-        if !any_cell_data.is_text() {
-            return Ok(true);
-        }
+        apply_text_filter::<TextCellData>(any_cell_data, &FieldType::RichText, filter)
+    }
+}
 
-        let text_cell_data: TextCellData = any_cell_data.try_into()?;
-        Ok(filter.is_visible(text_cell_data))
+/// Shared by the field types whose cell data can be filtered as text, e.g. URL, Email and Phone.
+/// The cell data, `T`, is the text that is compared with the filter content.
+pub(crate) fn apply_text_filter<T>(
+    any_cell_data: AnyCellData,
+    field_type: &FieldType,
+    filter: &GridTextFilter,
+) -> FlowyResult<bool>
+where
+    T: TryFrom<AnyCellData, Error = FlowyError> + AsRef<str>,
+{
+    if &any_cell_data.field_type != field_type {
+        return Ok(true);
     }
+
+    let cell_data = T::try_from(any_cell_data)?;
+    Ok(filter.is_visible(cell_data))
 }
 #[cfg(test)]
 mod tests {
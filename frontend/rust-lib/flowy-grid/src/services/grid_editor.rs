@@ -1,23 +1,26 @@
 use crate::dart_notification::{send_dart_notification, GridNotification};
 use crate::entities::CellIdentifier;
 use crate::entities::*;
-use crate::manager::{GridTaskSchedulerRwLock, GridUser};
+use crate::manager::{GridDocumentService, GridTaskSchedulerRwLock, GridUser};
 use crate::services::block_manager::GridBlockManager;
 use crate::services::cell::{apply_cell_data_changeset, decode_any_cell_data};
 use crate::services::field::{default_type_option_builder_from_type, type_option_builder_from_bytes, FieldBuilder};
 use crate::services::filter::{GridFilterChangeset, GridFilterService};
 use crate::services::persistence::block_index::BlockIndexCache;
+use crate::services::persistence::migration::run_v2_migration;
+use crate::services::persistence::row_activity::RowActivityPersistence;
 use crate::services::row::{
     make_grid_blocks, make_row_from_row_rev, make_row_rev_from_context, make_rows_from_row_revs,
-    CreateRowRevisionBuilder, CreateRowRevisionPayload, GridBlockSnapshot,
+    CellPlaceholderContext, CreateRowRevisionBuilder, CreateRowRevisionPayload, GridBlockSnapshot,
 };
-use crate::services::setting::make_grid_setting;
+use crate::services::setting::{make_field_settings, make_grid_setting};
 use bytes::Bytes;
 use flowy_error::{ErrorCode, FlowyError, FlowyResult};
 use flowy_grid_data_model::revision::*;
 use flowy_revision::{RevisionCloudService, RevisionCompactor, RevisionManager, RevisionObjectBuilder};
+use flowy_sync::client_document::default::initial_quill_delta_string;
 use flowy_sync::client_grid::{GridChangeset, GridRevisionPad, JsonDeserializer};
-use flowy_sync::entities::grid::{FieldChangesetParams, GridSettingChangesetParams};
+use flowy_sync::entities::grid::{FieldChangesetParams, FieldSettingChangesetParams, GridSettingChangesetParams};
 use flowy_sync::entities::revision::Revision;
 use flowy_sync::errors::CollaborateResult;
 use flowy_sync::util::make_delta_from_revisions;
@@ -32,6 +35,7 @@ pub struct GridRevisionEditor {
     user: Arc<dyn GridUser>,
     grid_pad: Arc<RwLock<GridRevisionPad>>,
     rev_manager: Arc<RevisionManager>,
+    document_service: Arc<dyn GridDocumentService>,
     block_manager: Arc<GridBlockManager>,
     #[allow(dead_code)]
     pub(crate) filter_service: Arc<GridFilterService>,
@@ -48,16 +52,25 @@ impl GridRevisionEditor {
         grid_id: &str,
         user: Arc<dyn GridUser>,
         mut rev_manager: RevisionManager,
+        document_service: Arc<dyn GridDocumentService>,
         persistence: Arc<BlockIndexCache>,
+        activity_persistence: Arc<RowActivityPersistence>,
         task_scheduler: GridTaskSchedulerRwLock,
     ) -> FlowyResult<Arc<Self>> {
         let token = user.token()?;
         let cloud = Arc::new(GridRevisionCloudService { token });
-        let grid_pad = rev_manager.load::<GridPadBuilder>(Some(cloud)).await?;
+        let _ = rev_manager
+            .run_format_migration(Box::new(GridRevisionCompactor()))
+            .await?;
+        let mut grid_pad = rev_manager.load::<GridPadBuilder>(Some(cloud)).await?;
+        if let Some(delta) = run_v2_migration(&user.user_id()?, &rev_manager, || grid_pad.snapshot_delta()).await? {
+            grid_pad = GridRevisionPad::from_delta(delta)?;
+        }
         let rev_manager = Arc::new(rev_manager);
         let grid_pad = Arc::new(RwLock::new(grid_pad));
         let block_meta_revs = grid_pad.read().await.get_block_meta_revs();
-        let block_manager = Arc::new(GridBlockManager::new(grid_id, &user, block_meta_revs, persistence).await?);
+        let block_manager =
+            Arc::new(GridBlockManager::new(grid_id, &user, block_meta_revs, persistence, activity_persistence).await?);
         let filter_service =
             Arc::new(GridFilterService::new(grid_pad.clone(), block_manager.clone(), task_scheduler.clone()).await);
         let editor = Arc::new(Self {
@@ -65,6 +78,7 @@ impl GridRevisionEditor {
             user,
             grid_pad,
             rev_manager,
+            document_service,
             block_manager,
             filter_service,
         });
@@ -94,6 +108,7 @@ impl GridRevisionEditor {
                         visibility: Some(field.visibility),
                         width: Some(field.width),
                         type_option_data: Some(type_option_data),
+                        default_value: Some(field.default_value.unwrap_or_default()),
                     };
                     Ok(grid.update_field_rev(changeset, deserializer)?)
                 })
@@ -269,13 +284,39 @@ impl GridRevisionEditor {
         Ok(())
     }
 
-    pub async fn create_row(&self, start_row_id: Option<String>) -> FlowyResult<RowInfo> {
+    pub async fn create_row(&self, params: CreateRowParams) -> FlowyResult<RowInfo> {
+        let CreateRowParams {
+            start_row_id,
+            template_id,
+            ..
+        } = params;
         let field_revs = self.grid_pad.read().await.get_field_revs(None)?;
+        let template_rev = match template_id {
+            None => None,
+            Some(template_id) => match self.grid_pad.read().await.get_row_template_rev(&template_id) {
+                None => {
+                    let msg = format!("Row template not found with id: {}", template_id);
+                    return Err(FlowyError::record_not_found().context(msg));
+                }
+                Some(template_rev) => Some(template_rev),
+            },
+        };
         let block_id = self.block_id().await?;
 
-        // insert empty row below the row whose id is upper_row_id
-        let row_rev_ctx = CreateRowRevisionBuilder::new(&field_revs).build();
-        let row_rev = make_row_rev_from_context(&block_id, row_rev_ctx);
+        // insert the row below the row whose id is start_row_id. The cells of the row are filled
+        // with the default values of the fields and the contents of the template.
+        let context = CellPlaceholderContext::new(&self.user.user_id()?);
+        let mut builder = CreateRowRevisionBuilder::new(&field_revs);
+        builder.add_default_cells(&context);
+        if let Some(template_rev) = template_rev.as_ref() {
+            builder.add_template_cells(template_rev, &context);
+        }
+        let mut row_rev = make_row_rev_from_context(&block_id, builder.build());
+        if let Some(delta_str) = template_rev.and_then(|template_rev| template_rev.document.clone()) {
+            let document_id = gen_row_document_id();
+            let _ = self.document_service.create_document(&document_id, delta_str).await?;
+            row_rev.document_id = Some(document_id);
+        }
         let row_order = RowInfo::from(&row_rev);
 
         // insert the row
@@ -343,11 +384,94 @@ impl GridRevisionEditor {
         }
     }
     pub async fn delete_row(&self, row_id: &str) -> FlowyResult<()> {
+        let row_rev = self.block_manager.get_row_rev(row_id).await?;
         let _ = self.block_manager.delete_row(row_id).await?;
+        if let Some(row_rev) = row_rev {
+            let _ = self.delete_row_documents(vec![row_rev]).await?;
+        }
         Ok(())
     }
 
-    pub async fn duplicate_row(&self, _row_id: &str) -> FlowyResult<()> {
+    pub async fn duplicate_row(&self, row_id: &str) -> FlowyResult<()> {
+        let row_rev = match self.block_manager.get_row_rev(row_id).await? {
+            None => return Ok(()),
+            Some(row_rev) => row_rev,
+        };
+
+        let mut duplicated_row_rev = row_rev.as_ref().clone();
+        duplicated_row_rev.id = gen_row_id();
+        duplicated_row_rev.document_id = match row_rev.document_id.as_ref() {
+            None => None,
+            Some(document_id) => Some(self.duplicate_row_document(document_id).await?),
+        };
+
+        // insert the duplicated row below the original row
+        let block_id = duplicated_row_rev.block_id.clone();
+        let row_count = self
+            .block_manager
+            .create_row(&block_id, duplicated_row_rev, Some(row_id.to_owned()))
+            .await?;
+
+        let changeset = GridBlockMetaRevisionChangeset::from_row_count(&block_id, row_count);
+        let _ = self.update_block(changeset).await?;
+        Ok(())
+    }
+
+    /// Returns the document of the row's detail page. The document is created when it's
+    /// requested for the first time.
+    pub async fn get_row_document(&self, row_id: &str) -> FlowyResult<RowDocument> {
+        let row_rev = match self.block_manager.get_row_rev(row_id).await? {
+            None => {
+                let msg = format!("Row not found with id: {}", row_id);
+                return Err(FlowyError::record_not_found().context(msg));
+            }
+            Some(row_rev) => row_rev,
+        };
+
+        if let Some(document_id) = row_rev.document_id.as_ref() {
+            return Ok(RowDocument {
+                row_id: row_id.to_owned(),
+                document_id: document_id.clone(),
+            });
+        }
+
+        let document_id = gen_row_document_id();
+        let _ = self
+            .document_service
+            .create_document(&document_id, initial_quill_delta_string())
+            .await?;
+
+        let changeset = RowMetaChangeset {
+            row_id: row_id.to_owned(),
+            height: None,
+            visibility: None,
+            document_id: Some(document_id.clone()),
+            cell_by_field_id: Default::default(),
+        };
+        let _ = self.update_row(changeset).await?;
+        Ok(RowDocument {
+            row_id: row_id.to_owned(),
+            document_id,
+        })
+    }
+
+    /// Copy the content of the document into a new document and return the id of the new document.
+    async fn duplicate_row_document(&self, document_id: &str) -> FlowyResult<String> {
+        let delta_str = self.document_service.read_document(document_id).await?;
+        let duplicated_document_id = gen_row_document_id();
+        let _ = self
+            .document_service
+            .create_document(&duplicated_document_id, delta_str)
+            .await?;
+        Ok(duplicated_document_id)
+    }
+
+    async fn delete_row_documents(&self, row_revs: Vec<Arc<RowRevision>>) -> FlowyResult<()> {
+        for row_rev in row_revs {
+            if let Some(document_id) = row_rev.document_id.as_ref() {
+                let _ = self.document_service.delete_document(document_id).await?;
+            }
+        }
         Ok(())
     }
 
@@ -404,13 +528,31 @@ impl GridRevisionEditor {
                 };
                 let _ = self
                     .block_manager
-                    .update_cell(cell_changeset, |row_rev| make_row_from_row_rev(&field_revs, row_rev))
+                    .update_cell(cell_changeset, field_rev, |row_rev| {
+                        make_row_from_row_rev(&field_revs, row_rev)
+                    })
                     .await?;
                 Ok(())
             }
         }
     }
 
+    pub async fn get_row_activity(&self, row_id: &str) -> FlowyResult<RowActivity> {
+        self.block_manager.get_row_activity(row_id)
+    }
+
+    pub async fn create_row_comment(&self, params: CreateRowCommentParams) -> FlowyResult<RowComment> {
+        self.block_manager.create_row_comment(params)
+    }
+
+    pub async fn update_row_comment(&self, params: UpdateRowCommentParams) -> FlowyResult<()> {
+        self.block_manager.update_row_comment(params)
+    }
+
+    pub async fn delete_row_comment(&self, comment_id: &str) -> FlowyResult<()> {
+        self.block_manager.delete_row_comment(comment_id)
+    }
+
     pub async fn get_blocks(&self, block_ids: Option<Vec<String>>) -> FlowyResult<RepeatedGridBlock> {
         let block_snapshots = self.grid_block_snapshots(block_ids.clone()).await?;
         make_grid_blocks(block_ids, block_snapshots)
@@ -422,10 +564,18 @@ impl GridRevisionEditor {
     }
 
     pub async fn delete_rows(&self, row_orders: Vec<RowInfo>) -> FlowyResult<()> {
+        let mut row_revs = vec![];
+        for row_order in &row_orders {
+            if let Some(row_rev) = self.block_manager.get_row_rev(row_order.row_id()).await? {
+                row_revs.push(row_rev);
+            }
+        }
+
         let changesets = self.block_manager.delete_rows(row_orders).await?;
         for changeset in changesets {
             let _ = self.update_block(changeset).await?;
         }
+        let _ = self.delete_row_documents(row_revs).await?;
         Ok(())
     }
 
@@ -461,6 +611,99 @@ impl GridRevisionEditor {
         Ok(grid_setting)
     }
 
+    pub async fn get_field_settings(&self, layout_type: &GridLayoutType) -> FlowyResult<LayoutFieldSettings> {
+        let read_guard = self.grid_pad.read().await;
+        let layout_rev = layout_type.clone().into();
+        let field_revs = read_guard.get_field_revs(None)?;
+        let items = make_field_settings(read_guard.get_grid_setting_rev(), &layout_rev, &field_revs);
+        Ok(LayoutFieldSettings {
+            layout_type: layout_type.clone(),
+            items,
+        })
+    }
+
+    pub async fn update_field_setting(&self, params: FieldSettingChangesetParams) -> FlowyResult<()> {
+        let layout_type: GridLayoutType = params.layout_type.clone().into();
+        let _ = self
+            .modify(|grid_pad| Ok(grid_pad.update_field_setting(params)?))
+            .await?;
+        let _ = self.notify_did_update_field_settings(&layout_type).await?;
+        Ok(())
+    }
+
+    pub async fn move_layout_field(&self, params: MoveLayoutFieldParams) -> FlowyResult<()> {
+        let layout_rev: GridLayoutRevision = params.layout_type.clone().into();
+        let _ = self
+            .modify(|grid_pad| {
+                Ok(grid_pad.move_layout_field(
+                    &layout_rev,
+                    &params.field_id,
+                    params.from_index as usize,
+                    params.to_index as usize,
+                )?)
+            })
+            .await?;
+        let _ = self.notify_did_update_field_settings(&params.layout_type).await?;
+        Ok(())
+    }
+
+    /// Copy the visibility, width and order of the fields into the settings of the layouts. It's
+    /// used to migrate the grids that were created before the fields could be configured per layout.
+    pub(crate) async fn init_layout_field_settings(&self) -> FlowyResult<()> {
+        let _ = self
+            .modify(|grid_pad| Ok(grid_pad.init_layout_field_settings()?))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_row_templates(&self) -> FlowyResult<RepeatedRowTemplate> {
+        let template_revs = self.grid_pad.read().await.get_row_template_revs();
+        Ok(RepeatedRowTemplate::from(template_revs))
+    }
+
+    pub async fn create_row_template(&self, params: CreateRowTemplateParams) -> FlowyResult<RowTemplate> {
+        let mut template_rev = RowTemplateRevision::new(&params.name);
+        template_rev.cells = params
+            .cells
+            .into_iter()
+            .map(|cell| (cell.field_id, cell.content))
+            .collect();
+        template_rev.document = params.document;
+
+        let template = RowTemplate::from(&template_rev);
+        let _ = self
+            .modify(|grid_pad| Ok(grid_pad.create_row_template(template_rev)?))
+            .await?;
+        let _ = self.notify_did_update_row_templates().await?;
+        Ok(template)
+    }
+
+    pub async fn update_row_template(&self, params: UpdateRowTemplateParams) -> FlowyResult<()> {
+        let template_rev = RowTemplateRevision {
+            id: params.template_id,
+            name: params.name,
+            cells: params
+                .cells
+                .into_iter()
+                .map(|cell| (cell.field_id, cell.content))
+                .collect(),
+            document: params.document,
+        };
+        let _ = self
+            .modify(|grid_pad| Ok(grid_pad.replace_row_template(template_rev)?))
+            .await?;
+        let _ = self.notify_did_update_row_templates().await?;
+        Ok(())
+    }
+
+    pub async fn delete_row_template(&self, template_id: &str) -> FlowyResult<()> {
+        let _ = self
+            .modify(|grid_pad| Ok(grid_pad.delete_row_template(template_id)?))
+            .await?;
+        let _ = self.notify_did_update_row_templates().await?;
+        Ok(())
+    }
+
     pub async fn get_grid_filter(&self, layout_type: &GridLayoutType) -> FlowyResult<Vec<GridFilter>> {
         let read_guard = self.grid_pad.read().await;
         let layout_rev = layout_type.clone().into();
@@ -544,6 +787,11 @@ impl GridRevisionEditor {
         let grid_pad = self.grid_pad.read().await;
         let original_blocks = grid_pad.get_block_meta_revs();
         let (duplicated_fields, duplicated_blocks) = grid_pad.duplicate_grid_block_meta().await;
+        let row_templates = grid_pad
+            .get_row_template_revs()
+            .iter()
+            .map(|template_rev| template_rev.as_ref().clone())
+            .collect();
 
         let mut blocks_meta_data = vec![];
         if original_blocks.len() == duplicated_blocks.len() {
@@ -560,10 +808,26 @@ impl GridRevisionEditor {
         }
         drop(grid_pad);
 
+        // The duplicated rows refer to the documents of the original rows, so copy the documents
+        // and let the rows refer to the copies.
+        let mut row_documents = HashMap::new();
+        for block_meta_data in blocks_meta_data.iter_mut() {
+            for row_rev in block_meta_data.rows.iter_mut() {
+                if let Some(document_id) = row_rev.document_id.clone() {
+                    let delta_str = self.document_service.read_document(&document_id).await?;
+                    let duplicated_document_id = gen_row_document_id();
+                    Arc::make_mut(row_rev).document_id = Some(duplicated_document_id.clone());
+                    row_documents.insert(duplicated_document_id, delta_str);
+                }
+            }
+        }
+
         Ok(BuildGridContext {
             field_revs: duplicated_fields,
             blocks: duplicated_blocks,
             blocks_meta_data,
+            row_documents,
+            row_templates,
         })
     }
 
@@ -582,7 +846,7 @@ impl GridRevisionEditor {
         let GridChangeset { delta, md5 } = change;
         let user_id = self.user.user_id()?;
         let (base_rev_id, rev_id) = self.rev_manager.next_rev_id_pair();
-        let delta_data = delta.to_delta_bytes();
+        let delta_data = delta.to_compact_bytes();
         let revision = Revision::new(
             &self.rev_manager.object_id,
             base_rev_id,
@@ -605,6 +869,22 @@ impl GridRevisionEditor {
         }
     }
 
+    async fn notify_did_update_row_templates(&self) -> FlowyResult<()> {
+        let row_templates = self.get_row_templates().await?;
+        send_dart_notification(&self.grid_id, GridNotification::DidUpdateRowTemplates)
+            .payload(row_templates)
+            .send();
+        Ok(())
+    }
+
+    async fn notify_did_update_field_settings(&self, layout_type: &GridLayoutType) -> FlowyResult<()> {
+        let field_settings = self.get_field_settings(layout_type).await?;
+        send_dart_notification(&self.grid_id, GridNotification::DidUpdateFieldSettings)
+            .payload(field_settings)
+            .send();
+        Ok(())
+    }
+
     #[tracing::instrument(level = "trace", skip_all, err)]
     async fn notify_did_insert_grid_field(&self, field_id: &str) -> FlowyResult<()> {
         if let Some((index, field_rev)) = self.grid_pad.read().await.get_field_rev(field_id) {
@@ -677,7 +957,7 @@ struct GridRevisionCompactor();
 impl RevisionCompactor for GridRevisionCompactor {
     fn bytes_from_revisions(&self, revisions: Vec<Revision>) -> FlowyResult<Bytes> {
         let delta = make_delta_from_revisions::<PlainTextAttributes>(revisions)?;
-        Ok(delta.to_delta_bytes())
+        Ok(delta.to_compact_bytes())
     }
 }
 
@@ -0,0 +1,70 @@
+use crate::services::grid_editor::GridRevisionEditor;
+use flowy_database::kv::KV;
+use flowy_error::FlowyResult;
+use flowy_revision::RevisionManager;
+use flowy_sync::entities::revision::{md5, Revision};
+use flowy_sync::errors::CollaborateResult;
+use lib_ot::core::PlainTextDelta;
+use std::sync::Arc;
+
+const V1_MIGRATION: &str = "GRID_V1_MIGRATION";
+const V2_MIGRATION: &str = "GRID_V2_MIGRATION";
+
+/// The visibility, width and order of the fields were shared by all the layouts. Copy them into
+/// the settings of each layout, so they can be changed per layout. It runs once for each grid
+/// after the editor of the grid is created.
+pub(crate) async fn run_v1_migration(grid_id: &str, editor: &Arc<GridRevisionEditor>) -> FlowyResult<()> {
+    let key = migration_key(grid_id, V1_MIGRATION);
+    if KV::get_bool(&key) {
+        return Ok(());
+    }
+
+    let _ = editor.init_layout_field_settings().await?;
+    KV::set_bool(&key, true);
+    tracing::trace!("Run grid v1 migration of {}", grid_id);
+    Ok(())
+}
+
+/// The grids that are created with the settings per layout don't need the v1 migration. Their
+/// layouts use the visibility and width of the fields until they are changed in the layout.
+pub(crate) fn skip_v1_migration(grid_id: &str) {
+    KV::set_bool(&migration_key(grid_id, V1_MIGRATION), true);
+}
+
+/// Rewrites the revisions of the grid or the block into one revision that contains the snapshot
+/// of the object. The revisions that were saved before the
+/// [GridOperation](flowy_sync::client_grid::GridOperation) log edit the JSON of the whole object,
+/// so the log would start with the legacy JSON forever. It runs once for each object after the
+/// object is loaded, and returns the delta of the rewritten revision.
+pub(crate) async fn run_v2_migration<F>(
+    user_id: &str,
+    rev_manager: &RevisionManager,
+    snapshot_delta: F,
+) -> FlowyResult<Option<PlainTextDelta>>
+where
+    F: FnOnce() -> CollaborateResult<PlainTextDelta>,
+{
+    let key = migration_key(&rev_manager.object_id, V2_MIGRATION);
+    if KV::get_bool(&key) {
+        return Ok(None);
+    }
+
+    let delta = snapshot_delta()?;
+    let md5 = md5(&delta.to_delta_bytes());
+    let revision = Revision::new(
+        &rev_manager.object_id,
+        0,
+        rev_manager.rev_id(),
+        delta.to_compact_bytes(),
+        user_id,
+        md5,
+    );
+    let _ = rev_manager.reset_object(revision.into()).await?;
+    KV::set_bool(&key, true);
+    tracing::trace!("Run grid v2 migration of {}", rev_manager.object_id);
+    Ok(Some(delta))
+}
+
+fn migration_key(object_id: &str, migration: &str) -> String {
+    md5(format!("{}{}", object_id, migration))
+}
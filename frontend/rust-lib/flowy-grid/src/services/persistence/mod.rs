@@ -4,6 +4,8 @@ use std::sync::Arc;
 
 pub mod block_index;
 pub mod kv;
+pub mod migration;
+pub mod row_activity;
 
 pub trait GridDatabase: Send + Sync {
     fn db_pool(&self) -> Result<Arc<ConnectionPool>, FlowyError>;
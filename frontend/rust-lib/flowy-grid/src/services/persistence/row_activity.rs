@@ -0,0 +1,123 @@
+use crate::services::persistence::GridDatabase;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use flowy_database::{
+    prelude::*,
+    schema::{grid_row_change_table, grid_row_comment_table},
+};
+use flowy_error::FlowyResult;
+use std::sync::Arc;
+
+/// Stores the comments and the cell changes of the rows. They are local data that is not
+/// synchronized through the grid revisions.
+pub struct RowActivityPersistence {
+    database: Arc<dyn GridDatabase>,
+}
+
+impl RowActivityPersistence {
+    pub fn new(database: Arc<dyn GridDatabase>) -> Self {
+        Self { database }
+    }
+
+    pub fn get_comments(&self, row_id: &str) -> FlowyResult<Vec<RowCommentTable>> {
+        let conn = self.database.db_connection()?;
+        let comments = grid_row_comment_table::dsl::grid_row_comment_table
+            .filter(grid_row_comment_table::row_id.eq(row_id))
+            .order(grid_row_comment_table::create_time.asc())
+            .load::<RowCommentTable>(&*conn)?;
+        Ok(comments)
+    }
+
+    pub fn get_comment(&self, comment_id: &str) -> FlowyResult<RowCommentTable> {
+        let conn = self.database.db_connection()?;
+        let comment = grid_row_comment_table::dsl::grid_row_comment_table
+            .filter(grid_row_comment_table::id.eq(comment_id))
+            .first::<RowCommentTable>(&*conn)?;
+        Ok(comment)
+    }
+
+    pub fn insert_comment(&self, comment: RowCommentTable) -> FlowyResult<()> {
+        let conn = self.database.db_connection()?;
+        let _ = diesel::insert_into(grid_row_comment_table::table)
+            .values(comment)
+            .execute(&*conn)?;
+        Ok(())
+    }
+
+    pub fn update_comment(&self, comment_id: &str, content: &str, modified_time: i64) -> FlowyResult<()> {
+        let conn = self.database.db_connection()?;
+        let filter =
+            grid_row_comment_table::dsl::grid_row_comment_table.filter(grid_row_comment_table::id.eq(comment_id));
+        let _ = diesel::update(filter)
+            .set((
+                grid_row_comment_table::content.eq(content),
+                grid_row_comment_table::modified_time.eq(modified_time),
+            ))
+            .execute(&*conn)?;
+        Ok(())
+    }
+
+    pub fn delete_comment(&self, comment_id: &str) -> FlowyResult<()> {
+        let conn = self.database.db_connection()?;
+        let filter =
+            grid_row_comment_table::dsl::grid_row_comment_table.filter(grid_row_comment_table::id.eq(comment_id));
+        let _ = diesel::delete(filter).execute(&*conn)?;
+        Ok(())
+    }
+
+    pub fn get_changes(&self, row_id: &str) -> FlowyResult<Vec<RowChangeTable>> {
+        let conn = self.database.db_connection()?;
+        let changes = grid_row_change_table::dsl::grid_row_change_table
+            .filter(grid_row_change_table::row_id.eq(row_id))
+            .order(grid_row_change_table::id.asc())
+            .load::<RowChangeTable>(&*conn)?;
+        Ok(changes)
+    }
+
+    pub fn insert_change(&self, change: NewRowChange) -> FlowyResult<()> {
+        let conn = self.database.db_connection()?;
+        let _ = diesel::insert_into(grid_row_change_table::table)
+            .values(change)
+            .execute(&*conn)?;
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Queryable, Identifiable, Insertable, Associations)]
+#[table_name = "grid_row_comment_table"]
+pub struct RowCommentTable {
+    pub id: String,
+    pub grid_id: String,
+    pub row_id: String,
+    pub author: String,
+    pub content: String,
+    pub create_time: i64,
+    pub modified_time: i64,
+}
+
+#[derive(PartialEq, Clone, Debug, Queryable, Identifiable)]
+#[table_name = "grid_row_change_table"]
+pub struct RowChangeTable {
+    pub id: i32,
+    pub grid_id: String,
+    pub row_id: String,
+    pub field_id: String,
+    pub field_name: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub author: String,
+    pub create_time: i64,
+}
+
+/// The id of the change is generated by the database.
+#[derive(Clone, Debug, Insertable)]
+#[table_name = "grid_row_change_table"]
+pub struct NewRowChange {
+    pub grid_id: String,
+    pub row_id: String,
+    pub field_id: String,
+    pub field_name: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub author: String,
+    pub create_time: i64,
+}
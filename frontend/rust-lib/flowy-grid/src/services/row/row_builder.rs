@@ -1,12 +1,18 @@
+use crate::entities::FieldType;
 use crate::services::cell::apply_cell_data_changeset;
 use crate::services::field::select_option::SelectOptionCellChangeset;
-use flowy_error::{FlowyError, FlowyResult};
-use flowy_grid_data_model::revision::{gen_row_id, CellRevision, FieldRevision, RowRevision, DEFAULT_ROW_HEIGHT};
+use crate::services::field::DateCellChangeset;
+use flowy_error::{internal_error, FlowyError, FlowyResult};
+use flowy_grid_data_model::revision::{
+    gen_row_id, CellRevision, FieldRevision, RowRevision, RowTemplateRevision, CELL_PLACEHOLDER_CURRENT_USER,
+    CELL_PLACEHOLDER_TODAY, DEFAULT_ROW_HEIGHT,
+};
 use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct CreateRowRevisionBuilder<'a> {
+    field_revs: &'a [Arc<FieldRevision>],
     field_rev_map: HashMap<&'a String, &'a Arc<FieldRevision>>,
     payload: CreateRowRevisionPayload,
 }
@@ -25,7 +31,11 @@ impl<'a> CreateRowRevisionBuilder<'a> {
             visibility: true,
         };
 
-        Self { field_rev_map, payload }
+        Self {
+            field_revs: fields,
+            field_rev_map,
+            payload,
+        }
     }
 
     pub fn add_cell(&mut self, field_id: &str, data: String) -> FlowyResult<()> {
@@ -59,6 +69,64 @@ impl<'a> CreateRowRevisionBuilder<'a> {
         }
     }
 
+    /// Fill in the cells with the default values of the fields.
+    pub fn add_default_cells(&mut self, context: &CellPlaceholderContext) {
+        for field_rev in self.field_revs {
+            if let Some(default_value) = field_rev.default_value.as_ref() {
+                if let Err(err) = self.add_cell_content(field_rev, default_value, context) {
+                    tracing::warn!(
+                        "Apply the default value of the field:{} failed: {:?}",
+                        field_rev.id,
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    /// Fill in the cells with the contents of the template. The contents override the default
+    /// values of the fields, and the cells of the deleted fields are ignored.
+    pub fn add_template_cells(&mut self, template_rev: &RowTemplateRevision, context: &CellPlaceholderContext) {
+        for (field_id, content) in template_rev.cells.iter() {
+            match self.field_rev_map.get(field_id) {
+                None => tracing::warn!("Can't find the field with id: {}", field_id),
+                Some(&field_rev) => {
+                    if let Err(err) = self.add_cell_content(field_rev, content, context) {
+                        tracing::warn!("Apply the template cell of the field:{} failed: {:?}", field_id, err);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The content is the text for the text-like fields, the option ids for the select option
+    /// fields and the timestamp for the date field.
+    fn add_cell_content(
+        &mut self,
+        field_rev: &FieldRevision,
+        content: &str,
+        context: &CellPlaceholderContext,
+    ) -> FlowyResult<()> {
+        let field_type: FieldType = field_rev.field_type_rev.into();
+        let content = context.replace_placeholder(content, &field_type);
+        let changeset = match field_type {
+            FieldType::SingleSelect | FieldType::MultiSelect => {
+                SelectOptionCellChangeset::from_insert(&content).to_str()
+            }
+            FieldType::DateTime => serde_json::to_string(&DateCellChangeset {
+                date: Some(content),
+                time: None,
+            })
+            .map_err(internal_error)?,
+            _ => content,
+        };
+        let data = apply_cell_data_changeset(changeset, None, field_rev)?;
+        self.payload
+            .cell_by_field_id
+            .insert(field_rev.id.clone(), CellRevision::new(data));
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn height(mut self, height: i32) -> Self {
         self.payload.height = height;
@@ -76,6 +144,34 @@ impl<'a> CreateRowRevisionBuilder<'a> {
     }
 }
 
+/// Provides the values of the cell placeholders when creating a row.
+pub struct CellPlaceholderContext {
+    pub user_id: String,
+    pub timestamp: i64,
+}
+
+impl CellPlaceholderContext {
+    pub fn new(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_owned(),
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    fn replace_placeholder(&self, content: &str, field_type: &FieldType) -> String {
+        match content {
+            CELL_PLACEHOLDER_TODAY => match field_type {
+                FieldType::DateTime => self.timestamp.to_string(),
+                _ => chrono::NaiveDateTime::from_timestamp(self.timestamp, 0)
+                    .format("%Y/%m/%d")
+                    .to_string(),
+            },
+            CELL_PLACEHOLDER_CURRENT_USER => self.user_id.clone(),
+            _ => content.to_owned(),
+        }
+    }
+}
+
 pub fn make_row_rev_from_context(block_id: &str, payload: CreateRowRevisionPayload) -> RowRevision {
     RowRevision {
         id: payload.row_id,
@@ -83,6 +179,7 @@ pub fn make_row_rev_from_context(block_id: &str, payload: CreateRowRevisionPaylo
         cells: payload.cell_by_field_id,
         height: payload.height,
         visibility: payload.visibility,
+        document_id: None,
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::entities::{
-    GridLayout, GridLayoutType, GridSetting, RepeatedGridFilter, RepeatedGridGroup, RepeatedGridSort,
+    FieldSetting, GridLayout, GridLayoutType, GridSetting, RepeatedGridFilter, RepeatedGridGroup, RepeatedGridSort,
 };
-use flowy_grid_data_model::revision::{FieldRevision, GridSettingRevision};
+use flowy_grid_data_model::revision::{FieldRevision, GridLayoutRevision, GridSettingRevision};
 use flowy_sync::entities::grid::{CreateGridFilterParams, DeleteFilterParams, GridSettingChangesetParams};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -70,11 +70,39 @@ pub fn make_grid_setting(grid_setting_rev: &GridSettingRevision, field_revs: &[A
         })
         .unwrap_or_default();
 
+    let field_settings = make_field_settings(grid_setting_rev, &grid_setting_rev.layout, field_revs);
+
     GridSetting {
         layouts: GridLayout::all(),
         current_layout_type,
         filters_by_field_id,
         groups_by_field_id,
         sorts_by_field_id,
+        field_settings,
     }
 }
+
+/// Return the settings of the fields in the order of the layout. The `visibility` and `width` of
+/// the [FieldRevision] are used if the layout doesn't set them.
+pub fn make_field_settings(
+    grid_setting_rev: &GridSettingRevision,
+    layout: &GridLayoutRevision,
+    field_revs: &[Arc<FieldRevision>],
+) -> Vec<FieldSetting> {
+    grid_setting_rev
+        .get_ordered_field_revs(layout, field_revs)
+        .iter()
+        .map(|field_rev| {
+            let field_setting = grid_setting_rev.get_field_setting(layout, &field_rev.id);
+            FieldSetting {
+                field_id: field_rev.id.clone(),
+                visibility: field_setting
+                    .and_then(|setting| setting.visibility)
+                    .unwrap_or(field_rev.visibility),
+                width: field_setting
+                    .and_then(|setting| setting.width)
+                    .unwrap_or(field_rev.width),
+            }
+        })
+        .collect()
+}
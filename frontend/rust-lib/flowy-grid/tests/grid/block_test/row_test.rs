@@ -2,13 +2,13 @@ use crate::grid::block_test::script::GridRowTest;
 use crate::grid::block_test::script::RowScript::*;
 use crate::grid::block_test::util::GridRowTestBuilder;
 use chrono::NaiveDateTime;
-use flowy_grid::entities::FieldType;
+use flowy_grid::entities::{FieldType, RowTemplateCell};
 use flowy_grid::services::cell::decode_any_cell_data;
-use flowy_grid::services::field::select_option::SELECTION_IDS_SEPARATOR;
+use flowy_grid::services::field::select_option::{SelectOptionCellData, SELECTION_IDS_SEPARATOR};
 use flowy_grid::services::field::{DateCellData, MultiSelectTypeOption, SingleSelectTypeOption};
 
 use crate::grid::field_test::util::make_date_cell_string;
-use flowy_grid_data_model::revision::RowMetaChangeset;
+use flowy_grid_data_model::revision::{RowMetaChangeset, CELL_PLACEHOLDER_CURRENT_USER, CELL_PLACEHOLDER_TODAY};
 
 #[tokio::test]
 async fn grid_create_row_count_test() {
@@ -33,6 +33,7 @@ async fn grid_update_row() {
         row_id: payload.row_id.clone(),
         height: None,
         visibility: None,
+        document_id: None,
         cell_by_field_id: Default::default(),
     };
 
@@ -109,6 +110,12 @@ async fn grid_row_add_cells_test() {
             FieldType::URL => {
                 builder.add_cell(&field.id, "1".to_string()).unwrap();
             }
+            FieldType::Email => {
+                builder.add_cell(&field.id, "nathan@appflowy.io".to_string()).unwrap();
+            }
+            FieldType::Phone => {
+                builder.add_cell(&field.id, "+1 555 010 9999".to_string()).unwrap();
+            }
         }
     }
     let context = builder.build();
@@ -148,3 +155,147 @@ async fn grid_row_add_date_cell_test() {
     let scripts = vec![CreateRow { payload: context }];
     test.run_scripts(scripts).await;
 }
+
+#[tokio::test]
+async fn grid_row_document_test() {
+    let mut test = GridRowTest::new().await;
+    let row_id = test.row_revs[0].id.clone();
+    let scripts = vec![
+        AssertRowDocument {
+            row_index: 0,
+            expected_delta_str: None,
+        },
+        OpenRowDocument { row_id: row_id.clone() },
+        AssertRowDocument {
+            row_index: 0,
+            expected_delta_str: Some(r#"[{"insert":"\n"}]"#.to_owned()),
+        },
+        InsertRowDocumentText {
+            row_id,
+            text: "AppFlowy".to_owned(),
+        },
+        AssertRowDocument {
+            row_index: 0,
+            expected_delta_str: Some(r#"[{"insert":"AppFlowy\n"}]"#.to_owned()),
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_duplicate_row_with_document_test() {
+    let mut test = GridRowTest::new().await;
+    let row_id = test.row_revs[0].id.clone();
+    let scripts = vec![
+        InsertRowDocumentText {
+            row_id: row_id.clone(),
+            text: "AppFlowy".to_owned(),
+        },
+        DuplicateRow { row_id: row_id.clone() },
+        AssertRowCount(4),
+        InsertRowDocumentText {
+            row_id,
+            text: "Hello ".to_owned(),
+        },
+        // The duplicated row is inserted below the original row and owns a copy of the document.
+        AssertRowDocument {
+            row_index: 0,
+            expected_delta_str: Some(r#"[{"insert":"Hello AppFlowy\n"}]"#.to_owned()),
+        },
+        AssertRowDocument {
+            row_index: 1,
+            expected_delta_str: Some(r#"[{"insert":"AppFlowy\n"}]"#.to_owned()),
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_create_row_with_default_values_test() {
+    let mut test = GridRowTest::new().await;
+    let text_field = test.text_field().clone();
+    let date_field = GridRowTestBuilder::new(&test).field_rev_with_type(&FieldType::DateTime);
+    let single_select_field = GridRowTestBuilder::new(&test).field_rev_with_type(&FieldType::SingleSelect);
+    let option = SingleSelectTypeOption::from(&single_select_field)
+        .options
+        .first()
+        .cloned()
+        .unwrap();
+
+    let scripts = vec![
+        UpdateFieldDefaultValue {
+            field_id: text_field.id.clone(),
+            default_value: CELL_PLACEHOLDER_CURRENT_USER.to_owned(),
+        },
+        UpdateFieldDefaultValue {
+            field_id: date_field.id.clone(),
+            default_value: CELL_PLACEHOLDER_TODAY.to_owned(),
+        },
+        UpdateFieldDefaultValue {
+            field_id: single_select_field.id.clone(),
+            default_value: option.id.clone(),
+        },
+        CreateEmptyRow,
+        AssertRowCount(4),
+    ];
+    test.run_scripts(scripts).await;
+
+    let row_rev = test.last_row().unwrap();
+    let user_id = test.sdk.user_session.user_id().unwrap();
+    let text_cell = row_rev.cells.get(&text_field.id).unwrap();
+    assert_eq!(decode_any_cell_data(text_cell, &text_field).to_string(), user_id);
+
+    let date_cell = row_rev.cells.get(&date_field.id).unwrap();
+    let today = chrono::Utc::now().format("%Y/%m/%d").to_string();
+    assert_eq!(
+        decode_any_cell_data(date_cell, &date_field)
+            .parse::<DateCellData>()
+            .unwrap()
+            .date,
+        today
+    );
+
+    let select_cell = row_rev.cells.get(&single_select_field.id).unwrap();
+    let select_options = decode_any_cell_data(select_cell, &single_select_field)
+        .parse::<SelectOptionCellData>()
+        .unwrap()
+        .select_options;
+    assert_eq!(select_options.len(), 1);
+    assert_eq!(select_options[0].id, option.id);
+}
+
+#[tokio::test]
+async fn grid_create_row_from_template_test() {
+    let mut test = GridRowTest::new().await;
+    let text_field = test.text_field().clone();
+    let document = r#"[{"insert":"Steps to reproduce\n"}]"#.to_owned();
+    let scripts = vec![
+        UpdateFieldDefaultValue {
+            field_id: text_field.id.clone(),
+            default_value: "Untitled".to_owned(),
+        },
+        CreateRowTemplate {
+            name: "Bug".to_owned(),
+            cells: vec![RowTemplateCell {
+                field_id: text_field.id.clone(),
+                content: "Bug report".to_owned(),
+            }],
+            document: Some(document.clone()),
+        },
+        AssertRowTemplateCount(1),
+        CreateRowFromTemplate {
+            template_name: "Bug".to_owned(),
+        },
+        AssertRowCount(4),
+        AssertRowDocument {
+            row_index: 3,
+            expected_delta_str: Some(document),
+        },
+    ];
+    test.run_scripts(scripts).await;
+
+    // The content of the template overrides the default value of the field.
+    let row_rev = test.last_row().unwrap();
+    let text_cell = row_rev.cells.get(&text_field.id).unwrap();
+    assert_eq!(decode_any_cell_data(text_cell, &text_field).to_string(), "Bug report");
+}
@@ -1,9 +1,10 @@
 use crate::grid::grid_editor::GridEditorTest;
-use flowy_grid::entities::RowInfo;
+use flowy_grid::entities::{CreateRowParams, CreateRowTemplateParams, RowInfo, RowTemplateCell};
 use flowy_grid::services::row::{CreateRowRevisionBuilder, CreateRowRevisionPayload};
 use flowy_grid_data_model::revision::{
     FieldRevision, GridBlockMetaRevision, GridBlockMetaRevisionChangeset, RowMetaChangeset, RowRevision,
 };
+use flowy_sync::entities::grid::FieldChangesetParams;
 use std::sync::Arc;
 
 pub enum RowScript {
@@ -20,6 +21,33 @@ pub enum RowScript {
     DeleteRows {
         row_ids: Vec<String>,
     },
+    DuplicateRow {
+        row_id: String,
+    },
+    OpenRowDocument {
+        row_id: String,
+    },
+    InsertRowDocumentText {
+        row_id: String,
+        text: String,
+    },
+    AssertRowDocument {
+        row_index: usize,
+        expected_delta_str: Option<String>,
+    },
+    UpdateFieldDefaultValue {
+        field_id: String,
+        default_value: String,
+    },
+    CreateRowTemplate {
+        name: String,
+        cells: Vec<RowTemplateCell>,
+        document: Option<String>,
+    },
+    CreateRowFromTemplate {
+        template_name: String,
+    },
+    AssertRowTemplateCount(usize),
     AssertRowCount(usize),
     CreateBlock {
         block: GridBlockMetaRevision,
@@ -70,7 +98,12 @@ impl GridRowTest {
     pub async fn run_script(&mut self, script: RowScript) {
         match script {
             RowScript::CreateEmptyRow => {
-                let row_order = self.editor.create_row(None).await.unwrap();
+                let params = CreateRowParams {
+                    grid_id: self.grid_id.clone(),
+                    start_row_id: None,
+                    template_id: None,
+                };
+                let row_order = self.editor.create_row(params).await.unwrap();
                 self.row_order_by_row_id
                     .insert(row_order.row_id().to_owned(), row_order);
                 self.row_revs = self.get_row_revs().await;
@@ -96,6 +129,86 @@ impl GridRowTest {
                 self.row_revs = self.get_row_revs().await;
                 self.block_meta_revs = self.editor.get_block_meta_revs().await.unwrap();
             }
+            RowScript::DuplicateRow { row_id } => {
+                self.editor.duplicate_row(&row_id).await.unwrap();
+                self.row_revs = self.get_row_revs().await;
+                self.block_meta_revs = self.editor.get_block_meta_revs().await.unwrap();
+            }
+            RowScript::OpenRowDocument { row_id } => {
+                let row_document = self.editor.get_row_document(&row_id).await.unwrap();
+                // The document is created only once.
+                let reopened_row_document = self.editor.get_row_document(&row_id).await.unwrap();
+                assert_eq!(row_document.document_id, reopened_row_document.document_id);
+                self.row_revs = self.get_row_revs().await;
+            }
+            RowScript::InsertRowDocumentText { row_id, text } => {
+                let row_document = self.editor.get_row_document(&row_id).await.unwrap();
+                let editor = self
+                    .sdk
+                    .text_block_manager
+                    .open_block(&row_document.document_id)
+                    .await
+                    .unwrap();
+                editor.insert(0, text).await.unwrap();
+            }
+            RowScript::AssertRowDocument {
+                row_index,
+                expected_delta_str,
+            } => {
+                let delta_str = match self.row_revs[row_index].document_id.as_ref() {
+                    None => None,
+                    Some(document_id) => {
+                        let editor = self.sdk.text_block_manager.open_block(document_id).await.unwrap();
+                        Some(editor.delta_str().await.unwrap())
+                    }
+                };
+                assert_eq!(delta_str, expected_delta_str);
+            }
+            RowScript::UpdateFieldDefaultValue {
+                field_id,
+                default_value,
+            } => {
+                let changeset = FieldChangesetParams {
+                    field_id,
+                    grid_id: self.grid_id.clone(),
+                    default_value: Some(default_value),
+                    ..Default::default()
+                };
+                self.editor.update_field(changeset).await.unwrap();
+                self.field_revs = self.editor.get_field_revs(None).await.unwrap();
+            }
+            RowScript::CreateRowTemplate { name, cells, document } => {
+                let params = CreateRowTemplateParams {
+                    grid_id: self.grid_id.clone(),
+                    name,
+                    cells,
+                    document,
+                };
+                self.editor.create_row_template(params).await.unwrap();
+            }
+            RowScript::CreateRowFromTemplate { template_name } => {
+                let template = self
+                    .editor
+                    .get_row_templates()
+                    .await
+                    .unwrap()
+                    .items
+                    .into_iter()
+                    .find(|template| template.name == template_name)
+                    .unwrap();
+                let params = CreateRowParams {
+                    grid_id: self.grid_id.clone(),
+                    start_row_id: None,
+                    template_id: Some(template.id),
+                };
+                self.editor.create_row(params).await.unwrap();
+                self.row_revs = self.get_row_revs().await;
+                self.block_meta_revs = self.editor.get_block_meta_revs().await.unwrap();
+            }
+            RowScript::AssertRowTemplateCount(expected_count) => {
+                let row_templates = self.editor.get_row_templates().await.unwrap();
+                assert_eq!(row_templates.items.len(), expected_count);
+            }
             RowScript::AssertRow { expected_row } => {
                 let row = &*self
                     .row_revs
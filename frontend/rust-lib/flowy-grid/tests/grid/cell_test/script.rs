@@ -1,8 +1,30 @@
 use crate::grid::grid_editor::GridEditorTest;
-use flowy_grid::entities::CellChangeset;
+use flowy_grid::entities::{CellChangeset, CreateRowCommentParams, UpdateRowCommentParams};
 
 pub enum CellScript {
-    UpdateCell { changeset: CellChangeset, is_err: bool },
+    UpdateCell {
+        changeset: CellChangeset,
+        is_err: bool,
+    },
+    AssertRowChanges {
+        row_id: String,
+        expected_descs: Vec<String>,
+    },
+    CreateRowComment {
+        row_id: String,
+        content: String,
+    },
+    UpdateLastRowComment {
+        row_id: String,
+        content: String,
+    },
+    DeleteLastRowComment {
+        row_id: String,
+    },
+    AssertRowComments {
+        row_id: String,
+        expected_contents: Vec<String>,
+    },
 }
 
 pub struct GridCellTest {
@@ -36,6 +58,49 @@ impl GridCellTest {
                     let _ = result.unwrap();
                     self.row_revs = self.get_row_revs().await;
                 }
+            }
+            CellScript::AssertRowChanges { row_id, expected_descs } => {
+                let activity = self.editor.get_row_activity(&row_id).await.unwrap();
+                let descs = activity
+                    .changes
+                    .into_iter()
+                    .map(|change| change.desc)
+                    .collect::<Vec<String>>();
+                assert_eq!(descs, expected_descs);
+            }
+            CellScript::CreateRowComment { row_id, content } => {
+                let params = CreateRowCommentParams {
+                    grid_id: self.grid_id.clone(),
+                    row_id,
+                    content,
+                };
+                let _ = self.editor.create_row_comment(params).await.unwrap();
+            }
+            CellScript::UpdateLastRowComment { row_id, content } => {
+                let activity = self.editor.get_row_activity(&row_id).await.unwrap();
+                let params = UpdateRowCommentParams {
+                    grid_id: self.grid_id.clone(),
+                    comment_id: activity.comments.last().unwrap().id.clone(),
+                    content,
+                };
+                let _ = self.editor.update_row_comment(params).await.unwrap();
+            }
+            CellScript::DeleteLastRowComment { row_id } => {
+                let activity = self.editor.get_row_activity(&row_id).await.unwrap();
+                let comment_id = &activity.comments.last().unwrap().id;
+                let _ = self.editor.delete_row_comment(comment_id).await.unwrap();
+            }
+            CellScript::AssertRowComments {
+                row_id,
+                expected_contents,
+            } => {
+                let activity = self.editor.get_row_activity(&row_id).await.unwrap();
+                let contents = activity
+                    .comments
+                    .into_iter()
+                    .map(|comment| comment.content)
+                    .collect::<Vec<String>>();
+                assert_eq!(contents, expected_contents);
             } // CellScript::AssertGridRevisionPad => {
               //     sleep(Duration::from_millis(2 * REVISION_WRITE_INTERVAL_IN_MILLIS)).await;
               //     let mut grid_rev_manager = grid_manager.make_grid_rev_manager(&self.grid_id, pool.clone()).unwrap();
@@ -33,6 +33,8 @@ async fn grid_cell_update() {
                 }
                 FieldType::Checkbox => "1".to_string(),
                 FieldType::URL => "1".to_string(),
+                FieldType::Email => "nathan@appflowy.io".to_string(),
+                FieldType::Phone => "+1 555 010 9999".to_string(),
             };
 
             scripts.push(UpdateCell {
@@ -49,3 +51,106 @@ async fn grid_cell_update() {
 
     test.run_scripts(scripts).await;
 }
+
+#[tokio::test]
+async fn grid_cell_update_invalid_contact_test() {
+    let mut test = GridCellTest::new().await;
+    let block_id = test.block_meta_revs.first().unwrap().block_id.clone();
+    let row_id = test.row_revs.first().unwrap().id.clone();
+
+    let mut scripts = vec![];
+    for field_rev in &test.field_revs {
+        let field_type: FieldType = field_rev.field_type_rev.into();
+        let data = match field_type {
+            FieldType::Email => "nathan@appflowy",
+            FieldType::Phone => "call me",
+            _ => continue,
+        };
+
+        scripts.push(UpdateCell {
+            changeset: CellChangeset {
+                grid_id: block_id.clone(),
+                row_id: row_id.clone(),
+                field_id: field_rev.id.clone(),
+                content: Some(data.to_owned()),
+            },
+            is_err: true,
+        });
+    }
+
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_cell_update_record_row_change_test() {
+    let mut test = GridCellTest::new().await;
+    let row_id = test.row_revs.first().unwrap().id.clone();
+    let text_field_id = test
+        .field_revs
+        .iter()
+        .find(|field_rev| FieldType::from(field_rev.field_type_rev).is_text())
+        .unwrap()
+        .id
+        .clone();
+    let changeset = |content: &str| CellChangeset {
+        grid_id: test.grid_id.clone(),
+        row_id: row_id.clone(),
+        field_id: text_field_id.clone(),
+        content: Some(content.to_owned()),
+    };
+
+    let scripts = vec![
+        UpdateCell {
+            changeset: changeset("Open"),
+            is_err: false,
+        },
+        UpdateCell {
+            changeset: changeset("Done"),
+            is_err: false,
+        },
+        // Updating the cell with the same content is not a change.
+        UpdateCell {
+            changeset: changeset("Done"),
+            is_err: false,
+        },
+        AssertRowChanges {
+            row_id: row_id.clone(),
+            expected_descs: vec!["Name:  → Open".to_owned(), "Name: Open → Done".to_owned()],
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_row_comment_test() {
+    let mut test = GridCellTest::new().await;
+    let row_id = test.row_revs.first().unwrap().id.clone();
+    let scripts = vec![
+        CreateRowComment {
+            row_id: row_id.clone(),
+            content: "Is it fixed?".to_owned(),
+        },
+        CreateRowComment {
+            row_id: row_id.clone(),
+            content: "Yes".to_owned(),
+        },
+        AssertRowComments {
+            row_id: row_id.clone(),
+            expected_contents: vec!["Is it fixed?".to_owned(), "Yes".to_owned()],
+        },
+        UpdateLastRowComment {
+            row_id: row_id.clone(),
+            content: "Yes, in the next release".to_owned(),
+        },
+        AssertRowComments {
+            row_id: row_id.clone(),
+            expected_contents: vec!["Is it fixed?".to_owned(), "Yes, in the next release".to_owned()],
+        },
+        DeleteLastRowComment { row_id: row_id.clone() },
+        AssertRowComments {
+            row_id,
+            expected_contents: vec!["Is it fixed?".to_owned()],
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
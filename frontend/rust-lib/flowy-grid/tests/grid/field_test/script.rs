@@ -1,7 +1,7 @@
 use crate::grid::grid_editor::GridEditorTest;
-use flowy_grid::entities::InsertFieldParams;
+use flowy_grid::entities::{GridLayoutType, InsertFieldParams, MoveLayoutFieldParams};
 use flowy_grid_data_model::revision::FieldRevision;
-use flowy_sync::entities::grid::FieldChangesetParams;
+use flowy_sync::entities::grid::{FieldChangesetParams, FieldSettingChangesetParams};
 
 pub enum FieldScript {
     CreateField {
@@ -18,6 +18,22 @@ pub enum FieldScript {
         field_index: usize,
         field_rev: FieldRevision,
     },
+    UpdateFieldSetting {
+        params: FieldSettingChangesetParams,
+    },
+    MoveLayoutField {
+        params: MoveLayoutFieldParams,
+    },
+    AssertFieldSetting {
+        layout_type: GridLayoutType,
+        field_id: String,
+        visibility: bool,
+        width: i32,
+    },
+    AssertLayoutFieldOrder {
+        layout_type: GridLayoutType,
+        field_ids: Vec<String>,
+    },
 }
 
 pub struct GridFieldTest {
@@ -75,6 +91,36 @@ impl GridFieldTest {
                 let field_revs = self.editor.get_field_revs(None).await.unwrap();
                 assert_eq!(field_revs[field_index].as_ref(), &field_rev);
             }
+            FieldScript::UpdateFieldSetting { params } => {
+                self.editor.update_field_setting(params).await.unwrap();
+            }
+            FieldScript::MoveLayoutField { params } => {
+                self.editor.move_layout_field(params).await.unwrap();
+            }
+            FieldScript::AssertFieldSetting {
+                layout_type,
+                field_id,
+                visibility,
+                width,
+            } => {
+                let field_settings = self.editor.get_field_settings(&layout_type).await.unwrap();
+                let field_setting = field_settings
+                    .items
+                    .into_iter()
+                    .find(|field_setting| field_setting.field_id == field_id)
+                    .unwrap();
+                assert_eq!(field_setting.visibility, visibility);
+                assert_eq!(field_setting.width, width);
+            }
+            FieldScript::AssertLayoutFieldOrder { layout_type, field_ids } => {
+                let field_settings = self.editor.get_field_settings(&layout_type).await.unwrap();
+                let layout_field_ids = field_settings
+                    .items
+                    .into_iter()
+                    .map(|field_setting| field_setting.field_id)
+                    .collect::<Vec<String>>();
+                assert_eq!(layout_field_ids, field_ids);
+            }
         }
     }
 }
@@ -1,10 +1,14 @@
 use crate::grid::field_test::script::FieldScript::*;
 use crate::grid::field_test::script::GridFieldTest;
 use crate::grid::field_test::util::*;
+use flowy_grid::entities::{GridLayoutType, MoveLayoutFieldParams};
 use flowy_grid::services::field::select_option::SelectOption;
 use flowy_grid::services::field::SingleSelectTypeOption;
 use flowy_grid_data_model::revision::TypeOptionDataEntry;
-use flowy_sync::entities::grid::FieldChangesetParams;
+use flowy_grid_data_model::revision::{gen_grid_id, GridLayoutRevision, GridRevision};
+use flowy_sync::client_grid::make_grid_revisions;
+use flowy_sync::entities::grid::{FieldChangesetParams, FieldSettingChangesetParams};
+use std::sync::Arc;
 
 #[tokio::test]
 async fn grid_create_field() {
@@ -113,3 +117,246 @@ async fn grid_delete_field() {
     ];
     test.run_scripts(scripts).await;
 }
+
+#[tokio::test]
+async fn grid_hide_field_in_one_layout_test() {
+    let mut test = GridFieldTest::new().await;
+    let field_rev = test.field_revs[1].as_ref().clone();
+    let scripts = vec![
+        UpdateFieldSetting {
+            params: FieldSettingChangesetParams {
+                grid_id: test.grid_id(),
+                layout_type: GridLayoutRevision::Board,
+                field_id: field_rev.id.clone(),
+                visibility: Some(false),
+                width: Some(field_rev.width + 50),
+            },
+        },
+        AssertFieldSetting {
+            layout_type: GridLayoutType::Board,
+            field_id: field_rev.id.clone(),
+            visibility: false,
+            width: field_rev.width + 50,
+        },
+        AssertFieldSetting {
+            layout_type: GridLayoutType::Table,
+            field_id: field_rev.id.clone(),
+            visibility: field_rev.visibility,
+            width: field_rev.width,
+        },
+        // The field's own properties are kept as they are.
+        AssertFieldEqual {
+            field_index: 1,
+            field_rev,
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_move_field_in_one_layout_test() {
+    let mut test = GridFieldTest::new().await;
+    let field_ids = test
+        .field_revs
+        .iter()
+        .map(|field_rev| field_rev.id.clone())
+        .collect::<Vec<String>>();
+
+    let mut board_field_ids = field_ids.clone();
+    let moved_field_id = board_field_ids.remove(0);
+    board_field_ids.insert(2, moved_field_id.clone());
+
+    let scripts = vec![
+        MoveLayoutField {
+            params: MoveLayoutFieldParams {
+                grid_id: test.grid_id(),
+                layout_type: GridLayoutType::Board,
+                field_id: moved_field_id,
+                from_index: 0,
+                to_index: 2,
+            },
+        },
+        AssertLayoutFieldOrder {
+            layout_type: GridLayoutType::Board,
+            field_ids: board_field_ids,
+        },
+        AssertLayoutFieldOrder {
+            layout_type: GridLayoutType::Table,
+            field_ids,
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_move_field_from_stale_index_in_one_layout_test() {
+    let mut test = GridFieldTest::new().await;
+    let field_ids = test
+        .field_revs
+        .iter()
+        .map(|field_rev| field_rev.id.clone())
+        .collect::<Vec<String>>();
+
+    // The field isn't at the from_index anymore, so nothing is moved.
+    let scripts = vec![
+        MoveLayoutField {
+            params: MoveLayoutFieldParams {
+                grid_id: test.grid_id(),
+                layout_type: GridLayoutType::Board,
+                field_id: field_ids[0].clone(),
+                from_index: 1,
+                to_index: 2,
+            },
+        },
+        AssertLayoutFieldOrder {
+            layout_type: GridLayoutType::Board,
+            field_ids,
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_update_field_width_without_layout_setting_test() {
+    let mut test = GridFieldTest::new().await;
+    let field_rev = test.field_revs[1].as_ref().clone();
+    let scripts = vec![
+        UpdateFieldSetting {
+            params: FieldSettingChangesetParams {
+                grid_id: test.grid_id(),
+                layout_type: GridLayoutRevision::Board,
+                field_id: field_rev.id.clone(),
+                visibility: None,
+                width: Some(field_rev.width + 50),
+            },
+        },
+        UpdateField {
+            changeset: FieldChangesetParams {
+                field_id: field_rev.id.clone(),
+                grid_id: test.grid_id(),
+                visibility: Some(!field_rev.visibility),
+                width: Some(field_rev.width + 100),
+                ..Default::default()
+            },
+        },
+        // The table doesn't have its own setting, so it follows the field.
+        AssertFieldSetting {
+            layout_type: GridLayoutType::Table,
+            field_id: field_rev.id.clone(),
+            visibility: !field_rev.visibility,
+            width: field_rev.width + 100,
+        },
+        // The board keeps its own width and follows the field's visibility.
+        AssertFieldSetting {
+            layout_type: GridLayoutType::Board,
+            field_id: field_rev.id.clone(),
+            visibility: !field_rev.visibility,
+            width: field_rev.width + 50,
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_migrate_field_settings_of_legacy_grid_test() {
+    let mut test = GridFieldTest::new().await;
+
+    // The grid that was saved before the fields could be configured per layout.
+    let mut field_rev = test.field_revs[1].as_ref().clone();
+    field_rev.visibility = false;
+    field_rev.width = 200;
+    let mut grid_rev = GridRevision::new(&gen_grid_id());
+    grid_rev.fields = test.field_revs.clone();
+    grid_rev.fields[1] = Arc::new(field_rev.clone());
+    grid_rev.blocks = test.block_meta_revs.clone();
+
+    let user_id = test.sdk.user_session.user_id().unwrap();
+    let revisions = make_grid_revisions(&user_id, &grid_rev);
+    test.sdk
+        .grid_manager
+        .create_grid(&grid_rev.grid_id, revisions)
+        .await
+        .unwrap();
+    test.editor = test.sdk.grid_manager.open_grid(&grid_rev.grid_id).await.unwrap();
+    test.grid_id = grid_rev.grid_id.clone();
+
+    let field_ids = grid_rev
+        .fields
+        .iter()
+        .map(|field_rev| field_rev.id.clone())
+        .collect::<Vec<String>>();
+    let scripts = vec![
+        AssertFieldSetting {
+            layout_type: GridLayoutType::Board,
+            field_id: field_rev.id.clone(),
+            visibility: false,
+            width: 200,
+        },
+        AssertLayoutFieldOrder {
+            layout_type: GridLayoutType::Board,
+            field_ids,
+        },
+        UpdateField {
+            changeset: FieldChangesetParams {
+                field_id: field_rev.id.clone(),
+                grid_id: test.grid_id(),
+                visibility: Some(true),
+                width: Some(300),
+                ..Default::default()
+            },
+        },
+        // The layouts keep the settings that were copied from the field.
+        AssertFieldSetting {
+            layout_type: GridLayoutType::Table,
+            field_id: field_rev.id.clone(),
+            visibility: false,
+            width: 200,
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn grid_field_settings_after_create_and_delete_field_test() {
+    let mut test = GridFieldTest::new().await;
+    let (params, field_rev) = create_text_field(&test.grid_id());
+    let mut field_ids = test
+        .field_revs
+        .iter()
+        .map(|field_rev| field_rev.id.clone())
+        .collect::<Vec<String>>();
+
+    // The new field uses its own properties until the layout changes them.
+    let scripts = vec![
+        CreateField { params },
+        AssertFieldSetting {
+            layout_type: GridLayoutType::Board,
+            field_id: field_rev.id.clone(),
+            visibility: field_rev.visibility,
+            width: field_rev.width,
+        },
+        AssertLayoutFieldOrder {
+            layout_type: GridLayoutType::Board,
+            field_ids: field_ids.iter().cloned().chain(vec![field_rev.id.clone()]).collect(),
+        },
+    ];
+    test.run_scripts(scripts).await;
+
+    let deleted_field_rev = test.field_revs[0].as_ref().clone();
+    field_ids.remove(0);
+    field_ids.push(field_rev.id.clone());
+    let scripts = vec![
+        DeleteField {
+            field_rev: deleted_field_rev,
+        },
+        AssertLayoutFieldOrder {
+            layout_type: GridLayoutType::Board,
+            field_ids: field_ids.clone(),
+        },
+        AssertLayoutFieldOrder {
+            layout_type: GridLayoutType::Table,
+            field_ids,
+        },
+    ];
+    test.run_scripts(scripts).await;
+}
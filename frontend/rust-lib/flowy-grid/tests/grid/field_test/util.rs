@@ -26,6 +26,7 @@ pub fn create_text_field(grid_id: &str) -> (InsertFieldParams, FieldRevision) {
         visibility: field_rev.visibility,
         width: field_rev.width,
         is_primary: false,
+        default_value: None,
     };
 
     let params = InsertFieldParams {
@@ -59,6 +60,7 @@ pub fn create_single_select_field(grid_id: &str) -> (InsertFieldParams, FieldRev
         visibility: field_rev.visibility,
         width: field_rev.width,
         is_primary: false,
+        default_value: None,
     };
 
     let params = InsertFieldParams {
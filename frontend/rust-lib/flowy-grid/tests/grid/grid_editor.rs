@@ -134,6 +134,14 @@ fn make_all_field_test_grid() -> BuildGridContext {
     let url = URLTypeOptionBuilder::default();
     let url_field = FieldBuilder::new(url).name("link").visibility(true).build();
 
+    // Email
+    let email = EmailTypeOptionBuilder::default();
+    let email_field = FieldBuilder::new(email).name("email").visibility(true).build();
+
+    // Phone
+    let phone = PhoneTypeOptionBuilder::default();
+    let phone_field = FieldBuilder::new(phone).name("phone").visibility(true).build();
+
     // for i in 0..3 {
     //     for field_type in FieldType::iter() {
     //         let field_type: FieldType = field_type;
@@ -145,6 +153,8 @@ fn make_all_field_test_grid() -> BuildGridContext {
     //             FieldType::MultiSelect => {}
     //             FieldType::Checkbox => {}
     //             FieldType::URL => {}
+    //             FieldType::Email => {}
+    //             FieldType::Phone => {}
     //         }
     //     }
     // }
@@ -157,6 +167,8 @@ fn make_all_field_test_grid() -> BuildGridContext {
         .add_field(date_field)
         .add_field(checkbox_field)
         .add_field(url_field)
+        .add_field(email_field)
+        .add_field(phone_field)
         .add_empty_row()
         .add_empty_row()
         .add_empty_row()
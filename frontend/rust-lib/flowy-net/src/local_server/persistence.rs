@@ -1,10 +1,11 @@
+use dashmap::DashMap;
 use flowy_sync::{
     entities::{folder::FolderInfo, text_block::TextBlockInfo},
     errors::CollaborateError,
     protobuf::{RepeatedRevision as RepeatedRevisionPB, Revision as RevisionPB},
     server_document::*,
     server_folder::FolderCloudPersistence,
-    util::{make_document_info_from_revisions_pb, make_folder_from_revisions_pb},
+    util::{make_document_info_from_revisions_pb, make_folder_from_revisions_pb, repeated_revision_pb_from_revisions},
 };
 use lib_infra::future::BoxResultFuture;
 use std::{
@@ -171,29 +172,53 @@ impl TextBlockCloudPersistence for LocalTextBlockCloudPersistence {
     }
 }
 
+/// Keeps the revisions of the documents and folders in memory, the server reads them to transform
+/// the revisions of the clients.
 #[derive(Default)]
-struct MemoryDocumentCloudStorage {}
+struct MemoryDocumentCloudStorage {
+    revisions: DashMap<String, Vec<RevisionPB>>,
+}
+
 impl RevisionCloudStorage for MemoryDocumentCloudStorage {
-    fn set_revisions(&self, _repeated_revision: RepeatedRevisionPB) -> BoxResultFuture<(), CollaborateError> {
+    fn set_revisions(&self, mut repeated_revision: RepeatedRevisionPB) -> BoxResultFuture<(), CollaborateError> {
+        for revision in repeated_revision.take_items() {
+            let mut revisions = self
+                .revisions
+                .entry(revision.object_id.clone())
+                .or_insert_with(Vec::new);
+            revisions.retain(|old_revision| old_revision.rev_id != revision.rev_id);
+            revisions.push(revision);
+            revisions.sort_by(|a, b| a.rev_id.cmp(&b.rev_id));
+        }
         Box::pin(async move { Ok(()) })
     }
 
     fn get_revisions(
         &self,
-        _doc_id: &str,
-        _rev_ids: Option<Vec<i64>>,
+        doc_id: &str,
+        rev_ids: Option<Vec<i64>>,
     ) -> BoxResultFuture<RepeatedRevisionPB, CollaborateError> {
-        Box::pin(async move {
-            let repeated_revisions = RepeatedRevisionPB::new();
-            Ok(repeated_revisions)
-        })
+        let revisions = match self.revisions.get(doc_id) {
+            None => vec![],
+            Some(revisions) => revisions
+                .iter()
+                .filter(|revision| match &rev_ids {
+                    None => true,
+                    Some(rev_ids) => rev_ids.contains(&revision.rev_id),
+                })
+                .cloned()
+                .collect::<Vec<RevisionPB>>(),
+        };
+        Box::pin(async move { Ok(repeated_revision_pb_from_revisions(revisions)) })
     }
 
     fn reset_object(
         &self,
-        _doc_id: &str,
-        _repeated_revision: RepeatedRevisionPB,
+        doc_id: &str,
+        mut repeated_revision: RepeatedRevisionPB,
     ) -> BoxResultFuture<(), CollaborateError> {
+        let revisions: Vec<RevisionPB> = repeated_revision.take_items().into();
+        self.revisions.insert(doc_id.to_owned(), revisions);
         Box::pin(async move { Ok(()) })
     }
 }
@@ -1,4 +1,4 @@
-use crate::local_server::persistence::LocalTextBlockCloudPersistence;
+use crate::local_server::{persistence::LocalTextBlockCloudPersistence, LocalWebSocket};
 use async_stream::stream;
 use bytes::Bytes;
 use flowy_error::{internal_error, FlowyError};
@@ -6,6 +6,7 @@ use flowy_folder::event_map::FolderCouldServiceV1;
 use flowy_sync::{
     client_document::default::initial_quill_delta_string,
     entities::{
+        presence::{PresenceWSData, PRESENCE_HEARTBEAT_INTERVAL_IN_MILLIS},
         text_block::{CreateTextBlockParams, ResetTextBlockParams, TextBlockId, TextBlockInfo},
         ws_data::{ClientRevisionWSData, ClientRevisionWSDataType},
     },
@@ -13,6 +14,7 @@ use flowy_sync::{
     protobuf::ClientRevisionWSData as ClientRevisionWSDataPB,
     server_document::ServerDocumentManager,
     server_folder::ServerFolderManager,
+    server_presence::{PresenceUser, ServerPresenceManager},
     synchronizer::{RevisionSyncResponse, RevisionUser},
 };
 use futures_util::stream::StreamExt;
@@ -23,13 +25,15 @@ use std::{
     convert::{TryFrom, TryInto},
     fmt::Debug,
     sync::Arc,
+    time::{Duration, Instant},
 };
-use tokio::sync::{broadcast, mpsc, mpsc::UnboundedSender};
+use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc};
 
 pub struct LocalServer {
     doc_manager: Arc<ServerDocumentManager>,
     folder_manager: Arc<ServerFolderManager>,
-    stop_tx: RwLock<Option<mpsc::Sender<()>>>,
+    presence_manager: Arc<ServerPresenceManager>,
+    stop_tx: RwLock<Option<broadcast::Sender<()>>>,
     client_ws_sender: mpsc::UnboundedSender<WebSocketRawMessage>,
     client_ws_receiver: broadcast::Sender<WebSocketRawMessage>,
 }
@@ -42,11 +46,13 @@ impl LocalServer {
         let persistence = Arc::new(LocalTextBlockCloudPersistence::default());
         let doc_manager = Arc::new(ServerDocumentManager::new(persistence.clone()));
         let folder_manager = Arc::new(ServerFolderManager::new(persistence));
+        let presence_manager = Arc::new(ServerPresenceManager::new());
         let stop_tx = RwLock::new(None);
 
         LocalServer {
             doc_manager,
             folder_manager,
+            presence_manager,
             stop_tx,
             client_ws_sender,
             client_ws_receiver,
@@ -55,28 +61,60 @@ impl LocalServer {
 
     pub async fn stop(&self) {
         if let Some(stop_tx) = self.stop_tx.read().clone() {
-            let _ = stop_tx.send(()).await;
+            let _ = stop_tx.send(());
         }
     }
 
     pub fn run(&self) {
-        let (stop_tx, stop_rx) = mpsc::channel(1);
+        let (stop_tx, _) = broadcast::channel(1);
+        let stop_rx = stop_tx.subscribe();
         *self.stop_tx.write() = Some(stop_tx);
+        tokio::spawn(remove_inactive_presences(self.presence_manager.clone(), stop_rx));
+        self.spawn_runner(self.client_ws_sender.clone(), self.client_ws_receiver.subscribe());
+    }
+
+    /// Connects another client to the server. Each client is a separate user of the documents and
+    /// folders, so the revisions sent by one client are pushed to the others.
+    pub fn connect(&self) -> LocalWebSocket {
+        let (client_ws_sender, server_ws_receiver) = mpsc::unbounded_channel();
+        let (server_ws_sender, client_ws_receiver) = broadcast::channel(16);
+        let local_ws = LocalWebSocket::new(server_ws_receiver, server_ws_sender);
+        self.spawn_runner(client_ws_sender, client_ws_receiver);
+        local_ws
+    }
+
+    fn spawn_runner(
+        &self,
+        client_ws_sender: mpsc::UnboundedSender<WebSocketRawMessage>,
+        client_ws_receiver: broadcast::Receiver<WebSocketRawMessage>,
+    ) {
+        let stop_rx = match self.stop_tx.read().as_ref() {
+            None => {
+                tracing::warn!("[LocalServer] should run before connecting the client");
+                return;
+            }
+            Some(stop_tx) => stop_tx.subscribe(),
+        };
         let runner = LocalWebSocketRunner {
+            user_id: nanoid!(10),
             doc_manager: self.doc_manager.clone(),
             folder_manager: self.folder_manager.clone(),
+            presence_manager: self.presence_manager.clone(),
             stop_rx: Some(stop_rx),
-            client_ws_sender: self.client_ws_sender.clone(),
-            client_ws_receiver: Some(self.client_ws_receiver.subscribe()),
+            client_ws_sender,
+            client_ws_receiver: Some(client_ws_receiver),
         };
         tokio::spawn(runner.run());
     }
 }
 
 struct LocalWebSocketRunner {
+    /// Identifies the connected client, which is a [RevisionUser] of the server.
+    user_id: String,
     doc_manager: Arc<ServerDocumentManager>,
     folder_manager: Arc<ServerFolderManager>,
-    stop_rx: Option<mpsc::Receiver<()>>,
+    presence_manager: Arc<ServerPresenceManager>,
+    stop_rx: Option<broadcast::Receiver<()>>,
     client_ws_sender: mpsc::UnboundedSender<WebSocketRawMessage>,
     client_ws_receiver: Option<broadcast::Receiver<WebSocketRawMessage>>,
 }
@@ -91,7 +129,11 @@ impl LocalWebSocketRunner {
                     result = client_ws_receiver.recv() => {
                         match result {
                             Ok(msg) => yield msg,
-                            Err(_e) => {},
+                            Err(RecvError::Lagged(_)) => {},
+                            Err(RecvError::Closed) => {
+                                tracing::trace!("[LocalWebSocketRunner] client disconnected");
+                                break
+                            },
                         }
                     },
                     _ = stop_rx.recv() => {
@@ -109,26 +151,56 @@ impl LocalWebSocketRunner {
                 }
             })
             .await;
+        // The other users stop seeing the presences of the disconnected client, and the revisions
+        // are not pushed to it anymore.
+        self.presence_manager.remove_user(&self.user_id);
+        self.doc_manager.remove_user(&self.user_id).await;
+        self.folder_manager.remove_user(&self.user_id).await;
     }
 
     async fn handle_message(&self, message: WebSocketRawMessage) -> Result<(), FlowyError> {
         let bytes = Bytes::from(message.data);
+        if message.channel == WSChannel::Presence {
+            let presence = PresenceWSData::try_from(bytes).map_err(internal_error)?;
+            self.handle_presence(presence);
+            return Ok(());
+        }
+
         let client_data = ClientRevisionWSData::try_from(bytes).map_err(internal_error)?;
         match message.channel {
             WSChannel::Document => {
-                let _ = self.handle_document_client_data(client_data, "".to_owned()).await?;
+                let _ = self
+                    .handle_document_client_data(client_data, self.user_id.clone())
+                    .await?;
                 Ok(())
             }
             WSChannel::Folder => {
-                let _ = self.handle_folder_client_data(client_data, "".to_owned()).await?;
+                let _ = self
+                    .handle_folder_client_data(client_data, self.user_id.clone())
+                    .await?;
                 Ok(())
             }
             WSChannel::Grid => {
                 todo!("Implement grid web socket channel")
             }
+            WSChannel::Presence => unreachable!(),
         }
     }
 
+    fn handle_presence(&self, presence: PresenceWSData) {
+        tracing::trace!(
+            "[LocalPresenceServer] receive: {}:{}-{:?}",
+            presence.object_id,
+            presence.user_id,
+            presence.ty,
+        );
+        let user = Arc::new(LocalPresenceUser {
+            user_id: self.user_id.clone(),
+            client_ws_sender: self.client_ws_sender.clone(),
+        });
+        self.presence_manager.handle_client_presence(user, presence);
+    }
+
     pub async fn handle_folder_client_data(
         &self,
         client_data: ClientRevisionWSData,
@@ -212,43 +284,55 @@ impl RevisionUser for LocalRevisionUser {
     }
 
     fn receive(&self, resp: RevisionSyncResponse) {
-        let sender = self.client_ws_sender.clone();
-        let send_fn = |sender: UnboundedSender<WebSocketRawMessage>, msg: WebSocketRawMessage| match sender.send(msg) {
-            Ok(_) => {}
-            Err(e) => {
-                tracing::error!("LocalDocumentUser send message failed: {}", e);
-            }
+        // Sends the message in place, the client applies the push of the transformed revision
+        // before the ack of the revision that it was transformed from.
+        let data = match resp {
+            RevisionSyncResponse::Pull(data) => data,
+            RevisionSyncResponse::Push(data) => data,
+            RevisionSyncResponse::Ack(data) => data,
         };
-        let channel = self.channel.clone();
-
-        tokio::spawn(async move {
-            match resp {
-                RevisionSyncResponse::Pull(data) => {
-                    let bytes: Bytes = data.try_into().unwrap();
-                    let msg = WebSocketRawMessage {
-                        channel,
-                        data: bytes.to_vec(),
-                    };
-                    send_fn(sender, msg);
-                }
-                RevisionSyncResponse::Push(data) => {
-                    let bytes: Bytes = data.try_into().unwrap();
-                    let msg = WebSocketRawMessage {
-                        channel,
-                        data: bytes.to_vec(),
-                    };
-                    send_fn(sender, msg);
-                }
-                RevisionSyncResponse::Ack(data) => {
-                    let bytes: Bytes = data.try_into().unwrap();
-                    let msg = WebSocketRawMessage {
-                        channel,
-                        data: bytes.to_vec(),
-                    };
-                    send_fn(sender, msg);
-                }
-            }
-        });
+        let bytes: Bytes = data.try_into().unwrap();
+        let msg = WebSocketRawMessage {
+            channel: self.channel.clone(),
+            data: bytes.to_vec(),
+        };
+        if let Err(e) = self.client_ws_sender.send(msg) {
+            tracing::error!("LocalDocumentUser send message failed: {}", e);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LocalPresenceUser {
+    user_id: String,
+    client_ws_sender: mpsc::UnboundedSender<WebSocketRawMessage>,
+}
+
+impl PresenceUser for LocalPresenceUser {
+    fn user_id(&self) -> String {
+        self.user_id.clone()
+    }
+
+    fn receive(&self, data: PresenceWSData) {
+        let bytes: Bytes = data.try_into().unwrap();
+        let msg = WebSocketRawMessage {
+            channel: WSChannel::Presence,
+            data: bytes.to_vec(),
+        };
+        if let Err(e) = self.client_ws_sender.send(msg) {
+            tracing::error!("LocalPresenceUser send message failed: {}", e);
+        }
+    }
+}
+
+// Checks the heartbeats of the presences until the server stops.
+async fn remove_inactive_presences(presence_manager: Arc<ServerPresenceManager>, mut stop_rx: broadcast::Receiver<()>) {
+    let mut interval = tokio::time::interval(Duration::from_millis(PRESENCE_HEARTBEAT_INTERVAL_IN_MILLIS));
+    loop {
+        tokio::select! {
+            _ = interval.tick() => presence_manager.remove_inactive_users(Instant::now()),
+            _ = stop_rx.recv() => break,
+        }
     }
 }
 
@@ -1,2 +1,3 @@
 pub mod connection;
 pub mod http_ws;
+pub mod presence;
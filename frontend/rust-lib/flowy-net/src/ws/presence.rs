@@ -0,0 +1,82 @@
+use crate::ws::connection::FlowyWebSocketConnect;
+use bytes::Bytes;
+use flowy_error::{internal_error, FlowyError};
+use flowy_sync::entities::presence::{PresenceWSData, PresenceWSDataType, PRESENCE_HEARTBEAT_INTERVAL_IN_MILLIS};
+use lib_ws::{WSChannel, WebSocketRawMessage};
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+/// Sends the presences of the current user over the presence channel. The heartbeat of each
+/// object is sent until the user leaves the object, so the server keeps the presence alive.
+pub struct FlowyPresenceSender {
+    ws_conn: Arc<FlowyWebSocketConnect>,
+    /// The ids of the objects that the user is viewing, the value is the id of the user.
+    objects: RwLock<HashMap<String, String>>,
+}
+
+impl FlowyPresenceSender {
+    pub fn new(ws_conn: Arc<FlowyWebSocketConnect>) -> Arc<Self> {
+        let sender = Arc::new(Self {
+            ws_conn,
+            objects: RwLock::new(HashMap::new()),
+        });
+        tokio::spawn(send_heartbeats(Arc::downgrade(&sender)));
+        sender
+    }
+
+    pub async fn send(&self, data: PresenceWSData) -> Result<(), FlowyError> {
+        match data.ty {
+            PresenceWSDataType::Leave => {
+                self.objects.write().remove(&data.object_id);
+            }
+            PresenceWSDataType::Update | PresenceWSDataType::Heartbeat => {
+                self.objects
+                    .write()
+                    .insert(data.object_id.clone(), data.user_id.clone());
+            }
+        }
+        send_presence(&self.ws_conn, data).await
+    }
+}
+
+async fn send_heartbeats(sender: Weak<FlowyPresenceSender>) {
+    let mut interval = tokio::time::interval(Duration::from_millis(PRESENCE_HEARTBEAT_INTERVAL_IN_MILLIS));
+    loop {
+        interval.tick().await;
+        let sender = match sender.upgrade() {
+            None => break,
+            Some(sender) => sender,
+        };
+        let heartbeats = sender
+            .objects
+            .read()
+            .iter()
+            .map(|(object_id, user_id)| PresenceWSData::heartbeat(object_id, user_id))
+            .collect::<Vec<PresenceWSData>>();
+        for heartbeat in heartbeats {
+            if let Err(e) = send_presence(&sender.ws_conn, heartbeat).await {
+                tracing::error!("Send the presence heartbeat failed: {:?}", e);
+            }
+        }
+    }
+}
+
+async fn send_presence(ws_conn: &Arc<FlowyWebSocketConnect>, data: PresenceWSData) -> Result<(), FlowyError> {
+    let bytes: Bytes = data.try_into().map_err(internal_error)?;
+    let msg = WebSocketRawMessage {
+        channel: WSChannel::Presence,
+        data: bytes.to_vec(),
+    };
+    match ws_conn.web_socket().await? {
+        None => {}
+        Some(sender) => {
+            let _ = sender.send(msg)?;
+        }
+    }
+    Ok(())
+}
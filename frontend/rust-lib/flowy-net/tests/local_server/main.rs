@@ -0,0 +1 @@
+mod revision_test;
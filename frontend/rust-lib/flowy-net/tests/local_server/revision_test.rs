@@ -0,0 +1,170 @@
+use bytes::Bytes;
+use flowy_net::{
+    local_server::{LocalServer, LocalWebSocket},
+    ws::connection::{FlowyRawWebSocket, FlowyWebSocket},
+};
+use flowy_sync::entities::{
+    revision::{md5, RepeatedRevision, RevId, Revision},
+    ws_data::{ClientRevisionWSData, ServerRevisionWSData, ServerRevisionWSDataType},
+};
+use lib_ot::{
+    core::OperationTransformable,
+    rich_text::{RichTextDelta, RichTextDeltaBuilder},
+};
+use lib_ws::{WSChannel, WSMessageReceiver, WebSocketRawMessage};
+use std::{
+    convert::{TryFrom, TryInto},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{broadcast, mpsc};
+
+const DOC_ID: &str = "local_server_doc";
+
+#[tokio::test]
+async fn local_server_two_clients_converge_test() {
+    let (client_ws_sender, _) = mpsc::unbounded_channel();
+    let (client_ws_receiver, _) = broadcast::channel(16);
+    let server = LocalServer::new(client_ws_sender, client_ws_receiver);
+    server.run();
+    let mut client_a = TestClient::connect(&server, "a").await;
+    let mut client_b = TestClient::connect(&server, "b").await;
+
+    client_a.send_revision(0, 1, RichTextDeltaBuilder::new().insert("\n").build());
+    client_a.assert_ack(1).await;
+    client_b.send_ping(0);
+    client_b.receive_push().await;
+    assert_eq!(client_b.document.to_delta_str(), client_a.document.to_delta_str());
+
+    // B doesn't see the revision of A before sending its own, so both are based on revision 1.
+    client_a.send_revision(1, 2, RichTextDeltaBuilder::new().insert("a").build());
+    client_a.assert_ack(2).await;
+    let revision_of_a = client_b
+        .receive_revisions(ServerRevisionWSDataType::ServerPushRev)
+        .await;
+    client_b.send_revision(1, 2, RichTextDeltaBuilder::new().insert("b").build());
+
+    // B composes the transformed revision instead of the revision of A.
+    let server_prime = client_b
+        .receive_revisions(ServerRevisionWSDataType::ServerPushTransformedRev)
+        .await;
+    assert_eq!(revision_of_a[0].rev_id, 2);
+    assert_eq!(server_prime[0].rev_id, 3);
+    client_b.compose_revisions(server_prime);
+    client_b.assert_ack(2).await;
+    client_a.receive_push().await;
+    assert_eq!(client_a.document.to_delta_str(), client_b.document.to_delta_str());
+    assert_eq!(client_a.document.apply("").unwrap(), "ab\n");
+
+    // The revision that was transformed before is acked again without being applied twice.
+    let last_revision = client_b.last_revision.clone().unwrap();
+    client_b.send(ClientRevisionWSData::from_revisions(DOC_ID, vec![last_revision]));
+    client_b.assert_ack(2).await;
+    client_a.assert_no_message().await;
+}
+
+struct TestClient {
+    user_id: String,
+    // Keeps the connection to the server open.
+    _local_ws: LocalWebSocket,
+    ws_sender: Arc<dyn FlowyWebSocket>,
+    receiver: mpsc::UnboundedReceiver<ServerRevisionWSData>,
+    document: RichTextDelta,
+    last_revision: Option<Revision>,
+}
+
+impl TestClient {
+    async fn connect(server: &LocalServer, user_id: &str) -> Self {
+        let local_ws = server.connect();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        local_ws
+            .add_msg_receiver(Arc::new(TestMessageReceiver(sender)))
+            .unwrap();
+        local_ws.initialize().await.unwrap();
+        let ws_sender = local_ws.ws_msg_sender().await.unwrap().unwrap();
+        Self {
+            user_id: user_id.to_owned(),
+            _local_ws: local_ws,
+            ws_sender,
+            receiver,
+            document: RichTextDelta::default(),
+            last_revision: None,
+        }
+    }
+
+    fn send_revision(&mut self, base_rev_id: i64, rev_id: i64, delta: RichTextDelta) {
+        self.document = self.document.compose(&delta).unwrap();
+        let revision = Revision::new(
+            DOC_ID,
+            base_rev_id,
+            rev_id,
+            delta.to_compact_bytes(),
+            &self.user_id,
+            md5(self.document.to_delta_bytes()),
+        );
+        self.last_revision = Some(revision.clone());
+        self.send(ClientRevisionWSData::from_revisions(DOC_ID, vec![revision]));
+    }
+
+    fn send_ping(&self, rev_id: i64) {
+        self.send(ClientRevisionWSData::ping(DOC_ID, rev_id));
+    }
+
+    fn send(&self, data: ClientRevisionWSData) {
+        let bytes: Bytes = data.try_into().unwrap();
+        let msg = WebSocketRawMessage {
+            channel: WSChannel::Document,
+            data: bytes.to_vec(),
+        };
+        self.ws_sender.send(msg).unwrap();
+    }
+
+    async fn receive_push(&mut self) {
+        let revisions = self.receive_revisions(ServerRevisionWSDataType::ServerPushRev).await;
+        self.compose_revisions(revisions);
+    }
+
+    async fn receive_revisions(&mut self, ty: ServerRevisionWSDataType) -> Vec<Revision> {
+        let data = self.receive().await;
+        assert_eq!(data.ty, ty);
+        RepeatedRevision::try_from(Bytes::from(data.data)).unwrap().into_inner()
+    }
+
+    fn compose_revisions(&mut self, revisions: Vec<Revision>) {
+        for revision in revisions {
+            let delta = RichTextDelta::from_bytes(&revision.delta_data).unwrap();
+            self.document = self.document.compose(&delta).unwrap();
+        }
+    }
+
+    async fn assert_ack(&mut self, rev_id: i64) {
+        let data = self.receive().await;
+        assert_eq!(data.ty, ServerRevisionWSDataType::ServerAck);
+        assert_eq!(RevId::try_from(Bytes::from(data.data)).unwrap().value, rev_id);
+    }
+
+    async fn assert_no_message(&mut self) {
+        let result = tokio::time::timeout(Duration::from_millis(100), self.receiver.recv()).await;
+        assert!(result.is_err(), "Unexpected message: {:?}", result);
+    }
+
+    async fn receive(&mut self) -> ServerRevisionWSData {
+        tokio::time::timeout(Duration::from_secs(1), self.receiver.recv())
+            .await
+            .expect("Wait for the server message timeout")
+            .unwrap()
+    }
+}
+
+struct TestMessageReceiver(mpsc::UnboundedSender<ServerRevisionWSData>);
+
+impl WSMessageReceiver for TestMessageReceiver {
+    fn source(&self) -> WSChannel {
+        WSChannel::Document
+    }
+
+    fn receive_message(&self, msg: WebSocketRawMessage) {
+        let data = ServerRevisionWSData::try_from(Bytes::from(msg.data)).unwrap();
+        let _ = self.0.send(data);
+    }
+}
@@ -70,6 +70,22 @@ impl RevisionMemoryCache {
         self.revs_map.get(rev_id).map(|r| r.value().clone())
     }
 
+    /// Sets the md5 of the revision. Returns the updated record if it was written to disk
+    /// already, otherwise it's written by the next checkpoint.
+    pub(crate) async fn set_md5(&self, rev_id: &i64, md5: String) -> Option<RevisionRecord> {
+        let record = {
+            let mut record = self.revs_map.get_mut(rev_id)?;
+            record.revision.md5 = md5;
+            record.clone()
+        };
+
+        if self.pending_write_revs.read().await.contains(rev_id) {
+            None
+        } else {
+            Some(record)
+        }
+    }
+
     pub(crate) fn remove(&self, rev_id: &i64) {
         let _ = self.revs_map.remove(rev_id);
     }
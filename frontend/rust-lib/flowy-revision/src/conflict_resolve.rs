@@ -9,7 +9,7 @@ use flowy_sync::{
     util::make_delta_from_revisions,
 };
 use lib_infra::future::BoxResultFuture;
-use lib_ot::core::{Attributes, Delta, PlainTextAttributes};
+use lib_ot::core::{Attributes, Delta, OperationTransformable, PlainTextAttributes};
 use lib_ot::rich_text::RichTextAttributes;
 use serde::de::DeserializeOwned;
 use std::{convert::TryFrom, sync::Arc};
@@ -77,6 +77,52 @@ where
         Ok(())
     }
 
+    /// Receives the revision that the server made by transforming its revisions against the
+    /// sending revision. It's based on the document after the sending revision, so it's only
+    /// transformed against the local revisions that are not sent yet, which are rebased on it.
+    pub async fn receive_transformed_bytes(&self, bytes: Bytes) -> FlowyResult<()> {
+        let server_revision = match RepeatedRevision::try_from(bytes)?.into_inner().pop() {
+            None => return Ok(()),
+            Some(server_revision) => server_revision,
+        };
+        let server_delta = Delta::<T>::from_bytes(&server_revision.delta_data)?;
+        let unsent_revisions = self.rev_manager.unsent_revisions().await?;
+        if unsent_revisions.is_empty() {
+            let _ = self.resolver.compose_delta(server_delta).await?;
+            let _ = self
+                .rev_manager
+                .add_transformed_revision(&server_revision, None)
+                .await?;
+            return Ok(());
+        }
+
+        // The local delta omits the trailing retain, but the server delta is based on the whole
+        // document.
+        let mut local_delta = make_delta_from_revisions::<T>(unsent_revisions)?;
+        if local_delta.utf16_base_len > server_delta.utf16_base_len {
+            return Err(FlowyError::internal().context(format!(
+                "The base length of the local delta is {}, but the length of the document is {}",
+                local_delta.utf16_base_len, server_delta.utf16_base_len
+            )));
+        }
+        local_delta.retain(server_delta.utf16_base_len - local_delta.utf16_base_len, T::default());
+        let (server_prime, local_prime) = server_delta.transform(&local_delta)?;
+        let md5 = self.resolver.compose_delta(server_prime).await?;
+        let local_revision = Revision::new(
+            &self.rev_manager.object_id,
+            server_revision.rev_id,
+            server_revision.rev_id + 1,
+            local_prime.to_compact_bytes(),
+            &self.user_id,
+            md5,
+        );
+        let _ = self
+            .rev_manager
+            .add_transformed_revision(&server_revision, Some(local_revision))
+            .await?;
+        Ok(())
+    }
+
     pub async fn ack_revision(&self, rev_id: String, ty: ServerRevisionWSDataType) -> FlowyResult<()> {
         let _ = self.rev_sink.ack(rev_id, ty).await?;
         Ok(())
@@ -154,7 +200,7 @@ where
         &rev_manager.object_id,
         base_rev_id,
         rev_id,
-        client_delta.to_delta_bytes(),
+        client_delta.to_compact_bytes(),
         user_id,
         md5.clone(),
     );
@@ -166,7 +212,7 @@ where
                 &rev_manager.object_id,
                 base_rev_id,
                 rev_id,
-                server_delta.to_delta_bytes(),
+                server_delta.to_compact_bytes(),
                 user_id,
                 md5,
             );
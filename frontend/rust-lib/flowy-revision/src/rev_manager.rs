@@ -1,14 +1,17 @@
 use crate::disk::RevisionState;
 use crate::{RevisionPersistence, WSDataProviderDataSource};
 use bytes::Bytes;
+use flowy_database::kv::KV;
 use flowy_error::{FlowyError, FlowyResult};
 use flowy_sync::{
-    entities::revision::{RepeatedRevision, Revision, RevisionRange},
+    entities::revision::{md5, RepeatedRevision, Revision, RevisionRange},
     util::{pair_rev_id_from_revisions, RevIdCounter},
 };
 use lib_infra::future::FutureResult;
 use std::sync::Arc;
 
+const REVISION_FORMAT_MIGRATION: &str = "REVISION_FORMAT_MIGRATION";
+
 pub trait RevisionCloudService: Send + Sync {
     fn fetch_object(&self, user_id: &str, object_id: &str) -> FutureResult<Vec<Revision>, FlowyError>;
 }
@@ -85,6 +88,21 @@ impl RevisionManager {
         B::build_object(&self.object_id, revisions)
     }
 
+    /// Re-encodes the revisions that were saved in the json format into the compact format. It
+    /// runs once for each object and should run before the object is loaded.
+    #[tracing::instrument(level = "debug", skip_all, fields(object_id=%self.object_id), err)]
+    pub async fn run_format_migration<'a>(&'a self, compactor: Box<dyn RevisionCompactor + 'a>) -> FlowyResult<()> {
+        let key = md5(format!("{}{}", self.object_id, REVISION_FORMAT_MIGRATION));
+        if KV::get_bool(&key) {
+            return Ok(());
+        }
+
+        let count = self.rev_persistence.reencode_json_revisions(compactor).await?;
+        KV::set_bool(&key, true);
+        tracing::trace!("Re-encode {} revisions of {}", count, self.object_id);
+        Ok(())
+    }
+
     #[tracing::instrument(level = "debug", skip(self, revisions), err)]
     pub async fn reset_object(&self, revisions: RepeatedRevision) -> FlowyResult<()> {
         let rev_id = pair_rev_id_from_revisions(&revisions).1;
@@ -119,6 +137,32 @@ impl RevisionManager {
     }
 
     #[tracing::instrument(level = "debug", skip(self), err)]
+    /// Saves the revision that the server transformed against the sending revision, see
+    /// [ConflictController::receive_transformed_bytes].
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn add_transformed_revision(
+        &self,
+        server_revision: &Revision,
+        local_revision: Option<Revision>,
+    ) -> FlowyResult<()> {
+        let rev_id = match &local_revision {
+            None => server_revision.rev_id,
+            Some(local_revision) => local_revision.rev_id,
+        };
+        let _ = self
+            .rev_persistence
+            .add_transformed_revision(server_revision, local_revision)
+            .await?;
+        self.rev_id_counter.set(rev_id);
+        Ok(())
+    }
+
+    /// Returns the local revisions that are not sent yet, they are sent after the sending
+    /// revision is acked.
+    pub async fn unsent_revisions(&self) -> FlowyResult<Vec<Revision>> {
+        self.rev_persistence.unsent_revisions().await
+    }
+
     pub async fn ack_revision(&self, rev_id: i64) -> Result<(), FlowyError> {
         if self.rev_persistence.ack_revision(rev_id).await.is_ok() {
             #[cfg(feature = "flowy_unit_test")]
@@ -146,6 +190,12 @@ impl RevisionManager {
         Ok(self.rev_persistence.next_sync_revision().await?)
     }
 
+    /// Sets the md5 of the local revision that was saved without it. The md5 of the document
+    /// can be computed only when the revision is sent instead of on every change.
+    pub async fn set_revision_md5(&self, rev_id: i64, md5: String) -> FlowyResult<()> {
+        self.rev_persistence.set_md5(rev_id, md5).await
+    }
+
     pub async fn get_revision(&self, rev_id: i64) -> Option<Revision> {
         self.rev_persistence.get(rev_id).await.map(|record| record.revision)
     }
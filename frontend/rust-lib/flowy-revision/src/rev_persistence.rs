@@ -7,7 +7,7 @@ use crate::memory::RevisionMemoryCache;
 use crate::RevisionCompactor;
 use flowy_database::ConnectionPool;
 use flowy_error::{internal_error, FlowyError, FlowyResult};
-use flowy_sync::entities::revision::{Revision, RevisionRange};
+use flowy_sync::entities::revision::{Revision, RevisionFormat, RevisionRange};
 use std::collections::VecDeque;
 use std::{borrow::Cow, sync::Arc};
 use tokio::sync::RwLock;
@@ -115,6 +115,69 @@ impl RevisionPersistence {
         }
     }
 
+    /// Returns the revisions that wait for the sending revision to be acked.
+    pub(crate) async fn unsent_revisions(&self) -> FlowyResult<Vec<Revision>> {
+        let rev_ids = self.sync_seq.read().await.unsent_rev_ids();
+        let mut revisions = vec![];
+        for rev_id in rev_ids {
+            match self.get(rev_id).await {
+                None => {
+                    return Err(FlowyError::record_not_found().context(format!("Can't find the revision {}", rev_id)))
+                }
+                Some(record) => revisions.push(record.revision),
+            }
+        }
+        Ok(revisions)
+    }
+
+    /// Saves the revision that the server made by transforming its revisions against the sending
+    /// revision. The unsent revisions are replaced by the `local_revision` that is rebased on it.
+    #[tracing::instrument(level = "trace", skip_all, fields(rev_id=server_revision.rev_id, object_id=%self.object_id), err)]
+    pub(crate) async fn add_transformed_revision(
+        &self,
+        server_revision: &Revision,
+        local_revision: Option<Revision>,
+    ) -> FlowyResult<()> {
+        let mut sync_seq = self.sync_seq.write().await;
+        let unsent_rev_ids = sync_seq.unsent_rev_ids();
+        if !unsent_rev_ids.is_empty() {
+            unsent_rev_ids
+                .iter()
+                .for_each(|rev_id| self.memory_cache.remove(rev_id));
+            let _ = self
+                .disk_cache
+                .delete_revision_records(&self.object_id, Some(unsent_rev_ids))?;
+        }
+
+        let mut new_seq = sync_seq.0.iter().take(1).cloned().collect::<VecDeque<i64>>();
+        self.add(server_revision.clone(), RevisionState::Ack, true).await?;
+        if let Some(local_revision) = local_revision {
+            new_seq.push_back(local_revision.rev_id);
+            self.add(local_revision, RevisionState::Sync, true).await?;
+        }
+        sync_seq.reset(new_seq);
+        Ok(())
+    }
+
+    /// Sets the md5 of the revision that was saved without it.
+    pub(crate) async fn set_md5(&self, rev_id: i64, md5: String) -> FlowyResult<()> {
+        let record = if self.memory_cache.contains(&rev_id) {
+            self.memory_cache.set_md5(&rev_id, md5).await
+        } else {
+            self.get(rev_id).await.map(|mut record| {
+                record.revision.md5 = md5;
+                record
+            })
+        };
+
+        if let Some(record) = record {
+            let _ = self
+                .disk_cache
+                .delete_and_insert_records(&self.object_id, Some(vec![rev_id]), vec![record])?;
+        }
+        Ok(())
+    }
+
     /// The cache gets reset while it conflicts with the remote revisions.
     #[tracing::instrument(level = "trace", skip(self, revisions), err)]
     pub(crate) async fn reset(&self, revisions: Vec<Revision>) -> FlowyResult<()> {
@@ -136,6 +199,45 @@ impl RevisionPersistence {
         Ok(())
     }
 
+    /// Re-encodes the revisions that are saved in the json format with the compactor, the state of
+    /// each revision is kept. Returns the number of the re-encoded revisions.
+    pub(crate) async fn reencode_json_revisions<'a>(
+        &'a self,
+        compactor: Box<dyn RevisionCompactor + 'a>,
+    ) -> FlowyResult<usize> {
+        let mut rev_ids = vec![];
+        let mut records = vec![];
+        for record in self.disk_cache.read_revision_records(&self.object_id, None)? {
+            if record.revision.format != RevisionFormat::Json {
+                continue;
+            }
+
+            let revision = &record.revision;
+            let delta_data = compactor.bytes_from_revisions(vec![revision.clone()])?;
+            rev_ids.push(revision.rev_id);
+            records.push(RevisionRecord {
+                revision: Revision::new(
+                    &self.object_id,
+                    revision.base_rev_id,
+                    revision.rev_id,
+                    delta_data,
+                    &revision.user_id,
+                    revision.md5.clone(),
+                ),
+                state: record.state,
+                write_to_disk: false,
+            });
+        }
+
+        let count = records.len();
+        if count > 0 {
+            let _ = self
+                .disk_cache
+                .delete_and_insert_records(&self.object_id, Some(rev_ids), records)?;
+        }
+        Ok(count)
+    }
+
     async fn add(&self, revision: Revision, state: RevisionState, write_to_disk: bool) -> FlowyResult<()> {
         if self.memory_cache.contains(&revision.rev_id) {
             tracing::warn!("Duplicate revision: {}:{}-{:?}", self.object_id, revision.rev_id, state);
@@ -286,6 +388,11 @@ impl RevisionSyncSequence {
         self.0.front().cloned()
     }
 
+    // The rev_ids after the current synchronizing rev_id.
+    fn unsent_rev_ids(&self) -> Vec<i64> {
+        self.0.iter().skip(1).cloned().collect()
+    }
+
     fn reset(&mut self, new_seq: VecDeque<i64>) {
         self.0 = new_seq;
     }
@@ -4,6 +4,7 @@ use async_stream::stream;
 use bytes::Bytes;
 use flowy_error::{FlowyError, FlowyResult};
 use flowy_sync::entities::{
+    presence::PresenceWSData,
     revision::{RevId, Revision, RevisionRange},
     ws_data::{ClientRevisionWSData, NewDocumentUser, ServerRevisionWSData, ServerRevisionWSDataType},
 };
@@ -26,6 +27,7 @@ pub trait RevisionWSDataStream: Send + Sync {
     fn receive_ack(&self, id: String, ty: ServerRevisionWSDataType) -> BoxResultFuture<(), FlowyError>;
     fn receive_new_user_connect(&self, new_user: NewDocumentUser) -> BoxResultFuture<(), FlowyError>;
     fn pull_revisions_in_range(&self, range: RevisionRange) -> BoxResultFuture<(), FlowyError>;
+    fn receive_transformed_revision(&self, bytes: Bytes) -> BoxResultFuture<(), FlowyError>;
 }
 
 // The sink provides the data that will be sent through the web socket to the
@@ -40,6 +42,12 @@ pub trait RevisionWebSocket: Send + Sync + 'static {
     fn subscribe_state_changed(&self) -> BoxFuture<WSStateReceiver>;
 }
 
+// Sends the presence of the current user, for example: the selection in the document. The
+// presences are ephemeral, so they don't go through the revisions.
+pub trait PresenceWebSocket: Send + Sync + 'static {
+    fn send(&self, data: PresenceWSData) -> FutureResult<(), FlowyError>;
+}
+
 pub struct RevisionWebSocketManager {
     pub object_name: String,
     pub object_id: String,
@@ -232,6 +240,10 @@ impl RevisionWSStream {
                 tracing::trace!("[{}]: new ack: {}:{}-{:?}", self, object_id, rev_id, ty);
                 let _ = self.consumer.receive_ack(rev_id.to_string(), ty).await;
             }
+            ServerRevisionWSDataType::ServerPushTransformedRev => {
+                tracing::trace!("[{}]: new transformed revision: {}:{:?}", self, object_id, ty);
+                let _ = self.consumer.receive_transformed_revision(bytes).await?;
+            }
             ServerRevisionWSDataType::UserConnect => {
                 let new_user = NewDocumentUser::try_from(bytes)?;
                 let _ = self.consumer.receive_new_user_connect(new_user).await;
@@ -1,6 +1,6 @@
 use bytes::Bytes;
 use flowy_database::ConnectionPool;
-use flowy_folder::entities::ViewDataType;
+use flowy_folder::entities::{ViewDataFormat, ViewDataType};
 use flowy_folder::manager::{ViewDataProcessor, ViewDataProcessorMap};
 use flowy_folder::{
     errors::{internal_error, FlowyError},
@@ -16,6 +16,7 @@ use flowy_net::{
 };
 use flowy_revision::{RevisionWebSocket, WSStateReceiver};
 use flowy_sync::client_document::default::initial_quill_delta_string;
+use flowy_sync::client_document::import::{html_to_delta, markdown_to_delta};
 use flowy_sync::entities::revision::{RepeatedRevision, Revision};
 use flowy_sync::entities::ws_data::ClientRevisionWSData;
 use flowy_text_block::TextBlockManager;
@@ -170,6 +171,16 @@ impl ViewDataProcessor for TextBlockViewDataProcessor {
         })
     }
 
+    fn did_rename_view(&self, view_id: &str, name: &str) -> FutureResult<(), FlowyError> {
+        let manager = self.0.clone();
+        let view_id = view_id.to_string();
+        let name = name.to_string();
+        FutureResult::new(async move {
+            let _ = manager.rename_view_links(&view_id, &name).await?;
+            Ok(())
+        })
+    }
+
     fn get_delta_data(&self, view_id: &str) -> FutureResult<Bytes, FlowyError> {
         let view_id = view_id.to_string();
         let manager = self.0.clone();
@@ -199,8 +210,22 @@ impl ViewDataProcessor for TextBlockViewDataProcessor {
         _user_id: &str,
         _view_id: &str,
         data: Vec<u8>,
+        data_format: ViewDataFormat,
     ) -> FutureResult<Bytes, FlowyError> {
-        FutureResult::new(async move { Ok(Bytes::from(data)) })
+        FutureResult::new(async move {
+            let delta_data = match data_format {
+                ViewDataFormat::Delta => Bytes::from(data),
+                ViewDataFormat::Markdown => {
+                    let markdown = String::from_utf8(data).map_err(internal_error)?;
+                    markdown_to_delta(&markdown).to_delta_bytes()
+                }
+                ViewDataFormat::Html => {
+                    let html = String::from_utf8(data).map_err(internal_error)?;
+                    html_to_delta(&html).to_delta_bytes()
+                }
+            };
+            Ok(delta_data)
+        })
     }
 
     fn data_type(&self) -> ViewDataType {
@@ -242,6 +267,11 @@ impl ViewDataProcessor for GridViewDataProcessor {
         })
     }
 
+    fn did_rename_view(&self, _view_id: &str, _name: &str) -> FutureResult<(), FlowyError> {
+        // The grid doesn't contain any page links.
+        FutureResult::new(async { Ok(()) })
+    }
+
     fn get_delta_data(&self, view_id: &str) -> FutureResult<Bytes, FlowyError> {
         let view_id = view_id.to_string();
         let grid_manager = self.0.clone();
@@ -266,12 +296,16 @@ impl ViewDataProcessor for GridViewDataProcessor {
         user_id: &str,
         view_id: &str,
         data: Vec<u8>,
+        data_format: ViewDataFormat,
     ) -> FutureResult<Bytes, FlowyError> {
         let user_id = user_id.to_string();
         let view_id = view_id.to_string();
         let grid_manager = self.0.clone();
 
         FutureResult::new(async move {
+            if data_format != ViewDataFormat::Delta {
+                return Err(FlowyError::internal().context("The grid can only be created from the delta data"));
+            }
             let bytes = Bytes::from(data);
             let build_context = BuildGridContext::try_from(bytes)?;
             make_grid_view_data(&user_id, &view_id, grid_manager, build_context).await
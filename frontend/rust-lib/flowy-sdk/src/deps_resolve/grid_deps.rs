@@ -1,14 +1,16 @@
 use crate::FlowyError;
 use bytes::Bytes;
 use flowy_database::ConnectionPool;
-use flowy_grid::manager::{GridManager, GridUser};
+use flowy_grid::manager::{GridDocumentService, GridManager, GridUser};
 use flowy_grid::services::persistence::GridDatabase;
 use flowy_net::ws::connection::FlowyWebSocketConnect;
-use flowy_revision::{RevisionWebSocket, WSStateReceiver};
+use flowy_revision::{PresenceWebSocket, RevisionWebSocket, WSStateReceiver};
+use flowy_sync::entities::revision::{RepeatedRevision, Revision};
 use flowy_sync::entities::ws_data::ClientRevisionWSData;
+use flowy_text_block::TextBlockManager;
 use flowy_user::services::UserSession;
 use futures_core::future::BoxFuture;
-use lib_infra::future::BoxResultFuture;
+use lib_infra::future::{BoxResultFuture, FutureResult};
 use lib_ws::{WSChannel, WebSocketRawMessage};
 use std::convert::TryInto;
 use std::sync::Arc;
@@ -16,13 +18,24 @@ use std::sync::Arc;
 pub struct GridDepsResolver();
 
 impl GridDepsResolver {
-    pub fn resolve(ws_conn: Arc<FlowyWebSocketConnect>, user_session: Arc<UserSession>) -> Arc<GridManager> {
+    pub fn resolve(
+        ws_conn: Arc<FlowyWebSocketConnect>,
+        presence_web_socket: Arc<dyn PresenceWebSocket>,
+        user_session: Arc<UserSession>,
+        text_block_manager: Arc<TextBlockManager>,
+    ) -> Arc<GridManager> {
         let user = Arc::new(GridUserImpl(user_session.clone()));
         let rev_web_socket = Arc::new(GridWebSocket(ws_conn));
+        let document_service = Arc::new(GridDocumentServiceImpl {
+            user_session: user_session.clone(),
+            text_block_manager,
+        });
         Arc::new(GridManager::new(
             user,
             rev_web_socket,
+            presence_web_socket,
             Arc::new(GridDatabaseImpl(user_session)),
+            document_service,
         ))
     }
 }
@@ -49,6 +62,44 @@ impl GridUser for GridUserImpl {
     }
 }
 
+struct GridDocumentServiceImpl {
+    user_session: Arc<UserSession>,
+    text_block_manager: Arc<TextBlockManager>,
+}
+
+impl GridDocumentService for GridDocumentServiceImpl {
+    fn create_document(&self, document_id: &str, delta_str: String) -> FutureResult<(), FlowyError> {
+        let user_session = self.user_session.clone();
+        let manager = self.text_block_manager.clone();
+        let document_id = document_id.to_string();
+        FutureResult::new(async move {
+            let user_id = user_session.user_id()?;
+            let repeated_revision: RepeatedRevision =
+                Revision::initial_revision(&user_id, &document_id, Bytes::from(delta_str)).into();
+            let _ = manager.create_block(document_id, repeated_revision).await?;
+            Ok(())
+        })
+    }
+
+    fn read_document(&self, document_id: &str) -> FutureResult<String, FlowyError> {
+        let manager = self.text_block_manager.clone();
+        let document_id = document_id.to_string();
+        FutureResult::new(async move {
+            let editor = manager.open_block(document_id).await?;
+            editor.delta_str().await
+        })
+    }
+
+    fn delete_document(&self, document_id: &str) -> FutureResult<(), FlowyError> {
+        let manager = self.text_block_manager.clone();
+        let document_id = document_id.to_string();
+        FutureResult::new(async move {
+            let _ = manager.delete_block(document_id)?;
+            Ok(())
+        })
+    }
+}
+
 struct GridWebSocket(Arc<FlowyWebSocketConnect>);
 impl RevisionWebSocket for GridWebSocket {
     fn send(&self, data: ClientRevisionWSData) -> BoxResultFuture<(), FlowyError> {
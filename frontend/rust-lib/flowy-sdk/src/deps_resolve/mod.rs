@@ -1,10 +1,12 @@
 mod folder_deps;
 mod grid_deps;
+mod presence_deps;
 mod text_block_deps;
 mod user_deps;
 mod util;
 
 pub use folder_deps::*;
 pub use grid_deps::*;
+pub use presence_deps::*;
 pub use text_block_deps::*;
 pub use user_deps::*;
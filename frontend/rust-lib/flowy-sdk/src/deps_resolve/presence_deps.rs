@@ -0,0 +1,64 @@
+use bytes::Bytes;
+use flowy_grid::manager::GridManager;
+use flowy_net::ws::{connection::FlowyWebSocketConnect, presence::FlowyPresenceSender};
+use flowy_revision::PresenceWebSocket;
+use flowy_sync::entities::presence::PresenceWSData;
+use flowy_text_block::{errors::FlowyError, TextBlockManager};
+use lib_infra::future::FutureResult;
+use lib_ws::{WSChannel, WSMessageReceiver, WebSocketRawMessage};
+use std::{convert::TryFrom, sync::Arc};
+
+pub struct PresenceDepsResolver();
+impl PresenceDepsResolver {
+    pub fn resolve(ws_conn: Arc<FlowyWebSocketConnect>) -> Arc<dyn PresenceWebSocket> {
+        Arc::new(PresenceWebSocketImpl(FlowyPresenceSender::new(ws_conn)))
+    }
+
+    /// The presences of the documents and the grids share the same channel, each manager
+    /// ignores the presences of the objects that it didn't open.
+    pub fn listen(
+        ws_conn: &Arc<FlowyWebSocketConnect>,
+        text_block_manager: Arc<TextBlockManager>,
+        grid_manager: Arc<GridManager>,
+    ) {
+        let receiver = Arc::new(PresenceWSMessageReceiverImpl {
+            text_block_manager,
+            grid_manager,
+        });
+        ws_conn.add_ws_message_receiver(receiver).unwrap();
+    }
+}
+
+struct PresenceWebSocketImpl(Arc<FlowyPresenceSender>);
+impl PresenceWebSocket for PresenceWebSocketImpl {
+    fn send(&self, data: PresenceWSData) -> FutureResult<(), FlowyError> {
+        let sender = self.0.clone();
+        FutureResult::new(async move { sender.send(data).await })
+    }
+}
+
+struct PresenceWSMessageReceiverImpl {
+    text_block_manager: Arc<TextBlockManager>,
+    grid_manager: Arc<GridManager>,
+}
+
+impl WSMessageReceiver for PresenceWSMessageReceiverImpl {
+    fn source(&self) -> WSChannel {
+        WSChannel::Presence
+    }
+
+    fn receive_message(&self, msg: WebSocketRawMessage) {
+        let data = match PresenceWSData::try_from(Bytes::from(msg.data)) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Presence ws data parser failed: {:?}", e);
+                return;
+            }
+        };
+        self.grid_manager.receive_presence(data.clone());
+        let text_block_manager = self.text_block_manager.clone();
+        tokio::spawn(async move {
+            text_block_manager.receive_presence(data).await;
+        });
+    }
+}
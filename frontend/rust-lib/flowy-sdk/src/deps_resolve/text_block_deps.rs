@@ -4,7 +4,7 @@ use flowy_net::ClientServerConfiguration;
 use flowy_net::{
     http_server::document::BlockHttpCloudService, local_server::LocalServer, ws::connection::FlowyWebSocketConnect,
 };
-use flowy_revision::{RevisionWebSocket, WSStateReceiver};
+use flowy_revision::{PresenceWebSocket, RevisionWebSocket, WSStateReceiver};
 use flowy_sync::entities::ws_data::ClientRevisionWSData;
 use flowy_text_block::{
     errors::{internal_error, FlowyError},
@@ -21,6 +21,7 @@ impl TextBlockDepsResolver {
     pub fn resolve(
         local_server: Option<Arc<LocalServer>>,
         ws_conn: Arc<FlowyWebSocketConnect>,
+        presence_web_socket: Arc<dyn PresenceWebSocket>,
         user_session: Arc<UserSession>,
         server_config: &ClientServerConfiguration,
     ) -> Arc<TextBlockManager> {
@@ -31,7 +32,12 @@ impl TextBlockDepsResolver {
             Some(local_server) => local_server,
         };
 
-        let manager = Arc::new(TextBlockManager::new(cloud_service, user, rev_web_socket));
+        let manager = Arc::new(TextBlockManager::new(
+            cloud_service,
+            user,
+            rev_web_socket,
+            presence_web_socket,
+        ));
         let receiver = Arc::new(DocumentWSMessageReceiverImpl(manager.clone()));
         ws_conn.add_ws_message_receiver(receiver).unwrap();
 
@@ -105,14 +105,22 @@ impl FlowySDK {
         let (local_server, ws_conn) = mk_local_server(&config.server_config);
         let (user_session, text_block_manager, folder_manager, local_server, grid_manager) = runtime.block_on(async {
             let user_session = mk_user_session(&config, &local_server, &config.server_config);
+            let presence_web_socket = PresenceDepsResolver::resolve(ws_conn.clone());
             let text_block_manager = TextBlockDepsResolver::resolve(
                 local_server.clone(),
                 ws_conn.clone(),
+                presence_web_socket.clone(),
                 user_session.clone(),
                 &config.server_config,
             );
 
-            let grid_manager = GridDepsResolver::resolve(ws_conn.clone(), user_session.clone());
+            let grid_manager = GridDepsResolver::resolve(
+                ws_conn.clone(),
+                presence_web_socket,
+                user_session.clone(),
+                text_block_manager.clone(),
+            );
+            PresenceDepsResolver::listen(&ws_conn, text_block_manager.clone(), grid_manager.clone());
 
             let folder_manager = FolderDepsResolver::resolve(
                 local_server.clone(),
@@ -99,6 +99,7 @@ async fn create_view(sdk: &FlowySDKTest, app_id: &str, data_type: ViewDataType,
         data_type,
         plugin_type: 0,
         data,
+        data_format: ViewDataFormat::Delta,
     };
 
     let view = FolderEventBuilder::new(sdk.clone())
@@ -0,0 +1,142 @@
+mod persistence;
+
+pub use persistence::*;
+
+use crate::entities::TextBlockCommentThread;
+use crate::TextBlockUser;
+use flowy_error::{FlowyError, FlowyResult};
+use lib_infra::util::timestamp;
+use lib_ot::core::Interval;
+use nanoid::nanoid;
+use std::sync::Arc;
+
+/// Reads and writes the comment threads of the document. The anchors of the threads are tracked
+/// by the document, see `ClientDocument::add_anchor`, and are saved here after they moved.
+pub struct TextBlockComments {
+    doc_id: String,
+    user: Arc<dyn TextBlockUser>,
+    persistence: Arc<TextBlockCommentPersistence>,
+}
+
+impl TextBlockComments {
+    pub fn new(doc_id: &str, user: Arc<dyn TextBlockUser>, persistence: Arc<TextBlockCommentPersistence>) -> Self {
+        Self {
+            doc_id: doc_id.to_owned(),
+            user,
+            persistence,
+        }
+    }
+
+    /// Returns the anchors of the threads, including the resolved ones, so they still move with
+    /// the text after the thread was reopened.
+    pub fn anchors(&self) -> FlowyResult<Vec<(String, Interval)>> {
+        let anchors = self
+            .persistence
+            .get_threads(&self.doc_id)?
+            .into_iter()
+            .map(|thread| {
+                let interval = Interval::new(thread.anchor_start as usize, thread.anchor_end as usize);
+                (thread.id, interval)
+            })
+            .collect();
+        Ok(anchors)
+    }
+
+    pub fn save_anchors(&self, anchors: Vec<(String, Interval)>) -> FlowyResult<()> {
+        for (thread_id, interval) in anchors {
+            let _ = self
+                .persistence
+                .update_anchor(&thread_id, interval.start as i64, interval.end as i64)?;
+        }
+        Ok(())
+    }
+
+    pub fn next_thread_id(&self) -> String {
+        nanoid!(10)
+    }
+
+    pub fn create_thread(
+        &self,
+        thread_id: String,
+        interval: Interval,
+        quote: String,
+        content: String,
+    ) -> FlowyResult<TextBlockCommentThread> {
+        let author = self.user.user_id()?;
+        let time = timestamp();
+        let thread = CommentThreadTable {
+            id: thread_id,
+            doc_id: self.doc_id.clone(),
+            anchor_start: interval.start as i64,
+            anchor_end: interval.end as i64,
+            quote,
+            resolved: false,
+            author: author.clone(),
+            create_time: time,
+            modified_time: time,
+        };
+        let comment = CommentTable {
+            id: nanoid!(10),
+            thread_id: thread.id.clone(),
+            author,
+            content,
+            create_time: time,
+        };
+        let _ = self.persistence.insert_thread(thread.clone())?;
+        let _ = self.persistence.insert_comment(comment.clone())?;
+        Ok(TextBlockCommentThread::from_table(thread, vec![comment]))
+    }
+
+    pub fn threads(&self) -> FlowyResult<Vec<TextBlockCommentThread>> {
+        let mut threads = vec![];
+        for thread in self.persistence.get_threads(&self.doc_id)? {
+            let comments = self.persistence.get_comments(&thread.id)?;
+            threads.push(TextBlockCommentThread::from_table(thread, comments));
+        }
+        Ok(threads)
+    }
+
+    pub fn thread(&self, thread_id: &str) -> FlowyResult<TextBlockCommentThread> {
+        let thread = self.get_thread(thread_id)?;
+        let comments = self.persistence.get_comments(thread_id)?;
+        Ok(TextBlockCommentThread::from_table(thread, comments))
+    }
+
+    pub fn reply(&self, thread_id: &str, content: String) -> FlowyResult<TextBlockCommentThread> {
+        let thread = self.get_thread(thread_id)?;
+        let comment = CommentTable {
+            id: nanoid!(10),
+            thread_id: thread.id,
+            author: self.user.user_id()?,
+            content,
+            create_time: timestamp(),
+        };
+        let _ = self.persistence.insert_comment(comment)?;
+        self.thread(thread_id)
+    }
+
+    pub fn set_resolved(&self, thread_id: &str, resolved: bool) -> FlowyResult<TextBlockCommentThread> {
+        let thread = self.get_thread(thread_id)?;
+        if thread.resolved != resolved {
+            let _ = self.persistence.update_resolved(&thread.id, resolved, timestamp())?;
+        }
+        self.thread(thread_id)
+    }
+
+    /// Only the author of the thread can delete it.
+    pub fn delete_thread(&self, thread_id: &str) -> FlowyResult<()> {
+        let thread = self.get_thread(thread_id)?;
+        if thread.author != self.user.user_id()? {
+            return Err(FlowyError::unauthorized().context("Only the author can delete the comment thread"));
+        }
+        self.persistence.delete_thread(&thread.id)
+    }
+
+    fn get_thread(&self, thread_id: &str) -> FlowyResult<CommentThreadTable> {
+        let thread = self.persistence.get_thread(thread_id)?;
+        if thread.doc_id != self.doc_id {
+            return Err(FlowyError::record_not_found().context(format!("Can't find the comment thread: {}", thread_id)));
+        }
+        Ok(thread)
+    }
+}
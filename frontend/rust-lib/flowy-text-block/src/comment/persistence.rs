@@ -0,0 +1,126 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use flowy_database::{
+    prelude::*,
+    schema::{text_comment_table, text_comment_thread_table},
+    ConnectionPool,
+};
+use flowy_error::{internal_error, FlowyError, FlowyResult};
+use std::sync::Arc;
+
+/// Stores the comment threads of the documents. The anchor of the thread is saved whenever the
+/// text it's attached to moves, they are local data that is not synchronized through the revisions.
+pub struct TextBlockCommentPersistence {
+    pool: Arc<ConnectionPool>,
+}
+
+impl TextBlockCommentPersistence {
+    pub fn new(pool: Arc<ConnectionPool>) -> Self {
+        Self { pool }
+    }
+
+    pub fn get_threads(&self, doc_id: &str) -> FlowyResult<Vec<CommentThreadTable>> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let threads = text_comment_thread_table::dsl::text_comment_thread_table
+            .filter(text_comment_thread_table::doc_id.eq(doc_id))
+            .order(text_comment_thread_table::create_time.asc())
+            .load::<CommentThreadTable>(&*conn)?;
+        Ok(threads)
+    }
+
+    pub fn get_thread(&self, thread_id: &str) -> FlowyResult<CommentThreadTable> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let thread = text_comment_thread_table::dsl::text_comment_thread_table
+            .filter(text_comment_thread_table::id.eq(thread_id))
+            .first::<CommentThreadTable>(&*conn)?;
+        Ok(thread)
+    }
+
+    pub fn insert_thread(&self, thread: CommentThreadTable) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let _ = diesel::insert_into(text_comment_thread_table::table)
+            .values(thread)
+            .execute(&*conn)?;
+        Ok(())
+    }
+
+    pub fn update_anchor(&self, thread_id: &str, anchor_start: i64, anchor_end: i64) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let filter = text_comment_thread_table::dsl::text_comment_thread_table
+            .filter(text_comment_thread_table::id.eq(thread_id));
+        let _ = diesel::update(filter)
+            .set((
+                text_comment_thread_table::anchor_start.eq(anchor_start),
+                text_comment_thread_table::anchor_end.eq(anchor_end),
+            ))
+            .execute(&*conn)?;
+        Ok(())
+    }
+
+    pub fn update_resolved(&self, thread_id: &str, resolved: bool, modified_time: i64) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let filter = text_comment_thread_table::dsl::text_comment_thread_table
+            .filter(text_comment_thread_table::id.eq(thread_id));
+        let _ = diesel::update(filter)
+            .set((
+                text_comment_thread_table::resolved.eq(resolved),
+                text_comment_thread_table::modified_time.eq(modified_time),
+            ))
+            .execute(&*conn)?;
+        Ok(())
+    }
+
+    /// Deletes the thread and its comments.
+    pub fn delete_thread(&self, thread_id: &str) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        conn.immediate_transaction::<_, FlowyError, _>(|| {
+            let comments =
+                text_comment_table::dsl::text_comment_table.filter(text_comment_table::thread_id.eq(thread_id));
+            let _ = diesel::delete(comments).execute(&*conn)?;
+            let thread = text_comment_thread_table::dsl::text_comment_thread_table
+                .filter(text_comment_thread_table::id.eq(thread_id));
+            let _ = diesel::delete(thread).execute(&*conn)?;
+            Ok(())
+        })
+    }
+
+    pub fn get_comments(&self, thread_id: &str) -> FlowyResult<Vec<CommentTable>> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let comments = text_comment_table::dsl::text_comment_table
+            .filter(text_comment_table::thread_id.eq(thread_id))
+            .order(text_comment_table::create_time.asc())
+            .load::<CommentTable>(&*conn)?;
+        Ok(comments)
+    }
+
+    pub fn insert_comment(&self, comment: CommentTable) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let _ = diesel::insert_into(text_comment_table::table)
+            .values(comment)
+            .execute(&*conn)?;
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Queryable, Identifiable, Insertable)]
+#[table_name = "text_comment_thread_table"]
+pub struct CommentThreadTable {
+    pub id: String,
+    pub doc_id: String,
+    pub anchor_start: i64,
+    pub anchor_end: i64,
+    pub quote: String,
+    pub resolved: bool,
+    pub author: String,
+    pub create_time: i64,
+    pub modified_time: i64,
+}
+
+#[derive(PartialEq, Clone, Debug, Queryable, Identifiable, Insertable)]
+#[table_name = "text_comment_table"]
+pub struct CommentTable {
+    pub id: String,
+    pub thread_id: String,
+    pub author: String,
+    pub content: String,
+    pub create_time: i64,
+}
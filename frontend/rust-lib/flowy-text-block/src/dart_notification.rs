@@ -0,0 +1,26 @@
+use dart_notify::DartNotifyBuilder;
+use flowy_derive::ProtoBuf_Enum;
+const OBSERVABLE_CATEGORY: &str = "TextBlock";
+
+#[derive(ProtoBuf_Enum, Debug)]
+pub(crate) enum TextBlockNotification {
+    Unknown = 0,
+    DidUpdateSelections = 10,
+}
+
+impl std::default::Default for TextBlockNotification {
+    fn default() -> Self {
+        TextBlockNotification::Unknown
+    }
+}
+
+impl std::convert::From<TextBlockNotification> for i32 {
+    fn from(notification: TextBlockNotification) -> Self {
+        notification as i32
+    }
+}
+
+#[tracing::instrument(level = "trace")]
+pub(crate) fn send_dart_notification(id: &str, ty: TextBlockNotification) -> DartNotifyBuilder {
+    DartNotifyBuilder::new(id, ty, OBSERVABLE_CATEGORY)
+}
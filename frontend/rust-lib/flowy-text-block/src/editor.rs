@@ -1,12 +1,18 @@
 use crate::web_socket::EditorCommandSender;
 use crate::{
+    comment::{TextBlockCommentPersistence, TextBlockComments},
+    entities::{CreateCommentThreadParams, TextBlockCommentThread, TextBlockVersionGroup, TextDiffSpan},
     errors::FlowyError,
-    queue::{EditBlockQueue, EditorCommand},
+    history::{TextBlockHistory, TextBlockHistoryPersistence},
+    link::TextBlockLinkPersistence,
+    queue::{EditBlockQueue, EditorCommand, TextBlockRevisionCompactor},
     TextBlockUser,
 };
 use bytes::Bytes;
 use flowy_error::{internal_error, FlowyResult};
 use flowy_revision::{RevisionCloudService, RevisionManager, RevisionObjectBuilder, RevisionWebSocket};
+use flowy_sync::client_document::find::DocumentFinder;
+use flowy_sync::client_document::outline::{DocumentStats, OutlineHeader};
 use flowy_sync::entities::ws_data::ServerRevisionWSData;
 use flowy_sync::{
     entities::{revision::Revision, text_block::TextBlockInfo},
@@ -14,7 +20,7 @@ use flowy_sync::{
     util::make_delta_from_revisions,
 };
 use lib_ot::{
-    core::{Interval, Operation},
+    core::{trim, Interval, Operation},
     rich_text::{RichTextAttribute, RichTextDelta},
 };
 use lib_ws::WSConnectState;
@@ -23,8 +29,9 @@ use tokio::sync::{mpsc, oneshot};
 
 pub struct TextBlockEditor {
     pub doc_id: String,
-    #[allow(dead_code)]
     rev_manager: Arc<RevisionManager>,
+    history: Arc<TextBlockHistory>,
+    comments: Arc<TextBlockComments>,
     #[cfg(feature = "sync")]
     ws_manager: Arc<flowy_revision::RevisionWebSocketManager>,
     edit_cmd_tx: EditorCommandSender,
@@ -38,26 +45,44 @@ impl TextBlockEditor {
         mut rev_manager: RevisionManager,
         rev_web_socket: Arc<dyn RevisionWebSocket>,
         cloud_service: Arc<dyn RevisionCloudService>,
+        history_persistence: Arc<TextBlockHistoryPersistence>,
+        comment_persistence: Arc<TextBlockCommentPersistence>,
+        link_persistence: Arc<TextBlockLinkPersistence>,
     ) -> FlowyResult<Arc<Self>> {
+        let _ = rev_manager
+            .run_format_migration(Box::new(TextBlockRevisionCompactor()))
+            .await?;
         let document_info = rev_manager.load::<TextBlockInfoBuilder>(Some(cloud_service)).await?;
         let delta = document_info.delta()?;
         let rev_manager = Arc::new(rev_manager);
         let doc_id = doc_id.to_string();
         let user_id = user.user_id()?;
+        let history = Arc::new(TextBlockHistory::new(&doc_id, rev_manager.clone(), history_persistence));
+        let comments = Arc::new(TextBlockComments::new(&doc_id, user.clone(), comment_persistence));
 
-        let edit_cmd_tx = spawn_edit_queue(user, rev_manager.clone(), delta);
+        let edit_cmd_tx = spawn_edit_queue(
+            user,
+            rev_manager.clone(),
+            history.clone(),
+            comments.clone(),
+            link_persistence,
+            delta,
+        );
         #[cfg(feature = "sync")]
         let ws_manager = crate::web_socket::make_block_ws_manager(
             doc_id.clone(),
             user_id.clone(),
             edit_cmd_tx.clone(),
             rev_manager.clone(),
+            history.clone(),
             rev_web_socket,
         )
         .await;
         let editor = Arc::new(Self {
             doc_id,
             rev_manager,
+            history,
+            comments,
             #[cfg(feature = "sync")]
             ws_manager,
             edit_cmd_tx,
@@ -109,6 +134,100 @@ impl TextBlockEditor {
         Ok(())
     }
 
+    /// Returns the utf16 intervals of the text that matches the finder.
+    pub async fn find(&self, finder: DocumentFinder) -> Result<Vec<Interval>, FlowyError> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<Vec<Interval>>>();
+        let msg = EditorCommand::Find { finder, ret };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let matches = rx.await.map_err(internal_error)??;
+        Ok(matches)
+    }
+
+    /// Reports the selection of the current user. The selections are transformed against the
+    /// following changes, including the ones received from the server, so they keep covering the
+    /// same text.
+    pub async fn update_selection(&self, interval: Interval) -> Result<(), FlowyError> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<()>>();
+        let msg = EditorCommand::UpdateSelection { interval, ret };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let _ = rx.await.map_err(internal_error)??;
+        Ok(())
+    }
+
+    /// Updates the selection of another user that was received from the presence channel. The
+    /// selection is removed if the interval is None. The interval is in the document at the
+    /// revision `rev_id` of the user, it's held until the revision is received.
+    pub(crate) async fn update_remote_selection(
+        &self,
+        user_id: String,
+        interval: Option<Interval>,
+        rev_id: i64,
+    ) -> Result<(), FlowyError> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<()>>();
+        let msg = EditorCommand::UpdateRemoteSelection {
+            user_id,
+            interval,
+            rev_id,
+            ret,
+        };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let _ = rx.await.map_err(internal_error)??;
+        Ok(())
+    }
+
+    /// The id of the latest revision of the block, the selection of the current user is sent with
+    /// it.
+    pub(crate) fn rev_id(&self) -> i64 {
+        self.rev_manager.rev_id()
+    }
+
+    /// Returns the selections of the users, ordered by the id of the user.
+    pub async fn selections(&self) -> Result<Vec<(String, Interval)>, FlowyError> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<Vec<(String, Interval)>>>();
+        let msg = EditorCommand::ReadSelections { ret };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let selections = rx.await.map_err(internal_error)??;
+        Ok(selections)
+    }
+
+    /// Returns the headers of the document, they are updated as the document changes, so reading
+    /// them doesn't parse the document again.
+    pub async fn outline(&self) -> Result<Vec<OutlineHeader>, FlowyError> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<Vec<OutlineHeader>>>();
+        let msg = EditorCommand::ReadOutline { ret };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let outline = rx.await.map_err(internal_error)??;
+        Ok(outline)
+    }
+
+    pub async fn stats(&self) -> Result<DocumentStats, FlowyError> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<DocumentStats>>();
+        let msg = EditorCommand::ReadStats { ret };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let stats = rx.await.map_err(internal_error)??;
+        Ok(stats)
+    }
+
+    /// Replaces the matches of the finder, all the matches are replaced if the `selected` is None.
+    /// The replacement is saved as one revision, and it's undone in one step.
+    pub async fn replace_matches(
+        &self,
+        finder: DocumentFinder,
+        replacement: String,
+        selected: Option<Vec<Interval>>,
+    ) -> Result<(), FlowyError> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<()>>();
+        let msg = EditorCommand::ReplaceMatches {
+            finder,
+            replacement,
+            selected,
+            ret,
+        };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let _ = rx.await.map_err(internal_error)??;
+        Ok(())
+    }
+
     pub async fn can_undo(&self) -> bool {
         let (ret, rx) = oneshot::channel::<bool>();
         let msg = EditorCommand::CanUndo { ret };
@@ -123,20 +242,22 @@ impl TextBlockEditor {
         rx.await.unwrap_or(false)
     }
 
-    pub async fn undo(&self) -> Result<(), FlowyError> {
+    /// Returns the selection before the undone change, the caller should restore it.
+    pub async fn undo(&self) -> Result<Interval, FlowyError> {
         let (ret, rx) = oneshot::channel();
         let msg = EditorCommand::Undo { ret };
         let _ = self.edit_cmd_tx.send(msg).await;
-        let _ = rx.await.map_err(internal_error)??;
-        Ok(())
+        let selection = rx.await.map_err(internal_error)??;
+        Ok(selection)
     }
 
-    pub async fn redo(&self) -> Result<(), FlowyError> {
+    /// Returns the selection after the redone change, the caller should restore it.
+    pub async fn redo(&self) -> Result<Interval, FlowyError> {
         let (ret, rx) = oneshot::channel();
         let msg = EditorCommand::Redo { ret };
         let _ = self.edit_cmd_tx.send(msg).await;
-        let _ = rx.await.map_err(internal_error)??;
-        Ok(())
+        let selection = rx.await.map_err(internal_error)??;
+        Ok(selection)
     }
 
     pub async fn delta_str(&self) -> FlowyResult<String> {
@@ -147,6 +268,84 @@ impl TextBlockEditor {
         Ok(json)
     }
 
+    /// Returns the versions of the document grouped by the editing sessions.
+    pub fn versions(&self) -> FlowyResult<Vec<TextBlockVersionGroup>> {
+        self.history.versions()
+    }
+
+    pub async fn delta_str_at_version(&self, rev_id: i64) -> FlowyResult<String> {
+        let delta = self.history.delta_at_revision(rev_id).await?;
+        Ok(delta.to_delta_str())
+    }
+
+    pub async fn diff_versions(&self, from_rev_id: i64, to_rev_id: i64) -> FlowyResult<Vec<TextDiffSpan>> {
+        self.history.diff(from_rev_id, to_rev_id).await
+    }
+
+    /// Restores the document to the version. The restore is saved as a new revision, so the
+    /// versions after it are kept and the restore can be undone.
+    pub async fn restore_version(&self, rev_id: i64) -> FlowyResult<()> {
+        let mut delta = self.history.restore_delta(rev_id).await?;
+        trim(&mut delta);
+        if delta.is_empty() {
+            return Ok(());
+        }
+        self.compose_local_delta(delta.to_delta_bytes()).await
+    }
+
+    /// Creates the comment thread that is attached to the text in the interval, the content is
+    /// the first comment of the thread.
+    pub async fn create_comment_thread(
+        &self,
+        params: CreateCommentThreadParams,
+    ) -> FlowyResult<TextBlockCommentThread> {
+        let thread_id = self.comments.next_thread_id();
+        let (ret, rx) = oneshot::channel::<CollaborateResult<String>>();
+        let msg = EditorCommand::AddAnchor {
+            id: thread_id.clone(),
+            interval: params.interval,
+            ret,
+        };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let quote = rx.await.map_err(internal_error)??;
+        match self
+            .comments
+            .create_thread(thread_id.clone(), params.interval, quote, params.content)
+        {
+            Ok(thread) => Ok(thread),
+            Err(e) => {
+                let _ = self.remove_anchor(&thread_id).await;
+                Err(e)
+            }
+        }
+    }
+
+    pub fn comment_threads(&self) -> FlowyResult<Vec<TextBlockCommentThread>> {
+        self.comments.threads()
+    }
+
+    pub fn reply_comment_thread(&self, thread_id: &str, content: String) -> FlowyResult<TextBlockCommentThread> {
+        self.comments.reply(thread_id, content)
+    }
+
+    /// The anchor of the resolved thread keeps moving with the text, so the thread can be reopened.
+    pub fn set_comment_thread_resolved(&self, thread_id: &str, resolved: bool) -> FlowyResult<TextBlockCommentThread> {
+        self.comments.set_resolved(thread_id, resolved)
+    }
+
+    pub async fn delete_comment_thread(&self, thread_id: &str) -> FlowyResult<()> {
+        let _ = self.comments.delete_thread(thread_id)?;
+        self.remove_anchor(thread_id).await
+    }
+
+    async fn remove_anchor(&self, id: &str) -> FlowyResult<()> {
+        let (ret, rx) = oneshot::channel::<CollaborateResult<()>>();
+        let msg = EditorCommand::RemoveAnchor { id: id.to_owned(), ret };
+        let _ = self.edit_cmd_tx.send(msg).await;
+        let _ = rx.await.map_err(internal_error)??;
+        Ok(())
+    }
+
     #[tracing::instrument(level = "trace", skip(self, data), err)]
     pub(crate) async fn compose_local_delta(&self, data: Bytes) -> Result<(), FlowyError> {
         let delta = RichTextDelta::from_bytes(&data)?;
@@ -195,10 +394,13 @@ impl std::ops::Drop for TextBlockEditor {
 fn spawn_edit_queue(
     user: Arc<dyn TextBlockUser>,
     rev_manager: Arc<RevisionManager>,
+    history: Arc<TextBlockHistory>,
+    comments: Arc<TextBlockComments>,
+    link_persistence: Arc<TextBlockLinkPersistence>,
     delta: RichTextDelta,
 ) -> EditorCommandSender {
     let (sender, receiver) = mpsc::channel(1000);
-    let edit_queue = EditBlockQueue::new(user, rev_manager, delta, receiver);
+    let edit_queue = EditBlockQueue::new(user, rev_manager, history, comments, link_persistence, delta, receiver);
     // We can use tokio::task::spawn_local here by using tokio::spawn_blocking.
     // https://github.com/tokio-rs/tokio/issues/2095
     // tokio::task::spawn_blocking(move || {
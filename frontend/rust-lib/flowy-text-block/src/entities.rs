@@ -1,5 +1,9 @@
+use crate::comment::{CommentTable, CommentThreadTable};
 use crate::errors::ErrorCode;
 use flowy_derive::{ProtoBuf, ProtoBuf_Enum};
+use flowy_sync::client_document::find::{DocumentFinder, FindOptions};
+use flowy_sync::client_document::outline::{BlockType, DocumentStats, OutlineHeader};
+use lib_ot::core::Interval;
 use std::convert::TryInto;
 
 #[derive(PartialEq, Debug, ProtoBuf_Enum, Clone)]
@@ -7,6 +11,7 @@ pub enum ExportType {
     Text = 0,
     Markdown = 1,
     Link = 2,
+    Html = 3,
 }
 
 impl std::default::Default for ExportType {
@@ -21,6 +26,7 @@ impl std::convert::From<i32> for ExportType {
             0 => ExportType::Text,
             1 => ExportType::Markdown,
             2 => ExportType::Link,
+            3 => ExportType::Html,
             _ => {
                 log::error!("Invalid export type: {}", val);
                 ExportType::Text
@@ -62,3 +68,634 @@ pub struct ExportData {
     #[pb(index = 2)]
     pub export_type: ExportType,
 }
+
+#[derive(PartialEq, Debug, ProtoBuf_Enum, Clone)]
+pub enum ImportType {
+    ImportMarkdown = 0,
+    ImportHtml = 1,
+}
+
+impl std::default::Default for ImportType {
+    fn default() -> Self {
+        ImportType::ImportMarkdown
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct ImportPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub import_type: ImportType,
+
+    #[pb(index = 3)]
+    pub data: String,
+}
+
+#[derive(Default, Debug)]
+pub struct ImportParams {
+    pub view_id: String,
+    pub import_type: ImportType,
+    pub data: String,
+}
+
+impl TryInto<ImportParams> for ImportPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<ImportParams, Self::Error> {
+        Ok(ImportParams {
+            view_id: self.view_id,
+            import_type: self.import_type,
+            data: self.data,
+        })
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct TextBlockVersion {
+    #[pb(index = 1)]
+    pub rev_id: i64,
+
+    #[pb(index = 2)]
+    pub author: String,
+
+    #[pb(index = 3)]
+    pub create_time: i64,
+}
+
+/// The versions of the same author that were made in one editing session.
+#[derive(Default, ProtoBuf)]
+pub struct TextBlockVersionGroup {
+    #[pb(index = 1)]
+    pub author: String,
+
+    #[pb(index = 2)]
+    pub start_time: i64,
+
+    #[pb(index = 3)]
+    pub end_time: i64,
+
+    #[pb(index = 4)]
+    pub versions: Vec<TextBlockVersion>,
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct RepeatedTextBlockVersionGroup {
+    #[pb(index = 1)]
+    pub items: Vec<TextBlockVersionGroup>,
+}
+
+impl std::convert::From<Vec<TextBlockVersionGroup>> for RepeatedTextBlockVersionGroup {
+    fn from(items: Vec<TextBlockVersionGroup>) -> Self {
+        Self { items }
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct TextBlockVersionPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub rev_id: i64,
+}
+
+#[derive(Default, Debug)]
+pub struct TextBlockVersionParams {
+    pub view_id: String,
+    pub rev_id: i64,
+}
+
+impl TryInto<TextBlockVersionParams> for TextBlockVersionPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<TextBlockVersionParams, Self::Error> {
+        Ok(TextBlockVersionParams {
+            view_id: self.view_id,
+            rev_id: self.rev_id,
+        })
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct TextBlockVersionDiffPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub from_rev_id: i64,
+
+    #[pb(index = 3)]
+    pub to_rev_id: i64,
+}
+
+#[derive(Default, Debug)]
+pub struct TextBlockVersionDiffParams {
+    pub view_id: String,
+    pub from_rev_id: i64,
+    pub to_rev_id: i64,
+}
+
+impl TryInto<TextBlockVersionDiffParams> for TextBlockVersionDiffPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<TextBlockVersionDiffParams, Self::Error> {
+        Ok(TextBlockVersionDiffParams {
+            view_id: self.view_id,
+            from_rev_id: self.from_rev_id,
+            to_rev_id: self.to_rev_id,
+        })
+    }
+}
+
+#[derive(PartialEq, Debug, ProtoBuf_Enum, Clone)]
+pub enum TextDiffType {
+    Equal = 0,
+    Insert = 1,
+    Delete = 2,
+}
+
+impl std::default::Default for TextDiffType {
+    fn default() -> Self {
+        TextDiffType::Equal
+    }
+}
+
+#[derive(PartialEq, Debug, Default, ProtoBuf, Clone)]
+pub struct TextDiffSpan {
+    #[pb(index = 1)]
+    pub ty: TextDiffType,
+
+    #[pb(index = 2)]
+    pub text: String,
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct TextBlockVersionDiff {
+    #[pb(index = 1)]
+    pub from_rev_id: i64,
+
+    #[pb(index = 2)]
+    pub to_rev_id: i64,
+
+    #[pb(index = 3)]
+    pub spans: Vec<TextDiffSpan>,
+}
+
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct TextBlockComment {
+    #[pb(index = 1)]
+    pub id: String,
+
+    #[pb(index = 2)]
+    pub author: String,
+
+    #[pb(index = 3)]
+    pub content: String,
+
+    #[pb(index = 4)]
+    pub create_time: i64,
+}
+
+impl std::convert::From<CommentTable> for TextBlockComment {
+    fn from(table: CommentTable) -> Self {
+        Self {
+            id: table.id,
+            author: table.author,
+            content: table.content,
+            create_time: table.create_time,
+        }
+    }
+}
+
+/// The comments that are attached to the text in [start, end) of the document. The anchor moves
+/// with the text, and it's collapsed if the text was deleted. The `quote` is the text that was
+/// commented when the thread was created.
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct TextBlockCommentThread {
+    #[pb(index = 1)]
+    pub id: String,
+
+    #[pb(index = 2)]
+    pub view_id: String,
+
+    #[pb(index = 3)]
+    pub start: i64,
+
+    #[pb(index = 4)]
+    pub end: i64,
+
+    #[pb(index = 5)]
+    pub quote: String,
+
+    #[pb(index = 6)]
+    pub resolved: bool,
+
+    #[pb(index = 7)]
+    pub collapsed: bool,
+
+    #[pb(index = 8)]
+    pub author: String,
+
+    #[pb(index = 9)]
+    pub create_time: i64,
+
+    #[pb(index = 10)]
+    pub modified_time: i64,
+
+    #[pb(index = 11)]
+    pub comments: Vec<TextBlockComment>,
+}
+
+impl TextBlockCommentThread {
+    pub fn from_table(table: CommentThreadTable, comments: Vec<CommentTable>) -> Self {
+        Self {
+            id: table.id,
+            view_id: table.doc_id,
+            start: table.anchor_start,
+            end: table.anchor_end,
+            quote: table.quote,
+            resolved: table.resolved,
+            collapsed: table.anchor_start == table.anchor_end,
+            author: table.author,
+            create_time: table.create_time,
+            modified_time: table.modified_time,
+            comments: comments.into_iter().map(TextBlockComment::from).collect(),
+        }
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct RepeatedTextBlockCommentThread {
+    #[pb(index = 1)]
+    pub items: Vec<TextBlockCommentThread>,
+}
+
+impl std::convert::From<Vec<TextBlockCommentThread>> for RepeatedTextBlockCommentThread {
+    fn from(items: Vec<TextBlockCommentThread>) -> Self {
+        Self { items }
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct CreateCommentThreadPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub start: i64,
+
+    #[pb(index = 3)]
+    pub end: i64,
+
+    #[pb(index = 4)]
+    pub content: String,
+}
+
+#[derive(Debug)]
+pub struct CreateCommentThreadParams {
+    pub view_id: String,
+    pub interval: Interval,
+    pub content: String,
+}
+
+impl TryInto<CreateCommentThreadParams> for CreateCommentThreadPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<CreateCommentThreadParams, Self::Error> {
+        let view_id = parse_not_empty(self.view_id, ErrorCode::ViewIdInvalid)?;
+        let content = parse_not_empty(self.content, ErrorCode::CommentIsEmpty)?;
+        if self.start < 0 || self.end < self.start {
+            return Err(ErrorCode::InvalidData);
+        }
+        Ok(CreateCommentThreadParams {
+            view_id,
+            interval: Interval::new(self.start as usize, self.end as usize),
+            content,
+        })
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct ReplyCommentThreadPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub thread_id: String,
+
+    #[pb(index = 3)]
+    pub content: String,
+}
+
+#[derive(Debug)]
+pub struct ReplyCommentThreadParams {
+    pub view_id: String,
+    pub thread_id: String,
+    pub content: String,
+}
+
+impl TryInto<ReplyCommentThreadParams> for ReplyCommentThreadPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<ReplyCommentThreadParams, Self::Error> {
+        Ok(ReplyCommentThreadParams {
+            view_id: parse_not_empty(self.view_id, ErrorCode::ViewIdInvalid)?,
+            thread_id: parse_not_empty(self.thread_id, ErrorCode::CommentThreadIdIsEmpty)?,
+            content: parse_not_empty(self.content, ErrorCode::CommentIsEmpty)?,
+        })
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct CommentThreadIdPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub thread_id: String,
+}
+
+#[derive(Debug)]
+pub struct CommentThreadIdParams {
+    pub view_id: String,
+    pub thread_id: String,
+}
+
+impl TryInto<CommentThreadIdParams> for CommentThreadIdPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<CommentThreadIdParams, Self::Error> {
+        Ok(CommentThreadIdParams {
+            view_id: parse_not_empty(self.view_id, ErrorCode::ViewIdInvalid)?,
+            thread_id: parse_not_empty(self.thread_id, ErrorCode::CommentThreadIdIsEmpty)?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct DocumentMatch {
+    #[pb(index = 1)]
+    pub start: i64,
+
+    #[pb(index = 2)]
+    pub end: i64,
+}
+
+impl std::convert::From<Interval> for DocumentMatch {
+    fn from(interval: Interval) -> Self {
+        Self {
+            start: interval.start as i64,
+            end: interval.end as i64,
+        }
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct RepeatedDocumentMatch {
+    #[pb(index = 1)]
+    pub items: Vec<DocumentMatch>,
+}
+
+impl std::convert::From<Vec<Interval>> for RepeatedDocumentMatch {
+    fn from(intervals: Vec<Interval>) -> Self {
+        let items = intervals.into_iter().map(DocumentMatch::from).collect();
+        Self { items }
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct FindInDocumentPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub query: String,
+
+    #[pb(index = 3)]
+    pub match_case: bool,
+
+    #[pb(index = 4)]
+    pub whole_word: bool,
+
+    #[pb(index = 5)]
+    pub regex: bool,
+}
+
+pub struct FindInDocumentParams {
+    pub view_id: String,
+    pub finder: DocumentFinder,
+}
+
+impl TryInto<FindInDocumentParams> for FindInDocumentPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<FindInDocumentParams, Self::Error> {
+        let view_id = parse_not_empty(self.view_id, ErrorCode::ViewIdInvalid)?;
+        let options = FindOptions {
+            match_case: self.match_case,
+            whole_word: self.whole_word,
+            regex: self.regex,
+        };
+        let finder = DocumentFinder::new(&self.query, options).map_err(|_| ErrorCode::FindQueryInvalid)?;
+        Ok(FindInDocumentParams { view_id, finder })
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct ReplaceInDocumentPayload {
+    #[pb(index = 1)]
+    pub find: FindInDocumentPayload,
+
+    #[pb(index = 2)]
+    pub replacement: String,
+
+    /// Replaces all the matches if it's true, otherwise only the `selected` matches are replaced.
+    #[pb(index = 3)]
+    pub replace_all: bool,
+
+    #[pb(index = 4)]
+    pub selected: Vec<DocumentMatch>,
+}
+
+pub struct ReplaceInDocumentParams {
+    pub view_id: String,
+    pub finder: DocumentFinder,
+    pub replacement: String,
+    pub selected: Option<Vec<Interval>>,
+}
+
+impl TryInto<ReplaceInDocumentParams> for ReplaceInDocumentPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<ReplaceInDocumentParams, Self::Error> {
+        let FindInDocumentParams { view_id, finder } = self.find.try_into()?;
+        let selected = match self.replace_all {
+            true => None,
+            false => Some(
+                self.selected
+                    .into_iter()
+                    .filter(|m| m.start >= 0 && m.start <= m.end)
+                    .map(|m| Interval::new(m.start as usize, m.end as usize))
+                    .collect(),
+            ),
+        };
+        Ok(ReplaceInDocumentParams {
+            view_id,
+            finder,
+            replacement: self.replacement,
+            selected,
+        })
+    }
+}
+
+fn parse_not_empty(s: String, error: ErrorCode) -> Result<String, ErrorCode> {
+    if s.trim().is_empty() {
+        return Err(error);
+    }
+    Ok(s)
+}
+
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct DocumentHeader {
+    #[pb(index = 1)]
+    pub level: i64,
+
+    #[pb(index = 2)]
+    pub text: String,
+
+    /// The utf16 offset of the start of the header.
+    #[pb(index = 3)]
+    pub offset: i64,
+}
+
+impl std::convert::From<OutlineHeader> for DocumentHeader {
+    fn from(header: OutlineHeader) -> Self {
+        Self {
+            level: header.level as i64,
+            text: header.text,
+            offset: header.offset as i64,
+        }
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct RepeatedDocumentHeader {
+    #[pb(index = 1)]
+    pub items: Vec<DocumentHeader>,
+}
+
+impl std::convert::From<Vec<OutlineHeader>> for RepeatedDocumentHeader {
+    fn from(headers: Vec<OutlineHeader>) -> Self {
+        let items = headers.into_iter().map(DocumentHeader::from).collect();
+        Self { items }
+    }
+}
+
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct DocumentStatistics {
+    #[pb(index = 1)]
+    pub words: i64,
+
+    #[pb(index = 2)]
+    pub characters: i64,
+
+    #[pb(index = 3)]
+    pub reading_time_in_secs: i64,
+
+    #[pb(index = 4)]
+    pub paragraphs: i64,
+
+    #[pb(index = 5)]
+    pub headings: i64,
+
+    #[pb(index = 6)]
+    pub bulleted_lists: i64,
+
+    #[pb(index = 7)]
+    pub numbered_lists: i64,
+
+    #[pb(index = 8)]
+    pub todo_lists: i64,
+
+    #[pb(index = 9)]
+    pub code_blocks: i64,
+
+    #[pb(index = 10)]
+    pub quotes: i64,
+}
+
+impl std::convert::From<DocumentStats> for DocumentStatistics {
+    fn from(stats: DocumentStats) -> Self {
+        let count = |block_type: BlockType| stats.blocks.get(&block_type).cloned().unwrap_or(0) as i64;
+        Self {
+            words: stats.words as i64,
+            characters: stats.characters as i64,
+            reading_time_in_secs: stats.reading_time_in_secs() as i64,
+            paragraphs: count(BlockType::Paragraph),
+            headings: count(BlockType::Heading),
+            bulleted_lists: count(BlockType::BulletedList),
+            numbered_lists: count(BlockType::NumberedList),
+            todo_lists: count(BlockType::TodoList),
+            code_blocks: count(BlockType::CodeBlock),
+            quotes: count(BlockType::Quote),
+        }
+    }
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct TextBlockSelectionPayload {
+    #[pb(index = 1)]
+    pub view_id: String,
+
+    #[pb(index = 2)]
+    pub start: i64,
+
+    #[pb(index = 3)]
+    pub end: i64,
+}
+
+#[derive(Debug)]
+pub struct TextBlockSelectionParams {
+    pub view_id: String,
+    pub interval: Interval,
+}
+
+impl TryInto<TextBlockSelectionParams> for TextBlockSelectionPayload {
+    type Error = ErrorCode;
+    fn try_into(self) -> Result<TextBlockSelectionParams, Self::Error> {
+        let view_id = parse_not_empty(self.view_id, ErrorCode::ViewIdInvalid)?;
+        if self.start < 0 || self.end < self.start {
+            return Err(ErrorCode::InvalidData);
+        }
+        Ok(TextBlockSelectionParams {
+            view_id,
+            interval: Interval::new(self.start as usize, self.end as usize),
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, ProtoBuf)]
+pub struct TextBlockSelection {
+    #[pb(index = 1)]
+    pub user_id: String,
+
+    #[pb(index = 2)]
+    pub start: i64,
+
+    #[pb(index = 3)]
+    pub end: i64,
+}
+
+#[derive(Default, ProtoBuf)]
+pub struct RepeatedTextBlockSelection {
+    #[pb(index = 1)]
+    pub items: Vec<TextBlockSelection>,
+}
+
+impl std::convert::From<Vec<(String, Interval)>> for RepeatedTextBlockSelection {
+    fn from(selections: Vec<(String, Interval)>) -> Self {
+        let items = selections
+            .into_iter()
+            .map(|(user_id, interval)| TextBlockSelection {
+                user_id,
+                start: interval.start as i64,
+                end: interval.end as i64,
+            })
+            .collect();
+        Self { items }
+    }
+}
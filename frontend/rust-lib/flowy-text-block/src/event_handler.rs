@@ -1,8 +1,19 @@
-use crate::entities::{ExportData, ExportParams, ExportPayload};
+use crate::entities::{
+    CommentThreadIdParams, CommentThreadIdPayload, CreateCommentThreadParams, CreateCommentThreadPayload,
+    DocumentStatistics, ExportData, ExportParams, ExportPayload, ExportType, FindInDocumentParams,
+    FindInDocumentPayload, ImportParams, ImportPayload, ImportType, RepeatedDocumentHeader, RepeatedDocumentMatch,
+    RepeatedTextBlockCommentThread, RepeatedTextBlockSelection, RepeatedTextBlockVersionGroup, ReplaceInDocumentParams,
+    ReplaceInDocumentPayload, ReplyCommentThreadParams, ReplyCommentThreadPayload, TextBlockCommentThread,
+    TextBlockSelectionParams, TextBlockSelectionPayload, TextBlockVersionDiff, TextBlockVersionDiffParams,
+    TextBlockVersionDiffPayload, TextBlockVersionParams, TextBlockVersionPayload,
+};
 use crate::TextBlockManager;
 use flowy_error::FlowyError;
+use flowy_sync::client_document::export::{delta_to_html, delta_to_markdown, delta_to_plain_text};
+use flowy_sync::client_document::import::{html_to_delta, markdown_to_delta};
 use flowy_sync::entities::text_block::{TextBlockDelta, TextBlockId};
 use lib_dispatch::prelude::{data_result, AppData, Data, DataResult};
+use lib_ot::rich_text::RichTextDelta;
 use std::convert::TryInto;
 use std::sync::Arc;
 
@@ -35,8 +46,214 @@ pub(crate) async fn export_handler(
     let params: ExportParams = data.into_inner().try_into()?;
     let editor = manager.open_block(&params.view_id).await?;
     let delta_json = editor.delta_str().await?;
+    let data = match params.export_type {
+        ExportType::Link => delta_json,
+        ExportType::Text => delta_to_plain_text(&RichTextDelta::from_delta_str(&delta_json)?),
+        ExportType::Markdown => delta_to_markdown(&RichTextDelta::from_delta_str(&delta_json)?),
+        ExportType::Html => delta_to_html(&RichTextDelta::from_delta_str(&delta_json)?),
+    };
     data_result(ExportData {
-        data: delta_json,
+        data,
         export_type: params.export_type,
     })
 }
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn import_handler(
+    data: Data<ImportPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockDelta, FlowyError> {
+    let params: ImportParams = data.into_inner().try_into()?;
+    let delta = match params.import_type {
+        ImportType::ImportMarkdown => markdown_to_delta(&params.data),
+        ImportType::ImportHtml => html_to_delta(&params.data),
+    };
+    let block_delta = manager.replace_block_delta(&params.view_id, delta).await?;
+    data_result(block_delta)
+}
+
+pub(crate) async fn get_versions_handler(
+    data: Data<TextBlockId>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<RepeatedTextBlockVersionGroup, FlowyError> {
+    let block_id: TextBlockId = data.into_inner();
+    let editor = manager.open_block(&block_id).await?;
+    let groups = editor.versions()?;
+    data_result(groups.into())
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn get_version_data_handler(
+    data: Data<TextBlockVersionPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockDelta, FlowyError> {
+    let params: TextBlockVersionParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let delta_str = editor.delta_str_at_version(params.rev_id).await?;
+    data_result(TextBlockDelta {
+        block_id: params.view_id,
+        delta_str,
+    })
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn diff_versions_handler(
+    data: Data<TextBlockVersionDiffPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockVersionDiff, FlowyError> {
+    let params: TextBlockVersionDiffParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let spans = editor.diff_versions(params.from_rev_id, params.to_rev_id).await?;
+    data_result(TextBlockVersionDiff {
+        from_rev_id: params.from_rev_id,
+        to_rev_id: params.to_rev_id,
+        spans,
+    })
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn restore_version_handler(
+    data: Data<TextBlockVersionPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockDelta, FlowyError> {
+    let params: TextBlockVersionParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let _ = editor.restore_version(params.rev_id).await?;
+    let delta_str = editor.delta_str().await?;
+    data_result(TextBlockDelta {
+        block_id: params.view_id,
+        delta_str,
+    })
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn create_comment_thread_handler(
+    data: Data<CreateCommentThreadPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockCommentThread, FlowyError> {
+    let params: CreateCommentThreadParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let thread = editor.create_comment_thread(params).await?;
+    data_result(thread)
+}
+
+pub(crate) async fn get_comment_threads_handler(
+    data: Data<TextBlockId>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<RepeatedTextBlockCommentThread, FlowyError> {
+    let block_id: TextBlockId = data.into_inner();
+    let editor = manager.open_block(&block_id).await?;
+    let threads = editor.comment_threads()?;
+    data_result(threads.into())
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn reply_comment_thread_handler(
+    data: Data<ReplyCommentThreadPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockCommentThread, FlowyError> {
+    let params: ReplyCommentThreadParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let thread = editor.reply_comment_thread(&params.thread_id, params.content)?;
+    data_result(thread)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn resolve_comment_thread_handler(
+    data: Data<CommentThreadIdPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockCommentThread, FlowyError> {
+    let params: CommentThreadIdParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let thread = editor.set_comment_thread_resolved(&params.thread_id, true)?;
+    data_result(thread)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn reopen_comment_thread_handler(
+    data: Data<CommentThreadIdPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockCommentThread, FlowyError> {
+    let params: CommentThreadIdParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let thread = editor.set_comment_thread_resolved(&params.thread_id, false)?;
+    data_result(thread)
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn delete_comment_thread_handler(
+    data: Data<CommentThreadIdPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> Result<(), FlowyError> {
+    let params: CommentThreadIdParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let _ = editor.delete_comment_thread(&params.thread_id).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn find_in_document_handler(
+    data: Data<FindInDocumentPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<RepeatedDocumentMatch, FlowyError> {
+    let params: FindInDocumentParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let matches = editor.find(params.finder).await?;
+    data_result(matches.into())
+}
+
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
+pub(crate) async fn replace_in_document_handler(
+    data: Data<ReplaceInDocumentPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<TextBlockDelta, FlowyError> {
+    let params: ReplaceInDocumentParams = data.into_inner().try_into()?;
+    let editor = manager.open_block(&params.view_id).await?;
+    let _ = editor
+        .replace_matches(params.finder, params.replacement, params.selected)
+        .await?;
+    let delta_str = editor.delta_str().await?;
+    data_result(TextBlockDelta {
+        block_id: params.view_id,
+        delta_str,
+    })
+}
+
+pub(crate) async fn get_document_outline_handler(
+    data: Data<TextBlockId>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<RepeatedDocumentHeader, FlowyError> {
+    let block_id: TextBlockId = data.into_inner();
+    let editor = manager.open_block(&block_id).await?;
+    let outline = editor.outline().await?;
+    data_result(outline.into())
+}
+
+pub(crate) async fn get_document_stats_handler(
+    data: Data<TextBlockId>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<DocumentStatistics, FlowyError> {
+    let block_id: TextBlockId = data.into_inner();
+    let editor = manager.open_block(&block_id).await?;
+    let stats = editor.stats().await?;
+    data_result(stats.into())
+}
+
+pub(crate) async fn update_selection_handler(
+    data: Data<TextBlockSelectionPayload>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> Result<(), FlowyError> {
+    let params: TextBlockSelectionParams = data.into_inner().try_into()?;
+    let _ = manager.update_selection(&params.view_id, params.interval).await?;
+    Ok(())
+}
+
+pub(crate) async fn get_selections_handler(
+    data: Data<TextBlockId>,
+    manager: AppData<Arc<TextBlockManager>>,
+) -> DataResult<RepeatedTextBlockSelection, FlowyError> {
+    let block_id: TextBlockId = data.into_inner();
+    let editor = manager.open_block(&block_id).await?;
+    let selections = editor.selections().await?;
+    data_result(selections.into())
+}
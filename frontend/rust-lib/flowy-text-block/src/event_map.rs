@@ -11,7 +11,24 @@ pub fn create(block_manager: Arc<TextBlockManager>) -> Module {
     module = module
         .event(TextBlockEvent::GetBlockData, get_block_data_handler)
         .event(TextBlockEvent::ApplyDelta, apply_delta_handler)
-        .event(TextBlockEvent::ExportDocument, export_handler);
+        .event(TextBlockEvent::ExportDocument, export_handler)
+        .event(TextBlockEvent::ImportDocument, import_handler)
+        .event(TextBlockEvent::GetVersions, get_versions_handler)
+        .event(TextBlockEvent::GetVersionData, get_version_data_handler)
+        .event(TextBlockEvent::DiffVersions, diff_versions_handler)
+        .event(TextBlockEvent::RestoreVersion, restore_version_handler)
+        .event(TextBlockEvent::CreateCommentThread, create_comment_thread_handler)
+        .event(TextBlockEvent::GetCommentThreads, get_comment_threads_handler)
+        .event(TextBlockEvent::ReplyCommentThread, reply_comment_thread_handler)
+        .event(TextBlockEvent::ResolveCommentThread, resolve_comment_thread_handler)
+        .event(TextBlockEvent::ReopenCommentThread, reopen_comment_thread_handler)
+        .event(TextBlockEvent::DeleteCommentThread, delete_comment_thread_handler)
+        .event(TextBlockEvent::FindInDocument, find_in_document_handler)
+        .event(TextBlockEvent::ReplaceInDocument, replace_in_document_handler)
+        .event(TextBlockEvent::GetDocumentOutline, get_document_outline_handler)
+        .event(TextBlockEvent::GetDocumentStats, get_document_stats_handler)
+        .event(TextBlockEvent::UpdateSelection, update_selection_handler)
+        .event(TextBlockEvent::GetSelections, get_selections_handler);
 
     module
 }
@@ -27,4 +44,55 @@ pub enum TextBlockEvent {
 
     #[event(input = "ExportPayload", output = "ExportData")]
     ExportDocument = 2,
+
+    #[event(input = "ImportPayload", output = "TextBlockDelta")]
+    ImportDocument = 3,
+
+    #[event(input = "TextBlockId", output = "RepeatedTextBlockVersionGroup")]
+    GetVersions = 4,
+
+    #[event(input = "TextBlockVersionPayload", output = "TextBlockDelta")]
+    GetVersionData = 5,
+
+    #[event(input = "TextBlockVersionDiffPayload", output = "TextBlockVersionDiff")]
+    DiffVersions = 6,
+
+    #[event(input = "TextBlockVersionPayload", output = "TextBlockDelta")]
+    RestoreVersion = 7,
+
+    #[event(input = "CreateCommentThreadPayload", output = "TextBlockCommentThread")]
+    CreateCommentThread = 8,
+
+    #[event(input = "TextBlockId", output = "RepeatedTextBlockCommentThread")]
+    GetCommentThreads = 9,
+
+    #[event(input = "ReplyCommentThreadPayload", output = "TextBlockCommentThread")]
+    ReplyCommentThread = 10,
+
+    #[event(input = "CommentThreadIdPayload", output = "TextBlockCommentThread")]
+    ResolveCommentThread = 11,
+
+    #[event(input = "CommentThreadIdPayload", output = "TextBlockCommentThread")]
+    ReopenCommentThread = 12,
+
+    #[event(input = "CommentThreadIdPayload")]
+    DeleteCommentThread = 13,
+
+    #[event(input = "FindInDocumentPayload", output = "RepeatedDocumentMatch")]
+    FindInDocument = 14,
+
+    #[event(input = "ReplaceInDocumentPayload", output = "TextBlockDelta")]
+    ReplaceInDocument = 15,
+
+    #[event(input = "TextBlockId", output = "RepeatedDocumentHeader")]
+    GetDocumentOutline = 16,
+
+    #[event(input = "TextBlockId", output = "DocumentStatistics")]
+    GetDocumentStats = 17,
+
+    #[event(input = "TextBlockSelectionPayload")]
+    UpdateSelection = 18,
+
+    #[event(input = "TextBlockId", output = "RepeatedTextBlockSelection")]
+    GetSelections = 19,
 }
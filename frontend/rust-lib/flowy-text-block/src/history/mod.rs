@@ -0,0 +1,206 @@
+mod persistence;
+
+pub use persistence::*;
+
+use crate::entities::{TextBlockVersion, TextBlockVersionGroup, TextDiffSpan, TextDiffType};
+use flowy_error::{FlowyError, FlowyResult};
+use flowy_revision::RevisionManager;
+use flowy_sync::{
+    entities::revision::{md5, Revision, RevisionRange},
+    util::cal_diff,
+};
+use lib_infra::util::timestamp;
+use lib_ot::{
+    core::{FlowyStr, Interval, Operation, OperationTransformable, PlainTextAttributes},
+    rich_text::RichTextDelta,
+};
+use std::sync::Arc;
+
+/// The document is saved as a checkpoint after replaying every `CHECKPOINT_INTERVAL` revisions,
+/// so reading the document at the later revisions starts from the checkpoint.
+pub const CHECKPOINT_INTERVAL: usize = 100;
+
+/// The consecutive revisions of the same author are grouped into one editing session if the
+/// time between them is less than the interval, in seconds.
+pub const SESSION_INTERVAL_IN_SECONDS: i64 = 10 * 60;
+
+/// Reads the versions of the document from its revisions. Each revision is a version of the
+/// document, and the document at the revision is rebuilt by composing the revisions before it.
+pub struct TextBlockHistory {
+    doc_id: String,
+    rev_manager: Arc<RevisionManager>,
+    persistence: Arc<TextBlockHistoryPersistence>,
+}
+
+impl TextBlockHistory {
+    pub fn new(doc_id: &str, rev_manager: Arc<RevisionManager>, persistence: Arc<TextBlockHistoryPersistence>) -> Self {
+        Self {
+            doc_id: doc_id.to_owned(),
+            rev_manager,
+            persistence,
+        }
+    }
+
+    pub fn record_revision(&self, rev_id: i64, author: &str) -> FlowyResult<()> {
+        self.persistence.save_revision_meta(NewRevisionMeta {
+            doc_id: self.doc_id.clone(),
+            rev_id,
+            author: author.to_owned(),
+            create_time: timestamp(),
+        })
+    }
+
+    /// Returns the versions grouped by the editing sessions, the oldest session comes first.
+    pub fn versions(&self) -> FlowyResult<Vec<TextBlockVersionGroup>> {
+        let metas = self
+            .persistence
+            .get_revision_metas(&self.doc_id, self.rev_manager.rev_id())?;
+        Ok(group_versions(metas))
+    }
+
+    pub async fn delta_at_revision(&self, rev_id: i64) -> FlowyResult<RichTextDelta> {
+        if rev_id < 0 || rev_id > self.rev_manager.rev_id() {
+            return Err(FlowyError::record_not_found().context(format!("Can't find the version: {}", rev_id)));
+        }
+
+        let (mut delta, start) = match self.checkpoint_before(rev_id).await? {
+            None => (RichTextDelta::default(), 0),
+            Some(checkpoint) => (RichTextDelta::from_bytes(&checkpoint.data)?, checkpoint.rev_id + 1),
+        };
+
+        let mut replayed = 0;
+        for revision in self.revisions_in_range(start, rev_id).await? {
+            let revision_delta = RichTextDelta::from_bytes(&revision.delta_data)?;
+            delta = delta.compose(&revision_delta)?;
+            replayed += 1;
+            if replayed % CHECKPOINT_INTERVAL == 0 {
+                let _ = self.persistence.save_checkpoint(NewRevisionCheckpoint {
+                    doc_id: self.doc_id.clone(),
+                    rev_id: revision.rev_id,
+                    rev_md5: md5(&revision.delta_data),
+                    data: delta.to_compact_bytes().to_vec(),
+                })?;
+            }
+        }
+        Ok(delta)
+    }
+
+    /// Returns the changes of the text from the version `from_rev_id` to the version `to_rev_id`.
+    pub async fn diff(&self, from_rev_id: i64, to_rev_id: i64) -> FlowyResult<Vec<TextDiffSpan>> {
+        let old = self.delta_at_revision(from_rev_id).await?.to_str()?;
+        let new = self.delta_at_revision(to_rev_id).await?.to_str()?;
+        Ok(diff_text(old, new))
+    }
+
+    /// Returns the delta that turns the current document into the document at the rev_id. The
+    /// delta is applied as a new revision, so the revisions after the rev_id are kept.
+    pub async fn restore_delta(&self, rev_id: i64) -> FlowyResult<RichTextDelta> {
+        let delta = self.delta_at_revision(rev_id).await?;
+        let mut changes = RichTextDelta::default();
+        for revision in self.revisions_in_range(rev_id + 1, self.rev_manager.rev_id()).await? {
+            changes = changes.compose(&RichTextDelta::from_bytes(&revision.delta_data)?)?;
+        }
+
+        // The invert of the changes against the empty document is empty, so delete everything.
+        if delta.is_empty() {
+            let mut restore_delta = RichTextDelta::default();
+            restore_delta.delete(changes.utf16_target_len);
+            return Ok(restore_delta);
+        }
+        Ok(changes.invert(&delta))
+    }
+
+    async fn checkpoint_before(&self, rev_id: i64) -> FlowyResult<Option<RevisionCheckpointTable>> {
+        let checkpoint = match self.persistence.get_checkpoint(&self.doc_id, rev_id)? {
+            None => return Ok(None),
+            Some(checkpoint) => checkpoint,
+        };
+
+        // The revisions are replaced if they conflict with the remote revisions, the checkpoints
+        // built from the old revisions are useless then.
+        match self.rev_manager.get_revision(checkpoint.rev_id).await {
+            Some(revision) if md5(&revision.delta_data) == checkpoint.rev_md5 => Ok(Some(checkpoint)),
+            _ => {
+                let _ = self.persistence.delete_checkpoints(&self.doc_id)?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn revisions_in_range(&self, start: i64, end: i64) -> FlowyResult<Vec<Revision>> {
+        if start > end {
+            return Ok(vec![]);
+        }
+
+        let mut revisions = self
+            .rev_manager
+            .get_revisions_in_range(RevisionRange { start, end })
+            .await?;
+
+        // The revisions that are not written to disk yet are only kept in memory.
+        let mut next_rev_id = revisions.last().map(|revision| revision.rev_id + 1).unwrap_or(start);
+        while next_rev_id <= end {
+            if let Some(revision) = self.rev_manager.get_revision(next_rev_id).await {
+                revisions.push(revision);
+            }
+            next_rev_id += 1;
+        }
+        Ok(revisions)
+    }
+}
+
+fn group_versions(metas: Vec<RevisionMetaTable>) -> Vec<TextBlockVersionGroup> {
+    let mut groups: Vec<TextBlockVersionGroup> = vec![];
+    for meta in metas {
+        let version = TextBlockVersion {
+            rev_id: meta.rev_id,
+            author: meta.author,
+            create_time: meta.create_time,
+        };
+
+        match groups.last_mut() {
+            Some(group)
+                if group.author == version.author
+                    && version.create_time - group.end_time < SESSION_INTERVAL_IN_SECONDS =>
+            {
+                group.end_time = version.create_time;
+                group.versions.push(version);
+            }
+            _ => groups.push(TextBlockVersionGroup {
+                author: version.author.clone(),
+                start_time: version.create_time,
+                end_time: version.create_time,
+                versions: vec![version],
+            }),
+        }
+    }
+    groups
+}
+
+fn diff_text(old: String, new: String) -> Vec<TextDiffSpan> {
+    let old_str = FlowyStr::from(old.as_str());
+    let diff = match cal_diff::<PlainTextAttributes>(old, new) {
+        None => return vec![],
+        Some(diff) => diff,
+    };
+
+    let mut spans = vec![];
+    let mut index = 0;
+    for op in &diff.ops {
+        let (ty, text) = match op {
+            Operation::Insert(insert) => (TextDiffType::Insert, insert.s.to_string()),
+            Operation::Retain(_) | Operation::Delete(_) => {
+                let interval = Interval::new(index, index + op.len());
+                index = interval.end;
+                let ty = match op {
+                    Operation::Delete(_) => TextDiffType::Delete,
+                    _ => TextDiffType::Equal,
+                };
+                (ty, old_str.sub_str(interval).unwrap_or_default())
+            }
+            Operation::Embed(_) => continue,
+        };
+        spans.push(TextDiffSpan { ty, text });
+    }
+    spans
+}
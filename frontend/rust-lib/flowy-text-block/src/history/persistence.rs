@@ -0,0 +1,107 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use flowy_database::{
+    prelude::*,
+    schema::{text_rev_checkpoint_table, text_rev_history_table},
+    ConnectionPool,
+};
+use flowy_error::{internal_error, FlowyResult};
+use std::sync::Arc;
+
+/// Stores the author and the time of each revision of the documents, and the snapshots of the
+/// documents at some revisions. They are local data that is not synchronized through the revisions.
+pub struct TextBlockHistoryPersistence {
+    pool: Arc<ConnectionPool>,
+}
+
+impl TextBlockHistoryPersistence {
+    pub fn new(pool: Arc<ConnectionPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Saves the author and the time of the revision. The record is replaced if the rev_id was
+    /// reused, for example: the unsynchronized revisions were compacted into one.
+    pub fn save_revision_meta(&self, meta: NewRevisionMeta) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let _ = diesel::replace_into(text_rev_history_table::table)
+            .values(meta)
+            .execute(&*conn)?;
+        Ok(())
+    }
+
+    /// Returns the records of the revisions whose rev_id is less than or equal to the rev_id.
+    pub fn get_revision_metas(&self, doc_id: &str, rev_id: i64) -> FlowyResult<Vec<RevisionMetaTable>> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let metas = text_rev_history_table::dsl::text_rev_history_table
+            .filter(text_rev_history_table::doc_id.eq(doc_id))
+            .filter(text_rev_history_table::rev_id.le(rev_id))
+            .order(text_rev_history_table::rev_id.asc())
+            .load::<RevisionMetaTable>(&*conn)?;
+        Ok(metas)
+    }
+
+    /// Returns the latest checkpoint whose rev_id is less than or equal to the rev_id.
+    pub fn get_checkpoint(&self, doc_id: &str, rev_id: i64) -> FlowyResult<Option<RevisionCheckpointTable>> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let mut checkpoints = text_rev_checkpoint_table::dsl::text_rev_checkpoint_table
+            .filter(text_rev_checkpoint_table::doc_id.eq(doc_id))
+            .filter(text_rev_checkpoint_table::rev_id.le(rev_id))
+            .order(text_rev_checkpoint_table::rev_id.desc())
+            .limit(1)
+            .load::<RevisionCheckpointTable>(&*conn)?;
+        Ok(checkpoints.pop())
+    }
+
+    pub fn save_checkpoint(&self, checkpoint: NewRevisionCheckpoint) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let _ = diesel::replace_into(text_rev_checkpoint_table::table)
+            .values(checkpoint)
+            .execute(&*conn)?;
+        Ok(())
+    }
+
+    pub fn delete_checkpoints(&self, doc_id: &str) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let filter = text_rev_checkpoint_table::dsl::text_rev_checkpoint_table
+            .filter(text_rev_checkpoint_table::doc_id.eq(doc_id));
+        let _ = diesel::delete(filter).execute(&*conn)?;
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Queryable)]
+pub struct RevisionMetaTable {
+    pub id: i32,
+    pub doc_id: String,
+    pub rev_id: i64,
+    pub author: String,
+    pub create_time: i64,
+}
+
+/// The id of the record is generated by the database.
+#[derive(Clone, Debug, Insertable)]
+#[table_name = "text_rev_history_table"]
+pub struct NewRevisionMeta {
+    pub doc_id: String,
+    pub rev_id: i64,
+    pub author: String,
+    pub create_time: i64,
+}
+
+#[derive(PartialEq, Clone, Debug, Queryable)]
+pub struct RevisionCheckpointTable {
+    pub id: i32,
+    pub doc_id: String,
+    pub rev_id: i64,
+    pub rev_md5: String,
+    pub data: Vec<u8>,
+}
+
+/// The id of the checkpoint is generated by the database.
+#[derive(Clone, Debug, Insertable)]
+#[table_name = "text_rev_checkpoint_table"]
+pub struct NewRevisionCheckpoint {
+    pub doc_id: String,
+    pub rev_id: i64,
+    pub rev_md5: String,
+    pub data: Vec<u8>,
+}
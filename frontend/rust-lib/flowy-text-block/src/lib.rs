@@ -1,7 +1,11 @@
+mod comment;
+mod dart_notification;
 pub mod editor;
-mod entities;
+pub mod entities;
 mod event_handler;
 pub mod event_map;
+mod history;
+pub mod link;
 pub mod manager;
 mod queue;
 mod web_socket;
@@ -0,0 +1,90 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use flowy_database::{prelude::*, schema::text_link_table, ConnectionPool};
+use flowy_error::{internal_error, FlowyError, FlowyResult};
+use flowy_sync::client_document::links::ViewLink;
+use std::sync::Arc;
+
+/// The index of the links between the documents and the views, it's updated whenever the document
+/// is saved. The link is broken if the view it links to is in the trash.
+pub struct TextBlockLinkPersistence {
+    pool: Arc<ConnectionPool>,
+}
+
+impl TextBlockLinkPersistence {
+    pub fn new(pool: Arc<ConnectionPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Replaces the links of the document with the links. The new link to the view that is in the
+    /// trash is broken too.
+    pub fn save_links(&self, source_id: &str, links: Vec<ViewLink>) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        conn.immediate_transaction::<_, FlowyError, _>(|| {
+            let target_ids = links.iter().map(|link| link.view_id.clone()).collect::<Vec<String>>();
+            let removed = text_link_table::dsl::text_link_table
+                .filter(text_link_table::source_id.eq(source_id))
+                .filter(text_link_table::target_id.ne_all(&target_ids));
+            let _ = diesel::delete(removed).execute(&*conn)?;
+
+            let existing = text_link_table::dsl::text_link_table
+                .filter(text_link_table::source_id.eq(source_id))
+                .select(text_link_table::target_id)
+                .load::<String>(&*conn)?;
+            for link in links {
+                if existing.contains(&link.view_id) {
+                    let filter = text_link_table::dsl::text_link_table
+                        .filter(text_link_table::source_id.eq(source_id))
+                        .filter(text_link_table::target_id.eq(&link.view_id));
+                    let _ = diesel::update(filter)
+                        .set(text_link_table::title.eq(&link.title))
+                        .execute(&*conn)?;
+                } else {
+                    let broken = text_link_table::dsl::text_link_table
+                        .filter(text_link_table::target_id.eq(&link.view_id))
+                        .filter(text_link_table::broken.eq(true))
+                        .count()
+                        .get_result::<i64>(&*conn)?
+                        > 0;
+                    let _ = diesel::insert_into(text_link_table::table)
+                        .values((
+                            text_link_table::source_id.eq(source_id),
+                            text_link_table::target_id.eq(&link.view_id),
+                            text_link_table::title.eq(&link.title),
+                            text_link_table::broken.eq(broken),
+                        ))
+                        .execute(&*conn)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the links to the view, they are the backlinks of the view.
+    pub fn get_backlinks(&self, target_id: &str) -> FlowyResult<Vec<LinkTable>> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let links = text_link_table::dsl::text_link_table
+            .filter(text_link_table::target_id.eq(target_id))
+            .order(text_link_table::id.asc())
+            .load::<LinkTable>(&*conn)?;
+        Ok(links)
+    }
+
+    pub fn set_broken(&self, target_ids: &[String], broken: bool) -> FlowyResult<()> {
+        let conn = self.pool.get().map_err(internal_error)?;
+        let filter = text_link_table::dsl::text_link_table.filter(text_link_table::target_id.eq_any(target_ids));
+        let _ = diesel::update(filter)
+            .set(text_link_table::broken.eq(broken))
+            .execute(&*conn)?;
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Queryable, Identifiable)]
+#[table_name = "text_link_table"]
+pub struct LinkTable {
+    pub id: i32,
+    pub source_id: String,
+    pub target_id: String,
+    pub title: String,
+    pub broken: bool,
+}
@@ -1,16 +1,24 @@
-use crate::{editor::TextBlockEditor, errors::FlowyError, BlockCloudService};
+use crate::{
+    comment::TextBlockCommentPersistence, editor::TextBlockEditor, errors::FlowyError,
+    history::TextBlockHistoryPersistence, link::TextBlockLinkPersistence, BlockCloudService,
+};
 use bytes::Bytes;
 use dashmap::DashMap;
 use flowy_database::ConnectionPool;
 use flowy_error::FlowyResult;
 use flowy_revision::disk::SQLiteTextBlockRevisionPersistence;
-use flowy_revision::{RevisionCloudService, RevisionManager, RevisionPersistence, RevisionWebSocket};
+use flowy_revision::{
+    PresenceWebSocket, RevisionCloudService, RevisionManager, RevisionPersistence, RevisionWebSocket,
+};
+use flowy_sync::client_document::links::rename_view_links;
 use flowy_sync::entities::{
+    presence::{PresenceWSData, PresenceWSDataType},
     revision::{md5, RepeatedRevision, Revision},
     text_block::{TextBlockDelta, TextBlockId},
     ws_data::ServerRevisionWSData,
 };
 use lib_infra::future::FutureResult;
+use lib_ot::{core::Interval, rich_text::RichTextDelta};
 use std::{convert::TryInto, sync::Arc};
 
 pub trait TextBlockUser: Send + Sync {
@@ -23,6 +31,7 @@ pub trait TextBlockUser: Send + Sync {
 pub struct TextBlockManager {
     cloud_service: Arc<dyn BlockCloudService>,
     rev_web_socket: Arc<dyn RevisionWebSocket>,
+    presence_web_socket: Arc<dyn PresenceWebSocket>,
     editor_map: Arc<TextBlockEditorMap>,
     user: Arc<dyn TextBlockUser>,
 }
@@ -32,10 +41,12 @@ impl TextBlockManager {
         cloud_service: Arc<dyn BlockCloudService>,
         text_block_user: Arc<dyn TextBlockUser>,
         rev_web_socket: Arc<dyn RevisionWebSocket>,
+        presence_web_socket: Arc<dyn PresenceWebSocket>,
     ) -> Self {
         Self {
             cloud_service,
             rev_web_socket,
+            presence_web_socket,
             editor_map: Arc::new(TextBlockEditorMap::new()),
             user: text_block_user,
         }
@@ -51,13 +62,16 @@ impl TextBlockManager {
     pub async fn open_block<T: AsRef<str>>(&self, block_id: T) -> Result<Arc<TextBlockEditor>, FlowyError> {
         let block_id = block_id.as_ref();
         tracing::Span::current().record("block_id", &block_id);
-        self.get_block_editor(block_id).await
+        let editor = self.get_block_editor(block_id).await?;
+        self.join_block(block_id);
+        Ok(editor)
     }
 
     #[tracing::instrument(level = "trace", skip(self, block_id), fields(block_id), err)]
     pub fn close_block<T: AsRef<str>>(&self, block_id: T) -> Result<(), FlowyError> {
         let block_id = block_id.as_ref();
         tracing::Span::current().record("block_id", &block_id);
+        self.leave_block(block_id);
         self.editor_map.remove(block_id);
         Ok(())
     }
@@ -66,10 +80,46 @@ impl TextBlockManager {
     pub fn delete_block<T: AsRef<str>>(&self, doc_id: T) -> Result<(), FlowyError> {
         let doc_id = doc_id.as_ref();
         tracing::Span::current().record("doc_id", &doc_id);
+        self.leave_block(doc_id);
         self.editor_map.remove(doc_id);
         Ok(())
     }
 
+    /// Updates the selection of the current user and sends it to the other users viewing the
+    /// block.
+    pub async fn update_selection(&self, block_id: &str, interval: Interval) -> FlowyResult<()> {
+        let editor = self.get_block_editor(block_id).await?;
+        let _ = editor.update_selection(interval).await?;
+        let user_id = self.user.user_id()?;
+        let data = PresenceWSData::selection(block_id, &user_id, editor.rev_id(), interval);
+        let _ = self.presence_web_socket.send(data).await?;
+        Ok(())
+    }
+
+    /// Receives the presence of another user. The selection of the user is transformed against
+    /// the following changes of the block until the user moves it again or leaves. The selection
+    /// that was made in a revision that isn't received yet is set when the revision arrives.
+    pub async fn receive_presence(&self, data: PresenceWSData) {
+        let editor = match self.editor_map.get(&data.object_id) {
+            None => return,
+            Some(editor) => editor,
+        };
+        let interval = match data.ty {
+            PresenceWSDataType::Heartbeat => return,
+            PresenceWSDataType::Leave => None,
+            PresenceWSDataType::Update => match data.selection.as_ref() {
+                None => return,
+                Some(selection) => Some(Interval::from(selection)),
+            },
+        };
+        if let Err(e) = editor
+            .update_remote_selection(data.user_id, interval, data.rev_id)
+            .await
+        {
+            tracing::error!("Update the selection of {} failed: {}", data.object_id, e);
+        }
+    }
+
     #[tracing::instrument(level = "debug", skip(self, delta), fields(doc_id = %delta.block_id), err)]
     pub async fn receive_local_delta(&self, delta: TextBlockDelta) -> Result<TextBlockDelta, FlowyError> {
         let editor = self.get_block_editor(&delta.block_id).await?;
@@ -81,6 +131,46 @@ impl TextBlockManager {
         })
     }
 
+    /// Replaces the content of the block with the delta, for example: the delta of the imported
+    /// markdown. The change is applied as a local edit, so it can be undone and synced.
+    pub async fn replace_block_delta<T: AsRef<str>>(
+        &self,
+        block_id: T,
+        delta: RichTextDelta,
+    ) -> Result<TextBlockDelta, FlowyError> {
+        let block_id = block_id.as_ref();
+        let editor = self.get_block_editor(block_id).await?;
+        let document = RichTextDelta::from_delta_str(&editor.delta_str().await?)?;
+        let mut changeset = delta;
+        changeset.delete(document.utf16_target_len);
+        let _ = editor.compose_local_delta(changeset.to_delta_bytes()).await?;
+        let document_json = editor.delta_str().await?;
+        Ok(TextBlockDelta {
+            block_id: block_id.to_owned(),
+            delta_str: document_json,
+        })
+    }
+
+    /// Updates the text of the page links to the view after the view was renamed. The documents
+    /// that were closed are closed again after they were updated.
+    #[tracing::instrument(level = "debug", skip(self, name), err)]
+    pub async fn rename_view_links(&self, view_id: &str, name: &str) -> FlowyResult<()> {
+        let db_pool = self.user.db_pool()?;
+        let backlinks = TextBlockLinkPersistence::new(db_pool).get_backlinks(view_id)?;
+        for backlink in backlinks {
+            let is_opened = self.editor_map.get(&backlink.source_id).is_some();
+            let editor = self.get_block_editor(&backlink.source_id).await?;
+            let document = RichTextDelta::from_delta_str(&editor.delta_str().await?)?;
+            if let Some(delta) = rename_view_links(&document, view_id, name) {
+                let _ = editor.compose_local_delta(delta.to_delta_bytes()).await?;
+            }
+            if !is_opened {
+                self.editor_map.remove(&backlink.source_id);
+            }
+        }
+        Ok(())
+    }
+
     pub async fn create_block<T: AsRef<str>>(&self, doc_id: T, revisions: RepeatedRevision) -> FlowyResult<()> {
         let doc_id = doc_id.as_ref().to_owned();
         let db_pool = self.user.db_pool()?;
@@ -108,6 +198,36 @@ impl TextBlockManager {
 }
 
 impl TextBlockManager {
+    /// Sends the heartbeat of the block, which makes the other users see the current user and
+    /// keeps sending the heartbeats until the user leaves the block.
+    fn join_block(&self, block_id: &str) {
+        let user_id = match self.user.user_id() {
+            Ok(user_id) => user_id,
+            Err(_) => return,
+        };
+        self.send_presence(PresenceWSData::heartbeat(block_id, &user_id));
+    }
+
+    fn leave_block(&self, block_id: &str) {
+        if self.editor_map.get(block_id).is_none() {
+            return;
+        }
+        let user_id = match self.user.user_id() {
+            Ok(user_id) => user_id,
+            Err(_) => return,
+        };
+        self.send_presence(PresenceWSData::leave(block_id, &user_id));
+    }
+
+    fn send_presence(&self, data: PresenceWSData) {
+        let presence_web_socket = self.presence_web_socket.clone();
+        tokio::spawn(async move {
+            if let Err(e) = presence_web_socket.send(data).await {
+                tracing::error!("Send the presence failed: {}", e);
+            }
+        });
+    }
+
     async fn get_block_editor(&self, block_id: &str) -> FlowyResult<Arc<TextBlockEditor>> {
         match self.editor_map.get(block_id) {
             None => {
@@ -131,8 +251,20 @@ impl TextBlockManager {
             token,
             server: self.cloud_service.clone(),
         });
-        let doc_editor =
-            TextBlockEditor::new(block_id, user, rev_manager, self.rev_web_socket.clone(), cloud_service).await?;
+        let history_persistence = Arc::new(TextBlockHistoryPersistence::new(pool.clone()));
+        let comment_persistence = Arc::new(TextBlockCommentPersistence::new(pool.clone()));
+        let link_persistence = Arc::new(TextBlockLinkPersistence::new(pool));
+        let doc_editor = TextBlockEditor::new(
+            block_id,
+            user,
+            rev_manager,
+            self.rev_web_socket.clone(),
+            cloud_service,
+            history_persistence,
+            comment_persistence,
+            link_persistence,
+        )
+        .await?;
         self.editor_map.insert(block_id, &doc_editor);
         Ok(doc_editor)
     }
@@ -1,3 +1,8 @@
+use crate::comment::TextBlockComments;
+use crate::dart_notification::{send_dart_notification, TextBlockNotification};
+use crate::entities::RepeatedTextBlockSelection;
+use crate::history::TextBlockHistory;
+use crate::link::TextBlockLinkPersistence;
 use crate::web_socket::EditorCommandReceiver;
 use crate::TextBlockUser;
 use async_stream::stream;
@@ -6,13 +11,19 @@ use flowy_error::{FlowyError, FlowyResult};
 use flowy_revision::{DeltaMD5, RevisionCompactor, RevisionManager, RichTextTransformDeltas, TransformDeltas};
 use flowy_sync::util::make_delta_from_revisions;
 use flowy_sync::{
-    client_document::{history::UndoResult, ClientDocument},
-    entities::revision::{RevId, Revision},
+    client_document::{
+        find::DocumentFinder,
+        history::UndoResult,
+        links::{may_change_links, view_links_from_delta},
+        outline::{DocumentStats, OutlineHeader},
+        ClientDocument,
+    },
+    entities::revision::{md5, RevId, Revision, RevisionRange},
     errors::CollaborateError,
 };
 use futures::stream::StreamExt;
 use lib_ot::{
-    core::{Interval, OperationTransformable},
+    core::{FlowyStr, Interval, OperationTransformable},
     rich_text::{RichTextAttribute, RichTextAttributes, RichTextDelta},
 };
 use std::sync::Arc;
@@ -24,6 +35,9 @@ pub(crate) struct EditBlockQueue {
     document: Arc<RwLock<ClientDocument>>,
     user: Arc<dyn TextBlockUser>,
     rev_manager: Arc<RevisionManager>,
+    history: Arc<TextBlockHistory>,
+    comments: Arc<TextBlockComments>,
+    link_persistence: Arc<TextBlockLinkPersistence>,
     receiver: Option<EditorCommandReceiver>,
 }
 
@@ -31,16 +45,30 @@ impl EditBlockQueue {
     pub(crate) fn new(
         user: Arc<dyn TextBlockUser>,
         rev_manager: Arc<RevisionManager>,
+        history: Arc<TextBlockHistory>,
+        comments: Arc<TextBlockComments>,
+        link_persistence: Arc<TextBlockLinkPersistence>,
         delta: RichTextDelta,
         receiver: EditorCommandReceiver,
     ) -> Self {
-        let document = Arc::new(RwLock::new(ClientDocument::from_delta(delta)));
-        Self {
+        let mut document = ClientDocument::from_delta(delta.clone());
+        match comments.anchors() {
+            Ok(anchors) => load_anchors(&mut document, anchors),
+            Err(e) => tracing::error!("Read the comment anchors of {} failed: {}", rev_manager.object_id, e),
+        }
+        let document = Arc::new(RwLock::new(document));
+        let queue = Self {
             document,
             user,
             rev_manager,
+            history,
+            comments,
+            link_persistence,
             receiver: Some(receiver),
-        }
+        };
+        // The document may be changed by the remote revisions while it was closed.
+        queue.save_links(&delta);
+        queue
     }
 
     pub(crate) async fn run(mut self) {
@@ -69,15 +97,16 @@ impl EditBlockQueue {
             EditorCommand::ComposeLocalDelta { delta, ret } => {
                 let mut document = self.document.write().await;
                 let _ = document.compose_delta(delta.clone())?;
-                let md5 = document.md5();
+                self.did_change(&mut document, &delta);
                 drop(document);
-                let _ = self.save_local_delta(delta, md5).await?;
+                let _ = self.save_local_delta(delta).await?;
                 let _ = ret.send(Ok(()));
             }
             EditorCommand::ComposeRemoteDelta { client_delta, ret } => {
                 let mut document = self.document.write().await;
-                let _ = document.compose_delta(client_delta.clone())?;
+                let _ = document.compose_remote_delta(client_delta.clone())?;
                 let md5 = document.md5();
+                self.did_change(&mut document, &client_delta);
                 drop(document);
                 let _ = ret.send(Ok(md5));
             }
@@ -85,6 +114,8 @@ impl EditBlockQueue {
                 let mut document = self.document.write().await;
                 let _ = document.set_delta(delta);
                 let md5 = document.md5();
+                self.save_moved_anchors(&mut document);
+                self.save_links(&document.delta());
                 drop(document);
                 let _ = ret.send(Ok(md5));
             }
@@ -113,15 +144,15 @@ impl EditBlockQueue {
             EditorCommand::Insert { index, data, ret } => {
                 let mut write_guard = self.document.write().await;
                 let delta = write_guard.insert(index, data)?;
-                let md5 = write_guard.md5();
-                let _ = self.save_local_delta(delta, md5).await?;
+                self.did_change(&mut write_guard, &delta);
+                let _ = self.save_local_delta(delta).await?;
                 let _ = ret.send(Ok(()));
             }
             EditorCommand::Delete { interval, ret } => {
                 let mut write_guard = self.document.write().await;
                 let delta = write_guard.delete(interval)?;
-                let md5 = write_guard.md5();
-                let _ = self.save_local_delta(delta, md5).await?;
+                self.did_change(&mut write_guard, &delta);
+                let _ = self.save_local_delta(delta).await?;
                 let _ = ret.send(Ok(()));
             }
             EditorCommand::Format {
@@ -131,17 +162,38 @@ impl EditBlockQueue {
             } => {
                 let mut write_guard = self.document.write().await;
                 let delta = write_guard.format(interval, attribute)?;
-                let md5 = write_guard.md5();
-                let _ = self.save_local_delta(delta, md5).await?;
+                self.did_change(&mut write_guard, &delta);
+                let _ = self.save_local_delta(delta).await?;
                 let _ = ret.send(Ok(()));
             }
             EditorCommand::Replace { interval, data, ret } => {
                 let mut write_guard = self.document.write().await;
                 let delta = write_guard.replace(interval, data)?;
-                let md5 = write_guard.md5();
-                let _ = self.save_local_delta(delta, md5).await?;
+                self.did_change(&mut write_guard, &delta);
+                let _ = self.save_local_delta(delta).await?;
+                let _ = ret.send(Ok(()));
+            }
+            EditorCommand::Find { finder, ret } => {
+                let matches = self.document.read().await.find(&finder);
+                let _ = ret.send(Ok(matches));
+            }
+            EditorCommand::ReplaceMatches {
+                finder,
+                replacement,
+                selected,
+                ret,
+            } => {
+                let mut write_guard = self.document.write().await;
+                let delta = write_guard.replace_matches(&finder, &replacement, selected.as_deref())?;
+                if !delta.is_empty() {
+                    self.did_change(&mut write_guard, &delta);
+                    let _ = self.save_local_delta(delta).await?;
+                }
                 let _ = ret.send(Ok(()));
             }
+            EditorCommand::ReadMD5 { rev_id, ret } => {
+                let _ = ret.send(self.md5_at_revision(rev_id).await);
+            }
             EditorCommand::CanUndo { ret } => {
                 let _ = ret.send(self.document.read().await.can_undo());
             }
@@ -150,32 +202,168 @@ impl EditBlockQueue {
             }
             EditorCommand::Undo { ret } => {
                 let mut write_guard = self.document.write().await;
-                let UndoResult { delta } = write_guard.undo()?;
-                let md5 = write_guard.md5();
-                let _ = self.save_local_delta(delta, md5).await?;
-                let _ = ret.send(Ok(()));
+                let UndoResult { delta, selection } = write_guard.undo()?;
+                self.did_change(&mut write_guard, &delta);
+                let _ = self.save_local_delta(delta).await?;
+                let _ = ret.send(Ok(selection));
             }
             EditorCommand::Redo { ret } => {
                 let mut write_guard = self.document.write().await;
-                let UndoResult { delta } = write_guard.redo()?;
-                let md5 = write_guard.md5();
-                let _ = self.save_local_delta(delta, md5).await?;
+                let UndoResult { delta, selection } = write_guard.redo()?;
+                self.did_change(&mut write_guard, &delta);
+                let _ = self.save_local_delta(delta).await?;
+                let _ = ret.send(Ok(selection));
+            }
+            EditorCommand::AddAnchor { id, interval, ret } => {
+                let mut write_guard = self.document.write().await;
+                let _ = write_guard.add_anchor(&id, interval)?;
+                let text = write_guard.delta().to_str()?;
+                let quote = FlowyStr::from(text).sub_str(interval).unwrap_or_default();
+                let _ = ret.send(Ok(quote));
+            }
+            EditorCommand::RemoveAnchor { id, ret } => {
+                let _ = self.document.write().await.remove_anchor(&id);
+                let _ = ret.send(Ok(()));
+            }
+            EditorCommand::UpdateSelection { interval, ret } => {
+                let user_id = self.user.user_id()?;
+                let _ = self.document.write().await.set_selection(&user_id, interval)?;
+                let _ = ret.send(Ok(()));
+            }
+            EditorCommand::UpdateRemoteSelection {
+                user_id,
+                interval,
+                rev_id,
+                ret,
+            } => {
+                let mut document = self.document.write().await;
+                match interval {
+                    None => {
+                        let _ = document.remove_selection(&user_id);
+                    }
+                    // The remote user may have selected the text that isn't received yet.
+                    Some(interval) if rev_id > self.rev_manager.rev_id() => {
+                        document.hold_selection(&user_id, rev_id, interval);
+                    }
+                    Some(interval) => {
+                        let deltas = self.deltas_after_revision(rev_id).await?;
+                        document.set_remote_selection(&user_id, interval, &deltas);
+                    }
+                }
+                self.notify_selections(&document);
+                let _ = ret.send(Ok(()));
+            }
+            EditorCommand::SetHeldSelections { ret } => {
+                let mut document = self.document.write().await;
+                let held_selections = document.take_held_selections(self.rev_manager.rev_id());
+                if !held_selections.is_empty() {
+                    for (user_id, rev_id, interval) in held_selections {
+                        let deltas = self.deltas_after_revision(rev_id).await?;
+                        document.set_remote_selection(&user_id, interval, &deltas);
+                    }
+                    self.notify_selections(&document);
+                }
                 let _ = ret.send(Ok(()));
             }
+            EditorCommand::ReadSelections { ret } => {
+                let selections = self.document.read().await.selections();
+                let _ = ret.send(Ok(selections));
+            }
+            EditorCommand::ReadOutline { ret } => {
+                let outline = self.document.read().await.outline();
+                let _ = ret.send(Ok(outline));
+            }
+            EditorCommand::ReadStats { ret } => {
+                let stats = self.document.read().await.stats();
+                let _ = ret.send(Ok(stats));
+            }
             EditorCommand::ReadDeltaStr { ret } => {
                 let data = self.document.read().await.delta_str();
                 let _ = ret.send(Ok(data));
             }
             EditorCommand::ReadDelta { ret } => {
-                let delta = self.document.read().await.delta().clone();
+                let delta = self.document.read().await.delta();
                 let _ = ret.send(Ok(delta));
             }
         }
         Ok(())
     }
 
-    async fn save_local_delta(&self, delta: RichTextDelta, md5: String) -> Result<RevId, FlowyError> {
-        let delta_data = delta.to_delta_bytes();
+    fn did_change(&self, document: &mut ClientDocument, delta: &RichTextDelta) {
+        self.save_moved_anchors(document);
+        if may_change_links(delta) {
+            self.save_links(&document.delta());
+        }
+
+        // The selections of the other users were transformed against the change.
+        if let Ok(user_id) = self.user.user_id() {
+            if document.selections().iter().any(|(id, _)| id != &user_id) {
+                self.notify_selections(document);
+            }
+        }
+    }
+
+    fn notify_selections(&self, document: &ClientDocument) {
+        let selections: RepeatedTextBlockSelection = document.selections().into();
+        send_dart_notification(&self.rev_manager.object_id, TextBlockNotification::DidUpdateSelections)
+            .payload(selections)
+            .send();
+    }
+
+    fn save_moved_anchors(&self, document: &mut ClientDocument) {
+        let anchors = document.take_moved_anchors();
+        if anchors.is_empty() {
+            return;
+        }
+        if let Err(e) = self.comments.save_anchors(anchors) {
+            tracing::error!(
+                "Save the comment anchors of {} failed: {}",
+                self.rev_manager.object_id,
+                e
+            );
+        }
+    }
+
+    fn save_links(&self, document: &RichTextDelta) {
+        let links = view_links_from_delta(document);
+        if let Err(e) = self.link_persistence.save_links(&self.rev_manager.object_id, links) {
+            tracing::error!("Save the links of {} failed: {}", self.rev_manager.object_id, e);
+        }
+    }
+
+    async fn md5_at_revision(&self, rev_id: i64) -> FlowyResult<DeltaMD5> {
+        if rev_id == self.rev_manager.rev_id() {
+            return Ok(self.document.read().await.md5());
+        }
+
+        // The revision is followed by other revisions, so the document at the revision is rebuilt.
+        let delta = self.history.delta_at_revision(rev_id).await?;
+        Ok(md5(delta.to_delta_bytes()))
+    }
+
+    /// Returns the deltas of the revisions after the revision `rev_id`, the document is at the
+    /// last of them.
+    async fn deltas_after_revision(&self, rev_id: i64) -> FlowyResult<Vec<RichTextDelta>> {
+        let current_rev_id = self.rev_manager.rev_id();
+        if rev_id >= current_rev_id {
+            return Ok(vec![]);
+        }
+
+        let range = RevisionRange {
+            start: rev_id + 1,
+            end: current_rev_id,
+        };
+        let revisions = self.rev_manager.get_revisions_in_range(range).await?;
+        let mut deltas = vec![];
+        for revision in revisions {
+            deltas.push(RichTextDelta::from_bytes(&revision.delta_data)?);
+        }
+        Ok(deltas)
+    }
+
+    // The md5 of the document is computed when the revision is sent, see TextBlockRevisionMD5.
+    async fn save_local_delta(&self, delta: RichTextDelta) -> Result<RevId, FlowyError> {
+        let delta_data = delta.to_compact_bytes();
         let (base_rev_id, rev_id) = self.rev_manager.next_rev_id_pair();
         let user_id = self.user.user_id()?;
         let revision = Revision::new(
@@ -184,21 +372,39 @@ impl EditBlockQueue {
             rev_id,
             delta_data,
             &user_id,
-            md5,
+            String::new(),
         );
         let _ = self
             .rev_manager
             .add_local_revision(&revision, Box::new(TextBlockRevisionCompactor()))
             .await?;
+
+        // The unsynchronized revisions may be compacted into one, so read the rev_id of the saved
+        // revision from the rev_manager.
+        if let Err(e) = self.history.record_revision(self.rev_manager.rev_id(), &user_id) {
+            tracing::error!("Record the revision of {} failed: {}", self.rev_manager.object_id, e);
+        }
         Ok(rev_id.into())
     }
 }
 
+// The document may be changed by the remote revisions that were applied while it was closed, so
+// the anchors that are out of the document are moved to its end.
+fn load_anchors(document: &mut ClientDocument, anchors: Vec<(String, Interval)>) {
+    let len = document.len();
+    for (id, interval) in anchors {
+        if document.add_anchor(&id, interval).is_err() {
+            let interval = Interval::new(interval.start.min(len), interval.end.min(len));
+            let _ = document.add_anchor(&id, interval);
+        }
+    }
+}
+
 pub(crate) struct TextBlockRevisionCompactor();
 impl RevisionCompactor for TextBlockRevisionCompactor {
     fn bytes_from_revisions(&self, revisions: Vec<Revision>) -> FlowyResult<Bytes> {
         let delta = make_delta_from_revisions::<RichTextAttributes>(revisions)?;
-        Ok(delta.to_delta_bytes())
+        Ok(delta.to_compact_bytes())
     }
 }
 
@@ -240,6 +446,21 @@ pub(crate) enum EditorCommand {
         data: String,
         ret: Ret<()>,
     },
+    Find {
+        finder: DocumentFinder,
+        ret: Ret<Vec<Interval>>,
+    },
+    ReplaceMatches {
+        finder: DocumentFinder,
+        replacement: String,
+        selected: Option<Vec<Interval>>,
+        ret: Ret<()>,
+    },
+    /// Returns the md5 of the document at the revision.
+    ReadMD5 {
+        rev_id: i64,
+        ret: oneshot::Sender<FlowyResult<DeltaMD5>>,
+    },
     CanUndo {
         ret: oneshot::Sender<bool>,
     },
@@ -247,14 +468,50 @@ pub(crate) enum EditorCommand {
         ret: oneshot::Sender<bool>,
     },
     Undo {
-        ret: Ret<()>,
+        ret: Ret<Interval>,
     },
     Redo {
+        ret: Ret<Interval>,
+    },
+    /// Returns the text that the anchor is attached to.
+    AddAnchor {
+        id: String,
+        interval: Interval,
+        ret: Ret<String>,
+    },
+    RemoveAnchor {
+        id: String,
         ret: Ret<()>,
     },
     ReadDeltaStr {
         ret: Ret<String>,
     },
+    /// Sets the selection of the current user.
+    UpdateSelection {
+        interval: Interval,
+        ret: Ret<()>,
+    },
+    /// Sets the selection of another user, the selection is removed if the interval is None. The
+    /// interval is in the document at the revision `rev_id`.
+    UpdateRemoteSelection {
+        user_id: String,
+        interval: Option<Interval>,
+        rev_id: i64,
+        ret: Ret<()>,
+    },
+    /// Sets the selections of other users that were held until their revisions are received.
+    SetHeldSelections {
+        ret: Ret<()>,
+    },
+    ReadSelections {
+        ret: Ret<Vec<(String, Interval)>>,
+    },
+    ReadOutline {
+        ret: Ret<Vec<OutlineHeader>>,
+    },
+    ReadStats {
+        ret: Ret<DocumentStats>,
+    },
     #[allow(dead_code)]
     ReadDelta {
         ret: Ret<RichTextDelta>,
@@ -272,10 +529,21 @@ impl std::fmt::Debug for EditorCommand {
             EditorCommand::Delete { .. } => "Delete",
             EditorCommand::Format { .. } => "Format",
             EditorCommand::Replace { .. } => "Replace",
+            EditorCommand::Find { .. } => "Find",
+            EditorCommand::ReplaceMatches { .. } => "ReplaceMatches",
+            EditorCommand::ReadMD5 { .. } => "ReadMD5",
             EditorCommand::CanUndo { .. } => "CanUndo",
             EditorCommand::CanRedo { .. } => "CanRedo",
             EditorCommand::Undo { .. } => "Undo",
             EditorCommand::Redo { .. } => "Redo",
+            EditorCommand::AddAnchor { .. } => "AddAnchor",
+            EditorCommand::RemoveAnchor { .. } => "RemoveAnchor",
+            EditorCommand::UpdateSelection { .. } => "UpdateSelection",
+            EditorCommand::UpdateRemoteSelection { .. } => "UpdateRemoteSelection",
+            EditorCommand::SetHeldSelections { .. } => "SetHeldSelections",
+            EditorCommand::ReadSelections { .. } => "ReadSelections",
+            EditorCommand::ReadOutline { .. } => "ReadOutline",
+            EditorCommand::ReadStats { .. } => "ReadStats",
             EditorCommand::ReadDeltaStr { .. } => "ReadDeltaStr",
             EditorCommand::ReadDelta { .. } => "ReadDocumentAsDelta",
         };
@@ -1,10 +1,10 @@
-use crate::{queue::EditorCommand, TEXT_BLOCK_SYNC_INTERVAL_IN_MILLIS};
+use crate::{history::TextBlockHistory, queue::EditorCommand, TEXT_BLOCK_SYNC_INTERVAL_IN_MILLIS};
 use bytes::Bytes;
-use flowy_error::{internal_error, FlowyError};
+use flowy_error::{internal_error, FlowyError, FlowyResult};
 use flowy_revision::*;
 use flowy_sync::{
     entities::{
-        revision::RevisionRange,
+        revision::{RepeatedRevision, Revision, RevisionRange},
         ws_data::{ClientRevisionWSData, NewDocumentUser, ServerRevisionWSDataType},
     },
     errors::CollaborateResult,
@@ -13,7 +13,7 @@ use lib_infra::future::{BoxResultFuture, FutureResult};
 use lib_ot::rich_text::RichTextAttributes;
 use lib_ot::rich_text::RichTextDelta;
 use lib_ws::WSConnectState;
-use std::{sync::Arc, time::Duration};
+use std::{convert::TryFrom, sync::Arc, time::Duration};
 use tokio::sync::{
     broadcast,
     mpsc::{Receiver, Sender},
@@ -29,13 +29,27 @@ pub(crate) async fn make_block_ws_manager(
     user_id: String,
     edit_cmd_tx: EditorCommandSender,
     rev_manager: Arc<RevisionManager>,
+    history: Arc<TextBlockHistory>,
     rev_web_socket: Arc<dyn RevisionWebSocket>,
 ) -> Arc<RevisionWebSocketManager> {
-    let ws_data_provider = Arc::new(WSDataProvider::new(&doc_id, Arc::new(rev_manager.clone())));
-    let resolver = Arc::new(TextBlockConflictResolver { edit_cmd_tx });
-    let conflict_controller =
-        RichTextConflictController::new(&user_id, resolver, Arc::new(ws_data_provider.clone()), rev_manager);
-    let ws_data_stream = Arc::new(TextBlockRevisionWSDataStream::new(conflict_controller));
+    let revision_md5 = TextBlockRevisionMD5 {
+        rev_manager: rev_manager.clone(),
+        edit_cmd_tx: edit_cmd_tx.clone(),
+    };
+    let ws_data_provider = Arc::new(WSDataProvider::new(&doc_id, Arc::new(revision_md5.clone())));
+    let resolver = Arc::new(TextBlockConflictResolver {
+        edit_cmd_tx: edit_cmd_tx.clone(),
+    });
+    let rev_sink = Arc::new(TextBlockRevisionSink {
+        ws_data_provider: ws_data_provider.clone(),
+        revision_md5,
+    });
+    let conflict_controller = RichTextConflictController::new(&user_id, resolver, rev_sink, rev_manager);
+    let ws_data_stream = Arc::new(TextBlockRevisionWSDataStream::new(
+        conflict_controller,
+        history,
+        edit_cmd_tx,
+    ));
     let ws_data_sink = Arc::new(TextBlockWSDataSink(ws_data_provider));
     let ping_duration = Duration::from_millis(TEXT_BLOCK_SYNC_INTERVAL_IN_MILLIS);
     let ws_manager = Arc::new(RevisionWebSocketManager::new(
@@ -66,13 +80,21 @@ fn listen_document_ws_state(_user_id: &str, _doc_id: &str, mut subscriber: broad
 
 pub(crate) struct TextBlockRevisionWSDataStream {
     conflict_controller: Arc<RichTextConflictController>,
+    history: Arc<TextBlockHistory>,
+    edit_cmd_tx: EditorCommandSender,
 }
 
 impl TextBlockRevisionWSDataStream {
     #[allow(dead_code)]
-    pub fn new(conflict_controller: RichTextConflictController) -> Self {
+    pub fn new(
+        conflict_controller: RichTextConflictController,
+        history: Arc<TextBlockHistory>,
+        edit_cmd_tx: EditorCommandSender,
+    ) -> Self {
         Self {
             conflict_controller: Arc::new(conflict_controller),
+            history,
+            edit_cmd_tx,
         }
     }
 }
@@ -80,7 +102,18 @@ impl TextBlockRevisionWSDataStream {
 impl RevisionWSDataStream for TextBlockRevisionWSDataStream {
     fn receive_push_revision(&self, bytes: Bytes) -> BoxResultFuture<(), FlowyError> {
         let resolver = self.conflict_controller.clone();
-        Box::pin(async move { resolver.receive_bytes(bytes).await })
+        let history = self.history.clone();
+        let edit_cmd_tx = self.edit_cmd_tx.clone();
+        Box::pin(async move {
+            let repeated_revision = RepeatedRevision::try_from(bytes.clone())?;
+            let _ = resolver.receive_bytes(bytes).await?;
+            // Keep the authors of the remote revisions, the local revisions are recorded by the
+            // EditBlockQueue.
+            for revision in repeated_revision.iter() {
+                let _ = history.record_revision(revision.rev_id, &revision.user_id)?;
+            }
+            set_held_selections(&edit_cmd_tx).await
+        })
     }
 
     fn receive_ack(&self, id: String, ty: ServerRevisionWSDataType) -> BoxResultFuture<(), FlowyError> {
@@ -97,6 +130,32 @@ impl RevisionWSDataStream for TextBlockRevisionWSDataStream {
         let resolver = self.conflict_controller.clone();
         Box::pin(async move { resolver.send_revisions(range).await })
     }
+
+    fn receive_transformed_revision(&self, bytes: Bytes) -> BoxResultFuture<(), FlowyError> {
+        let resolver = self.conflict_controller.clone();
+        let history = self.history.clone();
+        let edit_cmd_tx = self.edit_cmd_tx.clone();
+        Box::pin(async move {
+            let repeated_revision = RepeatedRevision::try_from(bytes.clone())?;
+            let _ = resolver.receive_transformed_bytes(bytes).await?;
+            for revision in repeated_revision.iter() {
+                let _ = history.record_revision(revision.rev_id, &revision.user_id)?;
+            }
+            set_held_selections(&edit_cmd_tx).await
+        })
+    }
+}
+
+/// Sets the selections of the other users that were made in the received revisions, they are
+/// held by the EditBlockQueue until the revisions are saved.
+async fn set_held_selections(edit_cmd_tx: &EditorCommandSender) -> FlowyResult<()> {
+    let (ret, rx) = oneshot::channel();
+    edit_cmd_tx
+        .send(EditorCommand::SetHeldSelections { ret })
+        .await
+        .map_err(internal_error)?;
+    let _ = rx.await.map_err(internal_error)??;
+    Ok(())
 }
 
 pub(crate) struct TextBlockWSDataSink(pub(crate) Arc<WSDataProvider>);
@@ -107,6 +166,82 @@ impl RevisionWebSocketSink for TextBlockWSDataSink {
     }
 }
 
+/// The local revisions are saved without the md5 of the document, it's computed when the
+/// revision is sent to the server.
+#[derive(Clone)]
+struct TextBlockRevisionMD5 {
+    rev_manager: Arc<RevisionManager>,
+    edit_cmd_tx: EditorCommandSender,
+}
+
+impl TextBlockRevisionMD5 {
+    async fn fill(&self, revision: &mut Revision) -> FlowyResult<()> {
+        if !revision.md5.is_empty() {
+            return Ok(());
+        }
+
+        let (ret, rx) = oneshot::channel();
+        self.edit_cmd_tx
+            .send(EditorCommand::ReadMD5 {
+                rev_id: revision.rev_id,
+                ret,
+            })
+            .await
+            .map_err(internal_error)?;
+        let md5 = rx.await.map_err(internal_error)??;
+        let _ = self.rev_manager.set_revision_md5(revision.rev_id, md5.clone()).await?;
+        revision.md5 = md5;
+        Ok(())
+    }
+}
+
+impl WSDataProviderDataSource for TextBlockRevisionMD5 {
+    fn next_revision(&self) -> FutureResult<Option<Revision>, FlowyError> {
+        let revision_md5 = self.clone();
+        FutureResult::new(async move {
+            match revision_md5.rev_manager.next_sync_revision().await? {
+                None => Ok(None),
+                Some(mut revision) => {
+                    let _ = revision_md5.fill(&mut revision).await?;
+                    Ok(Some(revision))
+                }
+            }
+        })
+    }
+
+    fn ack_revision(&self, rev_id: i64) -> FutureResult<(), FlowyError> {
+        let rev_manager = self.rev_manager.clone();
+        FutureResult::new(async move { rev_manager.ack_revision(rev_id).await })
+    }
+
+    fn current_rev_id(&self) -> i64 {
+        self.rev_manager.rev_id()
+    }
+}
+
+/// Sends the revisions that are pulled by the server, the local ones may not be sent before.
+struct TextBlockRevisionSink {
+    ws_data_provider: Arc<WSDataProvider>,
+    revision_md5: TextBlockRevisionMD5,
+}
+
+impl ConflictRevisionSink for TextBlockRevisionSink {
+    fn send(&self, mut revisions: Vec<Revision>) -> BoxResultFuture<(), FlowyError> {
+        let ws_data_provider = self.ws_data_provider.clone();
+        let revision_md5 = self.revision_md5.clone();
+        Box::pin(async move {
+            for revision in revisions.iter_mut() {
+                let _ = revision_md5.fill(revision).await?;
+            }
+            ws_data_provider.send(revisions).await
+        })
+    }
+
+    fn ack(&self, rev_id: String, ty: ServerRevisionWSDataType) -> BoxResultFuture<(), FlowyError> {
+        self.ws_data_provider.ack(rev_id, ty)
+    }
+}
+
 struct TextBlockConflictResolver {
     edit_cmd_tx: EditorCommandSender,
 }
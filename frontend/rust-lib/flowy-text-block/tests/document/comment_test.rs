@@ -0,0 +1,76 @@
+use crate::document::script::{EditorScript::*, *};
+use lib_ot::core::Interval;
+
+#[tokio::test]
+async fn text_block_comment_thread_move_with_text_test() {
+    let scripts = vec![
+        InsertText("123456", 0),
+        CreateCommentThread(Interval::new(2, 4), "first"),
+        InsertText("abc", 0),
+        AssertCommentThread {
+            index: 0,
+            anchor: Interval::new(5, 7),
+            quote: "34",
+            resolved: false,
+            comments: vec!["first"],
+        },
+        Delete(Interval::new(5, 7)),
+        AssertCommentThread {
+            index: 0,
+            anchor: Interval::new(5, 5),
+            quote: "34",
+            resolved: false,
+            comments: vec!["first"],
+        },
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn text_block_comment_thread_reply_and_resolve_test() {
+    let scripts = vec![
+        InsertText("123456", 0),
+        CreateCommentThread(Interval::new(0, 3), "first"),
+        ReplyCommentThread(0, "second"),
+        ResolveCommentThread(0),
+        AssertCommentThread {
+            index: 0,
+            anchor: Interval::new(0, 3),
+            quote: "123",
+            resolved: true,
+            comments: vec!["first", "second"],
+        },
+        // The anchor of the resolved thread still moves with the text.
+        InsertText("abc", 0),
+        ReopenCommentThread(0),
+        AssertCommentThread {
+            index: 0,
+            anchor: Interval::new(3, 6),
+            quote: "123",
+            resolved: false,
+            comments: vec!["first", "second"],
+        },
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn text_block_comment_thread_delete_test() {
+    let scripts = vec![
+        InsertText("123456", 0),
+        CreateCommentThread(Interval::new(0, 3), "first"),
+        CreateCommentThread(Interval::new(3, 6), "second"),
+        AssertCommentThreadCount(2),
+        DeleteCommentThread(0),
+        AssertCommentThreadCount(1),
+        InsertText("abc", 0),
+        AssertCommentThread {
+            index: 1,
+            anchor: Interval::new(6, 9),
+            quote: "456",
+            resolved: false,
+            comments: vec!["second"],
+        },
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
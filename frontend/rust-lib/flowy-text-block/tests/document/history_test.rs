@@ -0,0 +1,53 @@
+use crate::document::script::{EditorScript::*, *};
+use flowy_text_block::entities::TextDiffType;
+use lib_ot::core::Interval;
+
+#[tokio::test]
+async fn text_block_history_read_version_test() {
+    let scripts = vec![
+        InsertText("1", 0),
+        InsertText("2", 1),
+        InsertText("3", 2),
+        AssertVersionJson(1, r#"[{"insert":"1\n"}]"#),
+        AssertVersionJson(2, r#"[{"insert":"12\n"}]"#),
+        AssertVersionJson(3, r#"[{"insert":"123\n"}]"#),
+        AssertVersionCount(3),
+        AssertSessionCount(1),
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn text_block_history_restore_version_test() {
+    let scripts = vec![
+        InsertText("1", 0),
+        InsertText("2", 1),
+        InsertText("3", 2),
+        RestoreVersion(1),
+        AssertJson(r#"[{"insert":"1\n"}]"#),
+        AssertCurrentRevId(4),
+        // The versions after the restored version are kept.
+        AssertVersionJson(3, r#"[{"insert":"123\n"}]"#),
+        AssertVersionCount(4),
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn text_block_history_diff_versions_test() {
+    let scripts = vec![
+        InsertText("123", 0),
+        Replace(Interval::new(1, 2), "abc"),
+        AssertVersionDiff(
+            1,
+            2,
+            vec![
+                (TextDiffType::Equal, "1"),
+                (TextDiffType::Insert, "abc"),
+                (TextDiffType::Delete, "2"),
+                (TextDiffType::Equal, "3\n"),
+            ],
+        ),
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
@@ -1,2 +1,4 @@
+mod comment_test;
+mod history_test;
 mod script;
 mod text_block_test;
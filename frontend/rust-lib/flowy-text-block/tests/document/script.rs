@@ -1,8 +1,13 @@
 use flowy_revision::disk::RevisionState;
+use flowy_sync::client_document::find::{DocumentFinder, FindOptions};
 use flowy_test::{helper::ViewTest, FlowySDKTest};
 use flowy_text_block::editor::TextBlockEditor;
+use flowy_text_block::entities::{CreateCommentThreadParams, TextDiffType};
 use flowy_text_block::TEXT_BLOCK_SYNC_INTERVAL_IN_MILLIS;
-use lib_ot::{core::Interval, rich_text::RichTextDelta};
+use lib_ot::{
+    core::Interval,
+    rich_text::{RichTextAttribute, RichTextDelta},
+};
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 
@@ -10,16 +15,40 @@ pub enum EditorScript {
     InsertText(&'static str, usize),
     Delete(Interval),
     Replace(Interval, &'static str),
+    RestoreVersion(i64),
+    ReplaceAll(&'static str, &'static str),
+    Header(Interval, usize),
+    CreateCommentThread(Interval, &'static str),
+    ReplyCommentThread(usize, &'static str),
+    ResolveCommentThread(usize),
+    ReopenCommentThread(usize),
+    DeleteCommentThread(usize),
 
     AssertRevisionState(i64, RevisionState),
     AssertNextSyncRevId(Option<i64>),
     AssertCurrentRevId(i64),
     AssertJson(&'static str),
+    AssertVersionJson(i64, &'static str),
+    AssertVersionCount(usize),
+    AssertSessionCount(usize),
+    AssertVersionDiff(i64, i64, Vec<(TextDiffType, &'static str)>),
+    AssertCommentThreadCount(usize),
+    // The level, the text and the offset of each header.
+    AssertOutline(Vec<(usize, &'static str, usize)>),
+    AssertWordCount(usize),
+    AssertCommentThread {
+        index: usize,
+        anchor: Interval,
+        quote: &'static str,
+        resolved: bool,
+        comments: Vec<&'static str>,
+    },
 }
 
 pub struct TextBlockEditorTest {
     pub sdk: FlowySDKTest,
     pub editor: Arc<TextBlockEditor>,
+    pub thread_ids: Vec<String>,
 }
 
 impl TextBlockEditorTest {
@@ -28,7 +57,11 @@ impl TextBlockEditorTest {
         let _ = sdk.init_user().await;
         let test = ViewTest::new_text_block_view(&sdk).await;
         let editor = sdk.text_block_manager.open_block(&test.view.id).await.unwrap();
-        Self { sdk, editor }
+        Self {
+            sdk,
+            editor,
+            thread_ids: vec![],
+        }
     }
 
     pub async fn run_scripts(mut self, scripts: Vec<EditorScript>) {
@@ -52,6 +85,53 @@ impl TextBlockEditorTest {
             EditorScript::Replace(interval, s) => {
                 self.editor.replace(interval, s).await.unwrap();
             }
+            EditorScript::Header(interval, level) => {
+                let attribute = RichTextAttribute::Header(level);
+                self.editor.format(interval, attribute).await.unwrap();
+            }
+            EditorScript::ReplaceAll(query, replacement) => {
+                let finder = DocumentFinder::new(query, FindOptions::default()).unwrap();
+                self.editor
+                    .replace_matches(finder, replacement.to_owned(), None)
+                    .await
+                    .unwrap();
+            }
+            EditorScript::RestoreVersion(rev_id) => {
+                self.editor.restore_version(rev_id).await.unwrap();
+            }
+            EditorScript::CreateCommentThread(interval, content) => {
+                let params = CreateCommentThreadParams {
+                    view_id: self.editor.doc_id.clone(),
+                    interval,
+                    content: content.to_owned(),
+                };
+                let thread = self.editor.create_comment_thread(params).await.unwrap();
+                self.thread_ids.push(thread.id);
+            }
+            EditorScript::ReplyCommentThread(index, content) => {
+                let thread_id = &self.thread_ids[index];
+                self.editor.reply_comment_thread(thread_id, content.to_owned()).unwrap();
+            }
+            EditorScript::ResolveCommentThread(index) => {
+                let thread = self
+                    .editor
+                    .set_comment_thread_resolved(&self.thread_ids[index], true)
+                    .unwrap();
+                assert!(thread.resolved);
+            }
+            EditorScript::ReopenCommentThread(index) => {
+                let thread = self
+                    .editor
+                    .set_comment_thread_resolved(&self.thread_ids[index], false)
+                    .unwrap();
+                assert!(!thread.resolved);
+            }
+            EditorScript::DeleteCommentThread(index) => {
+                self.editor
+                    .delete_comment_thread(&self.thread_ids[index])
+                    .await
+                    .unwrap();
+            }
             EditorScript::AssertRevisionState(rev_id, state) => {
                 let record = cache.get(rev_id).await.unwrap();
                 assert_eq!(record.state, state);
@@ -70,6 +150,24 @@ impl TextBlockEditorTest {
                 let _ = notify.recv().await;
                 assert_eq!(next_revision.rev_id, rev_id.unwrap());
             }
+            EditorScript::AssertOutline(expected) => {
+                let headers = self
+                    .editor
+                    .outline()
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|header| (header.level, header.text, header.offset))
+                    .collect::<Vec<(usize, String, usize)>>();
+                let expected = expected
+                    .into_iter()
+                    .map(|(level, text, offset)| (level, text.to_owned(), offset))
+                    .collect::<Vec<(usize, String, usize)>>();
+                assert_eq!(headers, expected);
+            }
+            EditorScript::AssertWordCount(words) => {
+                assert_eq!(self.editor.stats().await.unwrap().words, words);
+            }
             EditorScript::AssertJson(expected) => {
                 let expected_delta: RichTextDelta = serde_json::from_str(expected).unwrap();
                 let delta = self.editor.text_block_delta().await.unwrap();
@@ -79,6 +177,60 @@ impl TextBlockEditorTest {
                 }
                 assert_eq!(expected_delta, delta);
             }
+            EditorScript::AssertVersionJson(rev_id, expected) => {
+                let expected_delta: RichTextDelta = serde_json::from_str(expected).unwrap();
+                let delta_str = self.editor.delta_str_at_version(rev_id).await.unwrap();
+                let delta = RichTextDelta::from_delta_str(&delta_str).unwrap();
+                assert_eq!(expected_delta, delta);
+            }
+            EditorScript::AssertVersionCount(expected) => {
+                let groups = self.editor.versions().unwrap();
+                let count: usize = groups.iter().map(|group| group.versions.len()).sum();
+                assert_eq!(count, expected);
+            }
+            EditorScript::AssertSessionCount(expected) => {
+                assert_eq!(self.editor.versions().unwrap().len(), expected);
+            }
+            EditorScript::AssertVersionDiff(from_rev_id, to_rev_id, expected) => {
+                let spans = self.editor.diff_versions(from_rev_id, to_rev_id).await.unwrap();
+                let spans = spans
+                    .into_iter()
+                    .map(|span| (span.ty, span.text))
+                    .collect::<Vec<(TextDiffType, String)>>();
+                let expected = expected
+                    .into_iter()
+                    .map(|(ty, text)| (ty, text.to_owned()))
+                    .collect::<Vec<(TextDiffType, String)>>();
+                assert_eq!(spans, expected);
+            }
+            EditorScript::AssertCommentThreadCount(expected) => {
+                assert_eq!(self.editor.comment_threads().unwrap().len(), expected);
+            }
+            EditorScript::AssertCommentThread {
+                index,
+                anchor,
+                quote,
+                resolved,
+                comments,
+            } => {
+                let thread = self
+                    .editor
+                    .comment_threads()
+                    .unwrap()
+                    .into_iter()
+                    .find(|thread| thread.id == self.thread_ids[index])
+                    .unwrap();
+                assert_eq!(Interval::new(thread.start as usize, thread.end as usize), anchor);
+                assert_eq!(thread.collapsed, anchor.is_empty());
+                assert_eq!(thread.quote, quote);
+                assert_eq!(thread.resolved, resolved);
+                let contents = thread
+                    .comments
+                    .iter()
+                    .map(|comment| comment.content.as_str())
+                    .collect::<Vec<&str>>();
+                assert_eq!(contents, comments);
+            }
         }
         sleep(Duration::from_millis(TEXT_BLOCK_SYNC_INTERVAL_IN_MILLIS)).await;
     }
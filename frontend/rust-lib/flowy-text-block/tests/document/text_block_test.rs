@@ -103,3 +103,29 @@ async fn text_block_sync_replace_test() {
     ];
     TextBlockEditorTest::new().await.run_scripts(scripts).await;
 }
+
+#[tokio::test]
+async fn text_block_replace_all_in_one_revision_test() {
+    let scripts = vec![
+        InsertText("abc abc", 0),
+        AssertCurrentRevId(1),
+        ReplaceAll("abc", "x"),
+        AssertCurrentRevId(2),
+        AssertJson(r#"[{"insert":"x x\n"}]"#),
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
+
+#[tokio::test]
+async fn text_block_outline_test() {
+    let scripts = vec![
+        InsertText("Guide\nfirst step\nSetup", 0),
+        Header(Interval::new(0, 5), 1),
+        Header(Interval::new(17, 22), 2),
+        AssertOutline(vec![(1, "Guide", 0), (2, "Setup", 17)]),
+        InsertText("the ", 6),
+        AssertOutline(vec![(1, "Guide", 0), (2, "Setup", 21)]),
+        AssertWordCount(5),
+    ];
+    TextBlockEditorTest::new().await.run_scripts(scripts).await;
+}
@@ -0,0 +1,80 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::{NewlineDoc, PlainDoc, RECORD_THRESHOLD};
+use lib_ot::core::Interval;
+
+#[test]
+fn anchor_move_with_text_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        AddAnchor(0, "comment", Interval::new(2, 4)),
+        Insert(0, "abc", 0),
+        AssertAnchor(0, "comment", Interval::new(5, 7)),
+        Delete(0, Interval::new(0, 4)),
+        AssertAnchor(0, "comment", Interval::new(1, 3)),
+        Insert(0, "xyz", 4),
+        AssertAnchor(0, "comment", Interval::new(1, 3)),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn anchor_insert_inside_and_at_edges_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        AddAnchor(0, "comment", Interval::new(2, 4)),
+        // The text inserted at the edges is not attached to the anchor.
+        Insert(0, "a", 2),
+        AssertAnchor(0, "comment", Interval::new(3, 5)),
+        Insert(0, "b", 5),
+        AssertAnchor(0, "comment", Interval::new(3, 5)),
+        Insert(0, "c", 4),
+        AssertAnchor(0, "comment", Interval::new(3, 6)),
+        AssertStr(0, "12a3c4b56"),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn anchor_collapse_after_deleting_text_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        AddAnchor(0, "comment", Interval::new(2, 4)),
+        Delete(0, Interval::new(3, 5)),
+        AssertAnchor(0, "comment", Interval::new(2, 3)),
+        Delete(0, Interval::new(1, 4)),
+        AssertAnchor(0, "comment", Interval::new(1, 1)),
+        Insert(0, "abc", 1),
+        AssertAnchor(0, "comment", Interval::new(4, 4)),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn anchor_transform_remote_delta_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        AddAnchor(0, "comment", Interval::new(2, 4)),
+        ComposeRemote(0, r#"[{"insert":"abc"}]"#),
+        AssertAnchor(0, "comment", Interval::new(5, 7)),
+        ComposeRemote(0, r#"[{"retain":6},{"delete":1}]"#),
+        AssertAnchor(0, "comment", Interval::new(5, 6)),
+        AssertStr(0, "abc12356\n"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn anchor_undo_redo_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        Wait(RECORD_THRESHOLD),
+        AddAnchor(0, "comment", Interval::new(2, 4)),
+        Insert(0, "abc", 0),
+        AssertAnchor(0, "comment", Interval::new(5, 7)),
+        Undo(0),
+        AssertAnchor(0, "comment", Interval::new(2, 4)),
+        Redo(0),
+        AssertAnchor(0, "comment", Interval::new(5, 7)),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
@@ -3,7 +3,9 @@ use crate::editor::{TestBuilder, TestOp::*};
 use flowy_sync::client_document::{NewlineDoc, PlainDoc};
 use lib_ot::core::{Interval, OperationTransformable, NEW_LINE, WHITESPACE, FlowyStr};
 use unicode_segmentation::UnicodeSegmentation;
-use lib_ot::rich_text::RichTextDelta;
+use lib_ot::rich_text::{
+    register_custom_attribute, AttributeScope, AttributeValueType, RichTextAttribute, RichTextDelta,
+};
 
 #[test]
 fn attributes_bold_added() {
@@ -18,6 +20,7 @@ fn attributes_bold_added() {
             {"insert":"6"}
             ]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -30,6 +33,7 @@ fn attributes_bold_added_and_invert_all() {
         AssertDocJson(0, r#"[{"insert":"123","attributes":{"bold":"true"}}]"#),
         Bold(0, Interval::new(0, 3), false),
         AssertDocJson(0, r#"[{"insert":"123"}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -42,6 +46,7 @@ fn attributes_bold_added_and_invert_partial_suffix() {
         AssertDocJson(0, r#"[{"insert":"1234","attributes":{"bold":"true"}}]"#),
         Bold(0, Interval::new(2, 4), false),
         AssertDocJson(0, r#"[{"insert":"12","attributes":{"bold":"true"}},{"insert":"34"}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -56,6 +61,7 @@ fn attributes_bold_added_and_invert_partial_suffix2() {
         AssertDocJson(0, r#"[{"insert":"12","attributes":{"bold":"true"}},{"insert":"34"}]"#),
         Bold(0, Interval::new(2, 4), true),
         AssertDocJson(0, r#"[{"insert":"1234","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -84,6 +90,7 @@ fn attributes_bold_added_with_new_line() {
             0,
             r#"[{"insert":"123","attributes":{"bold":"true"}},{"insert":"\na\n"},{"insert":"456","attributes":{"bold":"true"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
 }
@@ -96,6 +103,7 @@ fn attributes_bold_added_and_invert_partial_prefix() {
         AssertDocJson(0, r#"[{"insert":"1234","attributes":{"bold":"true"}}]"#),
         Bold(0, Interval::new(0, 2), false),
         AssertDocJson(0, r#"[{"insert":"12"},{"insert":"34","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -108,6 +116,7 @@ fn attributes_bold_added_consecutive() {
         AssertDocJson(0, r#"[{"insert":"1","attributes":{"bold":"true"}},{"insert":"234"}]"#),
         Bold(0, Interval::new(1, 2), true),
         AssertDocJson(0, r#"[{"insert":"12","attributes":{"bold":"true"}},{"insert":"34"}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -127,6 +136,7 @@ fn attributes_bold_added_italic() {
             0,
             r#"[{"insert":"12345678","attributes":{"bold":"true","italic":"true"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
 }
@@ -154,6 +164,7 @@ fn attributes_bold_added_italic2() {
             {"insert":"56","attributes":{"italic":"true","bold":"true"}}]
             "#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -191,6 +202,7 @@ fn attributes_bold_added_italic3() {
             {"insert":"89","attributes":{"bold":"true"}}]
             "#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -227,6 +239,7 @@ fn attributes_bold_added_italic_delete() {
         ),
         Delete(0, Interval::new(0, 5)),
         AssertDocJson(0, r#"[{"insert":"67"},{"insert":"89","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -239,6 +252,7 @@ fn attributes_merge_inserted_text_with_same_attribute() {
         AssertDocJson(0, r#"[{"insert":"123","attributes":{"bold":"true"}}]"#),
         InsertBold(0, "456", Interval::new(3, 6)),
         AssertDocJson(0, r#"[{"insert":"123456","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -253,6 +267,7 @@ fn attributes_compose_attr_attributes_with_attr_attributes_test() {
         Transform(0, 1),
         AssertDocJson(0, r#"[{"insert":"1234567","attributes":{"bold":"true"}}]"#),
         AssertDocJson(1, r#"[{"insert":"1234567","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -294,6 +309,7 @@ fn attributes_compose_attr_attributes_with_attr_attributes_test2() {
             {"insert":"7","attributes":{"bold":"true"}}]
             "#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -311,6 +327,7 @@ fn attributes_compose_attr_attributes_with_no_attr_attributes_test() {
         Transform(0, 1),
         AssertDocJson(0, expected),
         AssertDocJson(1, expected),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
 }
@@ -322,6 +339,7 @@ fn attributes_replace_heading() {
         AssertDocJson(0, r#"[{"insert":"123456","attributes":{"bold":"true"}}]"#),
         Delete(0, Interval::new(0, 2)),
         AssertDocJson(0, r#"[{"insert":"3456","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -334,6 +352,7 @@ fn attributes_replace_trailing() {
         AssertDocJson(0, r#"[{"insert":"123456","attributes":{"bold":"true"}}]"#),
         Delete(0, Interval::new(5, 6)),
         AssertDocJson(0, r#"[{"insert":"12345","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -348,6 +367,7 @@ fn attributes_replace_middle() {
         AssertDocJson(0, r#"[{"insert":"3456","attributes":{"bold":"true"}}]"#),
         Delete(0, Interval::new(2, 4)),
         AssertDocJson(0, r#"[{"insert":"34","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -360,6 +380,7 @@ fn attributes_replace_all() {
         AssertDocJson(0, r#"[{"insert":"123456","attributes":{"bold":"true"}}]"#),
         Delete(0, Interval::new(0, 6)),
         AssertDocJson(0, r#"[]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -372,6 +393,7 @@ fn attributes_replace_with_text() {
         AssertDocJson(0, r#"[{"insert":"123456","attributes":{"bold":"true"}}]"#),
         Replace(0, Interval::new(0, 3), "ab"),
         AssertDocJson(0, r#"[{"insert":"ab"},{"insert":"456","attributes":{"bold":"true"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<PlainDoc>(ops);
@@ -388,6 +410,7 @@ fn attributes_header_insert_newline_at_middle() {
             0,
             r#"[{"insert":"123"},{"insert":"\n","attributes":{"header":1}},{"insert":"456"},{"insert":"\n","attributes":{"header":1}}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -413,6 +436,7 @@ fn attributes_header_insert_double_newline_at_middle() {
             0,
             r#"[{"insert":"123"},{"insert":"\n\n","attributes":{"header":1}},{"insert":"\n456"},{"insert":"\n","attributes":{"header":1}}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -428,6 +452,7 @@ fn attributes_header_insert_newline_at_trailing() {
             0,
             r#"[{"insert":"123456"},{"insert":"\n","attributes":{"header":1}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -444,6 +469,7 @@ fn attributes_header_insert_double_newline_at_trailing() {
             0,
             r#"[{"insert":"123456"},{"insert":"\n","attributes":{"header":1}},{"insert":"\n\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -458,6 +484,7 @@ fn attributes_link_added() {
             0,
             r#"[{"insert":"123456","attributes":{"link":"https://appflowy.io"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -477,6 +504,7 @@ fn attributes_link_format_with_bold() {
             {"insert":"\n"}]
             "#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -496,6 +524,7 @@ fn attributes_link_insert_char_at_head() {
             0,
             r#"[{"insert":"a"},{"insert":"123456","attributes":{"link":"https://appflowy.io"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -511,6 +540,7 @@ fn attributes_link_insert_char_at_middle() {
             0,
             r#"[{"insert":"123456","attributes":{"link":"https://appflowy.io"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -530,6 +560,7 @@ fn attributes_link_insert_char_at_trailing() {
             0,
             r#"[{"insert":"123456","attributes":{"link":"https://appflowy.io"}},{"insert":"a\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -545,6 +576,7 @@ fn attributes_link_insert_newline_at_middle() {
             0,
             r#"[{"insert":"123","attributes":{"link":"https://appflowy.io"}},{"insert":"\n"},{"insert":"456","attributes":{"link":"https://appflowy.io"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -561,6 +593,7 @@ fn attributes_link_auto_format() {
             0,
             r#"[{"insert":"https://appflowy.io","attributes":{"link":"https://appflowy.io/"}},{"insert":" \n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -577,6 +610,7 @@ fn attributes_link_auto_format_exist() {
             0,
             r#"[{"insert":"https://appflowy.io","attributes":{"link":"https://appflowy.io/"}},{"insert":" \n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -593,6 +627,7 @@ fn attributes_link_auto_format_exist2() {
             0,
             r#"[{"insert":"https://a","attributes":{"link":"https://appflowy.io"}},{"insert":"ppflowy.io \n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -604,6 +639,7 @@ fn attributes_bullet_added() {
         Insert(0, "12", 0),
         Bullet(0, Interval::new(0, 1), true),
         AssertDocJson(0, r#"[{"insert":"12"},{"insert":"\n","attributes":{"list":"bullet"}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -625,6 +661,7 @@ fn attributes_bullet_added_2() {
             0,
             r#"[{"insert":"1"},{"insert":"\n","attributes":{"list":"bullet"}},{"insert":"2"},{"insert":"\n","attributes":{"list":"bullet"}}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -642,6 +679,7 @@ fn attributes_bullet_remove_partial() {
             0,
             r#"[{"insert":"1"},{"insert":"\n","attributes":{"list":"bullet"}},{"insert":"2\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -658,6 +696,7 @@ fn attributes_bullet_auto_exit() {
             0,
             r#"[{"insert":"1"},{"insert":"\n","attributes":{"list":"bullet"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -698,6 +737,7 @@ fn attributes_preserve_block_when_insert_newline_inside() {
             {"insert":"34"},{"insert":"\n","attributes":{"list":"bullet"}}
             ]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -715,6 +755,7 @@ fn attributes_preserve_header_format_on_merge() {
         ),
         Delete(0, Interval::new(3, 4)),
         AssertDocJson(0, r#"[{"insert":"123456"},{"insert":"\n","attributes":{"header":1}}]"#),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -736,6 +777,7 @@ fn attributes_format_emoji() {
             0,
             r#"[{"insert":"👋 "},{"insert":"\n","attributes":{"header":1}}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
 }
@@ -755,6 +797,7 @@ fn attributes_preserve_list_format_on_merge() {
             0,
             r#"[{"insert":"123456"},{"insert":"\n","attributes":{"list":"bullet"}}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
@@ -794,7 +837,54 @@ fn delta_compose() {
             0,
             r#"[{"insert":"a"},{"insert":"\n","attributes":{"list":"bullet"}},{"insert":"\n"}]"#,
         ),
+        AssertMarkdownRoundTrip(0),
     ];
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
 }
+
+#[test]
+fn attributes_custom_inline_added() {
+    register_custom_attribute("acme:highlight", AttributeScope::Inline, AttributeValueType::String).unwrap();
+    let highlight = RichTextAttribute::custom("acme:highlight", "yellow").unwrap();
+    let ops = vec![
+        Insert(0, "123456", 0),
+        Format(0, Interval::new(2, 4), highlight),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"12"},{"insert":"34","attributes":{"acme:highlight":"yellow"}},{"insert":"56\n"}]"#,
+        ),
+        Insert(0, "x", 3),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"12"},{"insert":"3x4","attributes":{"acme:highlight":"yellow"}},{"insert":"56\n"}]"#,
+        ),
+        AssertMarkdownRoundTrip(0),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn attributes_custom_block_insert_newline_at_middle() {
+    register_custom_attribute("acme:callout", AttributeScope::Block, AttributeValueType::Bool).unwrap();
+    let callout = RichTextAttribute::custom("acme:callout", true).unwrap();
+    let ops = vec![
+        Insert(0, "123456", 0),
+        Format(0, Interval::new(0, 6), callout),
+        AssertDocJson(0, r#"[{"insert":"123456"},{"insert":"\n","attributes":{"acme:callout":true}}]"#),
+        Insert(0, "\n", 3),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"123"},{"insert":"\n","attributes":{"acme:callout":true}},{"insert":"456"},{"insert":"\n","attributes":{"acme:callout":true}}]"#,
+        ),
+        AssertMarkdownRoundTrip(0),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn attributes_custom_unregistered_preserved() {
+    let json = r#"[{"insert":"123","attributes":{"other:tag":"a"}},{"insert":"\n"}]"#;
+    let delta = RichTextDelta::from_delta_str(json).unwrap();
+    assert_eq!(delta.to_delta_str(), json);
+}
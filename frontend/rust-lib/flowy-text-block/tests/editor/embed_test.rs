@@ -0,0 +1,124 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::{NewlineDoc, PlainDoc, RECORD_THRESHOLD};
+use lib_ot::core::{EmbedData, Interval};
+
+fn image() -> EmbedData {
+    EmbedData::image("https://appflowy.io/logo.png")
+}
+
+fn page_mention() -> EmbedData {
+    EmbedData::PageMention("page_id".to_owned())
+}
+
+#[test]
+fn embed_insert_image_in_the_middle_of_line_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        Header(0, Interval::new(0, 6), 1),
+        InsertEmbed(0, image(), 3),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"123"},{"insert":"\n","attributes":{"header":1}},{"insert":{"image":"https://appflowy.io/logo.png"}},{"insert":"\n456"},{"insert":"\n","attributes":{"header":1}}]"#,
+        ),
+        AssertStr(0, "123\n\u{FFFC}\n456\n"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn embed_insert_divider_in_empty_line_test() {
+    let ops = vec![
+        InsertEmbed(0, EmbedData::Divider, 0),
+        AssertDocJson(0, r#"[{"insert":{"divider":"hr"}},{"insert":"\n"}]"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn embed_insert_text_around_block_embed_test() {
+    let ops = vec![
+        InsertEmbed(0, EmbedData::Divider, 0),
+        Insert(0, "abc", 1),
+        AssertDocJson(0, r#"[{"insert":{"divider":"hr"}},{"insert":"\nabc\n"}]"#),
+        Insert(0, "123", 0),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"123\n"},{"insert":{"divider":"hr"}},{"insert":"\nabc\n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn embed_insert_page_mention_test() {
+    let ops = vec![
+        Insert(0, "123", 0),
+        InsertEmbed(0, page_mention(), 1),
+        Insert(0, " ", 2),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"1"},{"insert":{"mention":{"page":"page_id"}}},{"insert":" 23\n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn embed_format_page_mention_test() {
+    let ops = vec![
+        Insert(0, "1", 0),
+        InsertEmbed(0, page_mention(), 1),
+        Bold(0, Interval::new(1, 2), true),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"1"},{"insert":{"mention":{"page":"page_id"}},"attributes":{"bold":true}}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn embed_delete_newline_around_block_embed_test() {
+    let doc = r#"[{"insert":"123\n"},{"insert":{"image":"https://appflowy.io/logo.png"}},{"insert":"\n456\n"}]"#;
+    let ops = vec![
+        Insert(0, "123456", 0),
+        InsertEmbed(0, image(), 3),
+        AssertDocJson(0, doc),
+        Delete(0, Interval::new(5, 6)),
+        AssertDocJson(0, doc),
+        Delete(0, Interval::new(3, 4)),
+        AssertDocJson(0, doc),
+        Delete(0, Interval::new(4, 5)),
+        AssertDocJson(0, r#"[{"insert":"123\n\n456\n"}]"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn embed_undo_redo_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        Wait(RECORD_THRESHOLD),
+        InsertEmbed(0, image(), 3),
+        Undo(0),
+        AssertDocJson(0, r#"[{"insert":"123456\n"}]"#),
+        Redo(0),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"123\n"},{"insert":{"image":"https://appflowy.io/logo.png"}},{"insert":"\n456\n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn embed_transform_test() {
+    let ops = vec![
+        Insert(0, "123", 0),
+        InsertEmbed(1, page_mention(), 0),
+        Transform(0, 1),
+        AssertDocJson(0, r#"[{"insert":"123"},{"insert":{"mention":{"page":"page_id"}}}]"#),
+        AssertDocJson(1, r#"[{"insert":"123"},{"insert":{"mention":{"page":"page_id"}}}]"#),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
@@ -0,0 +1,138 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::NewlineDoc;
+use lib_ot::core::Interval;
+use lib_ot::rich_text::RichTextAttribute;
+
+#[test]
+fn export_header_and_bold_test() {
+    let ops = vec![
+        Insert(0, "Title\nHello world", 0),
+        Header(0, Interval::new(0, 5), 1),
+        Bold(0, Interval::new(6, 11), true),
+        AssertMarkdown(0, "# Title\n**Hello** world"),
+        AssertHtml(0, "<h1>Title</h1><p><strong>Hello</strong> world</p>"),
+        AssertPlainText(0, "Title\nHello world"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_list_test() {
+    let ops = vec![
+        Insert(0, "a\nb\nc\nd", 0),
+        Bullet(0, Interval::new(0, 1), true),
+        Format(0, Interval::new(2, 5), RichTextAttribute::Ordered(true)),
+        Format(0, Interval::new(6, 7), RichTextAttribute::Checked(true)),
+        AssertMarkdown(0, "- a\n1. b\n2. c\n- [x] d"),
+        AssertHtml(
+            0,
+            r#"<ul><li>a</li></ul><ol><li>b</li><li>c</li></ol><ul class="checklist"><li data-checked="true">d</li></ul>"#,
+        ),
+        AssertPlainText(0, "a\nb\nc\nd"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_code_block_and_quote_test() {
+    let ops = vec![
+        Insert(0, "let a = 1;\nlet b = a * 2;\nquote", 0),
+        Format(0, Interval::new(0, 25), RichTextAttribute::CodeBlock(true)),
+        Format(0, Interval::new(26, 31), RichTextAttribute::BlockQuote(true)),
+        AssertMarkdown(0, "```\nlet a = 1;\nlet b = a * 2;\n```\n> quote"),
+        AssertHtml(
+            0,
+            "<pre><code>let a = 1;\nlet b = a * 2;</code></pre><blockquote>quote</blockquote>",
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_inline_attributes_test() {
+    let ops = vec![
+        Insert(0, "AppFlowy is <open> source, use code a_b", 0),
+        Link(0, Interval::new(0, 8), "https://appflowy.io"),
+        Italic(0, Interval::new(12, 18), true),
+        Format(0, Interval::new(19, 25), RichTextAttribute::StrikeThrough(true)),
+        Format(0, Interval::new(36, 39), RichTextAttribute::InlineCode(true)),
+        AssertMarkdown(
+            0,
+            "[AppFlowy](https://appflowy.io) is _<open>_ ~~source~~, use code `a_b`",
+        ),
+        AssertHtml(
+            0,
+            r#"<p><a href="https://appflowy.io">AppFlowy</a> is <em>&lt;open&gt;</em> <s>source</s>, use code <code>a_b</code></p>"#,
+        ),
+        AssertPlainText(0, "AppFlowy is <open> source, use code a_b"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_unsafe_link_to_html_test() {
+    let ops = vec![
+        Insert(0, "click me or read the docs", 0),
+        Link(0, Interval::new(0, 5), "javascript:alert(1)"),
+        Link(0, Interval::new(21, 25), "/docs#intro"),
+        AssertHtml(0, r#"<p>click me or read the <a href="/docs#intro">docs</a></p>"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_escape_markdown_test() {
+    let ops = vec![
+        Insert(0, "1 * 2 = [2]", 0),
+        Bold(0, Interval::new(0, 1), true),
+        AssertMarkdown(0, r#"**1** \* 2 = \[2\]"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_escape_block_marker_test() {
+    let ops = vec![
+        Insert(0, "# tag\n- dash\n+ plus\n> arrow\n12. twelve\n3) three\n#", 0),
+        AssertMarkdown(
+            0,
+            r#"\# tag
+\- dash
+\+ plus
+\> arrow
+12\. twelve
+3\) three
+\#"#,
+        ),
+        AssertMarkdownRoundTrip(0),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_markdown_round_trip_test() {
+    let ops = vec![
+        Insert(0, "Title\n# tag\nlist\n2. item\nquote", 0),
+        Header(0, Interval::new(0, 5), 2),
+        Bullet(0, Interval::new(12, 16), true),
+        Bold(0, Interval::new(12, 16), true),
+        Format(0, Interval::new(25, 30), RichTextAttribute::BlockQuote(true)),
+        AssertMarkdown(0, "## Title\n\\# tag\n- **list**\n2\\. item\n> quote"),
+        AssertMarkdownRoundTrip(0),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn export_adjacent_inline_attributes_test() {
+    let ops = vec![
+        Insert(0, "123456 abc", 0),
+        Bold(0, Interval::new(0, 6), true),
+        Italic(0, Interval::new(0, 2), true),
+        Italic(0, Interval::new(4, 6), true),
+        Italic(0, Interval::new(8, 9), true),
+        AssertMarkdown(0, "_**12**_**34**_**56**_ a*b*c"),
+        AssertMarkdownRoundTrip(0),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
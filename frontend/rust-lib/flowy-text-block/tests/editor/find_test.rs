@@ -0,0 +1,114 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::find::FindOptions;
+use flowy_sync::client_document::{NewlineDoc, PlainDoc, RECORD_THRESHOLD};
+use lib_ot::core::Interval;
+
+fn options(match_case: bool, whole_word: bool, regex: bool) -> FindOptions {
+    FindOptions {
+        match_case,
+        whole_word,
+        regex,
+    }
+}
+
+#[test]
+fn find_literal_test() {
+    let ops = vec![
+        Insert(0, "Hello hello HELLO", 0),
+        AssertFind(
+            0,
+            "hello",
+            options(false, false, false),
+            vec![Interval::new(0, 5), Interval::new(6, 11), Interval::new(12, 17)],
+        ),
+        AssertFind(0, "hello", options(true, false, false), vec![Interval::new(6, 11)]),
+        // The query is not a regex in the literal mode.
+        AssertFind(0, "h.llo", options(false, false, false), vec![]),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn find_whole_word_test() {
+    let ops = vec![
+        Insert(0, "cat concat cats cat", 0),
+        AssertFind(
+            0,
+            "cat",
+            options(false, true, false),
+            vec![Interval::new(0, 3), Interval::new(16, 19)],
+        ),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn find_utf16_interval_test() {
+    let ops = vec![
+        Insert(0, "😀abc😀abc", 0),
+        AssertFind(
+            0,
+            "abc",
+            options(false, false, false),
+            vec![Interval::new(2, 5), Interval::new(7, 10)],
+        ),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn replace_all_matches_with_regex_test() {
+    let ops = vec![
+        Insert(0, "2022-06 and 2021-12", 0),
+        ReplaceMatches(0, r"(\d+)-(\d+)", options(false, false, true), "$2/$1", None),
+        AssertStr(0, "06/2022 and 12/2021\n"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn replace_selected_matches_test() {
+    let ops = vec![
+        Insert(0, "abc abc abc", 0),
+        ReplaceMatches(
+            0,
+            "abc",
+            options(false, false, false),
+            "x",
+            Some(vec![Interval::new(4, 7), Interval::new(0, 2)]),
+        ),
+        // The interval that is not a match is skipped.
+        AssertStr(0, "abc x abc\n"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn replace_matches_keep_attributes_test() {
+    let ops = vec![
+        Insert(0, "abc abc", 0),
+        Bold(0, Interval::new(0, 3), true),
+        ReplaceMatches(0, "abc", options(false, false, false), "12", None),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"12","attributes":{"bold":"true"}},{"insert":" 12\n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn replace_matches_undo_in_one_step_test() {
+    let ops = vec![
+        Insert(0, "abc abc abc", 0),
+        Wait(RECORD_THRESHOLD),
+        ReplaceMatches(0, "b", options(false, false, false), "", None),
+        AssertStr(0, "ac ac ac\n"),
+        Undo(0),
+        AssertStr(0, "abc abc abc\n"),
+        AssertSelection(0, Interval::new(1, 10)),
+        Redo(0),
+        AssertStr(0, "ac ac ac\n"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
@@ -0,0 +1,98 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::NewlineDoc;
+
+#[test]
+fn import_markdown_header_and_bold_test() {
+    let ops = vec![
+        ImportMarkdown(0, "# Title\n**Hello** world"),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"Title"},{"insert":"\n","attributes":{"header":1}},{"insert":"Hello","attributes":{"bold":true}},{"insert":" world\n"}]"#,
+        ),
+        AssertMarkdown(0, "# Title\n**Hello** world"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn import_markdown_list_test() {
+    let markdown = "- a\n1. b\n2. c\n- [x] d\n- [ ] e\n    - f";
+    let ops = vec![
+        ImportMarkdown(0, markdown),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"a"},{"insert":"\n","attributes":{"list":"bullet"}},{"insert":"b"},{"insert":"\n","attributes":{"list":"ordered"}},{"insert":"c"},{"insert":"\n","attributes":{"list":"ordered"}},{"insert":"d"},{"insert":"\n","attributes":{"list":"checked"}},{"insert":"e"},{"insert":"\n","attributes":{"list":"unchecked"}},{"insert":"f"},{"insert":"\n","attributes":{"list":"bullet","indent":1}}]"#,
+        ),
+        AssertMarkdown(0, markdown),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn import_markdown_code_block_and_inline_attributes_test() {
+    let ops = vec![
+        ImportMarkdown(
+            0,
+            "```\nlet a = 1;\n```\n> [AppFlowy](https://appflowy.io) is _open_ ~~source~~, use `a_b`",
+        ),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"let a = 1;"},{"insert":"\n","attributes":{"code_block":true}},{"insert":"AppFlowy","attributes":{"link":"https://appflowy.io"}},{"insert":" is "},{"insert":"open","attributes":{"italic":true}},{"insert":" "},{"insert":"source","attributes":{"strike":true}},{"insert":", use "},{"insert":"a_b","attributes":{"code":true}},{"insert":"\n","attributes":{"blockquote":true}}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn import_markdown_keep_unmatched_marks_test() {
+    let ops = vec![
+        ImportMarkdown(0, r#"snake_case **1** \* 2 = [2] **open"#),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"snake_case "},{"insert":"1","attributes":{"bold":true}},{"insert":" * 2 = [2] **open\n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn import_html_test() {
+    let ops = vec![
+        ImportHtml(
+            0,
+            r#"<html><head><title>Doc</title></head><body>
+            <h2>Hi &amp; bye</h2>
+            <p>one  <strong>two </strong>three<br>four</p>
+            <ul><li>a<ol><li>b</li></ol></li></ul>
+            <ul><li><input type="checkbox" checked> done</li></ul>
+            <pre>
+fn a() {}
+</pre>
+            <p><a href="https://appflowy.io?a=1&amp;b=2">link</a></p>
+            </body></html>"#,
+        ),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"Hi & bye"},{"insert":"\n","attributes":{"header":2}},{"insert":"one "},{"insert":"two ","attributes":{"bold":true}},{"insert":"three\nfour\na"},{"insert":"\n","attributes":{"list":"bullet"}},{"insert":"b"},{"insert":"\n","attributes":{"list":"ordered","indent":1}},{"insert":"done"},{"insert":"\n","attributes":{"list":"checked"}},{"insert":"fn a() {}"},{"insert":"\n","attributes":{"code_block":true}},{"insert":"link","attributes":{"link":"https://appflowy.io?a=1&b=2"}},{"insert":"\n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn import_exported_html_test() {
+    let html = r#"<ul><li>a</li><li class="ql-indent-1">b</li></ul><ul class="checklist"><li data-checked="false">c</li></ul><p><br></p><pre><code>let a = 1;
+let b = a * 2;</code></pre><blockquote><em>quote</em></blockquote>"#;
+    let ops = vec![ImportHtml(0, html), AssertHtml(0, html)];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn import_replace_document_test() {
+    let ops = vec![
+        Insert(0, "123", 0),
+        ImportMarkdown(0, "## 456"),
+        AssertDocJson(0, r#"[{"insert":"456"},{"insert":"\n","attributes":{"header":2}}]"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
@@ -0,0 +1,60 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::NewlineDoc;
+use lib_ot::core::{EmbedData, Interval};
+use lib_ot::rich_text::RichTextAttribute;
+
+#[test]
+fn links_read_page_links_and_mentions_test() {
+    let ops = vec![
+        Insert(0, "see Getting started and ", 0),
+        Format(0, Interval::new(4, 19), RichTextAttribute::PageLink("view_1")),
+        InsertEmbed(0, EmbedData::PageMention("view_2".to_owned()), 24),
+        // The mention of the linked view doesn't add another link.
+        InsertEmbed(0, EmbedData::PageMention("view_1".to_owned()), 25),
+        AssertViewLinks(0, vec![("view_1", "Getting started"), ("view_2", "")]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn links_partially_formatted_link_is_one_link_test() {
+    let ops = vec![
+        Insert(0, "Getting started", 0),
+        Format(0, Interval::new(0, 15), RichTextAttribute::PageLink("view_1")),
+        Bold(0, Interval::new(0, 7), true),
+        AssertViewLinks(0, vec![("view_1", "Getting started")]),
+        RenameViewLinks(0, "view_1", "Intro"),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"Intro","attributes":{"bold":"true","page_link":"view_1"}},{"insert":"\n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn links_rename_page_links_test() {
+    let ops = vec![
+        Insert(0, "a b a", 0),
+        Format(0, Interval::new(0, 1), RichTextAttribute::PageLink("view_1")),
+        Format(0, Interval::new(2, 3), RichTextAttribute::PageLink("view_2")),
+        Format(0, Interval::new(4, 5), RichTextAttribute::PageLink("view_1")),
+        RenameViewLinks(0, "view_1", "page"),
+        AssertStr(0, "page b page\n"),
+        AssertViewLinks(0, vec![("view_1", "page"), ("view_2", "b")]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn links_typing_after_page_link_test() {
+    let ops = vec![
+        Insert(0, "page", 0),
+        Format(0, Interval::new(0, 4), RichTextAttribute::PageLink("view_1")),
+        Insert(0, "!", 4),
+        AssertViewLinks(0, vec![("view_1", "page")]),
+        Delete(0, Interval::new(0, 4)),
+        AssertViewLinks(0, vec![]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
@@ -0,0 +1,108 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::{NewlineDoc, RECORD_THRESHOLD};
+use lib_ot::core::{NEW_LINE, WHITESPACE};
+
+#[test]
+fn markdown_shortcut_header_test() {
+    let ops = vec![
+        Insert(0, "##", 0),
+        Insert(0, WHITESPACE, 2),
+        Insert(0, "Title", 0),
+        AssertDocJson(0, r#"[{"insert":"Title"},{"insert":"\n","attributes":{"header":2}}]"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn markdown_shortcut_header_keep_line_content_test() {
+    let ops = vec![
+        Insert(0, "hello", 0),
+        Insert(0, "#", 0),
+        Insert(0, WHITESPACE, 1),
+        AssertDocJson(0, r#"[{"insert":"hello"},{"insert":"\n","attributes":{"header":1}}]"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn markdown_shortcut_list_and_quote_test() {
+    let ops = vec![
+        Insert(0, "a\nb\nc\nd\ne", 0),
+        Insert(0, "-", 0),
+        Insert(0, WHITESPACE, 1),
+        Insert(0, "1.", 2),
+        Insert(0, WHITESPACE, 4),
+        Insert(0, "[]", 4),
+        Insert(0, WHITESPACE, 6),
+        Insert(0, "[x]", 6),
+        Insert(0, WHITESPACE, 9),
+        Insert(0, ">", 8),
+        Insert(0, WHITESPACE, 9),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"a"},{"insert":"\n","attributes":{"list":"bullet"}},{"insert":"b"},{"insert":"\n","attributes":{"list":"ordered"}},{"insert":"c"},{"insert":"\n","attributes":{"list":"unchecked"}},{"insert":"d"},{"insert":"\n","attributes":{"list":"checked"}},{"insert":"e"},{"insert":"\n","attributes":{"blockquote":true}}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn markdown_shortcut_code_block_test() {
+    let ops = vec![
+        Insert(0, "```", 0),
+        Insert(0, NEW_LINE, 3),
+        Insert(0, "`a`", 0),
+        Insert(0, WHITESPACE, 3),
+        Insert(0, "-", 0),
+        Insert(0, WHITESPACE, 1),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"- `a` "},{"insert":"\n","attributes":{"code_block":true}}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn markdown_shortcut_inline_test() {
+    let ops = vec![
+        Insert(0, "**bold**", 0),
+        Insert(0, WHITESPACE, 8),
+        Insert(0, "_it_", 5),
+        Insert(0, WHITESPACE, 9),
+        Insert(0, "`c`", 8),
+        Insert(0, WHITESPACE, 11),
+        Insert(0, "~~s~~", 10),
+        Insert(0, WHITESPACE, 15),
+        AssertDocJson(
+            0,
+            r#"[{"insert":"bold","attributes":{"bold":true}},{"insert":" "},{"insert":"it","attributes":{"italic":true}},{"insert":" "},{"insert":"c","attributes":{"code":true}},{"insert":" "},{"insert":"s","attributes":{"strike":true}},{"insert":" \n"}]"#,
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn markdown_shortcut_inline_ignore_unmatched_marks_test() {
+    let ops = vec![
+        Insert(0, "snake_case_", 0),
+        Insert(0, WHITESPACE, 11),
+        Insert(0, "a ** b**", 12),
+        Insert(0, WHITESPACE, 20),
+        AssertDocJson(0, r#"[{"insert":"snake_case_ a ** b** \n"}]"#),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn markdown_shortcut_undo_test() {
+    let ops = vec![
+        Insert(0, "#", 0),
+        Wait(RECORD_THRESHOLD),
+        Insert(0, WHITESPACE, 1),
+        AssertDocJson(0, r#"[{"insert":"\n","attributes":{"header":1}}]"#),
+        Undo(0),
+        AssertDocJson(0, r##"[{"insert":"#\n"}]"##),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
@@ -1,10 +1,26 @@
 #![allow(clippy::module_inception)]
+mod anchor_test;
 mod attribute_test;
+mod embed_test;
+mod export_test;
+mod find_test;
+mod import_test;
+mod links_test;
+mod markdown_shortcut_test;
 mod op_test;
+mod outline_test;
+mod selection_test;
 mod serde_test;
+mod table_test;
 mod undo_redo_test;
 
 use derive_more::Display;
+use flowy_sync::client_document::export::{delta_to_html, delta_to_markdown, delta_to_plain_text};
+use flowy_sync::client_document::find::{DocumentFinder, FindOptions};
+use flowy_sync::client_document::history::HistoryConfig;
+use flowy_sync::client_document::import::{html_to_delta, markdown_to_delta};
+use flowy_sync::client_document::links::{rename_view_links, view_links_from_delta};
+use flowy_sync::client_document::outline::{BlockType, DocumentOutline};
 use flowy_sync::client_document::{ClientDocument, InitialDocumentText};
 use lib_ot::{
     core::*,
@@ -20,6 +36,9 @@ pub enum TestOp {
     #[display(fmt = "Insert")]
     Insert(usize, &'static str, usize),
 
+    #[display(fmt = "InsertEmbed")]
+    InsertEmbed(usize, EmbedData, usize),
+
     // delta_i, s, start, length,
     #[display(fmt = "InsertBold")]
     InsertBold(usize, &'static str, Interval),
@@ -46,6 +65,9 @@ pub enum TestOp {
     #[display(fmt = "Bullet")]
     Bullet(usize, Interval, bool),
 
+    #[display(fmt = "Format")]
+    Format(usize, Interval, RichTextAttribute),
+
     #[display(fmt = "Transform")]
     Transform(usize, usize),
 
@@ -56,6 +78,59 @@ pub enum TestOp {
     #[display(fmt = "Invert")]
     Invert(usize, usize),
 
+    // compose the delta of the collaborator, it's not recorded in the history
+    #[display(fmt = "ComposeRemote")]
+    ComposeRemote(usize, &'static str),
+
+    #[display(fmt = "SetHistoryConfig")]
+    SetHistoryConfig(usize, HistoryConfig),
+
+    // attach the anchor with the id to the interval
+    #[display(fmt = "AddAnchor")]
+    AddAnchor(usize, &'static str, Interval),
+
+    // set the selection of the user
+    #[display(fmt = "SetUserSelection")]
+    SetUserSelection(usize, &'static str, Interval),
+
+    // set the selection that the user made before the remote deltas were applied
+    #[display(fmt = "SetRemoteSelection")]
+    SetRemoteSelection(usize, &'static str, Interval, Vec<&'static str>),
+
+    // hold the selection that the user made in the revision that isn't received yet
+    #[display(fmt = "HoldRemoteSelection")]
+    HoldRemoteSelection(usize, &'static str, i64, Interval),
+
+    // set the held selections of the revisions up to the rev_id, the document is at their revision
+    #[display(fmt = "SetHeldSelections")]
+    SetHeldSelections(usize, i64),
+
+    // replace the matches of the query, only the selected matches are replaced if it's not None
+    #[display(fmt = "ReplaceMatches")]
+    ReplaceMatches(usize, &'static str, FindOptions, &'static str, Option<Vec<Interval>>),
+
+    // replace the text of the page links to the view with the title
+    #[display(fmt = "RenameViewLinks")]
+    RenameViewLinks(usize, &'static str, &'static str),
+
+    // insert the table after the line of the index, the number of the rows and the columns
+    #[display(fmt = "InsertTable")]
+    InsertTable(usize, usize, usize, usize),
+
+    // the index in the table and the index of the row
+    #[display(fmt = "InsertTableRow")]
+    InsertTableRow(usize, usize, usize),
+
+    #[display(fmt = "DeleteTableRow")]
+    DeleteTableRow(usize, usize, usize),
+
+    // the index in the table and the index of the column
+    #[display(fmt = "InsertTableColumn")]
+    InsertTableColumn(usize, usize, usize),
+
+    #[display(fmt = "DeleteTableColumn")]
+    DeleteTableColumn(usize, usize, usize),
+
     #[display(fmt = "Undo")]
     Undo(usize),
 
@@ -65,6 +140,41 @@ pub enum TestOp {
     #[display(fmt = "Wait")]
     Wait(usize),
 
+    #[display(fmt = "AssertCanUndo")]
+    AssertCanUndo(usize, bool),
+
+    // assert the selection restored by the last undo or redo
+    #[display(fmt = "AssertSelection")]
+    AssertSelection(usize, Interval),
+
+    #[display(fmt = "AssertAnchor")]
+    AssertAnchor(usize, &'static str, Interval),
+
+    #[display(fmt = "AssertUserSelection")]
+    AssertUserSelection(usize, &'static str, Interval),
+
+    #[display(fmt = "AssertNoUserSelection")]
+    AssertNoUserSelection(usize, &'static str),
+
+    #[display(fmt = "AssertFind")]
+    AssertFind(usize, &'static str, FindOptions, Vec<Interval>),
+
+    // the view id and the title of each link
+    #[display(fmt = "AssertViewLinks")]
+    AssertViewLinks(usize, Vec<(&'static str, &'static str)>),
+
+    // the level, the text and the offset of each header
+    #[display(fmt = "AssertOutline")]
+    AssertOutline(usize, Vec<(usize, &'static str, usize)>),
+
+    // the words and the characters of the document, the number of the blocks of each type
+    #[display(fmt = "AssertStats")]
+    AssertStats(usize, usize, usize, Vec<(BlockType, usize)>),
+
+    // the text of each cell of each row of the table that contains the index
+    #[display(fmt = "AssertTable")]
+    AssertTable(usize, usize, Vec<Vec<&'static str>>),
+
     #[display(fmt = "AssertStr")]
     AssertStr(usize, &'static str),
 
@@ -74,6 +184,27 @@ pub enum TestOp {
     #[display(fmt = "AssertPrimeJson")]
     AssertPrimeJson(usize, &'static str),
 
+    #[display(fmt = "AssertMarkdown")]
+    AssertMarkdown(usize, &'static str),
+
+    #[display(fmt = "AssertHtml")]
+    AssertHtml(usize, &'static str),
+
+    #[display(fmt = "AssertPlainText")]
+    AssertPlainText(usize, &'static str),
+
+    // replace the content of the document with the imported markdown
+    #[display(fmt = "ImportMarkdown")]
+    ImportMarkdown(usize, &'static str),
+
+    // replace the content of the document with the imported html
+    #[display(fmt = "ImportHtml")]
+    ImportHtml(usize, &'static str),
+
+    // export the document as markdown and import it again, the text and the markdown are kept
+    #[display(fmt = "AssertMarkdownRoundTrip")]
+    AssertMarkdownRoundTrip(usize),
+
     #[display(fmt = "DocComposeDelta")]
     DocComposeDelta(usize, usize),
 
@@ -85,6 +216,7 @@ pub struct TestBuilder {
     documents: Vec<ClientDocument>,
     deltas: Vec<Option<RichTextDelta>>,
     primes: Vec<Option<RichTextDelta>>,
+    selections: Vec<Option<Interval>>,
 }
 
 impl TestBuilder {
@@ -99,9 +231,17 @@ impl TestBuilder {
             documents: vec![],
             deltas: vec![],
             primes: vec![],
+            selections: vec![],
         }
     }
 
+    fn replace_document(&mut self, doc_i: usize, delta: RichTextDelta) {
+        let document = &mut self.documents[doc_i];
+        let mut changeset = delta;
+        changeset.delete(document.len());
+        document.compose_delta(changeset).unwrap();
+    }
+
     fn run_op(&mut self, op: &TestOp) {
         tracing::trace!("***************** 😈{} *******************", &op);
         match op {
@@ -112,6 +252,13 @@ impl TestBuilder {
 
                 self.deltas.insert(*delta_i, Some(delta));
             }
+            TestOp::InsertEmbed(delta_i, data, index) => {
+                let document = &mut self.documents[*delta_i];
+                let delta = document.insert_embed(*index, data.clone()).unwrap();
+                tracing::debug!("Insert embed delta: {}", delta.to_delta_str());
+
+                self.deltas.insert(*delta_i, Some(delta));
+            }
             TestOp::Delete(delta_i, iv) => {
                 let document = &mut self.documents[*delta_i];
                 let delta = document.replace(*iv, "").unwrap();
@@ -168,10 +315,16 @@ impl TestBuilder {
 
                 self.deltas.insert(*delta_i, Some(delta));
             }
+            TestOp::Format(delta_i, iv, attribute) => {
+                let document = &mut self.documents[*delta_i];
+                let delta = document.format(*iv, attribute.clone()).unwrap();
+                tracing::trace!("Format delta: {}", delta.to_delta_str());
+                self.deltas.insert(*delta_i, Some(delta));
+            }
             TestOp::Transform(delta_a_i, delta_b_i) => {
                 let (a_prime, b_prime) = self.documents[*delta_a_i]
                     .delta()
-                    .transform(self.documents[*delta_b_i].delta())
+                    .transform(&self.documents[*delta_b_i].delta())
                     .unwrap();
                 tracing::trace!("a:{:?},b:{:?}", a_prime, b_prime);
 
@@ -184,21 +337,21 @@ impl TestBuilder {
             TestOp::TransformPrime(a_doc_index, b_doc_index) => {
                 let (prime_left, prime_right) = self.documents[*a_doc_index]
                     .delta()
-                    .transform(self.documents[*b_doc_index].delta())
+                    .transform(&self.documents[*b_doc_index].delta())
                     .unwrap();
 
                 self.primes.insert(*a_doc_index, Some(prime_left));
                 self.primes.insert(*b_doc_index, Some(prime_right));
             }
             TestOp::Invert(delta_a_i, delta_b_i) => {
-                let delta_a = &self.documents[*delta_a_i].delta();
-                let delta_b = &self.documents[*delta_b_i].delta();
+                let delta_a = self.documents[*delta_a_i].delta();
+                let delta_b = self.documents[*delta_b_i].delta();
                 tracing::debug!("Invert: ");
                 tracing::debug!("a: {}", delta_a.to_delta_str());
                 tracing::debug!("b: {}", delta_b.to_delta_str());
 
-                let (_, b_prime) = delta_a.transform(delta_b).unwrap();
-                let undo = b_prime.invert(delta_a);
+                let (_, b_prime) = delta_a.transform(&delta_b).unwrap();
+                let undo = b_prime.invert(&delta_a);
 
                 let new_delta = delta_a.compose(&b_prime).unwrap();
                 tracing::debug!("new delta: {}", new_delta.to_delta_str());
@@ -208,19 +361,166 @@ impl TestBuilder {
 
                 tracing::debug!("inverted delta a: {}", new_delta_after_undo.to_string());
 
-                assert_eq!(delta_a, &&new_delta_after_undo);
+                assert_eq!(delta_a, new_delta_after_undo);
 
                 self.documents[*delta_a_i].set_delta(new_delta_after_undo);
             }
+            TestOp::ComposeRemote(delta_i, delta_json) => {
+                let delta = RichTextDelta::from_delta_str(delta_json).unwrap();
+                self.documents[*delta_i].compose_remote_delta(delta).unwrap();
+            }
+            TestOp::SetHistoryConfig(delta_i, config) => {
+                self.documents[*delta_i].set_history_config(config.clone());
+            }
+            TestOp::AddAnchor(delta_i, id, interval) => {
+                self.documents[*delta_i].add_anchor(id, *interval).unwrap();
+            }
+            TestOp::InsertTable(delta_i, index, rows, columns) => {
+                let delta = self.documents[*delta_i].insert_table(*index, *rows, *columns).unwrap();
+                self.deltas.insert(*delta_i, Some(delta));
+            }
+            TestOp::InsertTableRow(delta_i, index, row) => {
+                let delta = self.documents[*delta_i].insert_table_row(*index, *row).unwrap();
+                self.deltas.insert(*delta_i, Some(delta));
+            }
+            TestOp::DeleteTableRow(delta_i, index, row) => {
+                let delta = self.documents[*delta_i].delete_table_row(*index, *row).unwrap();
+                self.deltas.insert(*delta_i, Some(delta));
+            }
+            TestOp::InsertTableColumn(delta_i, index, column) => {
+                let delta = self.documents[*delta_i].insert_table_column(*index, *column).unwrap();
+                self.deltas.insert(*delta_i, Some(delta));
+            }
+            TestOp::DeleteTableColumn(delta_i, index, column) => {
+                let delta = self.documents[*delta_i].delete_table_column(*index, *column).unwrap();
+                self.deltas.insert(*delta_i, Some(delta));
+            }
+            TestOp::SetUserSelection(delta_i, user_id, interval) => {
+                self.documents[*delta_i].set_selection(user_id, *interval).unwrap();
+            }
+            TestOp::SetRemoteSelection(delta_i, user_id, interval, delta_jsons) => {
+                let deltas = delta_jsons
+                    .iter()
+                    .map(|delta_json| RichTextDelta::from_delta_str(delta_json).unwrap())
+                    .collect::<Vec<RichTextDelta>>();
+                self.documents[*delta_i].set_remote_selection(user_id, *interval, &deltas);
+            }
+            TestOp::HoldRemoteSelection(delta_i, user_id, rev_id, interval) => {
+                self.documents[*delta_i].hold_selection(user_id, *rev_id, *interval);
+            }
+            TestOp::SetHeldSelections(delta_i, rev_id) => {
+                let document = &mut self.documents[*delta_i];
+                for (user_id, _, interval) in document.take_held_selections(*rev_id) {
+                    document.set_remote_selection(&user_id, interval, &[]);
+                }
+            }
+            TestOp::ReplaceMatches(delta_i, query, options, replacement, selected) => {
+                let finder = DocumentFinder::new(query, options.clone()).unwrap();
+                let document = &mut self.documents[*delta_i];
+                let delta = document
+                    .replace_matches(&finder, replacement, selected.as_deref())
+                    .unwrap();
+                self.deltas.insert(*delta_i, Some(delta));
+            }
+            TestOp::RenameViewLinks(delta_i, view_id, title) => {
+                let document = &mut self.documents[*delta_i];
+                if let Some(delta) = rename_view_links(&document.delta(), view_id, title) {
+                    document.compose_delta(delta.clone()).unwrap();
+                    self.deltas.insert(*delta_i, Some(delta));
+                }
+            }
             TestOp::Undo(delta_i) => {
-                self.documents[*delta_i].undo().unwrap();
+                let result = self.documents[*delta_i].undo().unwrap();
+                self.selections[*delta_i] = Some(result.selection);
             }
             TestOp::Redo(delta_i) => {
-                self.documents[*delta_i].redo().unwrap();
+                let result = self.documents[*delta_i].redo().unwrap();
+                self.selections[*delta_i] = Some(result.selection);
             }
             TestOp::Wait(mills_sec) => {
                 std::thread::sleep(Duration::from_millis(*mills_sec as u64));
             }
+            TestOp::AssertCanUndo(delta_i, expected) => {
+                assert_eq!(self.documents[*delta_i].can_undo(), *expected);
+            }
+            TestOp::AssertSelection(delta_i, expected) => {
+                assert_eq!(self.selections[*delta_i], Some(*expected));
+            }
+            TestOp::AssertAnchor(delta_i, id, expected) => {
+                assert_eq!(self.documents[*delta_i].anchor(id), Some(*expected));
+            }
+            TestOp::AssertTable(delta_i, index, expected) => {
+                let document = &self.documents[*delta_i];
+                let text = FlowyStr::from(document.to_plain_string());
+                let table = document
+                    .tables()
+                    .into_iter()
+                    .find(|table| table.interval.contains(*index))
+                    .unwrap();
+                let cells: Vec<Vec<String>> = table
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        table
+                            .columns
+                            .iter()
+                            .map(|column_id| match row.cell(column_id) {
+                                None => "".to_owned(),
+                                Some(cell) => {
+                                    let interval = Interval::new(cell.interval.start, cell.interval.end - 1);
+                                    text.sub_str(interval).unwrap_or_default()
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect();
+                assert_eq!(&cells, expected);
+            }
+            TestOp::AssertUserSelection(delta_i, user_id, expected) => {
+                assert_eq!(self.documents[*delta_i].selection(user_id), Some(*expected));
+            }
+            TestOp::AssertNoUserSelection(delta_i, user_id) => {
+                assert_eq!(self.documents[*delta_i].selection(user_id), None);
+            }
+            TestOp::AssertFind(delta_i, query, options, expected) => {
+                let finder = DocumentFinder::new(query, options.clone()).unwrap();
+                assert_eq!(&self.documents[*delta_i].find(&finder), expected);
+            }
+            TestOp::AssertViewLinks(delta_i, expected) => {
+                let links = view_links_from_delta(&self.documents[*delta_i].delta())
+                    .into_iter()
+                    .map(|link| (link.view_id, link.title))
+                    .collect::<Vec<(String, String)>>();
+                let expected = expected
+                    .iter()
+                    .map(|(view_id, title)| (view_id.to_string(), title.to_string()))
+                    .collect::<Vec<(String, String)>>();
+                assert_eq!(links, expected);
+            }
+            TestOp::AssertOutline(delta_i, expected) => {
+                let document = &self.documents[*delta_i];
+                // The outline that was updated incrementally is the same as the rebuilt one.
+                let outline = DocumentOutline::from_delta(&document.delta());
+                assert_eq!(document.outline(), outline.headers());
+                assert_eq!(document.stats(), outline.stats());
+
+                let headers = document
+                    .outline()
+                    .into_iter()
+                    .map(|header| (header.level, header.text, header.offset))
+                    .collect::<Vec<(usize, String, usize)>>();
+                let expected = expected
+                    .iter()
+                    .map(|(level, text, offset)| (*level, text.to_string(), *offset))
+                    .collect::<Vec<(usize, String, usize)>>();
+                assert_eq!(headers, expected);
+            }
+            TestOp::AssertStats(delta_i, words, characters, blocks) => {
+                let stats = self.documents[*delta_i].stats();
+                assert_eq!(stats.words, *words);
+                assert_eq!(stats.characters, *characters);
+                assert_eq!(stats.blocks.into_iter().collect::<Vec<(BlockType, usize)>>(), *blocks);
+            }
             TestOp::AssertStr(delta_i, expected) => {
                 assert_eq!(&self.documents[*delta_i].to_plain_string(), expected);
             }
@@ -248,6 +548,37 @@ impl TestBuilder {
                 }
                 assert_eq!(target_prime, expected_prime);
             }
+            TestOp::AssertMarkdown(doc_i, expected) => {
+                let markdown = delta_to_markdown(&self.documents[*doc_i].delta());
+                assert_eq!(&markdown, expected);
+            }
+            TestOp::AssertHtml(doc_i, expected) => {
+                let html = delta_to_html(&self.documents[*doc_i].delta());
+                assert_eq!(&html, expected);
+            }
+            TestOp::AssertPlainText(doc_i, expected) => {
+                let text = delta_to_plain_text(&self.documents[*doc_i].delta());
+                assert_eq!(&text, expected);
+            }
+            TestOp::ImportMarkdown(doc_i, markdown) => {
+                self.replace_document(*doc_i, markdown_to_delta(markdown));
+            }
+            TestOp::ImportHtml(doc_i, html) => {
+                self.replace_document(*doc_i, html_to_delta(html));
+            }
+            TestOp::AssertMarkdownRoundTrip(doc_i) => {
+                let delta = self.documents[*doc_i].delta();
+                let markdown = delta_to_markdown(&delta);
+                let imported = markdown_to_delta(&markdown);
+                assert_eq!(
+                    trim_trailing_whitespaces(&delta_to_plain_text(&imported)),
+                    trim_trailing_whitespaces(&delta_to_plain_text(&delta))
+                );
+                assert_eq!(
+                    trim_trailing_whitespaces(&delta_to_markdown(&imported)),
+                    trim_trailing_whitespaces(&markdown)
+                );
+            }
             TestOp::DocComposeDelta(doc_index, delta_i) => {
                 let delta = self.deltas.get(*delta_i).unwrap().as_ref().unwrap();
                 self.documents[*doc_index].compose_delta(delta.clone()).unwrap();
@@ -269,6 +600,7 @@ impl TestBuilder {
         self.documents = vec![ClientDocument::new::<C>(), ClientDocument::new::<C>()];
         self.primes = vec![None, None];
         self.deltas = vec![None, None];
+        self.selections = vec![None, None];
         for (_i, op) in scripts.iter().enumerate() {
             self.run_op(op);
         }
@@ -329,3 +661,13 @@ impl Rng {
         delta
     }
 }
+
+// The markdown doesn't keep the trailing whitespaces of the lines and the trailing empty lines.
+fn trim_trailing_whitespaces(s: &str) -> String {
+    s.lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_owned()
+}
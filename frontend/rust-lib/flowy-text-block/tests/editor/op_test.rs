@@ -1,6 +1,6 @@
 #![allow(clippy::all)]
 use crate::editor::{Rng, TestBuilder, TestOp::*};
-use flowy_sync::client_document::{NewlineDoc, PlainDoc};
+use flowy_sync::client_document::{ClientDocument, NewlineDoc, PlainDoc};
 use lib_ot::{
     core::*,
     rich_text::{AttributeBuilder, RichTextAttribute, RichTextAttributes, RichTextDelta},
@@ -749,3 +749,45 @@ fn delta_compose_with_missing_delta() {
     ];
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
 }
+
+#[test]
+fn delta_invert_str_in_utf16() {
+    let s = "👋😁👋";
+    let delta = DeltaBuilder::new().retain(2).delete(2).retain(2).build();
+    assert_eq!(delta.apply(s).unwrap(), "👋👋");
+
+    let inverted = delta.invert_str(s);
+    let expected: RichTextDelta = DeltaBuilder::new().retain(2).insert("😁").retain(2).build();
+    assert_eq!(inverted, expected);
+}
+
+#[test]
+fn document_edit_long_document() {
+    let mut delta = RichTextDelta::default();
+    for i in 0..2000 {
+        delta.insert(&format!("line {}", i), RichTextAttributes::default());
+        match i % 10 {
+            0 => delta.insert("\n", RichTextAttribute::Header(1).into()),
+            _ => delta.insert("\n", RichTextAttributes::default()),
+        }
+    }
+    let text = delta.apply("").unwrap();
+    let offset = text.find("line 1000\n").unwrap();
+    let mut document = ClientDocument::from_delta(delta.clone());
+
+    // The header line that is split keeps the header.
+    let changeset = document.insert(offset + 4, "\n").unwrap();
+    let expected = DeltaBuilder::new()
+        .retain(offset + 4)
+        .insert_with_attributes("\n", RichTextAttribute::Header(1).into())
+        .build();
+    assert_eq!(changeset, expected);
+    assert_eq!(document.delta(), delta.compose(&changeset).unwrap());
+
+    let _ = document.format(Interval::new(offset, offset + 30), RichTextAttribute::Bold(true));
+    let _ = document.delete(Interval::new(offset + 20, offset + 40)).unwrap();
+    while document.can_undo() {
+        document.undo().unwrap();
+    }
+    assert_eq!(document.delta(), delta);
+}
@@ -0,0 +1,85 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::outline::BlockType;
+use flowy_sync::client_document::NewlineDoc;
+use lib_ot::core::{EmbedData, Interval};
+
+#[test]
+fn outline_headers_test() {
+    let ops = vec![
+        Insert(0, "Title\nintro\nSection\nbody", 0),
+        Header(0, Interval::new(0, 5), 1),
+        Header(0, Interval::new(12, 19), 2),
+        AssertOutline(0, vec![(1, "Title", 0), (2, "Section", 12)]),
+        // The headers after the change are moved.
+        Insert(0, "my ", 6),
+        AssertOutline(0, vec![(1, "Title", 0), (2, "Section", 15)]),
+        Delete(0, Interval::new(15, 22)),
+        Insert(0, "Usage", 15),
+        AssertOutline(0, vec![(1, "Title", 0), (2, "Usage", 15)]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn outline_split_and_merge_header_test() {
+    let ops = vec![
+        Insert(0, "TitleSub", 0),
+        Header(0, Interval::new(0, 8), 1),
+        // The newline inserted into the header keeps the header of the line.
+        Insert(0, "\n", 5),
+        AssertOutline(0, vec![(1, "Title", 0), (1, "Sub", 6)]),
+        Delete(0, Interval::new(5, 6)),
+        AssertOutline(0, vec![(1, "TitleSub", 0)]),
+        Header(0, Interval::new(0, 8), 0),
+        AssertOutline(0, vec![]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn outline_undo_redo_test() {
+    let ops = vec![
+        Insert(0, "Title", 0),
+        Header(0, Interval::new(0, 5), 3),
+        AssertOutline(0, vec![(3, "Title", 0)]),
+        Undo(0),
+        AssertOutline(0, vec![]),
+        Redo(0),
+        AssertOutline(0, vec![(3, "Title", 0)]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn outline_remote_delta_test() {
+    let ops = vec![
+        Insert(0, "Title", 0),
+        Header(0, Interval::new(0, 5), 1),
+        ComposeRemote(0, r#"[{"insert":"Intro\n"}]"#),
+        AssertOutline(0, vec![(1, "Title", 6)]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn stats_words_and_blocks_test() {
+    let ops = vec![
+        Insert(0, "Title\nhello world, 123\none\ntwo - three\n", 0),
+        Header(0, Interval::new(0, 5), 1),
+        Bullet(0, Interval::new(23, 26), true),
+        // The line of the embed is not empty.
+        InsertEmbed(0, EmbedData::Divider, 39),
+        AssertStats(
+            0,
+            7,
+            35,
+            vec![
+                (BlockType::Paragraph, 3),
+                (BlockType::Heading, 1),
+                (BlockType::BulletedList, 1),
+            ],
+        ),
+        AssertOutline(0, vec![(1, "Title", 0)]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
@@ -0,0 +1,83 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::PlainDoc;
+use lib_ot::core::Interval;
+
+#[test]
+fn selection_move_with_remote_insert_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        SetUserSelection(0, "a", Interval::new(2, 4)),
+        SetUserSelection(0, "b", Interval::new(0, 0)),
+        ComposeRemote(0, r#"[{"insert":"abc"},{"retain":6}]"#),
+        AssertUserSelection(0, "a", Interval::new(5, 7)),
+        AssertUserSelection(0, "b", Interval::new(3, 3)),
+        // The text inserted at the end of the selection is selected too.
+        ComposeRemote(0, r#"[{"retain":7},{"insert":"xy"},{"retain":2}]"#),
+        AssertUserSelection(0, "a", Interval::new(5, 9)),
+        AssertStr(0, "abc1234xy56"),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn selection_collapse_with_remote_delete_spanning_it_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        SetUserSelection(0, "a", Interval::new(2, 4)),
+        SetUserSelection(0, "b", Interval::new(3, 6)),
+        ComposeRemote(0, r#"[{"retain":1},{"delete":4},{"retain":1}]"#),
+        AssertUserSelection(0, "a", Interval::new(1, 1)),
+        AssertUserSelection(0, "b", Interval::new(1, 2)),
+        AssertStr(0, "16"),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn selection_arrives_before_remote_insert_test() {
+    // The user "a" inserted "abc" in the revision 2 and selected "34" after it, the selection
+    // arrives before the revision.
+    let ops = vec![
+        Insert(0, "123456", 0),
+        SetUserSelection(0, "a", Interval::new(0, 0)),
+        HoldRemoteSelection(0, "a", 2, Interval::new(5, 7)),
+        HoldRemoteSelection(0, "b", 3, Interval::new(1, 2)),
+        AssertUserSelection(0, "a", Interval::new(0, 0)),
+        ComposeRemote(0, r#"[{"insert":"abc"},{"retain":6}]"#),
+        SetHeldSelections(0, 2),
+        AssertUserSelection(0, "a", Interval::new(5, 7)),
+        AssertStr(0, "abc123456"),
+        // The revision of "b" isn't received yet.
+        AssertNoUserSelection(0, "b"),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn selection_of_earlier_revision_test() {
+    // The user "a" selected "34" before the remote insert was applied.
+    let ops = vec![
+        Insert(0, "123456", 0),
+        ComposeRemote(0, r#"[{"insert":"abc"},{"retain":6}]"#),
+        SetRemoteSelection(0, "a", Interval::new(2, 4), vec![r#"[{"insert":"abc"},{"retain":6}]"#]),
+        AssertUserSelection(0, "a", Interval::new(5, 7)),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
+
+#[test]
+fn selection_move_with_local_edit_test() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        SetUserSelection(0, "a", Interval::new(3, 5)),
+        Delete(0, Interval::new(0, 2)),
+        AssertUserSelection(0, "a", Interval::new(1, 3)),
+        Insert(0, "xyz", 2),
+        AssertUserSelection(0, "a", Interval::new(1, 6)),
+        // The two edits are merged into one undo step.
+        Undo(0),
+        AssertStr(0, "123456"),
+        AssertUserSelection(0, "a", Interval::new(3, 5)),
+    ];
+    TestBuilder::new().run_scripts::<PlainDoc>(ops);
+}
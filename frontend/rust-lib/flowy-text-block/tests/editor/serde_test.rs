@@ -36,6 +36,40 @@ fn operation_delete_serialize_test() {
     assert_eq!(insert_op, operation);
 }
 
+#[test]
+fn operation_embed_serialize_test() {
+    let image = EmbedData::Image(ImageEmbed {
+        source: "https://appflowy.io/logo.png".to_owned(),
+        width: Some(100),
+        height: None,
+    });
+    for data in vec![
+        EmbedData::image("https://appflowy.io/logo.png"),
+        image,
+        EmbedData::Divider,
+        EmbedData::PageMention("page_id".to_owned()),
+        EmbedData::DateMention(1650000000),
+    ] {
+        let operation = OpBuilder::embed(data).build();
+        let json = serde_json::to_string(&operation).unwrap();
+        let embed_op: RichTextOperation = serde_json::from_str(&json).unwrap();
+        assert_eq!(embed_op, operation);
+    }
+}
+
+#[test]
+fn delta_deserialize_quill_embed_test() {
+    let json = r#"[{"insert":"123\n"},{"insert":{"image":"https://appflowy.io/logo.png"},"attributes":{"width":100}},{"insert":"\n"},{"insert":{"divider":"hr"}},{"insert":"\n"}]"#;
+    let delta = RichTextDelta::from_delta_str(json).unwrap();
+    assert_eq!(delta.utf16_target_len, 8);
+    assert_eq!(
+        delta.ops[1].get_embed(),
+        Some(&EmbedData::image("https://appflowy.io/logo.png"))
+    );
+    assert_eq!(delta.ops[3].get_embed(), Some(&EmbedData::Divider));
+    assert_eq!(delta.to_delta_str(), json);
+}
+
 #[test]
 fn attributes_serialize_test() {
     let attributes = AttributeBuilder::new()
@@ -0,0 +1,134 @@
+use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::{NewlineDoc, RECORD_THRESHOLD};
+use lib_ot::core::Interval;
+
+#[test]
+fn table_insert_test() {
+    let ops = vec![
+        Insert(0, "title", 0),
+        Wait(RECORD_THRESHOLD),
+        InsertTable(0, 0, 2, 2),
+        AssertStr(0, "title\n\n\n\n\n\n"),
+        AssertTable(0, 6, vec![vec!["", ""], vec!["", ""]]),
+        Insert(0, "a", 6),
+        Insert(0, "b", 8),
+        AssertTable(0, 6, vec![vec!["a", "b"], vec!["", ""]]),
+        Undo(0),
+        AssertStr(0, "title\n"),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn table_insert_newline_in_cell_test() {
+    let ops = vec![
+        Insert(0, "title", 0),
+        InsertTable(0, 0, 1, 2),
+        Insert(0, "ab", 6),
+        Insert(0, "\n", 7),
+        AssertTable(0, 6, vec![vec!["a\nb", ""]]),
+        Insert(0, "\n", 9),
+        AssertTable(0, 6, vec![vec!["a\nb\n", ""]]),
+        Insert(0, "c\nd", 11),
+        AssertTable(0, 6, vec![vec!["a\nb\n", "c\nd"]]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn table_backspace_at_cell_start_test() {
+    let ops = vec![
+        Insert(0, "title", 0),
+        InsertTable(0, 0, 1, 2),
+        Insert(0, "a", 6),
+        Insert(0, "b", 8),
+        Insert(0, "end", 10),
+        AssertStr(0, "title\na\nb\nend\n"),
+        // The backspace at the start of the cell, the table and the line after the table.
+        Delete(0, Interval::new(7, 8)),
+        Delete(0, Interval::new(5, 6)),
+        Delete(0, Interval::new(9, 10)),
+        AssertStr(0, "title\na\nb\nend\n"),
+        AssertTable(0, 6, vec![vec!["a", "b"]]),
+        // The text in the cell is deleted as usual.
+        Delete(0, Interval::new(6, 7)),
+        AssertTable(0, 6, vec![vec!["", "b"]]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn table_delete_across_cells_test() {
+    let ops = vec![
+        Insert(0, "title", 0),
+        InsertTable(0, 0, 2, 2),
+        Insert(0, "a", 6),
+        Insert(0, "b", 8),
+        Insert(0, "c", 10),
+        Insert(0, "d", 12),
+        AssertTable(0, 6, vec![vec!["a", "b"], vec!["c", "d"]]),
+        Delete(0, Interval::new(3, 11)),
+        AssertStr(0, "tit\n\n\n\nd\n\n"),
+        AssertTable(0, 4, vec![vec!["", ""], vec!["", "d"]]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn table_insert_and_delete_row_test() {
+    let ops = vec![
+        Insert(0, "title", 0),
+        InsertTable(0, 0, 1, 2),
+        Insert(0, "a", 6),
+        Insert(0, "b", 8),
+        InsertTableRow(0, 6, 0),
+        AssertTable(0, 6, vec![vec!["", ""], vec!["a", "b"]]),
+        InsertTableRow(0, 6, 2),
+        AssertTable(0, 6, vec![vec!["", ""], vec!["a", "b"], vec!["", ""]]),
+        Wait(RECORD_THRESHOLD),
+        DeleteTableRow(0, 6, 1),
+        AssertTable(0, 6, vec![vec!["", ""], vec!["", ""]]),
+        Undo(0),
+        AssertTable(0, 6, vec![vec!["", ""], vec!["a", "b"], vec!["", ""]]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn table_insert_and_delete_column_test() {
+    let ops = vec![
+        Insert(0, "title", 0),
+        InsertTable(0, 0, 2, 2),
+        Insert(0, "a", 6),
+        Insert(0, "b", 8),
+        Insert(0, "c", 10),
+        Insert(0, "d", 12),
+        InsertTableColumn(0, 6, 1),
+        AssertTable(0, 6, vec![vec!["a", "", "b"], vec!["c", "", "d"]]),
+        InsertTableColumn(0, 6, 3),
+        AssertTable(0, 6, vec![vec!["a", "", "b", ""], vec!["c", "", "d", ""]]),
+        DeleteTableColumn(0, 6, 0),
+        AssertTable(0, 6, vec![vec!["", "b", ""], vec!["", "d", ""]]),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn table_export_test() {
+    let ops = vec![
+        Insert(0, "title", 0),
+        InsertTable(0, 0, 2, 2),
+        Insert(0, "a|b", 6),
+        Insert(0, "c", 10),
+        Insert(0, "\n", 11),
+        Insert(0, "d", 12),
+        Bold(0, Interval::new(6, 7), true),
+        AssertTable(0, 6, vec![vec!["a|b", "c\nd"], vec!["", ""]]),
+        AssertMarkdown(0, "title\n| **a**\\|b | c<br>d |\n| --- | --- |\n|  |  |\n"),
+        AssertHtml(
+            0,
+            "<p>title</p><table><tbody><tr><td><strong>a</strong>|b</td><td>c<br>d</td></tr><tr><td></td><td></td></tr></tbody></table><p><br></p>",
+        ),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
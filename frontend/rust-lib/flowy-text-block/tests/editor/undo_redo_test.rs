@@ -1,4 +1,5 @@
 use crate::editor::{TestBuilder, TestOp::*};
+use flowy_sync::client_document::history::HistoryConfig;
 use flowy_sync::client_document::{NewlineDoc, PlainDoc, RECORD_THRESHOLD};
 use lib_ot::core::{Interval, NEW_LINE, WHITESPACE};
 
@@ -370,3 +371,94 @@ fn history_undo_attribute_on_merge_between_line() {
 
     TestBuilder::new().run_scripts::<NewlineDoc>(ops);
 }
+
+#[test]
+fn history_merge_changes_within_merge_interval() {
+    let config = HistoryConfig {
+        merge_interval: 0,
+        capacity: 2,
+    };
+    let ops = vec![
+        SetHistoryConfig(0, config),
+        Insert(0, "1", 0),
+        Insert(0, "2", 1),
+        Insert(0, "3", 2),
+        Undo(0),
+        AssertDocJson(0, r#"[{"insert":"12\n"}]"#),
+        Undo(0),
+        AssertDocJson(0, r#"[{"insert":"1\n"}]"#),
+        AssertCanUndo(0, false),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn history_undo_redo_restore_selection() {
+    let ops = vec![
+        Insert(0, "123", 0),
+        Wait(RECORD_THRESHOLD),
+        Insert(0, "45", 3),
+        Insert(0, "6", 5),
+        Wait(RECORD_THRESHOLD),
+        Delete(0, Interval::new(1, 3)),
+        Undo(0),
+        AssertSelection(0, Interval::new(1, 3)),
+        Undo(0),
+        AssertStr(0, "123\n"),
+        AssertSelection(0, Interval::new(3, 3)),
+        Redo(0),
+        AssertStr(0, "123456\n"),
+        AssertSelection(0, Interval::new(6, 6)),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn history_undo_keep_remote_changes() {
+    let ops = vec![
+        Insert(0, "123", 0),
+        ComposeRemote(0, r#"[{"insert":"abc"}]"#),
+        AssertDocJson(0, r#"[{"insert":"abc123\n"}]"#),
+        Undo(0),
+        AssertDocJson(0, r#"[{"insert":"abc\n"}]"#),
+        AssertSelection(0, Interval::new(3, 3)),
+        Redo(0),
+        AssertDocJson(0, r#"[{"insert":"abc123\n"}]"#),
+        AssertSelection(0, Interval::new(6, 6)),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn history_undo_after_remote_delete() {
+    let ops = vec![
+        Insert(0, "123", 0),
+        Wait(RECORD_THRESHOLD),
+        Insert(0, "456", 3),
+        ComposeRemote(0, r#"[{"delete":3}]"#),
+        AssertDocJson(0, r#"[{"insert":"456\n"}]"#),
+        Undo(0),
+        AssertDocJson(0, r#"[{"insert":"\n"}]"#),
+        AssertCanUndo(0, false),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
+
+#[test]
+fn history_undo_restore_selection_after_remote_insert() {
+    let ops = vec![
+        Insert(0, "123456", 0),
+        Wait(RECORD_THRESHOLD),
+        Delete(0, Interval::new(3, 5)),
+        ComposeRemote(0, r#"[{"retain":4},{"insert":"ab"}]"#),
+        AssertDocJson(0, r#"[{"insert":"1236ab\n"}]"#),
+        Undo(0),
+        AssertDocJson(0, r#"[{"insert":"123456ab\n"}]"#),
+        AssertSelection(0, Interval::new(3, 5)),
+        ComposeRemote(0, r#"[{"retain":6},{"insert":"c"}]"#),
+        Redo(0),
+        AssertDocJson(0, r#"[{"insert":"1236cab\n"}]"#),
+        AssertSelection(0, Interval::new(3, 3)),
+    ];
+    TestBuilder::new().run_scripts::<NewlineDoc>(ops);
+}
@@ -88,6 +88,12 @@ pub enum ErrorCode {
     UserNotExist = 312,
     #[display(fmt = "Text is too long")]
     TextTooLong = 400,
+    #[display(fmt = "Comment thread id is empty")]
+    CommentThreadIdIsEmpty = 401,
+    #[display(fmt = "Comment can not be empty or whitespace")]
+    CommentIsEmpty = 402,
+    #[display(fmt = "The query of the find is empty or invalid")]
+    FindQueryInvalid = 403,
 
     #[display(fmt = "Grid id is empty")]
     GridIdIsEmpty = 410,
@@ -97,6 +103,14 @@ pub enum ErrorCode {
     RowIdIsEmpty = 430,
     #[display(fmt = "Select option id is empty")]
     OptionIdIsEmpty = 431,
+    #[display(fmt = "Row comment id is empty")]
+    RowCommentIdIsEmpty = 432,
+    #[display(fmt = "Row comment can not be empty or whitespace")]
+    RowCommentIsEmpty = 433,
+    #[display(fmt = "Row template id is empty")]
+    RowTemplateIdIsEmpty = 434,
+    #[display(fmt = "The name of the row template should not be empty")]
+    RowTemplateNameIsEmpty = 435,
     #[display(fmt = "Field id is empty")]
     FieldIdIsEmpty = 440,
     #[display(fmt = "Field doesn't exist")]
@@ -114,6 +128,9 @@ pub enum ErrorCode {
     #[display(fmt = "Invalid date time format")]
     InvalidDateTimeFormat = 500,
 
+    #[display(fmt = "Phone number format is not valid")]
+    PhoneNumberFormatInvalid = 501,
+
     #[display(fmt = "The input string is empty or contains invalid characters")]
     UnexpectedEmptyString = 999,
 
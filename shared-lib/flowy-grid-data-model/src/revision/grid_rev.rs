@@ -17,6 +17,10 @@ pub fn gen_block_id() -> String {
     nanoid!(10)
 }
 
+pub fn gen_row_document_id() -> String {
+    nanoid!(10)
+}
+
 pub fn gen_row_id() -> String {
     nanoid!(6)
 }
@@ -25,19 +29,26 @@ pub fn gen_field_id() -> String {
     nanoid!(6)
 }
 
+pub fn gen_row_template_id() -> String {
+    nanoid!(6)
+}
+
+/// The placeholders can be used as the default value of a field or as the cell content of a row
+/// template. They are replaced with the actual value when the row gets created.
+pub const CELL_PLACEHOLDER_TODAY: &str = "{{today}}";
+pub const CELL_PLACEHOLDER_CURRENT_USER: &str = "{{current_user}}";
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GridRevision {
     pub grid_id: String,
     pub fields: Vec<Arc<FieldRevision>>,
     pub blocks: Vec<Arc<GridBlockMetaRevision>>,
 
-    #[cfg(feature = "filter")]
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "GridSettingRevision::is_empty")]
     pub setting: GridSettingRevision,
 
-    #[cfg(not(feature = "filter"))]
-    #[serde(default, skip)]
-    pub setting: GridSettingRevision,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub row_templates: Vec<Arc<RowTemplateRevision>>,
 }
 
 impl GridRevision {
@@ -47,6 +58,7 @@ impl GridRevision {
             fields: vec![],
             blocks: vec![],
             setting: GridSettingRevision::default(),
+            row_templates: vec![],
         }
     }
 
@@ -56,6 +68,7 @@ impl GridRevision {
             fields: context.field_revs.into_iter().map(Arc::new).collect(),
             blocks: context.blocks.into_iter().map(Arc::new).collect(),
             setting: Default::default(),
+            row_templates: context.row_templates.into_iter().map(Arc::new).collect(),
         }
     }
 }
@@ -134,6 +147,11 @@ pub struct FieldRevision {
 
     #[serde(default = "DEFAULT_IS_PRIMARY")]
     pub is_primary: bool,
+
+    /// The content that fills the cell of this field when a new row gets created. It could be
+    /// one of the cell placeholders, for example, [CELL_PLACEHOLDER_TODAY].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
 }
 
 impl AsRef<FieldRevision> for FieldRevision {
@@ -162,6 +180,7 @@ impl FieldRevision {
             width,
             type_options: Default::default(),
             is_primary,
+            default_value: None,
         }
     }
 
@@ -211,6 +230,10 @@ pub struct RowRevision {
     pub cells: IndexMap<FieldId, CellRevision>,
     pub height: i32,
     pub visibility: bool,
+    /// The id of the text block that holds the body of the row's detail page. It's created
+    /// lazily when the page is opened for the first time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
 }
 
 impl RowRevision {
@@ -221,6 +244,7 @@ impl RowRevision {
             cells: Default::default(),
             height: DEFAULT_ROW_HEIGHT,
             visibility: true,
+            document_id: None,
         }
     }
 }
@@ -229,6 +253,7 @@ pub struct RowMetaChangeset {
     pub row_id: String,
     pub height: Option<i32>,
     pub visibility: Option<bool>,
+    pub document_id: Option<String>,
     pub cell_by_field_id: HashMap<FieldId, CellRevision>,
 }
 
@@ -243,11 +268,45 @@ impl CellRevision {
     }
 }
 
+/// A row template is a set of prefilled cells, and optionally the body of the row's document,
+/// that can be used to create new rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowTemplateRevision {
+    pub id: String,
+    pub name: String,
+    /// cells contains key/value pairs.
+    /// key: field id,
+    /// value: the content of the cell, same as the default value of the field.
+    #[serde(with = "indexmap::serde_seq")]
+    pub cells: IndexMap<FieldId, String>,
+    /// The delta string of the row's document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
+}
+
+impl RowTemplateRevision {
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: gen_row_template_id(),
+            name: name.to_owned(),
+            cells: Default::default(),
+            document: None,
+        }
+    }
+}
+
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct BuildGridContext {
     pub field_revs: Vec<FieldRevision>,
     pub blocks: Vec<GridBlockMetaRevision>,
     pub blocks_meta_data: Vec<GridBlockRevision>,
+
+    /// The delta string of the rows' documents, keyed by the document id.
+    #[serde(default)]
+    pub row_documents: HashMap<String, String>,
+
+    #[serde(default)]
+    pub row_templates: Vec<RowTemplateRevision>,
 }
 
 impl BuildGridContext {
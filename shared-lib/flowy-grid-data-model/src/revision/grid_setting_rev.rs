@@ -39,11 +39,14 @@ pub fn gen_grid_sort_id() -> String {
 ///                   FieldType: GridFilterRevision
 ///
 /// Group and sorts will be the same structure as filters.
+///
+/// The field_settings contains the visibility, width and order of the fields of each layout. So
+/// hiding a field in the board doesn't hide it in the table.
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq)]
 pub struct GridSettingRevision {
     pub layout: GridLayoutRevision,
 
-    #[serde(with = "indexmap::serde_seq")]
+    #[serde(skip, with = "indexmap::serde_seq")]
     filters: IndexMap<GridLayoutRevision, IndexMap<String, GridFilterRevisionMap>>,
 
     #[serde(skip, with = "indexmap::serde_seq")]
@@ -51,12 +54,19 @@ pub struct GridSettingRevision {
 
     #[serde(skip, with = "indexmap::serde_seq")]
     pub sorts: IndexMap<GridLayoutRevision, Vec<GridSortRevision>>,
+
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty", with = "indexmap::serde_seq")]
+    field_settings: IndexMap<GridLayoutRevision, LayoutFieldSettingsRevision>,
 }
 
 pub type FiltersByFieldId = HashMap<String, Vec<Arc<GridFilterRevision>>>;
 pub type GroupsByFieldId = HashMap<String, Vec<Arc<GridGroupRevision>>>;
 pub type SortsByFieldId = HashMap<String, Vec<Arc<GridSortRevision>>>;
 impl GridSettingRevision {
+    pub fn is_empty(&self) -> bool {
+        self.field_settings.is_empty()
+    }
+
     pub fn get_all_group(&self) -> Option<GroupsByFieldId> {
         None
     }
@@ -116,6 +126,134 @@ impl GridSettingRevision {
             .cloned()
     }
 
+    pub fn get_field_setting(&self, layout: &GridLayoutRevision, field_id: &str) -> Option<&FieldSettingRevision> {
+        self.field_settings
+            .get(layout)
+            .and_then(|layout_settings| layout_settings.setting_by_field_id.get(field_id))
+    }
+
+    /// Return the fields in the order of the layout. The fields that are not in the order of the
+    /// layout, e.g. the newly created fields, are appended in their original order.
+    pub fn get_ordered_field_revs(
+        &self,
+        layout: &GridLayoutRevision,
+        field_revs: &[Arc<FieldRevision>],
+    ) -> Vec<Arc<FieldRevision>> {
+        let field_order = match self.field_settings.get(layout) {
+            None => return field_revs.to_vec(),
+            Some(layout_settings) => &layout_settings.field_order,
+        };
+
+        let mut ordered_field_revs = field_order
+            .iter()
+            .flat_map(|field_id| field_revs.iter().find(|field_rev| &field_rev.id == field_id).cloned())
+            .collect::<Vec<Arc<FieldRevision>>>();
+
+        field_revs.iter().for_each(|field_rev| {
+            if !field_order.contains(&field_rev.id) {
+                ordered_field_revs.push(field_rev.clone());
+            }
+        });
+        ordered_field_revs
+    }
+
+    /// Return true if the setting of the field is changed.
+    pub fn update_field_setting(
+        &mut self,
+        layout: &GridLayoutRevision,
+        field_id: &str,
+        visibility: Option<bool>,
+        width: Option<i32>,
+    ) -> bool {
+        let field_setting = self
+            .field_settings
+            .entry(layout.clone())
+            .or_insert_with(LayoutFieldSettingsRevision::default)
+            .setting_by_field_id
+            .entry(field_id.to_owned())
+            .or_insert_with(FieldSettingRevision::default);
+
+        let mut is_changed = false;
+        if visibility.is_some() && field_setting.visibility != visibility {
+            field_setting.visibility = visibility;
+            is_changed = true;
+        }
+
+        if width.is_some() && field_setting.width != width {
+            field_setting.width = width;
+            is_changed = true;
+        }
+        is_changed
+    }
+
+    /// Move the field from the `from_index` to the `to_index` of the layout. Return true if the
+    /// order is changed. Nothing is moved if the field isn't at the `from_index` anymore, e.g. it
+    /// was moved by another user.
+    pub fn move_field(
+        &mut self,
+        layout: &GridLayoutRevision,
+        field_id: &str,
+        from_index: usize,
+        to_index: usize,
+        field_revs: &[Arc<FieldRevision>],
+    ) -> bool {
+        let mut field_order = self
+            .get_ordered_field_revs(layout, field_revs)
+            .iter()
+            .map(|field_rev| field_rev.id.clone())
+            .collect::<Vec<String>>();
+
+        match field_order.iter().position(|id| id == field_id) {
+            Some(index) if index == from_index && from_index != to_index && to_index < field_order.len() => {
+                let field_id = field_order.remove(from_index);
+                field_order.insert(to_index, field_id);
+                self.field_settings
+                    .entry(layout.clone())
+                    .or_insert_with(LayoutFieldSettingsRevision::default)
+                    .field_order = field_order;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remove the settings of the field from all the layouts.
+    pub fn remove_field_settings(&mut self, field_id: &str) {
+        self.field_settings.values_mut().for_each(|layout_settings| {
+            layout_settings.field_order.retain(|id| id != field_id);
+            layout_settings.setting_by_field_id.remove(field_id);
+        });
+    }
+
+    /// Initialize the field settings of the layout with the visibility, width and order of the
+    /// fields if the layout doesn't have any settings. Return true if the settings are created.
+    pub fn init_field_settings(&mut self, layout: &GridLayoutRevision, field_revs: &[Arc<FieldRevision>]) -> bool {
+        if self.field_settings.contains_key(layout) {
+            return false;
+        }
+
+        let field_order = field_revs.iter().map(|field_rev| field_rev.id.clone()).collect();
+        let setting_by_field_id = field_revs
+            .iter()
+            .map(|field_rev| {
+                let field_setting = FieldSettingRevision {
+                    visibility: Some(field_rev.visibility),
+                    width: Some(field_rev.width),
+                };
+                (field_rev.id.clone(), field_setting)
+            })
+            .collect();
+
+        self.field_settings.insert(
+            layout.clone(),
+            LayoutFieldSettingsRevision {
+                field_order,
+                setting_by_field_id,
+            },
+        );
+        true
+    }
+
     pub fn insert_filter(
         &mut self,
         layout: &GridLayoutRevision,
@@ -162,6 +300,26 @@ impl std::ops::DerefMut for GridFilterRevisionMap {
     }
 }
 
+/// The fields' presentation of the layout. The field that doesn't have a setting in the layout
+/// uses the `visibility` and `width` of its [FieldRevision].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq)]
+pub struct LayoutFieldSettingsRevision {
+    #[serde(default)]
+    pub field_order: Vec<String>,
+
+    #[serde(default, with = "indexmap::serde_seq")]
+    pub setting_by_field_id: IndexMap<String, FieldSettingRevision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq)]
+pub struct FieldSettingRevision {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<i32>,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
 pub enum GridLayoutRevision {
@@ -169,6 +327,12 @@ pub enum GridLayoutRevision {
     Board = 1,
 }
 
+impl GridLayoutRevision {
+    pub fn all() -> Vec<GridLayoutRevision> {
+        vec![GridLayoutRevision::Table, GridLayoutRevision::Board]
+    }
+}
+
 impl ToString for GridLayoutRevision {
     fn to_string(&self) -> String {
         let layout_rev = self.clone() as u8;
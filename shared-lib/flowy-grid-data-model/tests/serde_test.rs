@@ -1,4 +1,5 @@
 use flowy_grid_data_model::revision::*;
+use std::sync::Arc;
 
 #[test]
 fn grid_default_serde_test() {
@@ -8,3 +9,43 @@ fn grid_default_serde_test() {
     let json = serde_json::to_string(&grid).unwrap();
     assert_eq!(json, r#"{"grid_id":"1","fields":[],"blocks":[]}"#)
 }
+
+#[test]
+fn grid_layout_field_settings_serde_test() {
+    let mut grid = GridRevision::new("1");
+    let field_rev = FieldRevision::new("Name", "", 0u8, 150, true);
+    grid.setting
+        .update_field_setting(&GridLayoutRevision::Board, &field_rev.id, Some(false), None);
+    grid.fields.push(Arc::new(field_rev));
+
+    let json = serde_json::to_string(&grid).unwrap();
+    let grid: GridRevision = serde_json::from_str(&json).unwrap();
+    let field_id = &grid.fields[0].id;
+    let field_setting = grid
+        .setting
+        .get_field_setting(&GridLayoutRevision::Board, field_id)
+        .unwrap();
+    assert_eq!(field_setting.visibility, Some(false));
+    assert_eq!(field_setting.width, None);
+    assert!(grid
+        .setting
+        .get_field_setting(&GridLayoutRevision::Table, field_id)
+        .is_none());
+}
+
+#[test]
+fn grid_row_template_serde_test() {
+    let mut grid = GridRevision::new("1");
+    let mut field_rev = FieldRevision::new("Created at", "", 2u8, 150, false);
+    field_rev.default_value = Some(CELL_PLACEHOLDER_TODAY.to_owned());
+
+    let mut template = RowTemplateRevision::new("Bug");
+    template.cells.insert(field_rev.id.clone(), "Open".to_owned());
+    grid.fields.push(Arc::new(field_rev));
+    grid.row_templates.push(Arc::new(template.clone()));
+
+    let json = serde_json::to_string(&grid).unwrap();
+    let grid: GridRevision = serde_json::from_str(&json).unwrap();
+    assert_eq!(grid.fields[0].default_value.as_deref(), Some(CELL_PLACEHOLDER_TODAY));
+    assert_eq!(grid.row_templates[0].as_ref(), &template);
+}
@@ -0,0 +1,78 @@
+use lib_ot::{
+    core::{Bias, Interval},
+    rich_text::RichTextDelta,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The intervals that are attached to the text of the document, for example: the text of the
+/// comment. The anchors are transformed against each change of the document, so they move with
+/// the text. The anchor collapses into the caret if its text was deleted.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentAnchors {
+    anchors: HashMap<String, Interval>,
+    moved: HashSet<String>,
+}
+
+impl DocumentAnchors {
+    pub fn new() -> Self {
+        DocumentAnchors::default()
+    }
+
+    pub fn insert(&mut self, id: &str, interval: Interval) {
+        self.anchors.insert(id.to_owned(), interval);
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Interval> {
+        self.moved.remove(id);
+        self.anchors.remove(id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Interval> {
+        self.anchors.get(id).cloned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+
+    /// Transforms the anchors against the delta. The text inserted at the edges of the anchor is
+    /// not attached to the anchor, so the start sticks to the right and the end to the left.
+    pub fn transform(&mut self, delta: &RichTextDelta) {
+        if self.anchors.is_empty() {
+            return;
+        }
+
+        let ids = self.anchors.keys().cloned().collect::<Vec<String>>();
+        let intervals = ids.iter().map(|id| self.anchors[id]).collect::<Vec<Interval>>();
+        let new_intervals = delta.transform_intervals(&intervals, Bias::Right, Bias::Left);
+        for (id, new_interval) in ids.into_iter().zip(new_intervals) {
+            if self.anchors.get(&id) != Some(&new_interval) {
+                self.anchors.insert(id.clone(), new_interval);
+                self.moved.insert(id);
+            }
+        }
+    }
+
+    /// Moves the anchors into the document if the document was replaced without the delta.
+    pub fn clamp(&mut self, document_len: usize) {
+        for (id, interval) in self.anchors.iter_mut() {
+            if interval.end > document_len {
+                let start = interval.start.min(document_len);
+                *interval = Interval::new(start, document_len);
+                self.moved.insert(id.clone());
+            }
+        }
+    }
+
+    /// Returns the anchors that were moved since the last call.
+    pub fn take_moved(&mut self) -> Vec<(String, Interval)> {
+        let moved = std::mem::take(&mut self.moved);
+        moved
+            .into_iter()
+            .flat_map(|id| {
+                let interval = self.anchors.get(&id).cloned()?;
+                Some((id, interval))
+            })
+            .collect()
+    }
+}
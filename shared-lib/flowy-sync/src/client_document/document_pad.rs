@@ -1,16 +1,25 @@
 use crate::{
     client_document::{
+        anchor::DocumentAnchors,
         default::initial_quill_delta,
-        history::{History, UndoResult},
-        view::{ViewExtensions, RECORD_THRESHOLD},
+        find::DocumentFinder,
+        history::{caret_of_change, History, HistoryConfig, HistoryItem, HistorySelection, UndoResult},
+        outline::{DocumentOutline, DocumentStats, OutlineHeader},
+        selection::DocumentSelections,
+        view::ViewExtensions,
     },
     errors::CollaborateError,
 };
 use bytes::Bytes;
 use lib_ot::{
     core::*,
-    rich_text::{RichTextAttribute, RichTextDelta},
+    errors::OTError,
+    rich_text::{
+        insert_table, table_at, tables_from_delta, RichTextAttribute, RichTextAttributes, RichTextDelta, RichTextRope,
+        Table,
+    },
 };
+use nanoid::nanoid;
 use tokio::sync::mpsc;
 
 pub trait InitialDocumentText {
@@ -32,10 +41,12 @@ impl InitialDocumentText for NewlineDoc {
 }
 
 pub struct ClientDocument {
-    delta: RichTextDelta,
+    rope: RichTextRope,
     history: History,
+    anchors: DocumentAnchors,
+    outline: DocumentOutline,
+    selections: DocumentSelections,
     view: ViewExtensions,
-    last_edit_time: usize,
     notify: Option<mpsc::UnboundedSender<()>>,
 }
 
@@ -46,10 +57,12 @@ impl ClientDocument {
 
     pub fn from_delta(delta: RichTextDelta) -> Self {
         ClientDocument {
-            delta,
+            outline: DocumentOutline::from_delta(&delta),
+            rope: RichTextRope::from_delta(&delta),
             history: History::new(),
+            anchors: DocumentAnchors::new(),
+            selections: DocumentSelections::new(),
             view: ViewExtensions::new(),
-            last_edit_time: 0,
             notify: None,
         }
     }
@@ -60,19 +73,26 @@ impl ClientDocument {
     }
 
     pub fn delta_str(&self) -> String {
-        self.delta.to_delta_str()
+        self.delta().to_delta_str()
     }
 
     pub fn to_bytes(&self) -> Bytes {
-        self.delta.to_delta_bytes()
+        self.delta().to_delta_bytes()
     }
 
     pub fn to_plain_string(&self) -> String {
-        self.delta.apply("").unwrap()
+        self.delta().apply("").unwrap()
+    }
+
+    /// Returns the delta of the document. The document is kept in the rope, so the delta is built
+    /// from the rope each time.
+    pub fn delta(&self) -> RichTextDelta {
+        self.rope.to_delta()
     }
 
-    pub fn delta(&self) -> &RichTextDelta {
-        &self.delta
+    /// The utf16 length of the document.
+    pub fn len(&self) -> usize {
+        self.rope.len()
     }
 
     pub fn md5(&self) -> String {
@@ -80,13 +100,34 @@ impl ClientDocument {
         format!("{:x}", md5::compute(bytes))
     }
 
+    pub fn set_history_config(&mut self, config: HistoryConfig) {
+        self.history.set_config(config);
+    }
+
     pub fn set_notify(&mut self, notify: mpsc::UnboundedSender<()>) {
         self.notify = Some(notify);
     }
 
     pub fn set_delta(&mut self, data: RichTextDelta) {
         tracing::trace!("document: {}", data.to_delta_str());
-        self.delta = data;
+        self.outline = DocumentOutline::from_delta(&data);
+        self.rope = RichTextRope::from_delta(&data);
+        self.did_change();
+    }
+
+    // Applies the changeset to the rope, the anchors and the outline are updated with it.
+    fn apply_changeset(&mut self, changeset: &RichTextDelta) -> Result<(), CollaborateError> {
+        let _ = self.rope.apply(changeset)?;
+        self.anchors.transform(changeset);
+        self.selections.transform(changeset);
+        self.outline.apply(changeset, &self.rope);
+        self.did_change();
+        Ok(())
+    }
+
+    fn did_change(&mut self) {
+        self.anchors.clamp(self.rope.len());
+        self.selections.clamp(self.rope.len());
 
         match &self.notify {
             None => {}
@@ -97,46 +138,72 @@ impl ClientDocument {
     }
 
     pub fn compose_delta(&mut self, delta: RichTextDelta) -> Result<(), CollaborateError> {
-        tracing::trace!("{} compose {}", &self.delta.to_delta_str(), delta.to_delta_str());
-        let composed_delta = self.delta.compose(&delta)?;
-        let mut undo_delta = delta.invert(&self.delta);
-
-        let now = chrono::Utc::now().timestamp_millis() as usize;
-        if now - self.last_edit_time < RECORD_THRESHOLD {
-            if let Some(last_delta) = self.history.undo() {
-                tracing::trace!("compose previous change");
-                tracing::trace!("current = {}", undo_delta);
-                tracing::trace!("previous = {}", last_delta);
-                undo_delta = undo_delta.compose(&last_delta)?;
-            }
-        } else {
-            self.last_edit_time = now;
-        }
+        self.compose_local_delta(delta, None)
+    }
+
+    /// Composes the delta of the collaborator. The delta is not recorded in the history, and the
+    /// undo steps are transformed against it, so the undo never reverts the collaborator's change.
+    pub fn compose_remote_delta(&mut self, delta: RichTextDelta) -> Result<(), CollaborateError> {
+        tracing::trace!("compose remote {}", delta.to_delta_str());
+        let len = self.rope.len();
+        let _ = self.apply_changeset(&delta)?;
+        let _ = self.history.transform(&delta, len)?;
+        Ok(())
+    }
 
+    fn compose_local_delta(
+        &mut self,
+        delta: RichTextDelta,
+        selection: Option<HistorySelection>,
+    ) -> Result<(), CollaborateError> {
+        tracing::trace!("compose {}", delta.to_delta_str());
+        let undo_delta = self.rope.invert(&delta);
+        let _ = self.apply_changeset(&delta)?;
         if !undo_delta.is_empty() {
             tracing::trace!("add history delta: {}", undo_delta);
-            self.history.record(undo_delta);
+            let _ = self.history.record(undo_delta, selection)?;
         }
-
-        self.set_delta(composed_delta);
         Ok(())
     }
 
     pub fn insert<T: ToString>(&mut self, index: usize, data: T) -> Result<RichTextDelta, CollaborateError> {
         let text = data.to_string();
         let interval = Interval::new(index, index);
-        let _ = validate_interval(&self.delta, &interval)?;
-        let delta = self.view.insert(&self.delta, &text, interval)?;
-        self.compose_delta(delta.clone())?;
+        let _ = self.validate_interval(&interval)?;
+        let delta = self.view_changeset(interval, |delta, interval| self.view.insert(delta, &text, interval))?;
+        let caret = index + count_utf16_code_units(&text);
+        let selection = HistorySelection {
+            before: interval,
+            after: Interval::new(caret, caret),
+        };
+        self.compose_local_delta(delta.clone(), Some(selection))?;
+        Ok(delta)
+    }
+
+    pub fn insert_embed(&mut self, index: usize, data: EmbedData) -> Result<RichTextDelta, CollaborateError> {
+        let interval = Interval::new(index, index);
+        let _ = self.validate_interval(&interval)?;
+        let delta = self.view_changeset(interval, |delta, interval| {
+            self.view.insert_embed(delta, &data, interval)
+        })?;
+        let selection = HistorySelection {
+            before: interval,
+            after: caret_of_change(&delta),
+        };
+        self.compose_local_delta(delta.clone(), Some(selection))?;
         Ok(delta)
     }
 
     pub fn delete(&mut self, interval: Interval) -> Result<RichTextDelta, CollaborateError> {
-        let _ = validate_interval(&self.delta, &interval)?;
+        let _ = self.validate_interval(&interval)?;
         debug_assert!(!interval.is_empty());
-        let delete = self.view.delete(&self.delta, interval)?;
+        let delete = self.view_changeset(interval, |delta, interval| self.view.delete(delta, interval))?;
         if !delete.is_empty() {
-            let _ = self.compose_delta(delete.clone())?;
+            let selection = HistorySelection {
+                before: interval,
+                after: Interval::new(interval.start, interval.start),
+            };
+            let _ = self.compose_local_delta(delete.clone(), Some(selection))?;
         }
         Ok(delete)
     }
@@ -146,30 +213,192 @@ impl ClientDocument {
         interval: Interval,
         attribute: RichTextAttribute,
     ) -> Result<RichTextDelta, CollaborateError> {
-        let _ = validate_interval(&self.delta, &interval)?;
+        let _ = self.validate_interval(&interval)?;
         tracing::trace!("format {} with {}", interval, attribute);
-        let format_delta = self.view.format(&self.delta, attribute, interval).unwrap();
-        self.compose_delta(format_delta.clone())?;
+        let format_delta = self
+            .view_changeset(interval, |delta, interval| self.view.format(delta, attribute, interval))
+            .unwrap();
+        let selection = HistorySelection {
+            before: interval,
+            after: interval,
+        };
+        self.compose_local_delta(format_delta.clone(), Some(selection))?;
         Ok(format_delta)
     }
 
     pub fn replace<T: ToString>(&mut self, interval: Interval, data: T) -> Result<RichTextDelta, CollaborateError> {
-        let _ = validate_interval(&self.delta, &interval)?;
-        let mut delta = RichTextDelta::default();
+        let _ = self.validate_interval(&interval)?;
         let text = data.to_string();
-        if !text.is_empty() {
-            delta = self.view.insert(&self.delta, &text, interval)?;
-            self.compose_delta(delta.clone())?;
-        }
+        let delta = self.view_changeset(interval, |document, interval| {
+            let mut delta = RichTextDelta::default();
+            if !text.is_empty() {
+                delta = self.view.insert(document, &text, interval)?;
+            }
 
-        if !interval.is_empty() {
-            let delete = self.delete(interval)?;
-            delta = delta.compose(&delete)?;
+            // The insert and the delete are recorded as one undo step.
+            if !interval.is_empty() {
+                let inserted_delta = document.compose(&delta)?;
+                let delete = self.view.delete(&inserted_delta, interval)?;
+                delta = delta.compose(&delete)?;
+            }
+            Ok(delta)
+        })?;
+
+        if !delta.is_empty() {
+            let caret = interval.start + count_utf16_code_units(&text);
+            let selection = HistorySelection {
+                before: interval,
+                after: Interval::new(caret, caret),
+            };
+            self.compose_local_delta(delta.clone(), Some(selection))?;
         }
+        Ok(delta)
+    }
 
+    /// Returns the intervals of the text that matches the finder.
+    pub fn find(&self, finder: &DocumentFinder) -> Vec<Interval> {
+        finder.find(&self.to_plain_string())
+    }
+
+    /// Replaces the matches of the finder in one change, so it's undone in one step.
+    pub fn replace_matches(
+        &mut self,
+        finder: &DocumentFinder,
+        replacement: &str,
+        selected: Option<&[Interval]>,
+    ) -> Result<RichTextDelta, CollaborateError> {
+        let delta = finder.replace_delta(&self.delta(), replacement, selected)?;
+        if !delta.is_empty() {
+            // The delta ends with the last replaced match, so the text it covers starts with the
+            // first match and ends with the last one.
+            let start = delta.ops.first().filter(|op| op.is_retain()).map(|op| op.len());
+            let end = delta.ops.iter().filter(|op| !op.is_insert()).map(|op| op.len()).sum();
+            let selection = HistorySelection {
+                before: Interval::new(start.unwrap_or(0), end),
+                after: caret_of_change(&delta),
+            };
+            self.compose_local_delta(delta.clone(), Some(selection))?;
+        }
         Ok(delta)
     }
 
+    /// Inserts the empty table after the line that contains the index. The empty line is added
+    /// after the table if it's at the end of the document, so the text can be typed after it.
+    pub fn insert_table(
+        &mut self,
+        index: usize,
+        rows: usize,
+        columns: usize,
+    ) -> Result<RichTextDelta, CollaborateError> {
+        let _ = self.validate_interval(&Interval::new(index, index))?;
+        let row_ids: Vec<String> = (0..rows).map(|_| gen_table_id()).collect();
+        let column_ids: Vec<String> = (0..columns).map(|_| gen_table_id()).collect();
+        let position = self.rope.line_end(index);
+        let mut delta = insert_table(position, &row_ids, &column_ids);
+        if position == self.rope.len() {
+            delta.insert(NEW_LINE, RichTextAttributes::default());
+        }
+        self.compose_table_delta(index, delta)
+    }
+
+    pub fn tables(&self) -> Vec<Table> {
+        tables_from_delta(&self.delta())
+    }
+
+    /// Inserts the row before the row at `row` of the table that contains the index.
+    pub fn insert_table_row(&mut self, index: usize, row: usize) -> Result<RichTextDelta, CollaborateError> {
+        let delta = self.table_at(index)?.insert_row(row, &gen_table_id())?;
+        self.compose_table_delta(index, delta)
+    }
+
+    pub fn delete_table_row(&mut self, index: usize, row: usize) -> Result<RichTextDelta, CollaborateError> {
+        let delta = self.table_at(index)?.delete_row(row)?;
+        self.compose_table_delta(index, delta)
+    }
+
+    /// Inserts the column before the column at `column` of the table that contains the index.
+    pub fn insert_table_column(&mut self, index: usize, column: usize) -> Result<RichTextDelta, CollaborateError> {
+        let delta = self.table_at(index)?.insert_column(column, &gen_table_id())?;
+        self.compose_table_delta(index, delta)
+    }
+
+    pub fn delete_table_column(&mut self, index: usize, column: usize) -> Result<RichTextDelta, CollaborateError> {
+        let delta = self.table_at(index)?.delete_column(column)?;
+        self.compose_table_delta(index, delta)
+    }
+
+    /// Attaches the anchor to the text in the interval, the anchor moves with the text.
+    pub fn add_anchor(&mut self, id: &str, interval: Interval) -> Result<(), CollaborateError> {
+        let _ = self.validate_interval(&interval)?;
+        self.anchors.insert(id, interval);
+        Ok(())
+    }
+
+    pub fn remove_anchor(&mut self, id: &str) -> Option<Interval> {
+        self.anchors.remove(id)
+    }
+
+    pub fn anchor(&self, id: &str) -> Option<Interval> {
+        self.anchors.get(id)
+    }
+
+    /// Returns the anchors that were moved by the changes since the last call.
+    pub fn take_moved_anchors(&mut self) -> Vec<(String, Interval)> {
+        self.anchors.take_moved()
+    }
+
+    /// Sets the selection that the user reported, it's transformed against the following changes
+    /// of the document.
+    pub fn set_selection(&mut self, user_id: &str, interval: Interval) -> Result<(), CollaborateError> {
+        let _ = self.validate_interval(&interval)?;
+        self.selections.insert(user_id, interval);
+        Ok(())
+    }
+
+    pub fn remove_selection(&mut self, user_id: &str) -> Option<Interval> {
+        self.selections.remove(user_id)
+    }
+
+    /// Sets the selection that another user made in an earlier revision of the document, the
+    /// `deltas` are the changes of the following revisions. The selection is transformed against
+    /// them, so it covers the same text as in the document of the user.
+    pub fn set_remote_selection(&mut self, user_id: &str, interval: Interval, deltas: &[RichTextDelta]) {
+        self.selections.insert_before(user_id, interval, deltas);
+        // The delta of a revision may not be loaded, e.g. the revisions were compacted.
+        self.selections.clamp(self.rope.len());
+    }
+
+    /// Holds the selection that another user made in a revision that isn't received yet, see
+    /// [ClientDocument::take_held_selections].
+    pub fn hold_selection(&mut self, user_id: &str, rev_id: i64, interval: Interval) {
+        self.selections.hold(user_id, rev_id, interval);
+    }
+
+    /// Returns the held selections whose revisions are received, the document is at the revision
+    /// `rev_id`. They should be set with [ClientDocument::set_remote_selection].
+    pub fn take_held_selections(&mut self, rev_id: i64) -> Vec<(String, i64, Interval)> {
+        self.selections.take_held(rev_id)
+    }
+
+    pub fn selection(&self, user_id: &str) -> Option<Interval> {
+        self.selections.get(user_id)
+    }
+
+    /// Returns the selections of the users ordered by the id of the user.
+    pub fn selections(&self) -> Vec<(String, Interval)> {
+        self.selections.all()
+    }
+
+    /// Returns the headers of the document in order, the level of the header is its depth in the
+    /// outline.
+    pub fn outline(&self) -> Vec<OutlineHeader> {
+        self.outline.headers()
+    }
+
+    pub fn stats(&self) -> DocumentStats {
+        self.outline.stats()
+    }
+
     pub fn can_undo(&self) -> bool {
         self.history.can_undo()
     }
@@ -181,11 +410,18 @@ impl ClientDocument {
     pub fn undo(&mut self) -> Result<UndoResult, CollaborateError> {
         match self.history.undo() {
             None => Err(CollaborateError::undo().context("Undo stack is empty")),
-            Some(undo_delta) => {
-                let (new_delta, inverted_delta) = self.invert(&undo_delta)?;
-                self.set_delta(new_delta);
-                self.history.add_redo(inverted_delta);
-                Ok(UndoResult { delta: undo_delta })
+            Some(HistoryItem { delta, selection }) => {
+                let inverted_delta = self.rope.invert(&delta);
+                let _ = self.apply_changeset(&delta)?;
+                self.history.add_redo(HistoryItem {
+                    delta: inverted_delta,
+                    selection,
+                });
+                let selection = match selection {
+                    None => caret_of_change(&delta),
+                    Some(selection) => selection.before,
+                };
+                Ok(UndoResult { delta, selection })
             }
         }
     }
@@ -193,36 +429,78 @@ impl ClientDocument {
     pub fn redo(&mut self) -> Result<UndoResult, CollaborateError> {
         match self.history.redo() {
             None => Err(CollaborateError::redo()),
-            Some(redo_delta) => {
-                let (new_delta, inverted_delta) = self.invert(&redo_delta)?;
-                self.set_delta(new_delta);
-                self.history.add_undo(inverted_delta);
-                Ok(UndoResult { delta: redo_delta })
+            Some(HistoryItem { delta, selection }) => {
+                let inverted_delta = self.rope.invert(&delta);
+                let _ = self.apply_changeset(&delta)?;
+                self.history.add_undo(HistoryItem {
+                    delta: inverted_delta,
+                    selection,
+                });
+                let selection = match selection {
+                    None => caret_of_change(&delta),
+                    Some(selection) => selection.after,
+                };
+                Ok(UndoResult { delta, selection })
             }
         }
     }
 
     pub fn is_empty(&self) -> bool {
         // The document is empty if its text is equal to the initial text.
-        self.delta == NewlineDoc::initial_delta()
+        self.rope.len() <= 1 && self.delta() == NewlineDoc::initial_delta()
     }
 }
 
 impl ClientDocument {
-    fn invert(&self, delta: &RichTextDelta) -> Result<(RichTextDelta, RichTextDelta), CollaborateError> {
-        // c = a.compose(b)
-        // d = b.invert(a)
-        // a = c.compose(d)
-        let new_delta = self.delta.compose(delta)?;
-        let inverted_delta = delta.invert(&self.delta);
-        Ok((new_delta, inverted_delta))
+    fn validate_interval(&self, interval: &Interval) -> Result<(), CollaborateError> {
+        if self.rope.len() < interval.end {
+            log::error!("{:?} out of bounds. should 0..{}", interval, self.rope.len());
+            return Err(CollaborateError::out_of_bound());
+        }
+        Ok(())
     }
-}
 
-fn validate_interval(delta: &RichTextDelta, interval: &Interval) -> Result<(), CollaborateError> {
-    if delta.utf16_target_len < interval.end {
-        log::error!("{:?} out of bounds. should 0..{}", interval, delta.utf16_target_len);
-        return Err(CollaborateError::out_of_bound());
+    fn table_at(&self, index: usize) -> Result<Table, CollaborateError> {
+        table_at(&self.delta(), index)
+            .ok_or_else(|| CollaborateError::record_not_found().context(format!("No table at {}", index)))
     }
-    Ok(())
+
+    fn compose_table_delta(&mut self, index: usize, delta: RichTextDelta) -> Result<RichTextDelta, CollaborateError> {
+        let selection = HistorySelection {
+            before: Interval::new(index, index),
+            after: caret_of_change(&delta),
+        };
+        self.compose_local_delta(delta.clone(), Some(selection))?;
+        Ok(delta)
+    }
+
+    // Builds the changeset with the part of the document around the interval instead of the whole
+    // document. The part starts with the line before the interval and ends with the line after
+    // it, the extensions don't look any further.
+    fn view_changeset<F>(&self, interval: Interval, f: F) -> Result<RichTextDelta, CollaborateError>
+    where
+        F: FnOnce(&RichTextDelta, Interval) -> Result<RichTextDelta, OTError>,
+    {
+        let line_start = self.rope.line_start(interval.start);
+        let start = match line_start {
+            0 => 0,
+            _ => self.rope.line_start(line_start - 1),
+        };
+        let end = self.rope.line_end(self.rope.line_end(interval.end));
+
+        let part = self.rope.slice(Interval::new(start, end));
+        let changeset = f(&part, interval.translate_neg(start))?;
+        if changeset.is_empty() || start == 0 {
+            return Ok(changeset);
+        }
+
+        let mut delta = RichTextDelta::default();
+        delta.retain(start, Default::default());
+        delta.extend(changeset);
+        Ok(delta)
+    }
+}
+
+fn gen_table_id() -> String {
+    nanoid!(8)
 }
@@ -0,0 +1,206 @@
+use crate::client_document::export::{
+    attribute_value, blocks_from_delta, is_enabled, DocumentBlock, DocumentLine, DocumentTable,
+};
+use lib_ot::rich_text::{RichTextAttributeKey, RichTextAttributes, RichTextDelta};
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum HtmlList {
+    Bullet,
+    Ordered,
+    Check,
+}
+
+impl HtmlList {
+    fn from_line(line: &DocumentLine) -> Option<Self> {
+        match line.list()? {
+            "bullet" => Some(HtmlList::Bullet),
+            "ordered" => Some(HtmlList::Ordered),
+            "checked" | "unchecked" => Some(HtmlList::Check),
+            _ => None,
+        }
+    }
+
+    fn open_tag(&self) -> &'static str {
+        match self {
+            HtmlList::Bullet => "<ul>",
+            HtmlList::Ordered => "<ol>",
+            HtmlList::Check => r#"<ul class="checklist">"#,
+        }
+    }
+
+    fn close_tag(&self) -> &'static str {
+        match self {
+            HtmlList::Ordered => "</ol>",
+            _ => "</ul>",
+        }
+    }
+}
+
+/// Returns the html of the document. The consecutive lines of the same list are grouped into one
+/// list element and the consecutive lines of the code block are grouped into one `pre` element.
+pub fn delta_to_html(delta: &RichTextDelta) -> String {
+    let mut html = String::new();
+    let mut opened_list: Option<HtmlList> = None;
+    let mut in_code_block = false;
+    for block in blocks_from_delta(delta) {
+        let line = match block {
+            DocumentBlock::Line(line) => line,
+            DocumentBlock::Table(table) => {
+                close_list(&mut html, &mut opened_list);
+                if in_code_block {
+                    html.push_str("</code></pre>");
+                    in_code_block = false;
+                }
+                html.push_str(&html_from_table(&table));
+                continue;
+            }
+        };
+
+        if line.is_code_block() {
+            close_list(&mut html, &mut opened_list);
+            if in_code_block {
+                html.push('\n');
+            } else {
+                html.push_str("<pre><code>");
+                in_code_block = true;
+            }
+            html.push_str(&escape_html(&line.text()));
+            continue;
+        }
+
+        if in_code_block {
+            html.push_str("</code></pre>");
+            in_code_block = false;
+        }
+
+        let mut content = html_from_line(&line);
+        if content.is_empty() {
+            content = "<br>".to_owned();
+        }
+
+        match HtmlList::from_line(&line) {
+            Some(list) => {
+                if opened_list != Some(list) {
+                    close_list(&mut html, &mut opened_list);
+                    html.push_str(list.open_tag());
+                    opened_list = Some(list);
+                }
+
+                html.push_str("<li");
+                match line.list() {
+                    Some("checked") => html.push_str(r#" data-checked="true""#),
+                    Some("unchecked") => html.push_str(r#" data-checked="false""#),
+                    _ => {}
+                }
+                // Same as Quill, the indent of the list item is kept in the class.
+                if line.indent() > 0 {
+                    html.push_str(&format!(r#" class="ql-indent-{}""#, line.indent()));
+                }
+                html.push('>');
+                html.push_str(&content);
+                html.push_str("</li>");
+            }
+            None => {
+                close_list(&mut html, &mut opened_list);
+                if let Some(level) = line.header() {
+                    let level = level.min(6);
+                    html.push_str(&format!("<h{}>{}</h{}>", level, content, level));
+                } else if line.is_block_quote() {
+                    html.push_str(&format!("<blockquote>{}</blockquote>", content));
+                } else {
+                    html.push_str(&format!("<p>{}</p>", content));
+                }
+            }
+        }
+    }
+
+    if in_code_block {
+        html.push_str("</code></pre>");
+    }
+    close_list(&mut html, &mut opened_list);
+    html
+}
+
+fn close_list(html: &mut String, opened_list: &mut Option<HtmlList>) {
+    if let Some(list) = opened_list.take() {
+        html.push_str(list.close_tag());
+    }
+}
+
+fn html_from_line(line: &DocumentLine) -> String {
+    line.segments
+        .iter()
+        .map(|(s, attributes)| html_from_inline(s, attributes))
+        .collect()
+}
+
+fn html_from_table(table: &DocumentTable) -> String {
+    let mut html = "<table><tbody>".to_owned();
+    for row in table.cells() {
+        html.push_str("<tr>");
+        for lines in row {
+            let content = lines.iter().map(|line| html_from_line(line)).collect::<Vec<_>>();
+            html.push_str(&format!("<td>{}</td>", content.join("<br>")));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</tbody></table>");
+    html
+}
+
+fn html_from_inline(s: &str, attributes: &RichTextAttributes) -> String {
+    let mut html = escape_html(s);
+    let tags = [
+        (RichTextAttributeKey::InlineCode, "code"),
+        (RichTextAttributeKey::StrikeThrough, "s"),
+        (RichTextAttributeKey::Underline, "u"),
+        (RichTextAttributeKey::Italic, "em"),
+        (RichTextAttributeKey::Bold, "strong"),
+    ];
+    for (key, tag) in tags.iter() {
+        if is_enabled(attributes, key) {
+            html = format!("<{}>{}</{}>", tag, html, tag);
+        }
+    }
+
+    // The links with other schemes, e.g. `javascript:`, would run when the exported file is opened,
+    // so only their text is written.
+    if let Some(link) = attribute_value(attributes, &RichTextAttributeKey::Link) {
+        if is_safe_link(link) {
+            html = format!(r#"<a href="{}">{}</a>"#, escape_html(link), html);
+        }
+    }
+    html
+}
+
+/// Returns true if the link is a http, https or mailto URL, or a relative URL.
+fn is_safe_link(link: &str) -> bool {
+    let link = link.trim();
+    match link.find(':') {
+        None => true,
+        Some(index) => {
+            let scheme = &link[..index];
+            // The colon of a relative URL comes after its path, query or fragment.
+            if scheme.contains(['/', '?', '#']) {
+                return true;
+            }
+            ["http", "https", "mailto"]
+                .iter()
+                .any(|safe_scheme| scheme.eq_ignore_ascii_case(safe_scheme))
+        }
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
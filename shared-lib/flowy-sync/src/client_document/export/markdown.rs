@@ -0,0 +1,261 @@
+use crate::client_document::export::{
+    attribute_value, blocks_from_delta, is_enabled, DocumentBlock, DocumentLine, DocumentTable,
+};
+use lib_ot::rich_text::{RichTextAttributeKey, RichTextAttributes, RichTextDelta};
+
+const CODE_FENCE: &str = "```";
+const MARKDOWN_ESCAPED_CHARS: [char; 7] = ['\\', '*', '_', '~', '`', '[', ']'];
+
+/// Returns the markdown of the document. Each line of the document is converted into one line of
+/// markdown, and the consecutive lines of the code block are wrapped in one fenced code block. The
+/// table is converted into the table of GitHub flavored markdown.
+pub fn delta_to_markdown(delta: &RichTextDelta) -> String {
+    let mut markdown_lines = vec![];
+    let mut ordered_index = 0;
+    let mut in_code_block = false;
+    for block in blocks_from_delta(delta) {
+        let line = match block {
+            DocumentBlock::Line(line) => line,
+            DocumentBlock::Table(table) => {
+                if in_code_block {
+                    markdown_lines.push(CODE_FENCE.to_owned());
+                    in_code_block = false;
+                }
+                ordered_index = 0;
+                markdown_lines.extend(markdown_from_table(&table));
+                continue;
+            }
+        };
+
+        if line.is_code_block() {
+            if !in_code_block {
+                markdown_lines.push(CODE_FENCE.to_owned());
+                in_code_block = true;
+            }
+            markdown_lines.push(line.text());
+            continue;
+        }
+
+        if in_code_block {
+            markdown_lines.push(CODE_FENCE.to_owned());
+            in_code_block = false;
+        }
+
+        let list = line.list();
+        if list != Some("ordered") {
+            ordered_index = 0;
+        }
+
+        let content = markdown_from_line(&line);
+        let indent = "    ".repeat(line.indent());
+        let markdown_line = match (line.header(), list) {
+            (Some(level), _) => format!("{} {}", "#".repeat(level.min(6)), content),
+            (None, Some("bullet")) => format!("{}- {}", indent, content),
+            (None, Some("ordered")) => {
+                ordered_index += 1;
+                format!("{}{}. {}", indent, ordered_index, content)
+            }
+            (None, Some("checked")) => format!("{}- [x] {}", indent, content),
+            (None, Some("unchecked")) => format!("{}- [ ] {}", indent, content),
+            _ if line.is_block_quote() => format!("> {}", content),
+            _ => escape_block_marker(&content),
+        };
+        markdown_lines.push(markdown_line);
+    }
+
+    if in_code_block {
+        markdown_lines.push(CODE_FENCE.to_owned());
+    }
+    markdown_lines.join("\n")
+}
+
+/// The consecutive segments that share the marks are wrapped together, for example: "**12_34_**"
+/// instead of "**12****_34_**", which can't be imported back.
+fn markdown_from_line(line: &DocumentLine) -> String {
+    let mut tokens = vec![];
+    let mut marks: Vec<InlineMark> = vec![];
+    let mut whitespaces = String::new();
+    for (s, attributes) in &line.segments {
+        let text = if is_enabled(attributes, &RichTextAttributeKey::InlineCode) {
+            wrap_with(s, "`")
+        } else {
+            escape_markdown(s)
+        };
+
+        // The whitespaces are kept outside of the marks, otherwise, the marks will not be
+        // recognized. For example: "** bold**".
+        let content = text.trim();
+        if content.is_empty() {
+            whitespaces.push_str(&text);
+            continue;
+        }
+        let leading = text.len() - text.trim_start().len();
+        let line_marks = inline_marks(attributes);
+        let common = marks
+            .iter()
+            .zip(line_marks.iter())
+            .take_while(|(mark, other)| mark == other)
+            .count();
+        while marks.len() > common {
+            tokens.push(InlineToken::Close(marks.pop().unwrap()));
+        }
+        whitespaces.push_str(&text[..leading]);
+        tokens.push(InlineToken::Text(std::mem::take(&mut whitespaces)));
+        for mark in &line_marks[common..] {
+            tokens.push(InlineToken::Open(mark.clone()));
+            marks.push(mark.clone());
+        }
+        tokens.push(InlineToken::Text(content.to_owned()));
+        whitespaces.push_str(&text[leading + content.len()..]);
+    }
+
+    while let Some(mark) = marks.pop() {
+        tokens.push(InlineToken::Close(mark));
+    }
+    tokens.push(InlineToken::Text(whitespaces));
+    tokens.retain(|token| !matches!(token, InlineToken::Text(s) if s.is_empty()));
+    markdown_from_tokens(&tokens)
+}
+
+// The first row of the table is the header row, because the table of markdown always has one. The
+// lines of the cell are joined with `<br>` and the pipe in the cell is escaped.
+fn markdown_from_table(table: &DocumentTable) -> Vec<String> {
+    let mut markdown_lines = vec![];
+    for (i, row) in table.cells().iter().enumerate() {
+        let cells = row
+            .iter()
+            .map(|lines| {
+                let content = lines.iter().map(|line| markdown_from_line(line)).collect::<Vec<_>>();
+                content.join("<br>").replace('|', "\\|")
+            })
+            .collect::<Vec<_>>();
+        markdown_lines.push(format!("| {} |", cells.join(" | ")));
+        if i == 0 {
+            markdown_lines.push(format!("|{}", " --- |".repeat(cells.len())));
+        }
+    }
+    markdown_lines
+}
+
+/// The marks of the inline attributes, from the outermost to the innermost.
+#[derive(Clone, Debug, PartialEq)]
+enum InlineMark {
+    Link(String),
+    Italic,
+    Bold,
+    StrikeThrough,
+}
+
+enum InlineToken {
+    Text(String),
+    Open(InlineMark),
+    Close(InlineMark),
+}
+
+fn inline_marks(attributes: &RichTextAttributes) -> Vec<InlineMark> {
+    let mut marks = vec![];
+    if let Some(link) = attribute_value(attributes, &RichTextAttributeKey::Link) {
+        marks.push(InlineMark::Link(link.to_owned()));
+    }
+    if is_enabled(attributes, &RichTextAttributeKey::Italic) {
+        marks.push(InlineMark::Italic);
+    }
+    if is_enabled(attributes, &RichTextAttributeKey::Bold) {
+        marks.push(InlineMark::Bold);
+    }
+    if is_enabled(attributes, &RichTextAttributeKey::StrikeThrough) {
+        marks.push(InlineMark::StrikeThrough);
+    }
+    marks
+}
+
+fn markdown_from_tokens(tokens: &[InlineToken]) -> String {
+    let mut markdown = String::new();
+    let mut italic_mark = "_";
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            InlineToken::Text(s) => markdown.push_str(s),
+            InlineToken::Open(InlineMark::Link(_)) => markdown.push('['),
+            InlineToken::Close(InlineMark::Link(link)) => markdown.push_str(&format!("]({})", link)),
+            InlineToken::Open(InlineMark::Italic) => {
+                italic_mark = italic_mark_at(tokens, i);
+                markdown.push_str(italic_mark);
+            }
+            InlineToken::Close(InlineMark::Italic) => markdown.push_str(italic_mark),
+            InlineToken::Open(InlineMark::Bold) | InlineToken::Close(InlineMark::Bold) => markdown.push_str("**"),
+            InlineToken::Open(InlineMark::StrikeThrough) | InlineToken::Close(InlineMark::StrikeThrough) => {
+                markdown.push_str("~~")
+            }
+        }
+    }
+    markdown
+}
+
+/// The "_" can't open or close the italic inside a word, so the "*" is used if the italic starts
+/// or ends inside a word.
+fn italic_mark_at(tokens: &[InlineToken], open: usize) -> &'static str {
+    let close = tokens[open..]
+        .iter()
+        .position(|token| matches!(token, InlineToken::Close(InlineMark::Italic)))
+        .map(|i| open + i);
+    let before = open
+        .checked_sub(1)
+        .and_then(|i| token_text(&tokens[i]))
+        .and_then(|s| s.chars().last());
+    let after = close
+        .and_then(|i| tokens.get(i + 1))
+        .and_then(token_text)
+        .and_then(|s| s.chars().next());
+    if before.map_or(false, char::is_alphanumeric) || after.map_or(false, char::is_alphanumeric) {
+        "*"
+    } else {
+        "_"
+    }
+}
+
+fn token_text(token: &InlineToken) -> Option<&str> {
+    match token {
+        InlineToken::Text(s) => Some(s),
+        _ => None,
+    }
+}
+
+/// Wrap the text with the mark. The leading and trailing whitespaces are kept outside of the
+/// mark, otherwise, the mark will not be recognized. For example: "` code`".
+fn wrap_with(s: &str, mark: &str) -> String {
+    let trimmed_start = s.trim_start();
+    let start = s.len() - trimmed_start.len();
+    let end = s.trim_end().len();
+    if start >= end {
+        return s.to_owned();
+    }
+    format!("{}{}{}{}{}", &s[..start], mark, &s[start..end], mark, &s[end..])
+}
+
+fn escape_markdown(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if MARKDOWN_ESCAPED_CHARS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape the marker at the start of the plain line, otherwise, the line that starts with "#", "-",
+/// "+", ">" or "1." is imported as the header, the list or the quote.
+fn escape_block_marker(content: &str) -> String {
+    let trimmed = content.trim_start();
+    let start = content.len() - trimmed.len();
+    if trimmed.starts_with(&['#', '-', '+', '>'][..]) {
+        return format!("{}\\{}", &content[..start], trimmed);
+    }
+
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && trimmed[digits..].starts_with(&['.', ')'][..]) {
+        let marker_start = start + digits;
+        return format!("{}\\{}", &content[..marker_start], &content[marker_start..]);
+    }
+    content.to_owned()
+}
@@ -0,0 +1,177 @@
+mod html;
+mod markdown;
+mod text;
+
+pub use html::*;
+pub use markdown::*;
+pub use text::*;
+
+use lib_ot::rich_text::{table_cell_of, table_columns, RichTextAttributeKey, RichTextAttributes, RichTextDelta};
+
+/// A line of the document. The inline attributes are stored in the segments of the line and the
+/// block attributes are stored in the attributes of the newline that ends the line.
+pub(crate) struct DocumentLine {
+    pub(crate) segments: Vec<(String, RichTextAttributes)>,
+    pub(crate) attributes: RichTextAttributes,
+}
+
+impl DocumentLine {
+    fn new() -> Self {
+        Self {
+            segments: vec![],
+            attributes: RichTextAttributes::default(),
+        }
+    }
+
+    pub(crate) fn text(&self) -> String {
+        self.segments.iter().map(|(s, _)| s.as_str()).collect()
+    }
+
+    pub(crate) fn header(&self) -> Option<usize> {
+        attribute_value(&self.attributes, &RichTextAttributeKey::Header)
+            .and_then(|level| level.parse::<usize>().ok())
+            .filter(|level| *level > 0)
+    }
+
+    pub(crate) fn list(&self) -> Option<&str> {
+        attribute_value(&self.attributes, &RichTextAttributeKey::List)
+    }
+
+    pub(crate) fn indent(&self) -> usize {
+        attribute_value(&self.attributes, &RichTextAttributeKey::Indent)
+            .and_then(|indent| indent.parse::<usize>().ok())
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn is_code_block(&self) -> bool {
+        is_enabled(&self.attributes, &RichTextAttributeKey::CodeBlock)
+    }
+
+    pub(crate) fn is_block_quote(&self) -> bool {
+        is_enabled(&self.attributes, &RichTextAttributeKey::BlockQuote)
+    }
+}
+
+/// The id of the column and the lines of the cell.
+type DocumentCell = (String, Vec<DocumentLine>);
+
+/// The table of the document. The row is the id of the row and the cells of the row, the cell that
+/// is missing in the row is empty.
+pub(crate) struct DocumentTable {
+    pub(crate) columns: Vec<String>,
+    pub(crate) rows: Vec<(String, Vec<DocumentCell>)>,
+}
+
+impl DocumentTable {
+    fn from_lines(lines: Vec<DocumentLine>) -> Self {
+        let mut rows: Vec<(String, Vec<DocumentCell>)> = vec![];
+        for line in lines {
+            let (row_id, column_id) = match table_cell_of(&line.attributes) {
+                None => continue,
+                Some((row_id, column_id)) => (row_id.to_owned(), column_id.to_owned()),
+            };
+            let cells = match rows.iter().position(|(id, _)| id == &row_id) {
+                Some(position) => &mut rows[position].1,
+                None => {
+                    rows.push((row_id, vec![]));
+                    &mut rows.last_mut().unwrap().1
+                }
+            };
+            match cells.last_mut() {
+                Some((id, lines)) if id == &column_id => lines.push(line),
+                _ => cells.push((column_id, vec![line])),
+            }
+        }
+
+        let columns = table_columns(
+            rows.iter()
+                .map(|(_, cells)| cells.iter().map(|(column_id, _)| column_id.as_str())),
+        );
+        Self { columns, rows }
+    }
+
+    /// Returns the rows of the table, each row has the lines of the cell of each column.
+    pub(crate) fn cells(&self) -> Vec<Vec<Vec<&DocumentLine>>> {
+        self.rows
+            .iter()
+            .map(|(_, cells)| {
+                self.columns
+                    .iter()
+                    .map(|column_id| {
+                        cells
+                            .iter()
+                            .filter(|(id, _)| id == column_id)
+                            .flat_map(|(_, lines)| lines.iter())
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The block of the document is either one line or the table that is made of the consecutive
+/// lines in the cells of the table.
+pub(crate) enum DocumentBlock {
+    Line(DocumentLine),
+    Table(DocumentTable),
+}
+
+pub(crate) fn blocks_from_delta(delta: &RichTextDelta) -> Vec<DocumentBlock> {
+    let mut blocks = vec![];
+    let mut table_lines = vec![];
+    for line in lines_from_delta(delta) {
+        if table_cell_of(&line.attributes).is_some() {
+            table_lines.push(line);
+            continue;
+        }
+
+        if !table_lines.is_empty() {
+            let table = DocumentTable::from_lines(std::mem::take(&mut table_lines));
+            blocks.push(DocumentBlock::Table(table));
+        }
+        blocks.push(DocumentBlock::Line(line));
+    }
+
+    if !table_lines.is_empty() {
+        blocks.push(DocumentBlock::Table(DocumentTable::from_lines(table_lines)));
+    }
+    blocks
+}
+
+/// Split the document into lines. Only the insert operations are taken into account, so the
+/// delta should be the composed delta of the document.
+pub(crate) fn lines_from_delta(delta: &RichTextDelta) -> Vec<DocumentLine> {
+    let mut lines = vec![];
+    let mut line = DocumentLine::new();
+    for op in delta.ops.iter().filter(|op| op.is_insert()) {
+        let attributes = op.get_attributes();
+        let mut s = op.get_data();
+        while let Some(index) = s.find('\n') {
+            if index > 0 {
+                line.segments.push((s[..index].to_owned(), attributes.clone()));
+            }
+            line.attributes = attributes.clone();
+            lines.push(std::mem::replace(&mut line, DocumentLine::new()));
+            s = &s[index + 1..];
+        }
+
+        if !s.is_empty() {
+            line.segments.push((s.to_owned(), attributes));
+        }
+    }
+
+    // The document should always end with a newline, keep the remaining text anyway.
+    if !line.segments.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+pub(crate) fn attribute_value<'a>(attributes: &'a RichTextAttributes, key: &RichTextAttributeKey) -> Option<&'a str> {
+    attributes.get(key).and_then(|value| value.0.as_deref())
+}
+
+pub(crate) fn is_enabled(attributes: &RichTextAttributes, key: &RichTextAttributeKey) -> bool {
+    matches!(attribute_value(attributes, key), Some(value) if value != "false")
+}
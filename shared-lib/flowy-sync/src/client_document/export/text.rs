@@ -0,0 +1,11 @@
+use crate::client_document::export::lines_from_delta;
+use lib_ot::rich_text::RichTextDelta;
+
+/// Returns the text of the document without any format.
+pub fn delta_to_plain_text(delta: &RichTextDelta) -> String {
+    lines_from_delta(delta)
+        .iter()
+        .map(|line| line.text())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,49 @@
+use crate::{client_document::DeleteExt, util::is_newline};
+use lib_ot::{
+    core::{DeltaIter, Interval, Operation, NEW_LINE},
+    rich_text::{RichTextAttributes, RichTextDelta},
+};
+
+/// Prevents merging the line of the block embed with other lines, because the block embed always
+/// takes the whole line. The newline is kept if it's deleted right before or after the block embed
+/// and the line to be merged is not empty.
+pub struct PreserveEmbedLineOnDelete {}
+impl DeleteExt for PreserveEmbedLineOnDelete {
+    fn ext_name(&self) -> &str {
+        "PreserveEmbedLineOnDelete"
+    }
+
+    fn apply(&self, delta: &RichTextDelta, interval: Interval) -> Option<RichTextDelta> {
+        if interval.size() != 1 {
+            return None;
+        }
+
+        let mut iter = DeltaIter::new(delta);
+        let prev = iter.next_op_with_len(interval.start);
+        let newline_op = iter.next_op_with_len(1)?;
+        if !is_newline(newline_op.get_data()) {
+            return None;
+        }
+        let next = iter.next_op_with_len(1);
+
+        let is_prev_embed = prev.as_ref().map(is_block_embed).unwrap_or(false);
+        let is_next_embed = next.as_ref().map(is_block_embed).unwrap_or(false);
+        let is_prev_line_empty = match &prev {
+            None => true,
+            Some(prev) => !prev.is_embed() && prev.get_data().ends_with(NEW_LINE),
+        };
+        let is_next_line_empty = match &next {
+            None => true,
+            Some(next) => !next.is_embed() && is_newline(next.get_data()),
+        };
+
+        if (is_prev_embed && !is_next_line_empty) || (is_next_embed && !is_prev_line_empty) {
+            return Some(RichTextDelta::default());
+        }
+        None
+    }
+}
+
+fn is_block_embed(op: &Operation<RichTextAttributes>) -> bool {
+    op.get_embed().map(|data| data.is_block()).unwrap_or(false)
+}
@@ -0,0 +1,66 @@
+use crate::client_document::DeleteExt;
+use lib_ot::{
+    core::{count_utf16_code_units, DeltaIter, Interval, NEW_LINE},
+    rich_text::{plain_attributes, table_cell_of, RichTextAttributes, RichTextDelta},
+};
+
+/// Prevents merging the cell of the table with the other cells or with the lines around the
+/// table. The newline that ends the cell is kept if the next line is not in the same cell, and the
+/// rest of the interval is deleted, so deleting the text across the cells clears the cells. The
+/// backspace at the start of the cell does nothing.
+pub struct PreserveTableCellOnDelete {}
+impl DeleteExt for PreserveTableCellOnDelete {
+    fn ext_name(&self) -> &str {
+        "PreserveTableCellOnDelete"
+    }
+
+    fn apply(&self, delta: &RichTextDelta, interval: Interval) -> Option<RichTextDelta> {
+        let mut preserved = vec![];
+        let mut iter = DeltaIter::from_offset(delta, interval.start);
+        let mut offset = interval.start;
+        while offset < interval.end {
+            let len = iter.next_op_len()?.min(interval.end - offset);
+            let op = iter.next_op_with_len(len)?;
+            if op.is_insert() {
+                let mut s = op.get_data();
+                let mut index = offset;
+                while let Some(line_break) = s.find(NEW_LINE) {
+                    index += count_utf16_code_units(&s[..line_break]);
+                    if is_cell_boundary(delta, &op.get_attributes(), index + 1) {
+                        preserved.push(index);
+                    }
+                    index += 1;
+                    s = &s[line_break + 1..];
+                }
+            }
+            offset += op.len();
+        }
+
+        if preserved.is_empty() {
+            return None;
+        }
+
+        let mut new_delta = RichTextDelta::default();
+        new_delta.retain(interval.start, plain_attributes());
+        let mut start = interval.start;
+        for index in preserved {
+            new_delta.delete(index - start);
+            new_delta.retain(1, plain_attributes());
+            start = index + 1;
+        }
+        new_delta.delete(interval.end - start);
+        Some(new_delta)
+    }
+}
+
+// Returns true if the newline with the attributes and the line that starts at the offset are not in
+// the same cell, and one of them is in the table.
+fn is_cell_boundary(delta: &RichTextDelta, attributes: &RichTextAttributes, offset: usize) -> bool {
+    let next_attributes = DeltaIter::from_offset(delta, offset)
+        .next_op_with_newline()
+        .map(|(op, _)| op.get_attributes())
+        .unwrap_or_default();
+    let cell = table_cell_of(attributes);
+    let next_cell = table_cell_of(&next_attributes);
+    (cell.is_some() || next_cell.is_some()) && cell != next_cell
+}
@@ -1,6 +1,6 @@
 use crate::client_document::InsertExt;
 use lib_ot::{
-    core::{Attributes, DeltaBuilder, DeltaIter, NEW_LINE},
+    core::{Attributes, DeltaBuilder, DeltaIter, EmbedData, NEW_LINE},
     rich_text::{RichTextAttributeKey, RichTextAttributes, RichTextDelta},
 };
 
@@ -37,4 +37,19 @@ impl InsertExt for DefaultInsertAttribute {
                 .build(),
         )
     }
+
+    fn apply_embed(
+        &self,
+        _delta: &RichTextDelta,
+        replace_len: usize,
+        data: &EmbedData,
+        index: usize,
+    ) -> Option<RichTextDelta> {
+        Some(
+            DeltaBuilder::new()
+                .retain(index + replace_len)
+                .embed(data.clone())
+                .build(),
+        )
+    }
 }
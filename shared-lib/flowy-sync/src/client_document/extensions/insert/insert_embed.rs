@@ -0,0 +1,103 @@
+use crate::client_document::InsertExt;
+use lib_ot::{
+    core::{DeltaBuilder, DeltaIter, EmbedData, Operation, Utf16CodeUnitMetric, NEW_LINE},
+    rich_text::{RichTextAttributes, RichTextDelta},
+};
+
+/// Wraps the block embed with the newlines, so the block embed always takes the whole line. for
+/// example: insert the image at index 3 of "123456"
+///
+/// [{"insert":"123"},{"insert":"\n"},{"insert":{"image":"..."}},{"insert":"\n"},{"insert":"456\n"}]
+pub struct InsertEmbedsExt {}
+impl InsertExt for InsertEmbedsExt {
+    fn ext_name(&self) -> &str {
+        "InsertEmbedsExt"
+    }
+
+    fn apply(&self, _delta: &RichTextDelta, _replace_len: usize, _text: &str, _index: usize) -> Option<RichTextDelta> {
+        None
+    }
+
+    fn apply_embed(
+        &self,
+        delta: &RichTextDelta,
+        replace_len: usize,
+        data: &EmbedData,
+        index: usize,
+    ) -> Option<RichTextDelta> {
+        if !data.is_block() {
+            return None;
+        }
+
+        let mut iter = DeltaIter::new(delta);
+        let prev = iter.next_op_with_len(index);
+        iter.seek::<Utf16CodeUnitMetric>(replace_len);
+        let next = iter.next_op();
+
+        let is_newline_before = match &prev {
+            None => true,
+            Some(prev) => !prev.is_embed() && prev.get_data().ends_with(NEW_LINE),
+        };
+        let is_newline_after = match &next {
+            None => false,
+            Some(next) => !next.is_embed() && next.get_data().starts_with(NEW_LINE),
+        };
+
+        let mut new_delta = DeltaBuilder::new().retain(index + replace_len).build();
+        if !is_newline_before {
+            // The newline that ends the text before the embed keeps the format of the line.
+            let line_attributes = match next.as_ref().filter(|next| next.get_data().contains(NEW_LINE)) {
+                Some(next) => next.get_attributes(),
+                None => iter
+                    .find(|op| op.get_data().contains(NEW_LINE))
+                    .map(|op| op.get_attributes())
+                    .unwrap_or_default(),
+            };
+            new_delta.insert(NEW_LINE, line_attributes);
+        }
+
+        new_delta.embed(data.clone(), RichTextAttributes::default());
+        if !is_newline_after {
+            new_delta.insert(NEW_LINE, RichTextAttributes::default());
+        }
+        Some(new_delta)
+    }
+}
+
+/// Inserts the newline between the text and the block embed if the text is inserted right before
+/// or after the block embed.
+pub struct ForceNewlineForInsertsAroundEmbedExt {}
+impl InsertExt for ForceNewlineForInsertsAroundEmbedExt {
+    fn ext_name(&self) -> &str {
+        "ForceNewlineForInsertsAroundEmbedExt"
+    }
+
+    fn apply(&self, delta: &RichTextDelta, replace_len: usize, text: &str, index: usize) -> Option<RichTextDelta> {
+        let mut iter = DeltaIter::new(delta);
+        let prev = iter.next_op_with_len(index);
+        iter.seek::<Utf16CodeUnitMetric>(replace_len);
+        let next = iter.next_op();
+
+        let is_cursor_after_embed = prev.as_ref().map(is_block_embed).unwrap_or(false);
+        let is_cursor_before_embed = next.as_ref().map(is_block_embed).unwrap_or(false);
+        if !is_cursor_after_embed && !is_cursor_before_embed {
+            return None;
+        }
+
+        let mut new_delta = DeltaBuilder::new().retain(index + replace_len).build();
+        if is_cursor_before_embed && !text.ends_with(NEW_LINE) {
+            new_delta.insert(text, RichTextAttributes::default());
+            new_delta.insert(NEW_LINE, RichTextAttributes::default());
+        } else if is_cursor_after_embed && !text.starts_with(NEW_LINE) {
+            new_delta.insert(NEW_LINE, RichTextAttributes::default());
+            new_delta.insert(text, RichTextAttributes::default());
+        } else {
+            new_delta.insert(text, RichTextAttributes::default());
+        }
+        Some(new_delta)
+    }
+}
+
+fn is_block_embed(op: &Operation<RichTextAttributes>) -> bool {
+    op.get_embed().map(EmbedData::is_block).unwrap_or(false)
+}
@@ -0,0 +1,205 @@
+use crate::{
+    client_document::InsertExt,
+    util::{is_newline, is_whitespace},
+};
+use lib_ot::{
+    core::{count_utf16_code_units, DeltaBuilder, DeltaIter},
+    rich_text::{RichTextAttribute, RichTextAttributeKey, RichTextAttributes, RichTextDelta},
+};
+use std::cmp::min;
+
+const CODE_BLOCK_SHORTCUT: &str = "```";
+const INLINE_SHORTCUT_MARKERS: [&str; 5] = ["**", "~~", "`", "_", "*"];
+
+/// Converts the markdown prefix of the line into the block attribute of the line when the whitespace
+/// is entered after the prefix, for example: "# " becomes the header. The code block can also be
+/// triggered by the newline, for example: "```\n".
+pub struct MarkdownBlockShortcutExt {}
+impl InsertExt for MarkdownBlockShortcutExt {
+    fn ext_name(&self) -> &str {
+        "MarkdownBlockShortcutExt"
+    }
+
+    fn apply(&self, delta: &RichTextDelta, replace_len: usize, text: &str, index: usize) -> Option<RichTextDelta> {
+        if replace_len != 0 || !(is_whitespace(text) || is_newline(text)) {
+            return None;
+        }
+
+        let line = LineBeforeIndex::from_delta(delta, index)?;
+        if is_newline(text) && line.text != CODE_BLOCK_SHORTCUT {
+            return None;
+        }
+        let shortcut = block_shortcut_attribute(&line.text)?;
+
+        let mut iter = DeltaIter::from_offset(delta, index);
+        let (newline_op, offset) = iter.next_op_with_newline()?;
+        let mut attributes = newline_op.get_attributes();
+        if attributes.contains_key(&RichTextAttributeKey::CodeBlock) {
+            return None;
+        }
+        attributes.mark_all_as_removed_except(None);
+        attributes.add(shortcut);
+
+        let newline_op_data = newline_op.get_data();
+        let rest_of_line_len = offset + count_utf16_code_units(&newline_op_data[..newline_op_data.find('\n')?]);
+        Some(
+            DeltaBuilder::new()
+                .retain(line.start)
+                .delete(line.len())
+                .retain(rest_of_line_len)
+                .retain_with_attributes(1, attributes)
+                .build(),
+        )
+    }
+}
+
+/// Converts the text wrapped with the markdown marks into the inline attribute when the whitespace
+/// is entered after the closing mark, for example: "**bold** " becomes the bold text.
+pub struct MarkdownInlineShortcutExt {}
+impl InsertExt for MarkdownInlineShortcutExt {
+    fn ext_name(&self) -> &str {
+        "MarkdownInlineShortcutExt"
+    }
+
+    fn apply(&self, delta: &RichTextDelta, replace_len: usize, text: &str, index: usize) -> Option<RichTextDelta> {
+        if replace_len != 0 || !is_whitespace(text) {
+            return None;
+        }
+
+        let line = LineBeforeIndex::from_delta(delta, index)?;
+        if line.attributes.contains_key(&RichTextAttributeKey::InlineCode) || is_in_code_block(delta, index) {
+            return None;
+        }
+
+        let (before, marker, content) = parse_inline_shortcut(&line.text)?;
+        let marker_len = count_utf16_code_units(marker);
+        let mut insert_attributes = line.attributes.clone();
+        insert_attributes.remove(inline_shortcut_attribute(marker).key);
+        Some(
+            DeltaBuilder::new()
+                .retain(line.start + count_utf16_code_units(before))
+                .delete(marker_len)
+                .retain_with_attributes(
+                    count_utf16_code_units(content),
+                    inline_shortcut_attribute(marker).into(),
+                )
+                .delete(marker_len)
+                .insert_with_attributes(text, insert_attributes)
+                .build(),
+        )
+    }
+}
+
+/// The text from the start of the line to the index, and the attributes of the text right before
+/// the index.
+struct LineBeforeIndex {
+    start: usize,
+    text: String,
+    attributes: RichTextAttributes,
+}
+
+impl LineBeforeIndex {
+    fn from_delta(delta: &RichTextDelta, index: usize) -> Option<Self> {
+        let mut iter = DeltaIter::new(delta);
+        let mut text = String::new();
+        let mut attributes = RichTextAttributes::default();
+        let mut offset = 0;
+        while offset < index {
+            // Take the ops one by one, the next_op_with_len skips the ops before the expected len.
+            let len = min(iter.next_op_len()?, index - offset);
+            let op = iter.next_op_with_len(len)?;
+            offset += op.len();
+            attributes = op.get_attributes();
+            let data = op.get_data();
+            match data.rfind('\n') {
+                None => text.push_str(data),
+                Some(newline) => text = data[newline + 1..].to_owned(),
+            }
+        }
+
+        let start = index - count_utf16_code_units(&text);
+        Some(Self {
+            start,
+            text,
+            attributes,
+        })
+    }
+
+    fn len(&self) -> usize {
+        count_utf16_code_units(&self.text)
+    }
+}
+
+fn is_in_code_block(delta: &RichTextDelta, index: usize) -> bool {
+    match DeltaIter::from_offset(delta, index).next_op_with_newline() {
+        None => false,
+        Some((newline_op, _)) => newline_op
+            .get_attributes()
+            .contains_key(&RichTextAttributeKey::CodeBlock),
+    }
+}
+
+fn block_shortcut_attribute(prefix: &str) -> Option<RichTextAttribute> {
+    let attribute = match prefix {
+        "-" | "*" | "+" => RichTextAttribute::Bullet(true),
+        "[]" | "[ ]" => RichTextAttribute::UnChecked(true),
+        "[x]" | "[X]" => RichTextAttribute::Checked(true),
+        ">" => RichTextAttribute::BlockQuote(true),
+        CODE_BLOCK_SHORTCUT => RichTextAttribute::CodeBlock(true),
+        _ if (1..=6).contains(&prefix.len()) && prefix.chars().all(|c| c == '#') => {
+            RichTextAttribute::Header(prefix.len())
+        }
+        _ => {
+            let number = prefix.strip_suffix('.')?;
+            if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            RichTextAttribute::Ordered(true)
+        }
+    };
+    Some(attribute)
+}
+
+fn inline_shortcut_attribute(marker: &str) -> RichTextAttribute {
+    match marker {
+        "**" => RichTextAttribute::Bold(true),
+        "~~" => RichTextAttribute::StrikeThrough(true),
+        "`" => RichTextAttribute::InlineCode(true),
+        _ => RichTextAttribute::Italic(true),
+    }
+}
+
+/// Returns the text before the opening mark, the mark and the text between the marks if the line
+/// ends with the closing mark.
+fn parse_inline_shortcut(line: &str) -> Option<(&str, &'static str, &str)> {
+    for marker in INLINE_SHORTCUT_MARKERS.iter() {
+        let body = match line.strip_suffix(marker) {
+            None => continue,
+            Some(body) => body,
+        };
+        // The closing mark should not be the part of a longer mark, for example: "**" is not "*".
+        if body.ends_with(*marker) {
+            continue;
+        }
+
+        let open = match body.rfind(marker) {
+            None => continue,
+            Some(open) => open,
+        };
+        let (before, content) = (&body[..open], &body[open + marker.len()..]);
+        if content.is_empty() || content.starts_with(char::is_whitespace) || content.ends_with(char::is_whitespace) {
+            continue;
+        }
+
+        if before.ends_with(*marker) {
+            continue;
+        }
+
+        // The '_' can't open the italic inside a word, for example: "snake_case_ ".
+        if *marker == "_" && before.chars().last().map(char::is_alphanumeric).unwrap_or(false) {
+            continue;
+        }
+        return Some((before, marker, content));
+    }
+    None
+}
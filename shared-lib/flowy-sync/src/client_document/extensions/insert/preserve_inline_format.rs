@@ -25,7 +25,9 @@ impl InsertExt for PreserveInlineFormat {
         }
 
         let mut attributes = prev.get_attributes();
-        if attributes.is_empty() || !attributes.contains_key(&RichTextAttributeKey::Link) {
+        let is_link = attributes.contains_key(&RichTextAttributeKey::Link)
+            || attributes.contains_key(&RichTextAttributeKey::PageLink);
+        if attributes.is_empty() || !is_link {
             return Some(
                 DeltaBuilder::new()
                     .retain(index + replace_len)
@@ -0,0 +1,38 @@
+use crate::client_document::InsertExt;
+use lib_ot::{
+    core::{DeltaBuilder, DeltaIter, NEW_LINE},
+    rich_text::{plain_attributes, table_cell_of, RichTextDelta},
+};
+
+/// Keeps the newlines that are inserted into the cell of the table in the cell. The text of the
+/// cell becomes more than one line instead of splitting the cell or leaving the table.
+pub struct PreserveTableCellOnInsert {}
+impl InsertExt for PreserveTableCellOnInsert {
+    fn ext_name(&self) -> &str {
+        "PreserveTableCellOnInsert"
+    }
+
+    fn apply(&self, delta: &RichTextDelta, replace_len: usize, text: &str, index: usize) -> Option<RichTextDelta> {
+        if !text.contains(NEW_LINE) {
+            return None;
+        }
+
+        let mut iter = DeltaIter::from_offset(delta, index);
+        let (newline_op, _) = iter.next_op_with_newline()?;
+        let cell_attributes = newline_op.get_attributes();
+        let _ = table_cell_of(&cell_attributes)?;
+
+        let mut new_delta = DeltaBuilder::new().retain(index + replace_len).build();
+        let lines: Vec<_> = text.split(NEW_LINE).collect();
+        lines.iter().enumerate().for_each(|(i, line)| {
+            if !line.is_empty() {
+                new_delta.insert(line, plain_attributes());
+            }
+
+            if i < lines.len() - 1 {
+                new_delta.insert(NEW_LINE, cell_attributes.clone());
+            }
+        });
+        Some(new_delta)
+    }
+}
@@ -2,7 +2,7 @@ pub use delete::*;
 pub use format::*;
 pub use insert::*;
 use lib_ot::{
-    core::Interval,
+    core::{EmbedData, Interval},
     rich_text::{RichTextAttribute, RichTextDelta},
 };
 
@@ -18,6 +18,17 @@ pub type DeleteExtension = Box<dyn DeleteExt + Send + Sync>;
 pub trait InsertExt {
     fn ext_name(&self) -> &str;
     fn apply(&self, delta: &RichTextDelta, replace_len: usize, text: &str, index: usize) -> Option<RichTextDelta>;
+
+    // Most of the extensions only handle the text, so the embed is skipped by default.
+    fn apply_embed(
+        &self,
+        _delta: &RichTextDelta,
+        _replace_len: usize,
+        _data: &EmbedData,
+        _index: usize,
+    ) -> Option<RichTextDelta> {
+        None
+    }
 }
 
 pub trait FormatExt {
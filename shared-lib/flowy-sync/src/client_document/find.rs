@@ -0,0 +1,108 @@
+use crate::errors::{CollaborateError, CollaborateResult};
+use lib_ot::{
+    core::{count_utf16_code_units, DeltaIter, Interval},
+    rich_text::{RichTextAttributes, RichTextDelta},
+};
+use regex::{Captures, Regex, RegexBuilder};
+
+#[derive(Debug, Clone, Default)]
+pub struct FindOptions {
+    pub match_case: bool,
+    pub whole_word: bool,
+    /// The query is a regular expression, and the replacement can refer to its capture groups,
+    /// for example: $1.
+    pub regex: bool,
+}
+
+/// Finds the text of the document. The matches are the utf16 intervals of the document, the same
+/// as the intervals used to edit the document.
+#[derive(Debug, Clone)]
+pub struct DocumentFinder {
+    regex: Regex,
+    options: FindOptions,
+}
+
+impl DocumentFinder {
+    pub fn new(query: &str, options: FindOptions) -> CollaborateResult<Self> {
+        if query.is_empty() {
+            return Err(CollaborateError::internal().context("The query of the find should not be empty"));
+        }
+
+        let mut pattern = match options.regex {
+            true => query.to_owned(),
+            false => regex::escape(query),
+        };
+        if options.whole_word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!options.match_case)
+            .build()
+            .map_err(|e| CollaborateError::internal().context(e))?;
+        Ok(Self { regex, options })
+    }
+
+    pub fn find(&self, text: &str) -> Vec<Interval> {
+        self.captures(text).into_iter().map(|(interval, _)| interval).collect()
+    }
+
+    /// Returns the delta that replaces the matches with the replacement. Only the matches in the
+    /// `selected` are replaced if it's not None, the matches that moved since they were found are
+    /// skipped. The replacement keeps the inline attributes of the text it replaces.
+    pub fn replace_delta(
+        &self,
+        delta: &RichTextDelta,
+        replacement: &str,
+        selected: Option<&[Interval]>,
+    ) -> CollaborateResult<RichTextDelta> {
+        let text = delta.apply("")?;
+        let mut replace_delta = RichTextDelta::default();
+        let mut index = 0;
+        for (interval, captures) in self.captures(&text) {
+            if let Some(selected) = selected {
+                if !selected.contains(&interval) {
+                    continue;
+                }
+            }
+
+            let mut new_text = String::new();
+            match self.options.regex {
+                true => captures.expand(replacement, &mut new_text),
+                false => new_text.push_str(replacement),
+            }
+            replace_delta.retain(interval.start - index, RichTextAttributes::default());
+            if !new_text.is_empty() {
+                replace_delta.insert(&new_text, attributes_at(delta, interval));
+            }
+            replace_delta.delete(interval.size());
+            index = interval.end;
+        }
+        Ok(replace_delta)
+    }
+
+    // Returns the utf16 interval of each match, the empty matches are skipped.
+    fn captures<'t>(&self, text: &'t str) -> Vec<(Interval, Captures<'t>)> {
+        let mut matches = vec![];
+        let mut utf16_index = 0;
+        let mut byte_index = 0;
+        for captures in self.regex.captures_iter(text) {
+            let m = captures.get(0).unwrap();
+            if m.start() == m.end() {
+                continue;
+            }
+            let start = utf16_index + count_utf16_code_units(&text[byte_index..m.start()]);
+            let end = start + count_utf16_code_units(m.as_str());
+            utf16_index = end;
+            byte_index = m.end();
+            matches.push((Interval::new(start, end), captures));
+        }
+        matches
+    }
+}
+
+fn attributes_at(delta: &RichTextDelta, interval: Interval) -> RichTextAttributes {
+    DeltaIter::from_interval(delta, interval)
+        .next_op()
+        .map(|op| op.get_attributes())
+        .unwrap_or_default()
+}
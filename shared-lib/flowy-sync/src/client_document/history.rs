@@ -1,29 +1,60 @@
-use lib_ot::rich_text::RichTextDelta;
+use crate::client_document::RECORD_THRESHOLD;
+use lib_ot::{
+    core::{trim, Bias, Interval, OperationTransformable},
+    errors::OTError,
+    rich_text::{RichTextAttributes, RichTextDelta},
+};
 
-const MAX_UNDOES: usize = 20;
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct HistoryConfig {
+    /// The consecutive changes within the interval are merged into one undo step, in milliseconds.
+    pub merge_interval: usize,
+    /// The max number of the undo steps.
+    pub capacity: usize,
+}
+
+impl std::default::Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            merge_interval: RECORD_THRESHOLD,
+            capacity: DEFAULT_HISTORY_CAPACITY,
+        }
+    }
+}
+
+/// The selection before and after the change. The undo restores the selection before the change
+/// and the redo restores the selection after the change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistorySelection {
+    pub before: Interval,
+    pub after: Interval,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryItem {
+    pub delta: RichTextDelta,
+    pub selection: Option<HistorySelection>,
+}
 
 #[derive(Debug, Clone)]
 pub struct UndoResult {
     pub delta: RichTextDelta,
+    pub selection: Interval,
 }
 
 #[derive(Debug, Clone)]
 pub struct History {
-    #[allow(dead_code)]
-    cur_undo: usize,
-    undoes: Vec<RichTextDelta>,
-    redoes: Vec<RichTextDelta>,
-    capacity: usize,
+    undoes: Vec<HistoryItem>,
+    redoes: Vec<HistoryItem>,
+    config: HistoryConfig,
+    last_record_time: i64,
 }
 
 impl std::default::Default for History {
     fn default() -> Self {
-        History {
-            cur_undo: 1,
-            undoes: Vec::new(),
-            redoes: Vec::new(),
-            capacity: MAX_UNDOES,
-        }
+        History::with_config(HistoryConfig::default())
     }
 }
 
@@ -32,6 +63,20 @@ impl History {
         History::default()
     }
 
+    pub fn with_config(config: HistoryConfig) -> Self {
+        History {
+            undoes: Vec::new(),
+            redoes: Vec::new(),
+            config,
+            last_record_time: 0,
+        }
+    }
+
+    pub fn set_config(&mut self, config: HistoryConfig) {
+        self.config = config;
+        self.truncate();
+    }
+
     pub fn can_undo(&self) -> bool {
         !self.undoes.is_empty()
     }
@@ -40,41 +85,152 @@ impl History {
         !self.redoes.is_empty()
     }
 
-    pub fn add_undo(&mut self, delta: RichTextDelta) {
-        self.undoes.push(delta);
+    pub fn add_undo(&mut self, item: HistoryItem) {
+        self.undoes.push(item);
     }
 
-    pub fn add_redo(&mut self, delta: RichTextDelta) {
-        self.redoes.push(delta);
+    pub fn add_redo(&mut self, item: HistoryItem) {
+        self.redoes.push(item);
     }
 
-    pub fn record(&mut self, delta: RichTextDelta) {
+    /// Records the undo delta of the local change. The change is merged into the previous undo
+    /// step if it happens within the merge interval since the previous step was started.
+    pub fn record(&mut self, delta: RichTextDelta, selection: Option<HistorySelection>) -> Result<(), OTError> {
         if delta.ops.is_empty() {
-            return;
+            return Ok(());
         }
 
         self.redoes.clear();
-        self.add_undo(delta);
-
-        if self.undoes.len() > self.capacity {
-            self.undoes.remove(0);
+        let now = chrono::Utc::now().timestamp_millis();
+        if now - self.last_record_time < self.config.merge_interval as i64 {
+            if let Some(last) = self.undoes.pop() {
+                tracing::trace!("compose previous change");
+                tracing::trace!("current = {}", delta);
+                tracing::trace!("previous = {}", last.delta);
+                let selection = match (last.selection, selection) {
+                    (Some(last), Some(selection)) => Some(HistorySelection {
+                        before: last.before,
+                        after: selection.after,
+                    }),
+                    (last, selection) => last.or(selection),
+                };
+                self.add_undo(HistoryItem {
+                    delta: delta.compose(&last.delta)?,
+                    selection,
+                });
+                return Ok(());
+            }
         }
+
+        self.last_record_time = now;
+        self.add_undo(HistoryItem { delta, selection });
+        self.truncate();
+        Ok(())
     }
 
-    pub fn undo(&mut self) -> Option<RichTextDelta> {
+    /// Transforms the undo and redo steps against the remote change, so the undo never reverts the
+    /// remote change. The remote delta should be applicable to the current document, and the
+    /// `document_len` is the length of the current document.
+    pub fn transform(&mut self, remote_delta: &RichTextDelta, document_len: usize) -> Result<(), OTError> {
+        transform_items(&mut self.undoes, remote_delta, document_len, true)?;
+        transform_items(&mut self.redoes, remote_delta, document_len, false)?;
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Option<HistoryItem> {
         if !self.can_undo() {
             return None;
         }
-        let delta = self.undoes.pop().unwrap();
-        Some(delta)
+        let item = self.undoes.pop().unwrap();
+        Some(item)
     }
 
-    pub fn redo(&mut self) -> Option<RichTextDelta> {
+    pub fn redo(&mut self) -> Option<HistoryItem> {
         if !self.can_redo() {
             return None;
         }
 
-        let delta = self.redoes.pop().unwrap();
-        Some(delta)
+        let item = self.redoes.pop().unwrap();
+        Some(item)
+    }
+
+    fn truncate(&mut self) {
+        if self.undoes.len() > self.config.capacity {
+            let len = self.undoes.len() - self.config.capacity;
+            self.undoes.drain(..len);
+        }
     }
 }
+
+// The last item is applied to the current document, and each item is applied to the document
+// after applying the next item. So the remote delta is transformed from the last item to the
+// first item.
+//
+// The selection that is restored by the item is in the document after applying the item, so
+// it's transformed with the remote delta that is applied after the item. The undo restores the
+// `before` and the redo restores the `after`.
+fn transform_items(
+    items: &mut Vec<HistoryItem>,
+    remote_delta: &RichTextDelta,
+    document_len: usize,
+    is_undo: bool,
+) -> Result<(), OTError> {
+    let mut remote_delta = with_base_len(remote_delta, document_len);
+    let mut document_len = document_len;
+    for i in (0..items.len()).rev() {
+        let item = &items[i];
+        let delta = with_base_len(&item.delta, document_len);
+        remote_delta = with_base_len(&remote_delta, document_len);
+        let (remote_prime, mut delta_prime) = remote_delta.transform(&delta)?;
+        trim(&mut delta_prime);
+        let selection = item.selection.map(|selection| {
+            let (before_delta, after_delta) = match is_undo {
+                true => (&remote_prime, &remote_delta),
+                false => (&remote_delta, &remote_prime),
+            };
+            HistorySelection {
+                before: before_delta.transform_interval(selection.before, Bias::Right, Bias::Right),
+                after: after_delta.transform_interval(selection.after, Bias::Right, Bias::Right),
+            }
+        });
+
+        if delta_prime.is_noop() {
+            items.remove(i);
+        } else {
+            items[i] = HistoryItem {
+                delta: delta_prime,
+                selection,
+            };
+        }
+        remote_delta = remote_prime;
+        document_len = delta.utf16_target_len;
+    }
+    Ok(())
+}
+
+// The trailing retain is trimmed from the delta, but the transform requires both deltas to be
+// applied to the same document.
+pub(crate) fn with_base_len(delta: &RichTextDelta, base_len: usize) -> RichTextDelta {
+    let mut delta = delta.clone();
+    if delta.utf16_base_len < base_len {
+        delta.retain(base_len - delta.utf16_base_len, RichTextAttributes::default());
+    }
+    delta
+}
+
+/// Returns the caret at the end of the last change of the delta, it's used if the selection of
+/// the change is unknown.
+pub(crate) fn caret_of_change(delta: &RichTextDelta) -> Interval {
+    let last_change = delta
+        .ops
+        .iter()
+        .rposition(|op| !(op.is_retain() && op.is_plain()))
+        .map(|position| position + 1)
+        .unwrap_or(0);
+    let index = delta.ops[..last_change]
+        .iter()
+        .filter(|op| !op.is_delete())
+        .map(|op| op.len())
+        .sum::<usize>();
+    Interval::new(index, index)
+}
@@ -0,0 +1,462 @@
+use crate::client_document::import::DeltaLineBuilder;
+use lib_ot::rich_text::{RichTextAttribute, RichTextAttributes, RichTextDelta};
+
+const BLOCK_TAGS: [&str; 20] = [
+    "p",
+    "div",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "li",
+    "ul",
+    "ol",
+    "blockquote",
+    "pre",
+    "hr",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "tr",
+    "table",
+];
+const IGNORED_CONTENT_TAGS: [&str; 4] = ["script", "style", "head", "title"];
+
+/// Returns the delta of the html. The block elements are converted into the lines of the document
+/// and the inline elements are converted into the inline attributes of the text. The unsupported
+/// elements are ignored but their text is kept.
+pub fn html_to_delta(html: &str) -> RichTextDelta {
+    let mut parser = HtmlParser::new();
+    for token in HtmlTokenizer::new(html) {
+        match token {
+            HtmlToken::Text(text) => parser.push_text(&text),
+            HtmlToken::OpenTag(tag) => parser.open_tag(tag),
+            HtmlToken::CloseTag(name) => parser.close_tag(&name),
+        }
+    }
+    parser.finish()
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum HtmlList {
+    Bullet,
+    Ordered,
+    Check,
+}
+
+struct HtmlParser {
+    builder: DeltaLineBuilder,
+    inline_tags: Vec<(String, RichTextAttribute)>,
+    lists: Vec<HtmlList>,
+    checked: Option<bool>,
+    header: Option<usize>,
+    block_quote_depth: usize,
+    pre_depth: usize,
+    indent: Option<usize>,
+    pending_space: Option<RichTextAttributes>,
+}
+
+impl HtmlParser {
+    fn new() -> Self {
+        Self {
+            builder: DeltaLineBuilder::new(),
+            inline_tags: vec![],
+            lists: vec![],
+            checked: None,
+            header: None,
+            block_quote_depth: 0,
+            pre_depth: 0,
+            indent: None,
+            pending_space: None,
+        }
+    }
+
+    fn open_tag(&mut self, tag: HtmlTag) {
+        if BLOCK_TAGS.contains(&tag.name.as_str()) {
+            self.flush_line();
+        }
+
+        match tag.name.as_str() {
+            "br" => self.end_line(),
+            "ul" => {
+                let is_check_list = tag
+                    .attribute("class")
+                    .map(|class| class.split_whitespace().any(|class| class == "checklist"))
+                    .unwrap_or(false);
+                match is_check_list || tag.attribute("data-checked").is_some() {
+                    true => self.lists.push(HtmlList::Check),
+                    false => self.lists.push(HtmlList::Bullet),
+                }
+            }
+            "ol" => self.lists.push(HtmlList::Ordered),
+            "li" => {
+                self.checked = tag.attribute("data-checked").map(|checked| checked == "true");
+                self.indent = tag.attribute("class").and_then(quill_indent);
+            }
+            "input" if tag.attribute("type") == Some("checkbox") => {
+                self.checked = Some(tag.attribute("checked").is_some());
+            }
+            "blockquote" => self.block_quote_depth += 1,
+            "pre" => self.pre_depth += 1,
+            _ => {
+                if let Some(level) = header_level(&tag.name) {
+                    self.header = Some(level);
+                }
+            }
+        }
+
+        let attribute = match tag.name.as_str() {
+            "strong" | "b" => Some(RichTextAttribute::Bold(true)),
+            "em" | "i" => Some(RichTextAttribute::Italic(true)),
+            "u" => Some(RichTextAttribute::Underline(true)),
+            "s" | "strike" | "del" => Some(RichTextAttribute::StrikeThrough(true)),
+            "code" if self.pre_depth == 0 => Some(RichTextAttribute::InlineCode(true)),
+            "a" => tag.attribute("href").map(RichTextAttribute::Link),
+            _ => None,
+        };
+        if let Some(attribute) = attribute {
+            if !tag.self_closing {
+                self.inline_tags.push((tag.name, attribute));
+            }
+        }
+    }
+
+    fn close_tag(&mut self, name: &str) {
+        if let Some(index) = self.inline_tags.iter().rposition(|(tag, _)| tag == name) {
+            self.inline_tags.truncate(index);
+        }
+
+        if BLOCK_TAGS.contains(&name) {
+            self.flush_line();
+        }
+
+        match name {
+            "ul" | "ol" => {
+                self.lists.pop();
+            }
+            "li" => {
+                self.checked = None;
+                self.indent = None;
+            }
+            "blockquote" => self.block_quote_depth = self.block_quote_depth.saturating_sub(1),
+            "pre" => self.pre_depth = self.pre_depth.saturating_sub(1),
+            _ => {
+                if header_level(name).is_some() {
+                    self.header = None;
+                }
+            }
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let attributes = self.inline_attributes();
+        if self.pre_depth > 0 {
+            // The newline immediately following the pre start tag is ignored.
+            let text = match self.builder.line_is_empty() {
+                true => text.strip_prefix('\n').unwrap_or(text),
+                false => text,
+            };
+            let mut lines = text.split('\n').peekable();
+            while let Some(line) = lines.next() {
+                self.builder.push_text(line, attributes.clone());
+                if lines.peek().is_some() {
+                    self.end_line();
+                }
+            }
+            return;
+        }
+
+        // The whitespaces are collapsed into one space that keeps the attributes of the text where
+        // it is found, and the space is dropped if it is at the start or the end of the line.
+        let mut collapsed = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_ascii_whitespace() {
+                if !self.builder.line_is_empty() || !collapsed.is_empty() {
+                    self.pending_space = Some(attributes.clone());
+                }
+                continue;
+            }
+            match self.pending_space.take() {
+                Some(space_attributes) if space_attributes != attributes => {
+                    self.builder.push_text(" ", space_attributes);
+                }
+                Some(_) => collapsed.push(' '),
+                None => {}
+            }
+            collapsed.push(c);
+        }
+        self.builder.push_text(&collapsed, attributes);
+    }
+
+    /// Ends the current line if it has any text. Used by the block elements, so the empty block
+    /// elements don't produce empty lines.
+    fn flush_line(&mut self) {
+        if !self.builder.line_is_empty() {
+            self.end_line();
+        }
+    }
+
+    fn end_line(&mut self) {
+        let attributes = self.block_attributes();
+        self.builder.end_line(attributes);
+        self.pending_space = None;
+    }
+
+    fn finish(mut self) -> RichTextDelta {
+        self.flush_line();
+        self.builder.build()
+    }
+
+    fn inline_attributes(&self) -> RichTextAttributes {
+        let mut attributes = RichTextAttributes::default();
+        for (_, attribute) in self.inline_tags.iter() {
+            attributes.add(attribute.clone());
+        }
+        attributes
+    }
+
+    fn block_attributes(&self) -> RichTextAttributes {
+        let mut attributes = RichTextAttributes::default();
+        if self.pre_depth > 0 {
+            attributes.add(RichTextAttribute::CodeBlock(true));
+            return attributes;
+        }
+
+        if let Some(level) = self.header {
+            attributes.add(RichTextAttribute::Header(level));
+            return attributes;
+        }
+
+        if let Some(list) = self.lists.last() {
+            let attribute = match (list, self.checked) {
+                (_, Some(true)) => RichTextAttribute::Checked(true),
+                (_, Some(false)) | (HtmlList::Check, None) => RichTextAttribute::UnChecked(true),
+                (HtmlList::Ordered, None) => RichTextAttribute::Ordered(true),
+                (HtmlList::Bullet, None) => RichTextAttribute::Bullet(true),
+            };
+            attributes.add(attribute);
+            let indent = self.indent.unwrap_or(self.lists.len() - 1);
+            if indent > 0 {
+                attributes.add(RichTextAttribute::Indent(indent));
+            }
+            return attributes;
+        }
+
+        if self.block_quote_depth > 0 {
+            attributes.add(RichTextAttribute::BlockQuote(true));
+        }
+        attributes
+    }
+}
+
+/// The indent of the list item exported by Quill, for example: `<li class="ql-indent-1">`.
+fn quill_indent(class: &str) -> Option<usize> {
+    class
+        .split_whitespace()
+        .find_map(|class| class.strip_prefix("ql-indent-"))
+        .and_then(|indent| indent.parse::<usize>().ok())
+}
+
+fn header_level(name: &str) -> Option<usize> {
+    match name.as_bytes() {
+        [b'h', level @ b'1'..=b'6'] => Some((level - b'0') as usize),
+        _ => None,
+    }
+}
+
+struct HtmlTag {
+    name: String,
+    attributes: Vec<(String, String)>,
+    self_closing: bool,
+}
+
+impl HtmlTag {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+enum HtmlToken {
+    Text(String),
+    OpenTag(HtmlTag),
+    CloseTag(String),
+}
+
+/// A lenient tokenizer that splits the html into tags and text. The comments, the declarations and
+/// the content of the tags like `script` are skipped.
+struct HtmlTokenizer<'a> {
+    html: &'a str,
+    offset: usize,
+}
+
+impl<'a> HtmlTokenizer<'a> {
+    fn new(html: &'a str) -> Self {
+        Self { html, offset: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.html[self.offset..]
+    }
+
+    fn skip_past(&mut self, pattern: &str) {
+        match self.rest().find(pattern) {
+            None => self.offset = self.html.len(),
+            Some(index) => self.offset += index + pattern.len(),
+        }
+    }
+
+    fn read_text(&mut self) -> HtmlToken {
+        let rest = self.rest();
+        // The '<' that doesn't start a tag is the part of the text.
+        let first_len = rest.chars().next().map(|c| c.len_utf8()).unwrap_or(0);
+        let len = rest[first_len..]
+            .find('<')
+            .map(|index| index + first_len)
+            .unwrap_or(rest.len());
+        self.offset += len;
+        HtmlToken::Text(decode_entities(&rest[..len]))
+    }
+
+    fn read_close_tag(&mut self) -> HtmlToken {
+        let rest = self.rest();
+        let end = rest.find('>').unwrap_or(rest.len());
+        let name = rest[2..end].trim().to_lowercase();
+        self.offset += (end + 1).min(rest.len());
+        HtmlToken::CloseTag(name)
+    }
+
+    fn read_open_tag(&mut self) -> HtmlToken {
+        let rest = self.rest();
+        let chars = rest.char_indices().skip(1).collect::<Vec<_>>();
+        let mut i = 0;
+        let name = read_while(&chars, &mut i, |c| !c.is_whitespace() && c != '>' && c != '/').to_lowercase();
+        let mut attributes = vec![];
+        let mut self_closing = false;
+        while i < chars.len() {
+            let _ = read_while(&chars, &mut i, |c| c.is_whitespace());
+            match chars.get(i).map(|(_, c)| *c) {
+                None => break,
+                Some('>') => {
+                    i += 1;
+                    break;
+                }
+                Some('/') => {
+                    self_closing = true;
+                    i += 1;
+                }
+                Some(_) => {
+                    let key = read_while(&chars, &mut i, |c| !c.is_whitespace() && !matches!(c, '=' | '>' | '/'));
+                    let _ = read_while(&chars, &mut i, |c| c.is_whitespace());
+                    let mut value = String::new();
+                    if chars.get(i).map(|(_, c)| *c) == Some('=') {
+                        i += 1;
+                        let _ = read_while(&chars, &mut i, |c| c.is_whitespace());
+                        match chars.get(i).map(|(_, c)| *c) {
+                            Some(quote) if quote == '"' || quote == '\'' => {
+                                i += 1;
+                                value = read_while(&chars, &mut i, |c| c != quote);
+                                i += 1;
+                            }
+                            _ => value = read_while(&chars, &mut i, |c| !c.is_whitespace() && c != '>'),
+                        }
+                    }
+                    if key.is_empty() {
+                        i += 1;
+                    } else {
+                        attributes.push((key.to_lowercase(), decode_entities(&value)));
+                    }
+                }
+            }
+        }
+
+        self.offset += chars.get(i).map(|(index, _)| *index).unwrap_or(rest.len());
+        if IGNORED_CONTENT_TAGS.contains(&name.as_str()) && !self_closing {
+            self.skip_past(&format!("</{}", name));
+            self.skip_past(">");
+        }
+        HtmlToken::OpenTag(HtmlTag {
+            name,
+            attributes,
+            self_closing,
+        })
+    }
+}
+
+impl<'a> Iterator for HtmlTokenizer<'a> {
+    type Item = HtmlToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return None;
+            }
+
+            if rest.starts_with("<!--") {
+                self.skip_past("-->");
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                self.skip_past(">");
+            } else if rest.starts_with("</") {
+                return Some(self.read_close_tag());
+            } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+                return Some(self.read_open_tag());
+            } else {
+                return Some(self.read_text());
+            }
+        }
+    }
+}
+
+fn read_while(chars: &[(usize, char)], i: &mut usize, f: impl Fn(char) -> bool) -> String {
+    let mut s = String::new();
+    while *i < chars.len() && f(chars[*i].1) {
+        s.push(chars[*i].1);
+        *i += 1;
+    }
+    s
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(index) = rest.find('&') {
+        decoded.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let entity = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                code => code
+                    .strip_prefix("#x")
+                    .or_else(|| code.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| code.strip_prefix('#').and_then(|dec| dec.parse::<u32>().ok()))
+                    .and_then(std::char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match entity {
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
@@ -0,0 +1,318 @@
+use crate::client_document::import::DeltaLineBuilder;
+use lib_ot::rich_text::{RichTextAttribute, RichTextAttributes, RichTextDelta};
+
+const CODE_FENCES: [&str; 2] = ["```", "~~~"];
+
+/// Returns the delta of the markdown. Each line of the markdown is converted into one line of the
+/// document, and the lines of the fenced code block are converted into the lines of the code block.
+pub fn markdown_to_delta(markdown: &str) -> RichTextDelta {
+    let mut builder = DeltaLineBuilder::new();
+    let mut code_fence: Option<&str> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        match code_fence {
+            Some(fence) => {
+                if trimmed.starts_with(fence) && trimmed.trim_start_matches(&fence[..1]).is_empty() {
+                    code_fence = None;
+                } else {
+                    builder.push_text(line, RichTextAttributes::default());
+                    builder.end_line(RichTextAttribute::CodeBlock(true).into());
+                }
+            }
+            None => {
+                if let Some(fence) = CODE_FENCES.iter().find(|fence| trimmed.starts_with(*fence)) {
+                    code_fence = Some(fence);
+                    continue;
+                }
+
+                let (content, attributes) = parse_block(line);
+                InlineParser::new(content).parse(&mut builder, &RichTextAttributes::default());
+                builder.end_line(attributes);
+            }
+        }
+    }
+    builder.build()
+}
+
+/// Returns the content of the line and the block attributes of the line.
+fn parse_block(line: &str) -> (&str, RichTextAttributes) {
+    let trimmed = line.trim_start();
+    let leading_width = line[..line.len() - trimmed.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum::<usize>();
+
+    if let Some((level, content)) = parse_header(trimmed) {
+        return (content, RichTextAttribute::Header(level).into());
+    }
+
+    if let Some(content) = trimmed.strip_prefix('>') {
+        let content = content.strip_prefix(' ').unwrap_or(content);
+        return (content, RichTextAttribute::BlockQuote(true).into());
+    }
+
+    if let Some((list, content)) = parse_list(trimmed) {
+        let mut attributes: RichTextAttributes = list.into();
+        let indent = (leading_width + 2) / 4;
+        if indent > 0 {
+            attributes.add(RichTextAttribute::Indent(indent));
+        }
+        return (content, attributes);
+    }
+
+    (trimmed, RichTextAttributes::default())
+}
+
+fn parse_header(s: &str) -> Option<(usize, &str)> {
+    let level = s.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    strip_space_or_end(&s[level..]).map(|content| (level, content.trim_end()))
+}
+
+fn parse_list(s: &str) -> Option<(RichTextAttribute, &str)> {
+    if let Some(content) = s.strip_prefix(&['-', '*', '+'][..]).and_then(strip_space_or_end) {
+        return match content.get(..3) {
+            Some("[ ]") => strip_space_or_end(&content[3..]).map(|s| (RichTextAttribute::UnChecked(true), s)),
+            Some("[x]") | Some("[X]") => {
+                strip_space_or_end(&content[3..]).map(|s| (RichTextAttribute::Checked(true), s))
+            }
+            _ => None,
+        }
+        .or(Some((RichTextAttribute::Bullet(true), content)));
+    }
+
+    let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    s[digits..]
+        .strip_prefix(&['.', ')'][..])
+        .and_then(strip_space_or_end)
+        .map(|content| (RichTextAttribute::Ordered(true), content))
+}
+
+/// The markers of the block should be followed by a space or the end of the line.
+fn strip_space_or_end(s: &str) -> Option<&str> {
+    if s.is_empty() {
+        return Some(s);
+    }
+    s.strip_prefix(&[' ', '\t'][..])
+}
+
+struct InlineParser {
+    chars: Vec<char>,
+}
+
+impl InlineParser {
+    fn new(s: &str) -> Self {
+        Self {
+            chars: s.chars().collect(),
+        }
+    }
+
+    fn parse(&self, builder: &mut DeltaLineBuilder, attributes: &RichTextAttributes) {
+        self.parse_range(0, self.chars.len(), builder, attributes);
+    }
+
+    fn parse_range(&self, start: usize, end: usize, builder: &mut DeltaLineBuilder, attributes: &RichTextAttributes) {
+        let mut text = String::new();
+        let mut i = start;
+        while i < end {
+            let c = self.chars[i];
+            let span = match c {
+                '\\' if i + 1 < end && self.chars[i + 1].is_ascii_punctuation() => {
+                    text.push(self.chars[i + 1]);
+                    i += 2;
+                    continue;
+                }
+                '`' => self.parse_code(i, end),
+                '[' => self.parse_link(i, end),
+                '*' | '_' | '~' => self.parse_emphasis(i, end),
+                _ => None,
+            };
+
+            match span {
+                None => {
+                    let run = self.run_len(i, end);
+                    text.extend(&self.chars[i..i + run]);
+                    i += run;
+                }
+                Some(span) => {
+                    builder.push_text(&text, attributes.clone());
+                    text.clear();
+
+                    let mut attributes = attributes.clone();
+                    span.attributes
+                        .into_iter()
+                        .for_each(|attribute| attributes.add(attribute));
+                    if span.is_code {
+                        let code = self.chars[span.content_start..span.content_end]
+                            .iter()
+                            .collect::<String>();
+                        builder.push_text(&code, attributes);
+                    } else {
+                        self.parse_range(span.content_start, span.content_end, builder, &attributes);
+                    }
+                    i = span.end;
+                }
+            }
+        }
+        builder.push_text(&text, attributes.clone());
+    }
+
+    /// The length of the run of the same marker character, for example: "**" or "```". Other
+    /// characters are consumed one by one.
+    fn run_len(&self, start: usize, end: usize) -> usize {
+        let c = self.chars[start];
+        if !matches!(c, '`' | '*' | '_' | '~') {
+            return 1;
+        }
+        self.chars[start..end].iter().take_while(|other| **other == c).count()
+    }
+
+    fn parse_code(&self, start: usize, end: usize) -> Option<InlineSpan> {
+        let run = self.run_len(start, end);
+        let mut i = start + run;
+        while i < end {
+            if self.chars[i] != '`' {
+                i += 1;
+                continue;
+            }
+            let closing_run = self.run_len(i, end);
+            if closing_run == run {
+                return Some(InlineSpan {
+                    attributes: vec![RichTextAttribute::InlineCode(true)],
+                    is_code: true,
+                    content_start: start + run,
+                    content_end: i,
+                    end: i + run,
+                });
+            }
+            i += closing_run;
+        }
+        None
+    }
+
+    fn parse_link(&self, start: usize, end: usize) -> Option<InlineSpan> {
+        let text_end = self.find_closing(start, end, '[', ']')?;
+        if self.chars.get(text_end + 1) != Some(&'(') {
+            return None;
+        }
+        let url_end = self.find_closing(text_end + 1, end, '(', ')')?;
+        let destination = self.chars[text_end + 2..url_end].iter().collect::<String>();
+        // Ignore the title of the link, for example: [AppFlowy](https://appflowy.io "title")
+        let url = destination.split_whitespace().next().unwrap_or("");
+        let url = url.trim_start_matches('<').trim_end_matches('>');
+        if url.is_empty() {
+            return None;
+        }
+
+        Some(InlineSpan {
+            attributes: vec![RichTextAttribute::Link(url)],
+            is_code: false,
+            content_start: start + 1,
+            content_end: text_end,
+            end: url_end + 1,
+        })
+    }
+
+    fn find_closing(&self, start: usize, end: usize, open: char, close: char) -> Option<usize> {
+        let mut depth = 0;
+        let mut i = start;
+        while i < end {
+            match self.chars[i] {
+                '\\' => i += 1,
+                c if c == open => depth += 1,
+                c if c == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+
+    fn parse_emphasis(&self, start: usize, end: usize) -> Option<InlineSpan> {
+        let marker = self.chars[start];
+        let run = self.run_len(start, end);
+        let attributes = match (marker, run) {
+            ('~', 2) => vec![RichTextAttribute::StrikeThrough(true)],
+            ('~', _) => return None,
+            (_, 1) => vec![RichTextAttribute::Italic(true)],
+            (_, 2) => vec![RichTextAttribute::Bold(true)],
+            (_, 3) => vec![RichTextAttribute::Bold(true), RichTextAttribute::Italic(true)],
+            _ => return None,
+        };
+
+        if !self.can_open(start, run, end) {
+            return None;
+        }
+
+        let mut i = start + run;
+        while i < end {
+            let c = self.chars[i];
+            if c == '\\' {
+                i += 2;
+                continue;
+            }
+            if c != marker {
+                i += 1;
+                continue;
+            }
+
+            let closing_run = self.run_len(i, end);
+            if closing_run == run && i > start + run && self.can_close(i, run, end) {
+                return Some(InlineSpan {
+                    attributes,
+                    is_code: false,
+                    content_start: start + run,
+                    content_end: i,
+                    end: i + run,
+                });
+            }
+            i += closing_run;
+        }
+        None
+    }
+
+    /// The opening marker should be followed by a non-whitespace character, and the '_' can't
+    /// open the emphasis inside a word.
+    fn can_open(&self, start: usize, run: usize, end: usize) -> bool {
+        let next = if start + run < end {
+            self.chars.get(start + run)
+        } else {
+            None
+        };
+        let is_followed_by_text = matches!(next, Some(c) if !c.is_whitespace());
+        if self.chars[start] == '_' && start > 0 && self.chars[start - 1].is_alphanumeric() {
+            return false;
+        }
+        is_followed_by_text
+    }
+
+    /// The closing marker should be preceded by a non-whitespace character, and the '_' can't
+    /// close the emphasis inside a word.
+    fn can_close(&self, start: usize, run: usize, end: usize) -> bool {
+        if self.chars[start - 1].is_whitespace() {
+            return false;
+        }
+        if self.chars[start] == '_' && start + run < end && self.chars[start + run].is_alphanumeric() {
+            return false;
+        }
+        true
+    }
+}
+
+struct InlineSpan {
+    attributes: Vec<RichTextAttribute>,
+    is_code: bool,
+    content_start: usize,
+    content_end: usize,
+    end: usize,
+}
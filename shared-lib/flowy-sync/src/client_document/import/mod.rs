@@ -0,0 +1,48 @@
+mod html;
+mod markdown;
+
+pub use html::*;
+pub use markdown::*;
+
+use lib_ot::rich_text::{RichTextAttributes, RichTextDelta};
+
+/// Builds the delta of the imported document line by line. The inline attributes are attached to
+/// the text and the block attributes are attached to the newline that ends the line.
+pub(crate) struct DeltaLineBuilder {
+    delta: RichTextDelta,
+    line_is_empty: bool,
+}
+
+impl DeltaLineBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            delta: RichTextDelta::new(),
+            line_is_empty: true,
+        }
+    }
+
+    pub(crate) fn line_is_empty(&self) -> bool {
+        self.line_is_empty
+    }
+
+    pub(crate) fn push_text(&mut self, s: &str, attributes: RichTextAttributes) {
+        if s.is_empty() {
+            return;
+        }
+        self.delta.insert(s, attributes);
+        self.line_is_empty = false;
+    }
+
+    pub(crate) fn end_line(&mut self, attributes: RichTextAttributes) {
+        self.delta.insert("\n", attributes);
+        self.line_is_empty = true;
+    }
+
+    /// The document should always end with a newline.
+    pub(crate) fn build(mut self) -> RichTextDelta {
+        if !self.line_is_empty || self.delta.is_empty() {
+            self.end_line(RichTextAttributes::default());
+        }
+        self.delta
+    }
+}
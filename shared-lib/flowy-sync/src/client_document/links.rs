@@ -0,0 +1,127 @@
+use lib_ot::{
+    core::{EmbedData, Operation},
+    rich_text::{RichTextAttributeKey, RichTextAttributes, RichTextDelta, RichTextOperation},
+};
+
+/// The link from the document to the view, it's either the text with the `page_link` attribute
+/// or the page mention. The title of the mention is empty, it's displayed with the name of the
+/// view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewLink {
+    pub view_id: String,
+    pub title: String,
+}
+
+/// Returns the views that the document links to, each view is returned once in the order of its
+/// first link.
+pub fn view_links_from_delta(delta: &RichTextDelta) -> Vec<ViewLink> {
+    let mut links: Vec<ViewLink> = vec![];
+    for span in link_spans(delta) {
+        match links.iter_mut().find(|link| link.view_id == span.view_id) {
+            None => links.push(ViewLink {
+                view_id: span.view_id,
+                title: span.title,
+            }),
+            Some(link) => {
+                if link.title.is_empty() {
+                    link.title = span.title;
+                }
+            }
+        }
+    }
+    links
+}
+
+/// Returns the delta that replaces the text of the page links to the view with the title, the
+/// attributes of the links are kept. Returns None if there is nothing to replace.
+pub fn rename_view_links(delta: &RichTextDelta, view_id: &str, title: &str) -> Option<RichTextDelta> {
+    let mut rename_delta = RichTextDelta::default();
+    let mut index = 0;
+    for span in link_spans(delta) {
+        if span.view_id != view_id || span.is_mention || span.title == title {
+            continue;
+        }
+        rename_delta.retain(span.start - index, RichTextAttributes::default());
+        if !title.is_empty() {
+            rename_delta.insert(title, span.attributes);
+        }
+        rename_delta.delete(span.len);
+        index = span.start + span.len;
+    }
+
+    match rename_delta.is_empty() {
+        true => None,
+        false => Some(rename_delta),
+    }
+}
+
+/// Returns true if the delta may add or remove the links of the document.
+pub fn may_change_links(delta: &RichTextDelta) -> bool {
+    delta.ops.iter().any(|op| match op {
+        Operation::Delete(_) => true,
+        Operation::Embed(embed) => matches!(embed.data, EmbedData::PageMention(_)),
+        _ => op.get_attributes().contains_key(&RichTextAttributeKey::PageLink),
+    })
+}
+
+struct LinkSpan {
+    view_id: String,
+    title: String,
+    start: usize,
+    len: usize,
+    attributes: RichTextAttributes,
+    is_mention: bool,
+}
+
+// The consecutive operations that link to the same view are one link, for example: the link
+// that is partially bold.
+fn link_spans(delta: &RichTextDelta) -> Vec<LinkSpan> {
+    let mut spans: Vec<LinkSpan> = vec![];
+    let mut index = 0;
+    for op in &delta.ops {
+        match op {
+            Operation::Embed(embed) => {
+                if let EmbedData::PageMention(view_id) = &embed.data {
+                    spans.push(LinkSpan {
+                        view_id: view_id.clone(),
+                        title: "".to_owned(),
+                        start: index,
+                        len: op.len(),
+                        attributes: RichTextAttributes::default(),
+                        is_mention: true,
+                    });
+                }
+            }
+            Operation::Insert(insert) => {
+                if let Some(view_id) = page_link_of(op) {
+                    match spans.last_mut() {
+                        Some(span) if !span.is_mention && span.view_id == view_id && span.start + span.len == index => {
+                            span.title.push_str(&insert.s);
+                            span.len += op.len();
+                        }
+                        _ => spans.push(LinkSpan {
+                            view_id,
+                            title: insert.s.to_string(),
+                            start: index,
+                            len: op.len(),
+                            attributes: op.get_attributes(),
+                            is_mention: false,
+                        }),
+                    }
+                }
+            }
+            _ => {}
+        }
+        index += op.len();
+    }
+    spans
+}
+
+fn page_link_of(op: &RichTextOperation) -> Option<String> {
+    let attributes = op.get_attributes();
+    let view_id = attributes.get(&RichTextAttributeKey::PageLink)?.0.clone()?;
+    match view_id.is_empty() {
+        true => None,
+        false => Some(view_id),
+    }
+}
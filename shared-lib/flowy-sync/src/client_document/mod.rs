@@ -4,9 +4,16 @@ pub use document_pad::*;
 pub(crate) use extensions::*;
 pub use view::*;
 
+pub mod anchor;
 mod data;
 pub mod default;
 mod document_pad;
+pub mod export;
 mod extensions;
+pub mod find;
 pub mod history;
+pub mod import;
+pub mod links;
+pub mod outline;
+pub mod selection;
 mod view;
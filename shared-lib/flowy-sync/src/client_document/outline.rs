@@ -0,0 +1,227 @@
+use crate::client_document::export::{attribute_value, is_enabled};
+use lib_ot::{
+    core::{count_utf16_code_units, Interval, Operation},
+    rich_text::{RichTextAttributeKey, RichTextAttributes, RichTextDelta, RichTextOperation, RichTextRope},
+};
+use std::collections::BTreeMap;
+
+/// The average reading speed that is used to estimate the reading time.
+const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineHeader {
+    pub level: usize,
+    pub text: String,
+    /// The utf16 offset of the start of the header.
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BlockType {
+    Paragraph,
+    Heading,
+    BulletedList,
+    NumberedList,
+    TodoList,
+    CodeBlock,
+    Quote,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentStats {
+    pub words: usize,
+    /// The number of the characters, the newlines and the embeds are not counted.
+    pub characters: usize,
+    /// The number of the blocks of each type, the empty lines are not counted.
+    pub blocks: BTreeMap<BlockType, usize>,
+}
+
+impl DocumentStats {
+    pub fn reading_time_in_secs(&self) -> usize {
+        (self.words * 60 + WORDS_PER_MINUTE - 1) / WORDS_PER_MINUTE
+    }
+}
+
+/// The summary of each line of the document that the outline and the stats are read from. It's
+/// updated incrementally, only the lines that are touched by the change are parsed again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentOutline {
+    lines: Vec<LineSummary>,
+}
+
+impl DocumentOutline {
+    pub fn from_delta(delta: &RichTextDelta) -> Self {
+        Self {
+            lines: summarize_lines(delta.ops.iter().cloned()),
+        }
+    }
+
+    /// Updates the lines that are touched by the `changeset`, the `document` is the document after
+    /// the `changeset` was applied.
+    pub fn apply(&mut self, changeset: &RichTextDelta, document: &RichTextRope) {
+        let (min, max) = match touched_range(changeset) {
+            None => return,
+            Some(range) => range,
+        };
+
+        let old_len: usize = self.lines.iter().map(|line| line.len).sum();
+        let (first, region_start) = self.line_at(min);
+        let (last, last_start) = self.line_at(max);
+        let (region_end, end_index) = match self.lines.get(last) {
+            None => (old_len, self.lines.len()),
+            Some(line) => (last_start + line.len, last + 1),
+        };
+
+        // Nothing after the touched lines was changed, so they are shifted by the change of the
+        // length of the document.
+        let new_region_end = region_end + document.len() - old_len;
+        let ops = document.slice(Interval::new(region_start, new_region_end)).ops;
+        let new_lines = summarize_lines(ops);
+        self.lines.splice(first..end_index, new_lines);
+    }
+
+    pub fn headers(&self) -> Vec<OutlineHeader> {
+        let mut headers = vec![];
+        let mut offset = 0;
+        for line in &self.lines {
+            if let Some(level) = line.header {
+                headers.push(OutlineHeader {
+                    level,
+                    text: line.text.clone(),
+                    offset,
+                });
+            }
+            offset += line.len;
+        }
+        headers
+    }
+
+    pub fn stats(&self) -> DocumentStats {
+        let mut stats = DocumentStats::default();
+        for line in &self.lines {
+            stats.words += line.words;
+            stats.characters += line.characters;
+            if !line.is_empty {
+                *stats.blocks.entry(line.block).or_insert(0) += 1;
+            }
+        }
+        stats
+    }
+
+    // Returns the index and the start of the line that contains the offset. Returns the number of
+    // the lines and the length of the document if the offset is at the end of the document.
+    fn line_at(&self, offset: usize) -> (usize, usize) {
+        let mut start = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            if offset < start + line.len {
+                return (index, start);
+            }
+            start += line.len;
+        }
+        (self.lines.len(), start)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LineSummary {
+    /// The utf16 length of the line, including the newline.
+    len: usize,
+    block: BlockType,
+    header: Option<usize>,
+    /// The text of the line if it's a header.
+    text: String,
+    words: usize,
+    characters: usize,
+    is_empty: bool,
+}
+
+// Returns the interval of the document, before the change, that the changeset touched. The
+// delete of the newline touches the next line too, since the two lines are merged.
+fn touched_range(changeset: &RichTextDelta) -> Option<(usize, usize)> {
+    let mut range: Option<(usize, usize)> = None;
+    let mut touch = |start: usize, end: usize| {
+        range = Some(match range {
+            None => (start, end),
+            Some((min, max)) => (min.min(start), max.max(end)),
+        });
+    };
+
+    let mut index = 0;
+    for op in &changeset.ops {
+        match op {
+            Operation::Retain(retain) => {
+                if !retain.is_plain() {
+                    touch(index, index + retain.n);
+                }
+                index += retain.n;
+            }
+            Operation::Insert(_) | Operation::Embed(_) => touch(index, index),
+            Operation::Delete(n) => {
+                touch(index, index + n);
+                index += n;
+            }
+        }
+    }
+    range
+}
+
+fn summarize_lines<I: IntoIterator<Item = RichTextOperation>>(ops: I) -> Vec<LineSummary> {
+    let mut lines = vec![];
+    let mut text = String::new();
+    let mut len = 0;
+    for op in ops {
+        match &op {
+            Operation::Embed(_) => len += 1,
+            Operation::Insert(_) => {
+                let mut s = op.get_data();
+                while let Some(index) = s.find('\n') {
+                    text.push_str(&s[..index]);
+                    len += count_utf16_code_units(&s[..index]) + 1;
+                    lines.push(summarize_line(std::mem::take(&mut text), len, &op.get_attributes()));
+                    len = 0;
+                    s = &s[index + 1..];
+                }
+                text.push_str(s);
+                len += count_utf16_code_units(s);
+            }
+            _ => {}
+        }
+    }
+
+    // The document should always end with a newline, keep the remaining text anyway.
+    if len > 0 {
+        lines.push(summarize_line(text, len, &RichTextAttributes::default()));
+    }
+    lines
+}
+
+fn summarize_line(text: String, len: usize, attributes: &RichTextAttributes) -> LineSummary {
+    let header = attribute_value(attributes, &RichTextAttributeKey::Header)
+        .and_then(|level| level.parse::<usize>().ok())
+        .filter(|level| *level > 0);
+    let block = match attribute_value(attributes, &RichTextAttributeKey::List) {
+        _ if header.is_some() => BlockType::Heading,
+        Some("bullet") => BlockType::BulletedList,
+        Some("ordered") => BlockType::NumberedList,
+        Some("checked") | Some("unchecked") => BlockType::TodoList,
+        _ if is_enabled(attributes, &RichTextAttributeKey::CodeBlock) => BlockType::CodeBlock,
+        _ if is_enabled(attributes, &RichTextAttributeKey::BlockQuote) => BlockType::Quote,
+        _ => BlockType::Paragraph,
+    };
+    let words = text
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count();
+    let characters = text.chars().count();
+    // The line that only has the newline is empty, the line of the embed is not.
+    let is_empty = len <= 1 && block == BlockType::Paragraph;
+    LineSummary {
+        len,
+        block,
+        header,
+        text: if header.is_some() { text } else { String::new() },
+        words,
+        characters,
+        is_empty,
+    }
+}
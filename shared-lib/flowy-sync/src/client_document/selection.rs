@@ -0,0 +1,104 @@
+use lib_ot::{
+    core::{Bias, Interval},
+    rich_text::RichTextDelta,
+};
+use std::collections::HashMap;
+
+/// The selections that the users reported, keyed by the id of the user. The selections are
+/// transformed against each change of the document, so they keep covering the same text. The
+/// text inserted at the caret pushes the caret forward.
+///
+/// The selection that a remote user made in a revision that isn't received yet is held with the
+/// id of the revision until the revision is received.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentSelections {
+    selections: HashMap<String, Interval>,
+    held_selections: HashMap<String, (i64, Interval)>,
+}
+
+impl DocumentSelections {
+    pub fn new() -> Self {
+        DocumentSelections::default()
+    }
+
+    pub fn insert(&mut self, user_id: &str, interval: Interval) {
+        self.held_selections.remove(user_id);
+        self.selections.insert(user_id.to_owned(), interval);
+    }
+
+    /// Inserts the selection that was made before the deltas were applied, it's transformed
+    /// against them in order.
+    pub fn insert_before(&mut self, user_id: &str, interval: Interval, deltas: &[RichTextDelta]) {
+        let interval = deltas.iter().fold(interval, |interval, delta| {
+            delta.transform_interval(interval, Bias::Right, Bias::Right)
+        });
+        self.insert(user_id, interval);
+    }
+
+    pub fn remove(&mut self, user_id: &str) -> Option<Interval> {
+        self.held_selections.remove(user_id);
+        self.selections.remove(user_id)
+    }
+
+    /// Holds the selection until the revision is received, the current selection of the user is
+    /// kept until then.
+    pub fn hold(&mut self, user_id: &str, rev_id: i64, interval: Interval) {
+        self.held_selections.insert(user_id.to_owned(), (rev_id, interval));
+    }
+
+    /// Removes the held selections that were made in the revisions up to `rev_id` and returns
+    /// them with their revisions, ordered by the id of the user.
+    pub fn take_held(&mut self, rev_id: i64) -> Vec<(String, i64, Interval)> {
+        let user_ids = self
+            .held_selections
+            .iter()
+            .filter(|(_, (held_rev_id, _))| *held_rev_id <= rev_id)
+            .map(|(user_id, _)| user_id.clone())
+            .collect::<Vec<String>>();
+        let mut held_selections = user_ids
+            .into_iter()
+            .flat_map(|user_id| {
+                let (held_rev_id, interval) = self.held_selections.remove(&user_id)?;
+                Some((user_id, held_rev_id, interval))
+            })
+            .collect::<Vec<(String, i64, Interval)>>();
+        held_selections.sort_by(|a, b| a.0.cmp(&b.0));
+        held_selections
+    }
+
+    pub fn get(&self, user_id: &str) -> Option<Interval> {
+        self.selections.get(user_id).cloned()
+    }
+
+    /// Returns the selections ordered by the id of the user.
+    pub fn all(&self) -> Vec<(String, Interval)> {
+        let mut selections = self
+            .selections
+            .iter()
+            .map(|(user_id, interval)| (user_id.clone(), *interval))
+            .collect::<Vec<(String, Interval)>>();
+        selections.sort_by(|a, b| a.0.cmp(&b.0));
+        selections
+    }
+
+    pub fn transform(&mut self, delta: &RichTextDelta) {
+        if self.selections.is_empty() {
+            return;
+        }
+
+        let intervals = self.selections.values().cloned().collect::<Vec<Interval>>();
+        let new_intervals = delta.transform_intervals(&intervals, Bias::Right, Bias::Right);
+        for (interval, new_interval) in self.selections.values_mut().zip(new_intervals) {
+            *interval = new_interval;
+        }
+    }
+
+    /// Moves the selections into the document if the document was replaced without the delta.
+    pub fn clamp(&mut self, document_len: usize) {
+        for interval in self.selections.values_mut() {
+            if interval.end > document_len {
+                *interval = Interval::new(interval.start.min(document_len), document_len);
+            }
+        }
+    }
+}
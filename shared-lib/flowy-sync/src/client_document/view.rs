@@ -1,6 +1,6 @@
 use crate::client_document::*;
 use lib_ot::{
-    core::{trim, Interval},
+    core::{trim, EmbedData, Interval},
     errors::{ErrorBuilder, OTError, OTErrorCode},
     rich_text::{RichTextAttribute, RichTextDelta},
 };
@@ -44,6 +44,28 @@ impl ViewExtensions {
         }
     }
 
+    pub(crate) fn insert_embed(
+        &self,
+        delta: &RichTextDelta,
+        data: &EmbedData,
+        interval: Interval,
+    ) -> Result<RichTextDelta, OTError> {
+        let mut new_delta = None;
+        for ext in &self.insert_exts {
+            if let Some(mut delta) = ext.apply_embed(delta, interval.size(), data, interval.start) {
+                trim(&mut delta);
+                tracing::debug!("[{} extension]: process: {}", ext.ext_name(), delta);
+                new_delta = Some(delta);
+                break;
+            }
+        }
+
+        match new_delta {
+            None => Err(ErrorBuilder::new(OTErrorCode::ApplyInsertFail).build()),
+            Some(new_delta) => Ok(new_delta),
+        }
+    }
+
     pub(crate) fn delete(&self, delta: &RichTextDelta, interval: Interval) -> Result<RichTextDelta, OTError> {
         let mut new_delta = None;
         for ext in &self.delete_exts {
@@ -88,10 +110,13 @@ fn construct_insert_exts() -> Vec<InsertExtension> {
     vec![
         Box::new(InsertEmbedsExt {}),
         Box::new(ForceNewlineForInsertsAroundEmbedExt {}),
+        Box::new(PreserveTableCellOnInsert {}),
+        Box::new(MarkdownBlockShortcutExt {}),
         Box::new(AutoExitBlock {}),
         Box::new(PreserveBlockFormatOnInsert {}),
         Box::new(PreserveLineFormatOnSplit {}),
         Box::new(ResetLineFormatOnNewLine {}),
+        Box::new(MarkdownInlineShortcutExt {}),
         Box::new(AutoFormatExt {}),
         Box::new(PreserveInlineFormat {}),
         Box::new(DefaultInsertAttribute {}),
@@ -107,5 +132,10 @@ fn construct_format_exts() -> Vec<FormatExtension> {
 }
 
 fn construct_delete_exts() -> Vec<DeleteExtension> {
-    vec![Box::new(PreserveLineFormatOnMerge {}), Box::new(DefaultDelete {})]
+    vec![
+        Box::new(PreserveTableCellOnDelete {}),
+        Box::new(PreserveEmbedLineOnDelete {}),
+        Box::new(PreserveLineFormatOnMerge {}),
+        Box::new(DefaultDelete {}),
+    ]
 }
@@ -1,17 +1,15 @@
 use crate::entities::folder::FolderDelta;
-use crate::util::make_delta_from_revisions;
+use crate::util::{make_delta_from_revisions, parse_operation_log};
 use crate::{
-    client_folder::{default_folder_delta, FolderPad},
+    client_folder::{default_folder_delta, initial_folder_delta, FolderOperation, FolderPad, FolderSnapshot},
     entities::revision::Revision,
-    errors::{CollaborateError, CollaborateResult},
+    errors::CollaborateResult,
 };
 
 use flowy_folder_data_model::revision::{TrashRevision, WorkspaceRevision};
-use lib_ot::core::{PlainTextAttributes, PlainTextDelta, PlainTextDeltaBuilder};
-use serde::{Deserialize, Serialize};
+use lib_ot::core::PlainTextAttributes;
 use std::sync::Arc;
 
-#[derive(Serialize, Deserialize)]
 pub(crate) struct FolderPadBuilder {
     workspaces: Vec<Arc<WorkspaceRevision>>,
     trash: Vec<Arc<TrashRevision>>,
@@ -35,17 +33,13 @@ impl FolderPadBuilder {
         self
     }
 
-    pub(crate) fn build_with_delta(self, mut delta: PlainTextDelta) -> CollaborateResult<FolderPad> {
-        if delta.is_empty() {
-            delta = default_folder_delta();
-        }
-
+    pub(crate) fn build_with_delta(self, delta: FolderDelta) -> CollaborateResult<FolderPad> {
         // TODO: Reconvert from history if delta.to_str() failed.
-        let folder_json = delta.to_str()?;
-        let mut folder: FolderPad = serde_json::from_str(&folder_json).map_err(|e| {
-            tracing::error!("Deserialize folder from json failed: {}", folder_json);
-            return CollaborateError::internal().context(format!("Deserialize delta to folder failed: {}", e));
-        })?;
+        let folder_text = delta.to_str()?;
+        let mut folder = FolderPad::default();
+        for entry in parse_operation_log::<FolderOperation, FolderSnapshot>(&folder_text)? {
+            folder.apply_log_entry(entry);
+        }
         folder.delta = delta;
         Ok(folder)
     }
@@ -56,12 +50,12 @@ impl FolderPadBuilder {
     }
 
     pub(crate) fn build(self) -> CollaborateResult<FolderPad> {
-        let json = serde_json::to_string(&self)
-            .map_err(|e| CollaborateError::internal().context(format!("Serialize to folder json str failed: {}", e)))?;
-        Ok(FolderPad {
+        let mut folder = FolderPad {
             workspaces: self.workspaces,
             trash: self.trash,
-            delta: PlainTextDeltaBuilder::new().insert(&json).build(),
-        })
+            delta: default_folder_delta(),
+        };
+        folder.delta = initial_folder_delta(&folder)?;
+        Ok(folder)
     }
 }
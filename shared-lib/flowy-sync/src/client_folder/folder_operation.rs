@@ -0,0 +1,419 @@
+use crate::util::{move_element, set_if_changed};
+use flowy_folder_data_model::revision::{AppRevision, TrashRevision, ViewRevision, WorkspaceRevision};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// An operation on the folder. The text of the folder's delta is a log of these operations, one
+/// JSON-encoded operation per line, and the folder is rebuilt by applying the log in order.
+///
+/// Every change appends its operation to the end of the log, so each replica ends up with the
+/// same log after the transform. The operations address the workspaces, apps and views by id and
+/// an operation on a record that doesn't exist anymore is ignored, so the log can be applied in
+/// any order that the transform produces.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FolderOperation {
+    CreateWorkspace {
+        workspace: WorkspaceRevision,
+    },
+    UpdateWorkspace {
+        workspace_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        desc: Option<String>,
+    },
+    DeleteWorkspace {
+        workspace_id: String,
+    },
+    CreateApp {
+        app: AppRevision,
+    },
+    UpdateApp {
+        app_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        desc: Option<String>,
+    },
+    DeleteApp {
+        app_id: String,
+    },
+    /// Moves the app from the index `from` to the index `to` of its workspace. The index `to` is
+    /// clamped to the last position if the workspace has fewer apps when the operation is applied.
+    ///
+    /// The move is skipped if the app isn't at `from` anymore, which means that a concurrent
+    /// operation reordered the apps, so the first of the conflicting moves in the log wins. The
+    /// moves that were logged without `from` are always applied.
+    MoveApp {
+        app_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<usize>,
+        to: usize,
+    },
+    CreateView {
+        view: ViewRevision,
+    },
+    UpdateView {
+        view_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        desc: Option<String>,
+        modified_time: i64,
+    },
+    DeleteView {
+        view_id: String,
+    },
+    /// Moves the view from the index `from` to the index `to` of its app, the conflicting moves
+    /// are resolved like [FolderOperation::MoveApp].
+    MoveView {
+        view_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<usize>,
+        to: usize,
+    },
+    CreateTrash {
+        trash: Vec<TrashRevision>,
+    },
+    /// Deletes the trash with the given ids, or all the trash if `trash_ids` is None.
+    DeleteTrash {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trash_ids: Option<Vec<String>>,
+    },
+}
+
+impl FolderOperation {
+    /// Applies the operation to the folder. Returns false if the operation doesn't change
+    /// anything, e.g. the record it addresses was deleted.
+    pub(crate) fn apply(
+        &self,
+        workspaces: &mut Vec<Arc<WorkspaceRevision>>,
+        trash: &mut Vec<Arc<TrashRevision>>,
+    ) -> bool {
+        match self {
+            FolderOperation::CreateWorkspace { workspace } => {
+                if workspaces.iter().any(|w| w.as_ref() == workspace) {
+                    tracing::warn!("[FolderOperation]: Duplicate workspace");
+                    return false;
+                }
+                workspaces.push(Arc::new(workspace.clone()));
+                true
+            }
+            FolderOperation::UpdateWorkspace {
+                workspace_id,
+                name,
+                desc,
+            } => match workspaces.iter_mut().find(|w| &w.id == workspace_id) {
+                None => false,
+                Some(workspace) => {
+                    let workspace = Arc::make_mut(workspace);
+                    let changed = set_if_changed(&mut workspace.name, name);
+                    set_if_changed(&mut workspace.desc, desc) || changed
+                }
+            },
+            FolderOperation::DeleteWorkspace { workspace_id } => {
+                let len = workspaces.len();
+                workspaces.retain(|w| &w.id != workspace_id);
+                workspaces.len() != len
+            }
+            FolderOperation::CreateApp { app } => match workspaces.iter_mut().find(|w| w.id == app.workspace_id) {
+                None => false,
+                Some(workspace) => {
+                    if workspace.apps.contains(app) {
+                        tracing::warn!("[FolderOperation]: Duplicate app");
+                        return false;
+                    }
+                    Arc::make_mut(workspace).apps.push(app.clone());
+                    true
+                }
+            },
+            FolderOperation::UpdateApp { app_id, name, desc } => with_app(workspaces, app_id, |app| {
+                let changed = set_if_changed(&mut app.name, name);
+                set_if_changed(&mut app.desc, desc) || changed
+            }),
+            FolderOperation::DeleteApp { app_id } => with_apps(workspaces, app_id, |apps| {
+                apps.retain(|app| &app.id != app_id);
+                true
+            }),
+            FolderOperation::MoveApp { app_id, from, to } => with_apps(workspaces, app_id, |apps| {
+                let index = apps.iter().position(|app| &app.id == app_id);
+                is_at_index(index, from) && move_element(apps, |app| &app.id == app_id, *to)
+            }),
+            FolderOperation::CreateView { view } => with_app(workspaces, &view.belong_to_id, |app| {
+                if app.belongings.contains(view) {
+                    tracing::warn!("[FolderOperation]: Duplicate view");
+                    return false;
+                }
+                app.belongings.push(view.clone());
+                true
+            }),
+            FolderOperation::UpdateView {
+                view_id,
+                name,
+                desc,
+                modified_time,
+            } => with_views(workspaces, view_id, |views| {
+                // It's ok to unwrap because the views contain the view.
+                let view = views.iter_mut().find(|view| &view.id == view_id).unwrap();
+                let mut changed = set_if_changed(&mut view.name, name);
+                changed = set_if_changed(&mut view.desc, desc) || changed;
+                set_if_changed(&mut view.modified_time, &Some(*modified_time)) || changed
+            }),
+            FolderOperation::DeleteView { view_id } => with_views(workspaces, view_id, |views| {
+                views.retain(|view| &view.id != view_id);
+                true
+            }),
+            FolderOperation::MoveView { view_id, from, to } => with_views(workspaces, view_id, |views| {
+                let index = views.iter().position(|view| &view.id == view_id);
+                is_at_index(index, from) && move_element(views, |view| &view.id == view_id, *to)
+            }),
+            FolderOperation::CreateTrash { trash: new_trash } => {
+                if new_trash.is_empty() {
+                    return false;
+                }
+                trash.extend(new_trash.iter().cloned().map(Arc::new));
+                true
+            }
+            FolderOperation::DeleteTrash { trash_ids } => {
+                let len = trash.len();
+                match trash_ids {
+                    None => trash.clear(),
+                    Some(trash_ids) => trash.retain(|t| !trash_ids.contains(&t.id)),
+                }
+                trash.len() != len
+            }
+        }
+    }
+}
+
+/// The operations that create the folder from scratch.
+pub(crate) fn make_folder_operations(
+    workspaces: &[Arc<WorkspaceRevision>],
+    trash: &[Arc<TrashRevision>],
+) -> Vec<FolderOperation> {
+    let mut operations = workspaces
+        .iter()
+        .map(|workspace| FolderOperation::CreateWorkspace {
+            workspace: workspace.as_ref().clone(),
+        })
+        .collect::<Vec<_>>();
+
+    if !trash.is_empty() {
+        operations.push(FolderOperation::CreateTrash {
+            trash: trash.iter().map(|t| t.as_ref().clone()).collect(),
+        });
+    }
+    operations
+}
+
+/// The folder in the JSON format that was used before the operations, which is the content of
+/// the existing folder revisions. The compaction of the log replaces the operations with it.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct FolderSnapshot {
+    pub(crate) workspaces: Vec<Arc<WorkspaceRevision>>,
+    pub(crate) trash: Vec<Arc<TrashRevision>>,
+}
+
+/// Returns true if the element is at the index `from` of the move, or the move has no `from`.
+fn is_at_index(index: Option<usize>, from: &Option<usize>) -> bool {
+    match from {
+        None => true,
+        Some(from) => index == Some(*from),
+    }
+}
+
+fn with_app<F>(workspaces: &mut [Arc<WorkspaceRevision>], app_id: &str, f: F) -> bool
+where
+    F: FnOnce(&mut AppRevision) -> bool,
+{
+    with_apps(workspaces, app_id, |apps| {
+        // It's ok to unwrap because the apps contain the app.
+        f(apps.iter_mut().find(|app| app.id == app_id).unwrap())
+    })
+}
+
+/// Calls `f` with the apps of the workspace that contains the app.
+fn with_apps<F>(workspaces: &mut [Arc<WorkspaceRevision>], app_id: &str, f: F) -> bool
+where
+    F: FnOnce(&mut Vec<AppRevision>) -> bool,
+{
+    match workspaces
+        .iter_mut()
+        .find(|workspace| workspace.apps.iter().any(|app| app.id == app_id))
+    {
+        None => false,
+        Some(workspace) => f(&mut Arc::make_mut(workspace).apps),
+    }
+}
+
+/// Calls `f` with the views of the app that contains the view.
+fn with_views<F>(workspaces: &mut [Arc<WorkspaceRevision>], view_id: &str, f: F) -> bool
+where
+    F: FnOnce(&mut Vec<ViewRevision>) -> bool,
+{
+    let app_id = workspaces
+        .iter()
+        .flat_map(|workspace| workspace.apps.iter())
+        .find(|app| app.belongings.iter().any(|view| view.id == view_id))
+        .map(|app| app.id.clone());
+
+    match app_id {
+        None => false,
+        Some(app_id) => with_app(workspaces, &app_id, |app| f(&mut app.belongings)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client_folder::{FolderOperation, FolderPad};
+    use crate::util::make_append_delta;
+    use flowy_folder_data_model::revision::{AppRevision, ViewRevision, WorkspaceRevision};
+    use lib_ot::core::{OperationTransformable, PlainTextDeltaBuilder};
+
+    fn test_folder() -> FolderPad {
+        let mut workspace = WorkspaceRevision::default();
+        workspace.id = "w1".to_owned();
+        let mut app = AppRevision::default();
+        app.id = "a1".to_owned();
+        app.workspace_id = workspace.id.clone();
+        app.belongings = ["v1", "v2", "v3"]
+            .iter()
+            .map(|id| {
+                let mut view = ViewRevision::default();
+                view.id = id.to_string();
+                view.belong_to_id = app.id.clone();
+                view
+            })
+            .collect();
+        workspace.apps = vec![app];
+        FolderPad::new(vec![workspace], vec![]).unwrap()
+    }
+
+    fn view_ids(folder: &FolderPad) -> Vec<String> {
+        folder
+            .read_views("a1")
+            .unwrap()
+            .into_iter()
+            .map(|view| view.id)
+            .collect()
+    }
+
+    fn log_lines(folder: &FolderPad) -> usize {
+        folder.delta().to_str().unwrap().lines().count()
+    }
+
+    #[test]
+    fn folder_concurrent_changes_converge() {
+        let mut folder_a = test_folder();
+        let mut folder_b = test_folder();
+
+        let delta_a = folder_a.move_view("v1", 0, 2).unwrap().unwrap().delta;
+        let delta_a_2 = folder_a
+            .update_view("v2", Some("renamed by a".to_owned()), None, 1)
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_a = delta_a.compose(&delta_a_2).unwrap();
+
+        let delta_b = folder_b.move_view("v3", 2, 0).unwrap().unwrap().delta;
+        let delta_b_2 = folder_b.delete_view("v2").unwrap().unwrap().delta;
+        let delta_b = delta_b.compose(&delta_b_2).unwrap();
+
+        let (a_prime, b_prime) = delta_a.transform(&delta_b).unwrap();
+        folder_a.compose_remote_delta(b_prime).unwrap();
+        folder_b.compose_remote_delta(a_prime).unwrap();
+
+        assert_eq!(folder_a, folder_b);
+        assert_eq!(folder_a.to_json().unwrap(), folder_b.to_json().unwrap());
+        assert_eq!(view_ids(&folder_a), vec!["v3".to_owned(), "v1".to_owned()]);
+    }
+
+    #[test]
+    fn folder_concurrent_moves_converge() {
+        let mut folder_a = test_folder();
+        let mut folder_b = test_folder();
+
+        let delta_a = folder_a.move_view("v1", 0, 2).unwrap().unwrap().delta;
+        let delta_b = folder_b.move_view("v1", 0, 1).unwrap().unwrap().delta;
+        assert!(folder_b.move_view("v1", 0, 2).is_err());
+
+        let (a_prime, b_prime) = delta_a.transform(&delta_b).unwrap();
+        folder_a.compose_remote_delta(b_prime).unwrap();
+        folder_b.compose_remote_delta(a_prime).unwrap();
+
+        // The move of b conflicts with the move of a, which comes first in the log.
+        assert_eq!(folder_a, folder_b);
+        assert_eq!(
+            view_ids(&folder_a),
+            vec!["v2".to_owned(), "v3".to_owned(), "v1".to_owned()]
+        );
+    }
+
+    #[test]
+    fn folder_compact_log() {
+        let mut folder = test_folder();
+        let mut folder_b = test_folder();
+        for i in 0..499 {
+            let name = Some(format!("name {}", i));
+            let delta = folder.update_view("v1", name, None, i).unwrap().unwrap().delta;
+            folder_b.compose_remote_delta(delta).unwrap();
+        }
+        assert_eq!(log_lines(&folder), 500);
+
+        let delta_a = folder
+            .update_view("v2", Some("compacted".to_owned()), None, 0)
+            .unwrap()
+            .unwrap()
+            .delta;
+        assert_eq!(log_lines(&folder), 2);
+        assert_eq!(FolderPad::from_delta(folder.delta().clone()).unwrap(), folder);
+
+        // Both replicas compact the log concurrently, the repeated snapshot is skipped.
+        let delta_b = folder_b.delete_view("v3").unwrap().unwrap().delta;
+        let (a_prime, b_prime) = delta_a.transform(&delta_b).unwrap();
+        folder.compose_remote_delta(b_prime).unwrap();
+        folder_b.compose_remote_delta(a_prime).unwrap();
+        assert_eq!(folder, folder_b);
+        assert_eq!(log_lines(&folder), 4);
+        assert_eq!(view_ids(&folder), vec!["v1".to_owned(), "v2".to_owned()]);
+        assert_eq!(folder.read_view("v1").unwrap().name, "name 498");
+        assert_eq!(folder.read_view("v2").unwrap().name, "compacted");
+    }
+
+    #[test]
+    fn folder_operation_on_deleted_record_is_ignored() {
+        let mut folder = test_folder();
+        let mut remote = test_folder();
+        let delta = remote.delete_app("a1").unwrap().unwrap().delta;
+        folder.compose_remote_delta(delta).unwrap();
+
+        let operation = FolderOperation::UpdateView {
+            view_id: "v1".to_owned(),
+            name: Some("rename".to_owned()),
+            desc: None,
+            modified_time: 1,
+        };
+        let delta = make_append_delta(folder.delta(), &[operation]).unwrap();
+        let json = folder.to_json().unwrap();
+        folder.compose_remote_delta(delta).unwrap();
+        assert_eq!(folder.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn folder_migrate_from_json_delta() {
+        let json = r#"{"workspaces":[{"id":"w1","name":"my workspace","desc":"","apps":[],"modified_time":0,"create_time":0}],"trash":[]}"#;
+        let mut folder = FolderPad::from_delta(PlainTextDeltaBuilder::new().insert(json).build()).unwrap();
+        assert_eq!(folder.read_workspaces(None).unwrap()[0].name, "my workspace");
+
+        let delta = folder
+            .update_workspace("w1", Some("renamed".to_owned()), None)
+            .unwrap()
+            .unwrap()
+            .delta;
+        let mut migrated = FolderPad::from_delta(PlainTextDeltaBuilder::new().insert(json).build()).unwrap();
+        migrated.compose_remote_delta(delta).unwrap();
+        assert_eq!(migrated, folder);
+        assert_eq!(migrated.read_workspaces(None).unwrap()[0].name, "renamed");
+    }
+}
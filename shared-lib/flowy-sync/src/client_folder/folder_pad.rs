@@ -1,7 +1,9 @@
 use crate::errors::internal_error;
-use crate::util::cal_diff;
+use crate::util::{
+    appended_text, count_log_lines, make_append_delta, make_compaction_delta, parse_operation_log, OperationLogEntry,
+};
 use crate::{
-    client_folder::builder::FolderPadBuilder,
+    client_folder::{builder::FolderPadBuilder, make_folder_operations, FolderOperation, FolderSnapshot},
     entities::{
         folder::FolderDelta,
         revision::{md5, Revision},
@@ -9,7 +11,6 @@ use crate::{
     errors::{CollaborateError, CollaborateResult},
 };
 use flowy_folder_data_model::revision::{AppRevision, TrashRevision, ViewRevision, WorkspaceRevision};
-use lib_infra::util::move_vec_element;
 use lib_ot::core::*;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -53,7 +54,25 @@ impl FolderPad {
 
     pub fn compose_remote_delta(&mut self, delta: FolderDelta) -> CollaborateResult<String> {
         let composed_delta = self.delta.compose(&delta)?;
-        self.reset_folder(composed_delta)
+        let entries = match appended_text(&self.delta, &delta) {
+            None => vec![],
+            Some(text) => parse_operation_log::<FolderOperation, FolderSnapshot>(&text)?,
+        };
+        // The remote operations that follow all the operations of the folder are applied without
+        // replaying the log. The log is replayed if the remote operations are inserted before the
+        // local ones or the remote log was compacted.
+        if entries.is_empty()
+            || entries
+                .iter()
+                .any(|entry| matches!(entry, OperationLogEntry::Snapshot(_)))
+        {
+            return self.reset_folder(composed_delta);
+        }
+        for entry in entries {
+            self.apply_log_entry(entry);
+        }
+        self.delta = composed_delta;
+        Ok(self.md5())
     }
 
     pub fn is_empty(&self) -> bool {
@@ -62,15 +81,8 @@ impl FolderPad {
 
     #[tracing::instrument(level = "trace", skip(self, workspace_rev), fields(workspace_name=%workspace_rev.name), err)]
     pub fn create_workspace(&mut self, workspace_rev: WorkspaceRevision) -> CollaborateResult<Option<FolderChange>> {
-        let workspace = Arc::new(workspace_rev);
-        if self.workspaces.contains(&workspace) {
-            tracing::warn!("[RootFolder]: Duplicate workspace");
-            return Ok(None);
-        }
-
-        self.modify_workspaces(move |workspaces| {
-            workspaces.push(workspace);
-            Ok(Some(()))
+        self.apply_operation(FolderOperation::CreateWorkspace {
+            workspace: workspace_rev,
         })
     }
 
@@ -80,15 +92,10 @@ impl FolderPad {
         name: Option<String>,
         desc: Option<String>,
     ) -> CollaborateResult<Option<FolderChange>> {
-        self.with_workspace(workspace_id, |workspace| {
-            if let Some(name) = name {
-                workspace.name = name;
-            }
-
-            if let Some(desc) = desc {
-                workspace.desc = desc;
-            }
-            Ok(Some(()))
+        self.apply_operation(FolderOperation::UpdateWorkspace {
+            workspace_id: workspace_id.to_owned(),
+            name,
+            desc,
         })
     }
 
@@ -115,23 +122,14 @@ impl FolderPad {
 
     #[tracing::instrument(level = "trace", skip(self), err)]
     pub fn delete_workspace(&mut self, workspace_id: &str) -> CollaborateResult<Option<FolderChange>> {
-        self.modify_workspaces(|workspaces| {
-            workspaces.retain(|w| w.id != workspace_id);
-            Ok(Some(()))
+        self.apply_operation(FolderOperation::DeleteWorkspace {
+            workspace_id: workspace_id.to_owned(),
         })
     }
 
     #[tracing::instrument(level = "trace", skip(self), fields(app_name=%app_rev.name), err)]
     pub fn create_app(&mut self, app_rev: AppRevision) -> CollaborateResult<Option<FolderChange>> {
-        let workspace_id = app_rev.workspace_id.clone();
-        self.with_workspace(&workspace_id, move |workspace| {
-            if workspace.apps.contains(&app_rev) {
-                tracing::warn!("[RootFolder]: Duplicate app");
-                return Ok(None);
-            }
-            workspace.apps.push(app_rev);
-            Ok(Some(()))
-        })
+        self.apply_operation(FolderOperation::CreateApp { app: app_rev })
     }
 
     pub fn read_app(&self, app_id: &str) -> CollaborateResult<AppRevision> {
@@ -149,49 +147,41 @@ impl FolderPad {
         name: Option<String>,
         desc: Option<String>,
     ) -> CollaborateResult<Option<FolderChange>> {
-        self.with_app(app_id, move |app| {
-            if let Some(name) = name {
-                app.name = name;
-            }
-
-            if let Some(desc) = desc {
-                app.desc = desc;
-            }
-            Ok(Some(()))
+        self.apply_operation(FolderOperation::UpdateApp {
+            app_id: app_id.to_owned(),
+            name,
+            desc,
         })
     }
 
     #[tracing::instrument(level = "trace", skip(self), err)]
     pub fn delete_app(&mut self, app_id: &str) -> CollaborateResult<Option<FolderChange>> {
-        let app = self.read_app(app_id)?;
-        self.with_workspace(&app.workspace_id, |workspace| {
-            workspace.apps.retain(|app| app.id != app_id);
-            Ok(Some(()))
+        let _ = self.read_app(app_id)?;
+        self.apply_operation(FolderOperation::DeleteApp {
+            app_id: app_id.to_owned(),
         })
     }
 
     #[tracing::instrument(level = "trace", skip(self), err)]
     pub fn move_app(&mut self, app_id: &str, from: usize, to: usize) -> CollaborateResult<Option<FolderChange>> {
         let app = self.read_app(app_id)?;
-        self.with_workspace(&app.workspace_id, |workspace| {
-            match move_vec_element(&mut workspace.apps, |app| app.id == app_id, from, to).map_err(internal_error)? {
-                true => Ok(Some(())),
-                false => Ok(None),
-            }
+        let workspace = self.read_workspaces(Some(app.workspace_id))?.pop().unwrap();
+        check_move_index(
+            workspace.apps.iter().position(|app| app.id == app_id),
+            from,
+            to,
+            workspace.apps.len(),
+        )?;
+        self.apply_operation(FolderOperation::MoveApp {
+            app_id: app_id.to_owned(),
+            from: Some(from),
+            to,
         })
     }
 
     #[tracing::instrument(level = "trace", skip(self), fields(view_name=%view_rev.name), err)]
     pub fn create_view(&mut self, view_rev: ViewRevision) -> CollaborateResult<Option<FolderChange>> {
-        let app_id = view_rev.belong_to_id.clone();
-        self.with_app(&app_id, move |app| {
-            if app.belongings.contains(&view_rev) {
-                tracing::warn!("[RootFolder]: Duplicate view");
-                return Ok(None);
-            }
-            app.belongings.push(view_rev);
-            Ok(Some(()))
-        })
+        self.apply_operation(FolderOperation::CreateView { view: view_rev })
     }
 
     pub fn read_view(&self, view_id: &str) -> CollaborateResult<ViewRevision> {
@@ -223,48 +213,37 @@ impl FolderPad {
         desc: Option<String>,
         modified_time: i64,
     ) -> CollaborateResult<Option<FolderChange>> {
-        let view = self.read_view(view_id)?;
-        self.with_view(&view.belong_to_id, view_id, |view| {
-            if let Some(name) = name {
-                view.name = name;
-            }
-
-            if let Some(desc) = desc {
-                view.desc = desc;
-            }
-
-            view.modified_time = modified_time;
-            Ok(Some(()))
+        let _ = self.read_view(view_id)?;
+        self.apply_operation(FolderOperation::UpdateView {
+            view_id: view_id.to_owned(),
+            name,
+            desc,
+            modified_time,
         })
     }
 
     #[tracing::instrument(level = "trace", skip(self), err)]
     pub fn delete_view(&mut self, view_id: &str) -> CollaborateResult<Option<FolderChange>> {
-        let view = self.read_view(view_id)?;
-        self.with_app(&view.belong_to_id, |app| {
-            app.belongings.retain(|view| view.id != view_id);
-            Ok(Some(()))
+        let _ = self.read_view(view_id)?;
+        self.apply_operation(FolderOperation::DeleteView {
+            view_id: view_id.to_owned(),
         })
     }
 
     #[tracing::instrument(level = "trace", skip(self), err)]
     pub fn move_view(&mut self, view_id: &str, from: usize, to: usize) -> CollaborateResult<Option<FolderChange>> {
         let view = self.read_view(view_id)?;
-        self.with_app(&view.belong_to_id, |app| {
-            match move_vec_element(&mut app.belongings, |view| view.id == view_id, from, to).map_err(internal_error)? {
-                true => Ok(Some(())),
-                false => Ok(None),
-            }
+        let views = self.read_views(&view.belong_to_id)?;
+        check_move_index(views.iter().position(|view| view.id == view_id), from, to, views.len())?;
+        self.apply_operation(FolderOperation::MoveView {
+            view_id: view_id.to_owned(),
+            from: Some(from),
+            to,
         })
     }
 
     pub fn create_trash(&mut self, trash: Vec<TrashRevision>) -> CollaborateResult<Option<FolderChange>> {
-        self.with_trash(|t| {
-            let mut new_trash = trash.into_iter().map(Arc::new).collect::<Vec<Arc<TrashRevision>>>();
-            t.append(&mut new_trash);
-
-            Ok(Some(()))
-        })
+        self.apply_operation(FolderOperation::CreateTrash { trash })
     }
 
     pub fn read_trash(&self, trash_id: Option<String>) -> CollaborateResult<Vec<TrashRevision>> {
@@ -282,16 +261,7 @@ impl FolderPad {
     }
 
     pub fn delete_trash(&mut self, trash_ids: Option<Vec<String>>) -> CollaborateResult<Option<FolderChange>> {
-        match trash_ids {
-            None => self.with_trash(|trash| {
-                trash.clear();
-                Ok(Some(()))
-            }),
-            Some(trash_ids) => self.with_trash(|trash| {
-                trash.retain(|t| !trash_ids.contains(&t.id));
-                Ok(Some(()))
-            }),
-        }
+        self.apply_operation(FolderOperation::DeleteTrash { trash_ids })
     }
 
     pub fn md5(&self) -> String {
@@ -304,111 +274,67 @@ impl FolderPad {
     }
 }
 
+/// The number of lines of the folder's log that triggers the compaction. The next change
+/// replaces the log with the snapshot of the folder, followed by the operation of the change.
+const FOLDER_LOG_COMPACTION_LINES: usize = 500;
+
 impl FolderPad {
-    fn modify_workspaces<F>(&mut self, f: F) -> CollaborateResult<Option<FolderChange>>
-    where
-        F: FnOnce(&mut Vec<Arc<WorkspaceRevision>>) -> CollaborateResult<Option<()>>,
-    {
-        let cloned_self = self.clone();
-        match f(&mut self.workspaces)? {
-            None => Ok(None),
-            Some(_) => {
-                let old = cloned_self.to_json()?;
-                let new = self.to_json()?;
-                match cal_diff::<PlainTextAttributes>(old, new) {
-                    None => Ok(None),
-                    Some(delta) => {
-                        self.delta = self.delta.compose(&delta)?;
-                        Ok(Some(FolderChange { delta, md5: self.md5() }))
-                    }
-                }
-            }
+    fn apply_operation(&mut self, operation: FolderOperation) -> CollaborateResult<Option<FolderChange>> {
+        // The snapshot is taken before applying the operation, so the replicas that compact the
+        // same log concurrently make the same snapshot.
+        let snapshot = match count_log_lines(&self.delta) >= FOLDER_LOG_COMPACTION_LINES {
+            false => None,
+            true => Some(FolderSnapshot {
+                workspaces: self.workspaces.clone(),
+                trash: self.trash.clone(),
+            }),
+        };
+        if !operation.apply(&mut self.workspaces, &mut self.trash) {
+            return Ok(None);
         }
-    }
 
-    fn with_workspace<F>(&mut self, workspace_id: &str, f: F) -> CollaborateResult<Option<FolderChange>>
-    where
-        F: FnOnce(&mut WorkspaceRevision) -> CollaborateResult<Option<()>>,
-    {
-        self.modify_workspaces(|workspaces| {
-            if let Some(workspace) = workspaces.iter_mut().find(|workspace| workspace_id == workspace.id) {
-                f(Arc::make_mut(workspace))
-            } else {
-                tracing::warn!("[FolderPad]: Can't find any workspace with id: {}", workspace_id);
-                Ok(None)
-            }
-        })
+        let delta = match snapshot {
+            None => make_append_delta(&self.delta, &[operation])?,
+            Some(snapshot) => make_compaction_delta(&self.delta, &snapshot, &[operation])?,
+        };
+        self.delta = self.delta.compose(&delta)?;
+        Ok(Some(FolderChange { delta, md5: self.md5() }))
     }
 
-    fn with_trash<F>(&mut self, f: F) -> CollaborateResult<Option<FolderChange>>
-    where
-        F: FnOnce(&mut Vec<Arc<TrashRevision>>) -> CollaborateResult<Option<()>>,
-    {
-        let cloned_self = self.clone();
-        match f(&mut self.trash)? {
-            None => Ok(None),
-            Some(_) => {
-                let old = cloned_self.to_json()?;
-                let new = self.to_json()?;
-                match cal_diff::<PlainTextAttributes>(old, new) {
-                    None => Ok(None),
-                    Some(delta) => {
-                        self.delta = self.delta.compose(&delta)?;
-                        Ok(Some(FolderChange { delta, md5: self.md5() }))
-                    }
-                }
+    pub(crate) fn apply_log_entry(&mut self, entry: OperationLogEntry<FolderOperation, FolderSnapshot>) {
+        match entry {
+            OperationLogEntry::Operation(operation) => {
+                let _ = operation.apply(&mut self.workspaces, &mut self.trash);
+            }
+            OperationLogEntry::Snapshot(snapshot) => {
+                self.workspaces = snapshot.workspaces;
+                self.trash = snapshot.trash;
             }
         }
     }
+}
 
-    fn with_app<F>(&mut self, app_id: &str, f: F) -> CollaborateResult<Option<FolderChange>>
-    where
-        F: FnOnce(&mut AppRevision) -> CollaborateResult<Option<()>>,
-    {
-        let workspace_id = match self
-            .workspaces
-            .iter()
-            .find(|workspace| workspace.apps.iter().any(|app| app.id == app_id))
-        {
-            None => {
-                tracing::warn!("[FolderPad]: Can't find any app with id: {}", app_id);
-                return Ok(None);
-            }
-            Some(workspace) => workspace.id.clone(),
-        };
-
-        self.with_workspace(&workspace_id, |workspace| {
-            // It's ok to unwrap because we get the workspace from the app_id.
-            f(workspace.apps.iter_mut().find(|app| app_id == app.id).unwrap())
-        })
+fn check_move_index(index: Option<usize>, from: usize, to: usize, len: usize) -> CollaborateResult<()> {
+    if index != Some(from) {
+        let msg = format!("Move element from invalid index: {}, current index: {:?}", from, index);
+        return Err(internal_error(msg));
     }
-
-    fn with_view<F>(&mut self, belong_to_id: &str, view_id: &str, f: F) -> CollaborateResult<Option<FolderChange>>
-    where
-        F: FnOnce(&mut ViewRevision) -> CollaborateResult<Option<()>>,
-    {
-        self.with_app(belong_to_id, |app| {
-            match app.belongings.iter_mut().find(|view| view_id == view.id) {
-                None => {
-                    tracing::warn!("[FolderPad]: Can't find any view with id: {}", view_id);
-                    Ok(None)
-                }
-                Some(view) => f(view),
-            }
-        })
+    if to >= len {
+        let msg = format!("Move element to invalid index: {}, current len: {}", to, len);
+        return Err(internal_error(msg));
     }
+    Ok(())
 }
 
 pub fn default_folder_delta() -> FolderDelta {
-    PlainTextDeltaBuilder::new()
-        .insert(r#"{"workspaces":[],"trash":[]}"#)
-        .build()
+    PlainTextDeltaBuilder::new().build()
 }
 
+/// Returns the delta that creates the folder from scratch, which is shorter than the folder's
+/// own delta because it doesn't contain the history of the folder.
 pub fn initial_folder_delta(folder_pad: &FolderPad) -> CollaborateResult<FolderDelta> {
-    let json = folder_pad.to_json()?;
-    let delta = PlainTextDeltaBuilder::new().insert(&json).build();
-    Ok(delta)
+    let operations = make_folder_operations(&folder_pad.workspaces, &folder_pad.trash);
+    make_append_delta(&default_folder_delta(), &operations)
 }
 
 impl std::default::Default for FolderPad {
@@ -434,7 +360,7 @@ mod tests {
     use chrono::Utc;
 
     use flowy_folder_data_model::revision::{AppRevision, TrashRevision, ViewRevision, WorkspaceRevision};
-    use lib_ot::core::{OperationTransformable, PlainTextDelta, PlainTextDeltaBuilder};
+    use lib_ot::core::{OperationTransformable, PlainTextDelta};
 
     #[test]
     fn folder_add_workspace() {
@@ -748,8 +674,7 @@ mod tests {
 
     fn test_folder() -> (FolderPad, FolderDelta, WorkspaceRevision) {
         let mut folder = FolderPad::default();
-        let folder_json = serde_json::to_string(&folder).unwrap();
-        let mut delta = PlainTextDeltaBuilder::new().insert(&folder_json).build();
+        let mut delta = folder.delta().clone();
 
         let mut workspace_rev = WorkspaceRevision::default();
         workspace_rev.name = "😁 my first workspace".to_owned();
@@ -790,8 +715,7 @@ mod tests {
 
     fn test_trash() -> (FolderPad, FolderDelta, TrashRevision) {
         let mut folder = FolderPad::default();
-        let folder_json = serde_json::to_string(&folder).unwrap();
-        let mut delta = PlainTextDeltaBuilder::new().insert(&folder_json).build();
+        let mut delta = folder.delta().clone();
 
         let mut trash_rev = TrashRevision::default();
         trash_rev.name = "🚽 my first trash".to_owned();
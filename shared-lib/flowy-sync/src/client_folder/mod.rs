@@ -1,4 +1,6 @@
 mod builder;
+mod folder_operation;
 mod folder_pad;
 
+pub use folder_operation::*;
 pub use folder_pad::*;
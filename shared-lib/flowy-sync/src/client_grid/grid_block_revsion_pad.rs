@@ -1,6 +1,10 @@
+use crate::client_grid::GridBlockOperation;
 use crate::entities::revision::{md5, RepeatedRevision, Revision};
 use crate::errors::{CollaborateError, CollaborateResult};
-use crate::util::{cal_diff, make_delta_from_revisions};
+use crate::util::{
+    count_log_lines, make_append_delta, make_compaction_delta, make_delta_from_revisions, parse_operation_log,
+    OperationLogEntry,
+};
 use flowy_grid_data_model::revision::{
     gen_block_id, gen_row_id, CellRevision, GridBlockRevision, RowMetaChangeset, RowRevision,
 };
@@ -12,6 +16,9 @@ use std::sync::Arc;
 pub type GridBlockRevisionDelta = PlainTextDelta;
 pub type GridBlockRevisionDeltaBuilder = PlainTextDeltaBuilder;
 
+/// The number of lines of the block's log that makes the next change compact the log.
+const BLOCK_LOG_COMPACTION_LINES: usize = 500;
+
 #[derive(Debug, Clone)]
 pub struct GridBlockRevisionPad {
     block_revision: GridBlockRevision,
@@ -47,12 +54,26 @@ impl GridBlockRevisionPad {
 
     pub fn from_delta(delta: GridBlockRevisionDelta) -> CollaborateResult<Self> {
         let s = delta.to_str()?;
-        let block_revision: GridBlockRevision = serde_json::from_str(&s).map_err(|e| {
-            let msg = format!("Deserialize delta to block meta failed: {}", e);
-            tracing::error!("{}", s);
-            CollaborateError::internal().context(msg)
-        })?;
-        Ok(Self { block_revision, delta })
+        let mut block_revision = None;
+        for entry in parse_operation_log::<GridBlockOperation, GridBlockRevision>(&s)? {
+            match entry {
+                OperationLogEntry::Snapshot(snapshot) => block_revision = Some(snapshot),
+                OperationLogEntry::Operation(operation) => match block_revision.as_mut() {
+                    None => {
+                        tracing::error!("{}", s);
+                        return Err(CollaborateError::internal().context("The block log should start with a snapshot"));
+                    }
+                    Some(block_revision) => {
+                        let _ = operation.apply(block_revision);
+                    }
+                },
+            }
+        }
+
+        match block_revision {
+            None => Err(CollaborateError::internal().context("Deserialize delta to block meta failed: empty delta")),
+            Some(block_revision) => Ok(Self { block_revision, delta }),
+        }
     }
 
     pub fn from_revisions(_grid_id: &str, revisions: Vec<Revision>) -> CollaborateResult<Self> {
@@ -66,26 +87,13 @@ impl GridBlockRevisionPad {
         row: RowRevision,
         start_row_id: Option<String>,
     ) -> CollaborateResult<Option<GridBlockMetaChange>> {
-        self.modify(|rows| {
-            if let Some(start_row_id) = start_row_id {
-                if !start_row_id.is_empty() {
-                    if let Some(index) = rows.iter().position(|row| row.id == start_row_id) {
-                        rows.insert(index + 1, Arc::new(row));
-                        return Ok(Some(()));
-                    }
-                }
-            }
-
-            rows.push(Arc::new(row));
-            Ok(Some(()))
-        })
+        let start_row_id = start_row_id.filter(|start_row_id| !start_row_id.is_empty());
+        self.apply_operations(vec![GridBlockOperation::InsertRow { row, start_row_id }])
     }
 
     pub fn delete_rows(&mut self, row_ids: Vec<Cow<'_, String>>) -> CollaborateResult<Option<GridBlockMetaChange>> {
-        self.modify(|rows| {
-            rows.retain(|row| !row_ids.contains(&Cow::Borrowed(&row.id)));
-            Ok(Some(()))
-        })
+        let row_ids = row_ids.into_iter().map(|row_id| row_id.into_owned()).collect();
+        self.apply_operations(vec![GridBlockOperation::DeleteRows { row_ids }])
     }
 
     pub fn get_row_revs<T>(&self, row_ids: Option<Vec<Cow<'_, T>>>) -> CollaborateResult<Vec<Arc<RowRevision>>>
@@ -148,81 +156,79 @@ impl GridBlockRevisionPad {
     }
 
     pub fn update_row(&mut self, changeset: RowMetaChangeset) -> CollaborateResult<Option<GridBlockMetaChange>> {
-        let row_id = changeset.row_id.clone();
-        self.modify_row(&row_id, |row| {
-            let mut is_changed = None;
-            if let Some(height) = changeset.height {
-                row.height = height;
-                is_changed = Some(());
-            }
-
-            if let Some(visibility) = changeset.visibility {
-                row.visibility = visibility;
-                is_changed = Some(());
-            }
+        let RowMetaChangeset {
+            row_id,
+            height,
+            visibility,
+            document_id,
+            cell_by_field_id,
+        } = changeset;
+
+        if self.index_of_row(&row_id).is_none() {
+            tracing::warn!("[BlockMetaPad]: Can't find any row with id: {}", row_id);
+            return Ok(None);
+        }
 
-            if !changeset.cell_by_field_id.is_empty() {
-                is_changed = Some(());
-                changeset.cell_by_field_id.into_iter().for_each(|(field_id, cell)| {
-                    row.cells.insert(field_id, cell);
-                })
-            }
+        let mut operations = vec![];
+        if height.is_some() || visibility.is_some() || document_id.is_some() {
+            operations.push(GridBlockOperation::UpdateRow {
+                row_id: row_id.clone(),
+                height,
+                visibility,
+                document_id,
+            });
+        }
 
-            Ok(is_changed)
-        })
+        // Each cell is a separate operation, so concurrent edits of different cells are all kept.
+        operations.extend(
+            cell_by_field_id
+                .into_iter()
+                .map(|(field_id, cell)| GridBlockOperation::SetCell {
+                    row_id: row_id.clone(),
+                    field_id,
+                    cell,
+                }),
+        );
+        self.apply_operations(operations)
     }
 
     pub fn move_row(&mut self, row_id: &str, from: usize, to: usize) -> CollaborateResult<Option<GridBlockMetaChange>> {
-        self.modify(|row_revs| {
-            if let Some(position) = row_revs.iter().position(|row_rev| row_rev.id == row_id) {
-                debug_assert_eq!(from, position);
-                let row_rev = row_revs.remove(position);
-                row_revs.insert(to, row_rev);
-                Ok(Some(()))
-            } else {
-                Ok(None)
-            }
-        })
+        debug_assert_eq!(Some(from as i32), self.index_of_row(row_id));
+        self.apply_operations(vec![GridBlockOperation::MoveRow {
+            row_id: row_id.to_owned(),
+            to,
+        }])
     }
 
-    pub fn modify<F>(&mut self, f: F) -> CollaborateResult<Option<GridBlockMetaChange>>
-    where
-        F: for<'a> FnOnce(&'a mut Vec<Arc<RowRevision>>) -> CollaborateResult<Option<()>>,
-    {
-        let cloned_self = self.clone();
-        match f(&mut self.block_revision.rows)? {
-            None => Ok(None),
-            Some(_) => {
-                let old = cloned_self.to_json()?;
-                let new = self.to_json()?;
-                match cal_diff::<PlainTextAttributes>(old, new) {
-                    None => Ok(None),
-                    Some(delta) => {
-                        tracing::trace!("[GridBlockMeta] Composing delta {}", delta.to_delta_str());
-                        // tracing::debug!(
-                        //     "[GridBlockMeta] current delta: {}",
-                        //     self.delta.to_str().unwrap_or_else(|_| "".to_string())
-                        // );
-                        self.delta = self.delta.compose(&delta)?;
-                        Ok(Some(GridBlockMetaChange { delta, md5: self.md5() }))
-                    }
-                }
-            }
-        }
+    /// Returns the log that contains the snapshot of the block only, which replaces the history of
+    /// the block.
+    pub fn snapshot_delta(&self) -> CollaborateResult<GridBlockRevisionDelta> {
+        make_compaction_delta::<_, GridBlockOperation>(&GridBlockRevisionDelta::default(), &self.block_revision, &[])
     }
 
-    fn modify_row<F>(&mut self, row_id: &str, f: F) -> CollaborateResult<Option<GridBlockMetaChange>>
-    where
-        F: FnOnce(&mut RowRevision) -> CollaborateResult<Option<()>>,
-    {
-        self.modify(|rows| {
-            if let Some(row_rev) = rows.iter_mut().find(|row_rev| row_id == row_rev.id) {
-                f(Arc::make_mut(row_rev))
-            } else {
-                tracing::warn!("[BlockMetaPad]: Can't find any row with id: {}", row_id);
-                Ok(None)
-            }
-        })
+    fn apply_operations(
+        &mut self,
+        operations: Vec<GridBlockOperation>,
+    ) -> CollaborateResult<Option<GridBlockMetaChange>> {
+        let snapshot = match count_log_lines(&self.delta) >= BLOCK_LOG_COMPACTION_LINES {
+            true => Some(self.block_revision.clone()),
+            false => None,
+        };
+        let operations = operations
+            .into_iter()
+            .filter(|operation| operation.apply(&mut self.block_revision))
+            .collect::<Vec<_>>();
+        if operations.is_empty() {
+            return Ok(None);
+        }
+
+        let delta = match snapshot {
+            None => make_append_delta(&self.delta, &operations)?,
+            Some(snapshot) => make_compaction_delta(&self.delta, &snapshot, &operations)?,
+        };
+        tracing::trace!("[GridBlockMeta] Composing delta {}", delta.to_delta_str());
+        self.delta = self.delta.compose(&delta)?;
+        Ok(Some(GridBlockMetaChange { delta, md5: self.md5() }))
     }
 
     pub fn to_json(&self) -> CollaborateResult<String> {
@@ -245,6 +251,8 @@ pub struct GridBlockMetaChange {
     pub md5: String,
 }
 
+/// Returns the delta that starts the block's log with the snapshot of the block, see
+/// [make_grid_delta](crate::client_grid::make_grid_delta).
 pub fn make_block_meta_delta(block_rev: &GridBlockRevision) -> GridBlockRevisionDelta {
     let json = serde_json::to_string(&block_rev).unwrap();
     PlainTextDeltaBuilder::new().insert(&json).build()
@@ -252,7 +260,7 @@ pub fn make_block_meta_delta(block_rev: &GridBlockRevision) -> GridBlockRevision
 
 pub fn make_block_meta_revisions(user_id: &str, grid_block_meta_data: &GridBlockRevision) -> RepeatedRevision {
     let delta = make_block_meta_delta(grid_block_meta_data);
-    let bytes = delta.to_delta_bytes();
+    let bytes = delta.to_compact_bytes();
     let revision = Revision::initial_revision(user_id, &grid_block_meta_data.block_id, bytes);
     revision.into()
 }
@@ -272,7 +280,8 @@ impl std::default::Default for GridBlockRevisionPad {
 #[cfg(test)]
 mod tests {
     use crate::client_grid::{GridBlockRevisionDelta, GridBlockRevisionPad};
-    use flowy_grid_data_model::revision::{RowMetaChangeset, RowRevision};
+    use flowy_grid_data_model::revision::{CellRevision, RowMetaChangeset, RowRevision};
+    use lib_ot::core::OperationTransformable;
     use std::borrow::Cow;
 
     #[test]
@@ -284,13 +293,14 @@ mod tests {
             cells: Default::default(),
             height: 0,
             visibility: false,
+            document_id: None,
         };
 
         let change = pad.add_row_rev(row.clone(), None).unwrap().unwrap();
         assert_eq!(pad.rows.first().unwrap().as_ref(), &row);
         assert_eq!(
             change.delta.to_delta_str(),
-            r#"[{"retain":24},{"insert":"{\"id\":\"1\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false}"},{"retain":2}]"#
+            r#"[{"retain":26},{"insert":"\n{\"op\":\"insert_row\",\"row\":{\"id\":\"1\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false}}\n"}]"#
         );
     }
 
@@ -304,19 +314,19 @@ mod tests {
         let change = pad.add_row_rev(row_1.clone(), None).unwrap().unwrap();
         assert_eq!(
             change.delta.to_delta_str(),
-            r#"[{"retain":24},{"insert":"{\"id\":\"1\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false}"},{"retain":2}]"#
+            r#"[{"retain":26},{"insert":"\n{\"op\":\"insert_row\",\"row\":{\"id\":\"1\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false}}\n"}]"#
         );
 
         let change = pad.add_row_rev(row_2.clone(), None).unwrap().unwrap();
         assert_eq!(
             change.delta.to_delta_str(),
-            r#"[{"retain":90},{"insert":",{\"id\":\"2\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false}"},{"retain":2}]"#
+            r#"[{"retain":120},{"insert":"{\"op\":\"insert_row\",\"row\":{\"id\":\"2\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false}}\n"}]"#
         );
 
         let change = pad.add_row_rev(row_3.clone(), Some("2".to_string())).unwrap().unwrap();
         assert_eq!(
             change.delta.to_delta_str(),
-            r#"[{"retain":157},{"insert":",{\"id\":\"3\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false}"},{"retain":2}]"#
+            r#"[{"retain":213},{"insert":"{\"op\":\"insert_row\",\"row\":{\"id\":\"3\",\"block_id\":\"1\",\"cells\":[],\"height\":0,\"visibility\":false},\"start_row_id\":\"2\"}\n"}]"#
         );
 
         assert_eq!(*pad.rows[0], row_1);
@@ -331,6 +341,7 @@ mod tests {
             cells: Default::default(),
             height: 0,
             visibility: false,
+            document_id: None,
         }
     }
 
@@ -369,23 +380,24 @@ mod tests {
     #[test]
     fn block_meta_delete_row() {
         let mut pad = test_pad();
-        let pre_delta_str = pad.delta_str();
+        let pre_json = pad.to_json().unwrap();
         let row = RowRevision {
             id: "1".to_string(),
             block_id: pad.block_id.clone(),
             cells: Default::default(),
             height: 0,
             visibility: false,
+            document_id: None,
         };
 
         let _ = pad.add_row_rev(row.clone(), None).unwrap().unwrap();
         let change = pad.delete_rows(vec![Cow::Borrowed(&row.id)]).unwrap().unwrap();
         assert_eq!(
             change.delta.to_delta_str(),
-            r#"[{"retain":24},{"delete":66},{"retain":2}]"#
+            r#"[{"retain":120},{"insert":"{\"op\":\"delete_rows\",\"row_ids\":[\"1\"]}\n"}]"#
         );
 
-        assert_eq!(pad.delta_str(), pre_delta_str);
+        assert_eq!(pad.to_json().unwrap(), pre_json);
     }
 
     #[test]
@@ -397,12 +409,14 @@ mod tests {
             cells: Default::default(),
             height: 0,
             visibility: false,
+            document_id: None,
         };
 
         let changeset = RowMetaChangeset {
             row_id: row.id.clone(),
             height: Some(100),
             visibility: Some(true),
+            document_id: None,
             cell_by_field_id: Default::default(),
         };
 
@@ -411,7 +425,7 @@ mod tests {
 
         assert_eq!(
             change.delta.to_delta_str(),
-            r#"[{"retain":69},{"insert":"10"},{"retain":15},{"insert":"tru"},{"delete":4},{"retain":4}]"#
+            r#"[{"retain":120},{"insert":"{\"op\":\"update_row\",\"row_id\":\"1\",\"height\":100,\"visibility\":true}\n"}]"#
         );
 
         assert_eq!(
@@ -420,6 +434,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn block_meta_update_row_document() {
+        let mut pad = test_pad();
+        let row = test_row_rev("1", &pad);
+        let changeset = RowMetaChangeset {
+            row_id: row.id.clone(),
+            height: None,
+            visibility: None,
+            document_id: Some("doc_1".to_owned()),
+            cell_by_field_id: Default::default(),
+        };
+
+        let _ = pad.add_row_rev(row, None).unwrap().unwrap();
+        let _ = pad.update_row(changeset).unwrap().unwrap();
+        assert_eq!(
+            pad.to_json().unwrap(),
+            r#"{"block_id":"1","rows":[{"id":"1","block_id":"1","cells":[],"height":0,"visibility":false,"document_id":"doc_1"}]}"#
+        );
+
+        let pad = GridBlockRevisionPad::from_delta(pad.delta.clone()).unwrap();
+        assert_eq!(pad.rows[0].document_id, Some("doc_1".to_owned()));
+    }
+
+    #[test]
+    fn block_meta_concurrent_cell_edits() {
+        let mut pad = test_pad();
+        let _ = pad.add_row_rev(test_row_rev("1", &pad), None).unwrap().unwrap();
+        let _ = pad.add_row_rev(test_row_rev("2", &pad), None).unwrap().unwrap();
+        let mut pad_a = GridBlockRevisionPad::from_delta(pad.delta.clone()).unwrap();
+        let mut pad_b = GridBlockRevisionPad::from_delta(pad.delta.clone()).unwrap();
+
+        let delta_a = pad_a
+            .update_row(test_cell_changeset("1", "f1", "a"))
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_a_2 = pad_a
+            .update_row(test_cell_changeset("2", "f1", "a"))
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_a = delta_a.compose(&delta_a_2).unwrap();
+
+        let delta_b = pad_b
+            .update_row(test_cell_changeset("1", "f2", "b"))
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_b_2 = pad_b
+            .delete_rows(vec![Cow::Owned("2".to_owned())])
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_b = delta_b.compose(&delta_b_2).unwrap();
+
+        let (a_prime, b_prime) = delta_a.transform(&delta_b).unwrap();
+        let pad_a = GridBlockRevisionPad::from_delta(pad_a.delta.compose(&b_prime).unwrap()).unwrap();
+        let pad_b = GridBlockRevisionPad::from_delta(pad_b.delta.compose(&a_prime).unwrap()).unwrap();
+
+        assert_eq!(pad_a.delta_str(), pad_b.delta_str());
+        assert_eq!(pad_a.to_json().unwrap(), pad_b.to_json().unwrap());
+        assert_eq!(pad_a.rows.len(), 1);
+        assert_eq!(pad_a.rows[0].cells.get("f1").unwrap().data, "a");
+        assert_eq!(pad_a.rows[0].cells.get("f2").unwrap().data, "b");
+    }
+
+    fn test_cell_changeset(row_id: &str, field_id: &str, data: &str) -> RowMetaChangeset {
+        RowMetaChangeset {
+            row_id: row_id.to_owned(),
+            height: None,
+            visibility: None,
+            document_id: None,
+            cell_by_field_id: vec![(field_id.to_owned(), CellRevision::new(data.to_owned()))]
+                .into_iter()
+                .collect(),
+        }
+    }
+
     fn test_pad() -> GridBlockRevisionPad {
         let delta =
             GridBlockRevisionDelta::from_delta_str(r#"[{"insert":"{\"block_id\":\"1\",\"rows\":[]}"}]"#).unwrap();
@@ -0,0 +1,778 @@
+use crate::util::{move_element, set_if_changed};
+use flowy_grid_data_model::revision::{
+    CellRevision, FieldRevision, FieldTypeRevision, GridBlockMetaRevision, GridBlockRevision, GridFilterRevision,
+    GridGroupRevision, GridLayoutRevision, GridRevision, GridSettingRevision, GridSortRevision, RowRevision,
+    RowTemplateRevision,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// An operation on the [GridRevision]. The text of the grid's delta is a log of these
+/// operations, one JSON-encoded operation per line, that starts with the snapshot of the grid.
+///
+/// Every change appends its operations to the end of the log, so concurrent changes never
+/// break each other and each replica ends up with the same log after the transform. The
+/// operations address the fields, blocks and templates by id, which resolves the conflicts when
+/// the log is applied:
+/// * An operation on a field, block or template that was deleted is dropped.
+/// * The last update of the same property wins.
+/// * A move whose index is out of range after a concurrent delete moves to the end.
+///
+/// The settings are changed by the operations on the filters, groups, sorts and field settings of
+/// a layout, so the concurrent changes of different settings are all kept.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GridOperation {
+    /// Inserts the field in front of the field with `start_field_id`, or appends the field if
+    /// `start_field_id` is None or doesn't exist.
+    InsertField {
+        field: FieldRevision,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start_field_id: Option<String>,
+    },
+    DeleteField {
+        field_id: String,
+    },
+    MoveField {
+        field_id: String,
+        to: usize,
+    },
+    UpdateField {
+        field_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        desc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        field_type: Option<FieldTypeRevision>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        frozen: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        visibility: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        width: Option<i32>,
+        /// An empty string means removing the default value of the field.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default_value: Option<String>,
+    },
+    UpdateTypeOption {
+        field_id: String,
+        field_type: FieldTypeRevision,
+        type_option: String,
+    },
+    ReplaceField {
+        field: FieldRevision,
+    },
+    InsertBlock {
+        block: GridBlockMetaRevision,
+    },
+    UpdateBlock {
+        block_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start_row_index: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        row_count: Option<i32>,
+    },
+    /// Replaces the whole setting. Only the logs that were written before the operations on the
+    /// settings of a layout contain it.
+    UpdateSetting {
+        setting: GridSettingRevision,
+    },
+    InsertFilter {
+        layout: GridLayoutRevision,
+        field_type: FieldTypeRevision,
+        filter: GridFilterRevision,
+    },
+    DeleteFilter {
+        layout: GridLayoutRevision,
+        field_id: String,
+        field_type: FieldTypeRevision,
+        filter_id: String,
+    },
+    InsertGroup {
+        layout: GridLayoutRevision,
+        group: GridGroupRevision,
+    },
+    DeleteGroup {
+        layout: GridLayoutRevision,
+        group_id: String,
+    },
+    InsertSort {
+        layout: GridLayoutRevision,
+        sort: GridSortRevision,
+    },
+    DeleteSort {
+        layout: GridLayoutRevision,
+        sort_id: String,
+    },
+    SetFieldSetting {
+        layout: GridLayoutRevision,
+        field_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        visibility: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        width: Option<i32>,
+    },
+    /// Moves the field from the index `from` to the index `to` of the layout's field order. The
+    /// move is skipped if the field isn't at `from` anymore, so the first of the conflicting
+    /// moves in the log wins.
+    MoveLayoutField {
+        layout: GridLayoutRevision,
+        field_id: String,
+        from: usize,
+        to: usize,
+    },
+    /// Copies the visibility, width and order of the fields into the layout's field settings. It's
+    /// skipped if the layout already has its own field settings.
+    InitLayoutFieldSettings {
+        layout: GridLayoutRevision,
+    },
+    InsertRowTemplate {
+        template: RowTemplateRevision,
+    },
+    ReplaceRowTemplate {
+        template: RowTemplateRevision,
+    },
+    DeleteRowTemplate {
+        template_id: String,
+    },
+}
+
+impl GridOperation {
+    /// Applies the operation to the grid. Returns false if the operation doesn't change
+    /// anything.
+    pub(crate) fn apply(&self, grid_rev: &mut GridRevision) -> bool {
+        match self {
+            GridOperation::InsertField { field, start_field_id } => {
+                if grid_rev.fields.iter().any(|field_rev| field_rev.id == field.id) {
+                    tracing::error!("Duplicate grid field");
+                    return false;
+                }
+                let index = start_field_id
+                    .as_ref()
+                    .and_then(|start_field_id| grid_rev.fields.iter().position(|field| &field.id == start_field_id));
+                let field = Arc::new(field.clone());
+                match index {
+                    None => grid_rev.fields.push(field),
+                    Some(index) => grid_rev.fields.insert(index, field),
+                }
+                true
+            }
+            GridOperation::DeleteField { field_id } => {
+                match grid_rev.fields.iter().position(|field| &field.id == field_id) {
+                    None => false,
+                    Some(index) => {
+                        grid_rev.fields.remove(index);
+                        grid_rev.setting.remove_field_settings(field_id);
+                        grid_rev
+                            .row_templates
+                            .iter_mut()
+                            .filter(|template| template.cells.contains_key(field_id))
+                            .for_each(|template| {
+                                Arc::make_mut(template).cells.remove(field_id);
+                            });
+                        true
+                    }
+                }
+            }
+            GridOperation::MoveField { field_id, to } => {
+                move_element(&mut grid_rev.fields, |field| &field.id == field_id, *to)
+            }
+            GridOperation::UpdateField {
+                field_id,
+                name,
+                desc,
+                field_type,
+                frozen,
+                visibility,
+                width,
+                default_value,
+            } => with_field(grid_rev, field_id, |field| {
+                let mut changed = set_if_changed(&mut field.name, name);
+                changed = set_if_changed(&mut field.desc, desc) || changed;
+                changed = set_if_changed(&mut field.field_type_rev, field_type) || changed;
+                changed = set_if_changed(&mut field.frozen, frozen) || changed;
+                changed = set_if_changed(&mut field.visibility, visibility) || changed;
+                changed = set_if_changed(&mut field.width, width) || changed;
+                if let Some(default_value) = default_value {
+                    let default_value = Some(default_value.clone()).filter(|value| !value.is_empty());
+                    changed = set_if_changed(&mut field.default_value, &Some(default_value)) || changed;
+                }
+                changed
+            }),
+            GridOperation::UpdateTypeOption {
+                field_id,
+                field_type,
+                type_option,
+            } => with_field(grid_rev, field_id, |field| {
+                if field.get_type_option_str(*field_type).as_ref() == Some(type_option) {
+                    return false;
+                }
+                field.insert_type_option_str(field_type, type_option.clone());
+                true
+            }),
+            GridOperation::ReplaceField { field } => {
+                match grid_rev.fields.iter_mut().find(|field_rev| field_rev.id == field.id) {
+                    Some(field_rev) if field_rev.as_ref() != field => {
+                        *field_rev = Arc::new(field.clone());
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            GridOperation::InsertBlock { block } => {
+                if grid_rev.blocks.iter().any(|b| b.block_id == block.block_id) {
+                    tracing::warn!("Duplicate grid block");
+                    return false;
+                }
+                grid_rev.blocks.push(Arc::new(block.clone()));
+                true
+            }
+            GridOperation::UpdateBlock {
+                block_id,
+                start_row_index,
+                row_count,
+            } => match grid_rev.blocks.iter_mut().find(|block| &block.block_id == block_id) {
+                None => false,
+                Some(block) => {
+                    let block = Arc::make_mut(block);
+                    let changed = set_if_changed(&mut block.start_row_index, start_row_index);
+                    set_if_changed(&mut block.row_count, row_count) || changed
+                }
+            },
+            GridOperation::UpdateSetting { setting } => set_if_changed(&mut grid_rev.setting, &Some(setting.clone())),
+            GridOperation::InsertFilter {
+                layout,
+                field_type,
+                filter,
+            } => {
+                let filters = grid_rev.setting.get_filters(layout, &filter.field_id, field_type);
+                if filters.into_iter().flatten().any(|f| f.id == filter.id) {
+                    tracing::warn!("Duplicate grid filter");
+                    return false;
+                }
+                grid_rev
+                    .setting
+                    .insert_filter(layout, &filter.field_id, field_type, filter.clone());
+                true
+            }
+            GridOperation::DeleteFilter {
+                layout,
+                field_id,
+                field_type,
+                filter_id,
+            } => match grid_rev.setting.get_mut_filters(layout, field_id, field_type) {
+                None => false,
+                Some(filters) => {
+                    let len = filters.len();
+                    filters.retain(|filter| &filter.id != filter_id);
+                    filters.len() != len
+                }
+            },
+            GridOperation::InsertGroup { layout, group } => {
+                let groups = grid_rev.setting.groups.entry(layout.clone()).or_default();
+                if groups.iter().any(|g| g.id == group.id) {
+                    tracing::warn!("Duplicate grid group");
+                    return false;
+                }
+                groups.push(group.clone());
+                true
+            }
+            GridOperation::DeleteGroup { layout, group_id } => match grid_rev.setting.groups.get_mut(layout) {
+                None => false,
+                Some(groups) => {
+                    let len = groups.len();
+                    groups.retain(|group| &group.id != group_id);
+                    groups.len() != len
+                }
+            },
+            GridOperation::InsertSort { layout, sort } => {
+                let sorts = grid_rev.setting.sorts.entry(layout.clone()).or_default();
+                if sorts.iter().any(|s| s.id == sort.id) {
+                    tracing::warn!("Duplicate grid sort");
+                    return false;
+                }
+                sorts.push(sort.clone());
+                true
+            }
+            GridOperation::DeleteSort { layout, sort_id } => match grid_rev.setting.sorts.get_mut(layout) {
+                None => false,
+                Some(sorts) => {
+                    let len = sorts.len();
+                    sorts.retain(|sort| &sort.id != sort_id);
+                    sorts.len() != len
+                }
+            },
+            GridOperation::SetFieldSetting {
+                layout,
+                field_id,
+                visibility,
+                width,
+            } => {
+                // The settings of the field that was deleted concurrently are not created again.
+                if !grid_rev.fields.iter().any(|field| &field.id == field_id) {
+                    tracing::warn!("[GridOperation]: Can't find any field with id: {}", field_id);
+                    return false;
+                }
+                grid_rev
+                    .setting
+                    .update_field_setting(layout, field_id, *visibility, *width)
+            }
+            GridOperation::MoveLayoutField {
+                layout,
+                field_id,
+                from,
+                to,
+            } => grid_rev
+                .setting
+                .move_field(layout, field_id, *from, *to, &grid_rev.fields),
+            GridOperation::InitLayoutFieldSettings { layout } => {
+                grid_rev.setting.init_field_settings(layout, &grid_rev.fields)
+            }
+            GridOperation::InsertRowTemplate { template } => {
+                if grid_rev.row_templates.iter().any(|t| t.id == template.id) {
+                    tracing::warn!("Duplicate row template");
+                    return false;
+                }
+                grid_rev.row_templates.push(Arc::new(template.clone()));
+                true
+            }
+            GridOperation::ReplaceRowTemplate { template } => {
+                match grid_rev.row_templates.iter_mut().find(|t| t.id == template.id) {
+                    Some(t) if t.as_ref() != template => {
+                        *t = Arc::new(template.clone());
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            GridOperation::DeleteRowTemplate { template_id } => {
+                let len = grid_rev.row_templates.len();
+                grid_rev.row_templates.retain(|t| &t.id != template_id);
+                grid_rev.row_templates.len() != len
+            }
+        }
+    }
+}
+
+/// An operation on the [GridBlockRevision]. The log of the block works like the log of the
+/// grid, see [GridOperation]. Editing a row or a cell of a row that was deleted is dropped, and
+/// concurrent edits of different cells of the same row are all kept.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GridBlockOperation {
+    /// Inserts the row after the row with `start_row_id`, or appends the row if `start_row_id`
+    /// is None or doesn't exist.
+    InsertRow {
+        row: RowRevision,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start_row_id: Option<String>,
+    },
+    DeleteRows {
+        row_ids: Vec<String>,
+    },
+    MoveRow {
+        row_id: String,
+        to: usize,
+    },
+    UpdateRow {
+        row_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        height: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        visibility: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document_id: Option<String>,
+    },
+    SetCell {
+        row_id: String,
+        field_id: String,
+        cell: CellRevision,
+    },
+}
+
+impl GridBlockOperation {
+    /// Applies the operation to the block. Returns false if the operation doesn't change
+    /// anything.
+    pub(crate) fn apply(&self, block_rev: &mut GridBlockRevision) -> bool {
+        let rows = &mut block_rev.rows;
+        match self {
+            GridBlockOperation::InsertRow { row, start_row_id } => {
+                if rows.iter().any(|row_rev| row_rev.id == row.id) {
+                    tracing::warn!("Duplicate row");
+                    return false;
+                }
+                let index = start_row_id
+                    .as_ref()
+                    .and_then(|start_row_id| rows.iter().position(|row| &row.id == start_row_id));
+                let row = Arc::new(row.clone());
+                match index {
+                    None => rows.push(row),
+                    Some(index) => rows.insert(index + 1, row),
+                }
+                true
+            }
+            GridBlockOperation::DeleteRows { row_ids } => {
+                let len = rows.len();
+                rows.retain(|row| !row_ids.contains(&row.id));
+                rows.len() != len
+            }
+            GridBlockOperation::MoveRow { row_id, to } => move_element(rows, |row| &row.id == row_id, *to),
+            GridBlockOperation::UpdateRow {
+                row_id,
+                height,
+                visibility,
+                document_id,
+            } => with_row(rows, row_id, |row| {
+                let mut changed = set_if_changed(&mut row.height, height);
+                changed = set_if_changed(&mut row.visibility, visibility) || changed;
+                if document_id.is_some() {
+                    changed = set_if_changed(&mut row.document_id, &Some(document_id.clone())) || changed;
+                }
+                changed
+            }),
+            GridBlockOperation::SetCell { row_id, field_id, cell } => with_row(rows, row_id, |row| {
+                if row.cells.get(field_id) == Some(cell) {
+                    return false;
+                }
+                row.cells.insert(field_id.clone(), cell.clone());
+                true
+            }),
+        }
+    }
+}
+
+fn with_field<F>(grid_rev: &mut GridRevision, field_id: &str, f: F) -> bool
+where
+    F: FnOnce(&mut FieldRevision) -> bool,
+{
+    match grid_rev.fields.iter_mut().find(|field| field.id == field_id) {
+        None => {
+            tracing::warn!("[GridOperation]: Can't find any field with id: {}", field_id);
+            false
+        }
+        Some(field) => f(Arc::make_mut(field)),
+    }
+}
+
+fn with_row<F>(rows: &mut [Arc<RowRevision>], row_id: &str, f: F) -> bool
+where
+    F: FnOnce(&mut RowRevision) -> bool,
+{
+    match rows.iter_mut().find(|row| row.id == row_id) {
+        None => {
+            tracing::warn!("[GridBlockOperation]: Can't find any row with id: {}", row_id);
+            false
+        }
+        Some(row) => f(Arc::make_mut(row)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client_grid::{
+        make_block_meta_delta, make_grid_delta, GridBlockRevisionDelta, GridBlockRevisionPad, GridRevisionDelta,
+        GridRevisionPad,
+    };
+    use crate::entities::grid::{CreateGridFilterParams, FieldSettingChangesetParams, GridSettingChangesetParams};
+    use flowy_grid_data_model::revision::{
+        CellRevision, FieldRevision, GridBlockRevision, GridLayoutRevision, GridRevision, RowMetaChangeset, RowRevision,
+    };
+    use lib_ot::core::OperationTransformable;
+    use std::sync::Arc;
+
+    fn test_grid() -> GridRevisionPad {
+        let mut grid_rev = GridRevision::new("g1");
+        grid_rev.fields = ["f1", "f2", "f3"]
+            .iter()
+            .map(|id| {
+                let mut field_rev = FieldRevision::new(id, "", 0, 150, false);
+                field_rev.id = id.to_string();
+                Arc::new(field_rev)
+            })
+            .collect();
+        GridRevisionPad::from_delta(make_grid_delta(&grid_rev)).unwrap()
+    }
+
+    fn test_block() -> GridBlockRevisionPad {
+        let rows = ["r1", "r2", "r3"]
+            .iter()
+            .map(|id| {
+                let mut row_rev = RowRevision::new("b1");
+                row_rev.id = id.to_string();
+                Arc::new(row_rev)
+            })
+            .collect();
+        let block_rev = GridBlockRevision {
+            block_id: "b1".to_owned(),
+            rows,
+        };
+        GridBlockRevisionPad::from_delta(make_block_meta_delta(&block_rev)).unwrap()
+    }
+
+    /// Applies the concurrent changes of the other replica to each replica.
+    fn converge_grids(
+        grid_a: GridRevisionPad,
+        delta_a: GridRevisionDelta,
+        grid_b: GridRevisionPad,
+        delta_b: GridRevisionDelta,
+    ) -> (GridRevisionPad, GridRevisionPad) {
+        let (a_prime, b_prime) = delta_a.transform(&delta_b).unwrap();
+        let grid_a = GridRevisionPad::from_delta(grid_a.delta.compose(&b_prime).unwrap()).unwrap();
+        let grid_b = GridRevisionPad::from_delta(grid_b.delta.compose(&a_prime).unwrap()).unwrap();
+        assert_eq!(grid_a.delta_str(), grid_b.delta_str());
+        assert_eq!(grid_a.fields(), grid_b.fields());
+        assert_eq!(grid_a.get_grid_setting_rev(), grid_b.get_grid_setting_rev());
+        (grid_a, grid_b)
+    }
+
+    fn converge_blocks(
+        block_a: GridBlockRevisionPad,
+        delta_a: GridBlockRevisionDelta,
+        block_b: GridBlockRevisionPad,
+        delta_b: GridBlockRevisionDelta,
+    ) -> (GridBlockRevisionPad, GridBlockRevisionPad) {
+        let (a_prime, b_prime) = delta_a.transform(&delta_b).unwrap();
+        let block_a = GridBlockRevisionPad::from_delta(block_a.delta.compose(&b_prime).unwrap()).unwrap();
+        let block_b = GridBlockRevisionPad::from_delta(block_b.delta.compose(&a_prime).unwrap()).unwrap();
+        assert_eq!(block_a.delta_str(), block_b.delta_str());
+        assert_eq!(block_a.to_json().unwrap(), block_b.to_json().unwrap());
+        (block_a, block_b)
+    }
+
+    fn field_ids(grid: &GridRevisionPad) -> Vec<&str> {
+        grid.fields().iter().map(|field| field.id.as_str()).collect()
+    }
+
+    fn layout_field_ids(grid: &GridRevisionPad) -> Vec<String> {
+        grid.get_grid_setting_rev()
+            .get_ordered_field_revs(&GridLayoutRevision::Table, grid.fields())
+            .iter()
+            .map(|field| field.id.clone())
+            .collect()
+    }
+
+    fn row_ids(block: &GridBlockRevisionPad) -> Vec<&str> {
+        block.rows.iter().map(|row| row.id.as_str()).collect()
+    }
+
+    fn field_setting_params(
+        field_id: &str,
+        visibility: Option<bool>,
+        width: Option<i32>,
+    ) -> FieldSettingChangesetParams {
+        FieldSettingChangesetParams {
+            grid_id: "g1".to_owned(),
+            layout_type: GridLayoutRevision::Table,
+            field_id: field_id.to_owned(),
+            visibility,
+            width,
+        }
+    }
+
+    fn cell_changeset(row_id: &str, field_id: &str, data: &str) -> RowMetaChangeset {
+        RowMetaChangeset {
+            row_id: row_id.to_owned(),
+            height: None,
+            visibility: None,
+            document_id: None,
+            cell_by_field_id: vec![(field_id.to_owned(), CellRevision::new(data.to_owned()))]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn grid_concurrent_field_moves_converge() {
+        let mut grid_a = test_grid();
+        let mut grid_b = test_grid();
+
+        let delta_a = grid_a.move_field("f1", 0, 2).unwrap().unwrap().delta;
+        let delta_b = grid_b.move_field("f3", 2, 0).unwrap().unwrap().delta;
+        let delta_b_2 = grid_b.delete_field_rev("f2").unwrap().unwrap().delta;
+        let delta_b = delta_b.compose(&delta_b_2).unwrap();
+
+        let (grid_a, _) = converge_grids(grid_a, delta_a, grid_b, delta_b);
+        assert_eq!(field_ids(&grid_a), vec!["f3", "f1"]);
+    }
+
+    #[test]
+    fn grid_concurrent_setting_changes_converge() {
+        let mut grid_a = test_grid();
+        let mut grid_b = test_grid();
+
+        let delta_a = grid_a
+            .update_field_setting(field_setting_params("f2", None, Some(200)))
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_a_2 = grid_a
+            .update_grid_setting_rev(GridSettingChangesetParams {
+                grid_id: "g1".to_owned(),
+                layout_type: GridLayoutRevision::Table,
+                insert_filter: Some(CreateGridFilterParams {
+                    field_id: "f1".to_owned(),
+                    field_type_rev: 0,
+                    condition: 1,
+                    content: None,
+                }),
+                delete_filter: None,
+                insert_group: None,
+                delete_group: None,
+                insert_sort: None,
+                delete_sort: None,
+            })
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_a = delta_a.compose(&delta_a_2).unwrap();
+
+        let delta_b = grid_b
+            .update_field_setting(field_setting_params("f2", Some(false), None))
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_b_2 = grid_b
+            .move_layout_field(&GridLayoutRevision::Table, "f3", 2, 0)
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_b = delta_b.compose(&delta_b_2).unwrap();
+
+        // The changes of different settings are all kept.
+        let (grid_a, _) = converge_grids(grid_a, delta_a, grid_b, delta_b);
+        let setting = grid_a.get_grid_setting_rev();
+        let field_setting = setting.get_field_setting(&GridLayoutRevision::Table, "f2").unwrap();
+        assert_eq!(field_setting.width, Some(200));
+        assert_eq!(field_setting.visibility, Some(false));
+        assert_eq!(
+            setting.get_filters(&GridLayoutRevision::Table, "f1", &0).unwrap().len(),
+            1
+        );
+        assert_eq!(layout_field_ids(&grid_a), vec!["f3", "f1", "f2"]);
+    }
+
+    #[test]
+    fn grid_init_layout_field_settings_keeps_concurrent_settings() {
+        let mut grid_a = test_grid();
+        let mut grid_b = test_grid();
+
+        let delta_a = grid_a.init_layout_field_settings().unwrap().unwrap().delta;
+        assert!(grid_a.init_layout_field_settings().unwrap().is_none());
+        let delta_b = grid_b
+            .update_field_setting(field_setting_params("f2", None, Some(200)))
+            .unwrap()
+            .unwrap()
+            .delta;
+
+        let (grid_a, _) = converge_grids(grid_a, delta_a, grid_b, delta_b);
+        let setting = grid_a.get_grid_setting_rev();
+        let field_setting = setting.get_field_setting(&GridLayoutRevision::Table, "f2").unwrap();
+        assert_eq!(field_setting.width, Some(200));
+        let field_setting = setting.get_field_setting(&GridLayoutRevision::Board, "f2").unwrap();
+        assert_eq!(field_setting.width, Some(150));
+        assert_eq!(field_setting.visibility, Some(true));
+    }
+
+    #[test]
+    fn grid_concurrent_layout_field_moves_converge() {
+        let mut grid_a = test_grid();
+        let mut grid_b = test_grid();
+
+        let delta_a = grid_a
+            .move_layout_field(&GridLayoutRevision::Table, "f1", 0, 2)
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_b = grid_b
+            .move_layout_field(&GridLayoutRevision::Table, "f1", 0, 1)
+            .unwrap()
+            .unwrap()
+            .delta;
+
+        // The move of b conflicts with the move of a, which comes first in the log.
+        let (grid_a, _) = converge_grids(grid_a, delta_a, grid_b, delta_b);
+        assert_eq!(layout_field_ids(&grid_a), vec!["f2", "f3", "f1"]);
+    }
+
+    #[test]
+    fn grid_setting_of_deleted_field_is_ignored() {
+        let mut grid_a = test_grid();
+        let mut grid_b = test_grid();
+
+        let delta_a = grid_a.delete_field_rev("f2").unwrap().unwrap().delta;
+        let delta_b = grid_b
+            .update_field_setting(field_setting_params("f2", None, Some(200)))
+            .unwrap()
+            .unwrap()
+            .delta;
+
+        let (grid_a, _) = converge_grids(grid_a, delta_a, grid_b, delta_b);
+        assert!(grid_a
+            .get_grid_setting_rev()
+            .get_field_setting(&GridLayoutRevision::Table, "f2")
+            .is_none());
+    }
+
+    #[test]
+    fn grid_compact_log() {
+        let mut grid = test_grid();
+        for i in 0..499 {
+            let _ = grid
+                .update_field_setting(field_setting_params("f1", None, Some(i)))
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(grid.delta.to_str().unwrap().lines().count(), 500);
+
+        let _ = grid.move_field("f1", 0, 2).unwrap().unwrap();
+        assert_eq!(grid.delta.to_str().unwrap().lines().count(), 2);
+        let compacted = GridRevisionPad::from_delta(grid.delta.clone()).unwrap();
+        assert_eq!(compacted.fields(), grid.fields());
+        assert_eq!(compacted.get_grid_setting_rev(), grid.get_grid_setting_rev());
+
+        let migrated = GridRevisionPad::from_delta(grid.snapshot_delta().unwrap()).unwrap();
+        assert_eq!(migrated.delta.to_str().unwrap().lines().count(), 1);
+        assert_eq!(migrated.fields(), grid.fields());
+    }
+
+    #[test]
+    fn block_concurrent_row_moves_converge() {
+        let mut block_a = test_block();
+        let mut block_b = test_block();
+
+        let delta_a = block_a.move_row("r1", 0, 2).unwrap().unwrap().delta;
+        let delta_b = block_b.move_row("r3", 2, 0).unwrap().unwrap().delta;
+        let delta_b_2 = block_b
+            .update_row(cell_changeset("r1", "f1", "b"))
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_b = delta_b.compose(&delta_b_2).unwrap();
+
+        let (block_a, _) = converge_blocks(block_a, delta_a, block_b, delta_b);
+        assert_eq!(row_ids(&block_a), vec!["r3", "r2", "r1"]);
+        assert_eq!(block_a.rows[2].cells.get("f1").unwrap().data, "b");
+    }
+
+    #[test]
+    fn block_concurrent_cell_updates_converge() {
+        let mut block_a = test_block();
+        let mut block_b = test_block();
+
+        let delta_a = block_a
+            .update_row(cell_changeset("r1", "f1", "a"))
+            .unwrap()
+            .unwrap()
+            .delta;
+        let delta_b = block_b
+            .update_row(cell_changeset("r1", "f1", "b"))
+            .unwrap()
+            .unwrap()
+            .delta;
+
+        // The update of b comes last in the log, so it wins.
+        let (block_a, _) = converge_blocks(block_a, delta_a, block_b, delta_b);
+        assert_eq!(block_a.rows[0].cells.get("f1").unwrap().data, "b");
+    }
+}
@@ -1,14 +1,17 @@
-use crate::entities::grid::{FieldChangesetParams, GridSettingChangesetParams};
+use crate::client_grid::GridOperation;
+use crate::entities::grid::{FieldChangesetParams, FieldSettingChangesetParams, GridSettingChangesetParams};
 use crate::entities::revision::{md5, RepeatedRevision, Revision};
 use crate::errors::{internal_error, CollaborateError, CollaborateResult};
-use crate::util::{cal_diff, make_delta_from_revisions};
+use crate::util::{
+    count_log_lines, make_append_delta, make_compaction_delta, make_delta_from_revisions, parse_operation_log,
+    OperationLogEntry,
+};
 use bytes::Bytes;
 use flowy_grid_data_model::revision::{
     gen_block_id, gen_grid_filter_id, gen_grid_group_id, gen_grid_id, gen_grid_sort_id, FieldRevision,
     FieldTypeRevision, GridBlockMetaRevision, GridBlockMetaRevisionChangeset, GridFilterRevision, GridGroupRevision,
-    GridLayoutRevision, GridRevision, GridSettingRevision, GridSortRevision,
+    GridLayoutRevision, GridRevision, GridSettingRevision, GridSortRevision, RowTemplateRevision,
 };
-use lib_infra::util::move_vec_element;
 use lib_ot::core::{OperationTransformable, PlainTextAttributes, PlainTextDelta, PlainTextDeltaBuilder};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -16,9 +19,12 @@ use std::sync::Arc;
 pub type GridRevisionDelta = PlainTextDelta;
 pub type GridRevisionDeltaBuilder = PlainTextDeltaBuilder;
 
+/// The number of lines of the grid's log that makes the next change compact the log.
+const GRID_LOG_COMPACTION_LINES: usize = 500;
+
 pub struct GridRevisionPad {
     grid_rev: Arc<GridRevision>,
-    delta: GridRevisionDelta,
+    pub(crate) delta: GridRevisionDelta,
 }
 
 pub trait JsonDeserializer {
@@ -53,13 +59,28 @@ impl GridRevisionPad {
 
     pub fn from_delta(delta: GridRevisionDelta) -> CollaborateResult<Self> {
         let s = delta.to_str()?;
-        let grid: GridRevision = serde_json::from_str(&s)
-            .map_err(|e| CollaborateError::internal().context(format!("Deserialize delta to grid failed: {}", e)))?;
+        let mut grid = None;
+        for entry in parse_operation_log::<GridOperation, GridRevision>(&s)? {
+            match entry {
+                OperationLogEntry::Snapshot(snapshot) => grid = Some(snapshot),
+                OperationLogEntry::Operation(operation) => match grid.as_mut() {
+                    None => {
+                        return Err(CollaborateError::internal().context("The grid log should start with a snapshot"))
+                    }
+                    Some(grid) => {
+                        let _ = operation.apply(grid);
+                    }
+                },
+            }
+        }
 
-        Ok(Self {
-            grid_rev: Arc::new(grid),
-            delta,
-        })
+        match grid {
+            None => Err(CollaborateError::internal().context("Deserialize delta to grid failed: empty delta")),
+            Some(grid) => Ok(Self {
+                grid_rev: Arc::new(grid),
+                delta,
+            }),
+        }
     }
 
     pub fn from_revisions(_grid_id: &str, revisions: Vec<Revision>) -> CollaborateResult<Self> {
@@ -73,40 +94,16 @@ impl GridRevisionPad {
         new_field_rev: FieldRevision,
         start_field_id: Option<String>,
     ) -> CollaborateResult<Option<GridChangeset>> {
-        self.modify_grid(|grid_meta| {
-            // Check if the field exists or not
-            if grid_meta
-                .fields
-                .iter()
-                .any(|field_rev| field_rev.id == new_field_rev.id)
-            {
-                tracing::error!("Duplicate grid field");
-                return Ok(None);
-            }
-
-            let insert_index = match start_field_id {
-                None => None,
-                Some(start_field_id) => grid_meta.fields.iter().position(|field| field.id == start_field_id),
-            };
-            let new_field_rev = Arc::new(new_field_rev);
-            match insert_index {
-                None => grid_meta.fields.push(new_field_rev),
-                Some(index) => grid_meta.fields.insert(index, new_field_rev),
-            }
-            Ok(Some(()))
-        })
+        self.apply_operations(vec![GridOperation::InsertField {
+            field: new_field_rev,
+            start_field_id,
+        }])
     }
 
     pub fn delete_field_rev(&mut self, field_id: &str) -> CollaborateResult<Option<GridChangeset>> {
-        self.modify_grid(
-            |grid_meta| match grid_meta.fields.iter().position(|field| field.id == field_id) {
-                None => Ok(None),
-                Some(index) => {
-                    grid_meta.fields.remove(index);
-                    Ok(Some(()))
-                }
-            },
-        )
+        self.apply_operations(vec![GridOperation::DeleteField {
+            field_id: field_id.to_owned(),
+        }])
     }
 
     pub fn duplicate_field_rev(
@@ -114,18 +111,20 @@ impl GridRevisionPad {
         field_id: &str,
         duplicated_field_id: &str,
     ) -> CollaborateResult<Option<GridChangeset>> {
-        self.modify_grid(
-            |grid_meta| match grid_meta.fields.iter().position(|field| field.id == field_id) {
-                None => Ok(None),
-                Some(index) => {
-                    let mut duplicate_field_rev = grid_meta.fields[index].as_ref().clone();
-                    duplicate_field_rev.id = duplicated_field_id.to_string();
-                    duplicate_field_rev.name = format!("{} (copy)", duplicate_field_rev.name);
-                    grid_meta.fields.insert(index + 1, Arc::new(duplicate_field_rev));
-                    Ok(Some(()))
-                }
-            },
-        )
+        match self.grid_rev.fields.iter().position(|field| field.id == field_id) {
+            None => Ok(None),
+            Some(index) => {
+                let mut duplicate_field_rev = self.grid_rev.fields[index].as_ref().clone();
+                duplicate_field_rev.id = duplicated_field_id.to_string();
+                duplicate_field_rev.name = format!("{} (copy)", duplicate_field_rev.name);
+                // Insert the duplicated field in front of the field that is next to the original one.
+                let start_field_id = self.grid_rev.fields.get(index + 1).map(|field| field.id.clone());
+                self.apply_operations(vec![GridOperation::InsertField {
+                    field: duplicate_field_rev,
+                    start_field_id,
+                }])
+            }
+        }
     }
 
     pub fn switch_to_field<B, T>(
@@ -139,25 +138,34 @@ impl GridRevisionPad {
         T: Into<FieldTypeRevision>,
     {
         let field_type = field_type.into();
-        self.modify_grid(|grid_meta| {
-            //
-            match grid_meta.fields.iter_mut().find(|field_rev| field_rev.id == field_id) {
-                None => {
-                    tracing::warn!("Can not find the field with id: {}", field_id);
-                    Ok(None)
-                }
-                Some(field_rev) => {
-                    let mut_field_rev = Arc::make_mut(field_rev);
-                    if mut_field_rev.get_type_option_str(field_type).is_none() {
-                        let type_option_json = type_option_json_builder(&field_type);
-                        mut_field_rev.insert_type_option_str(&field_type, type_option_json);
-                    }
-
-                    mut_field_rev.field_type_rev = field_type;
-                    Ok(Some(()))
+        let mut operations = vec![];
+        match self.get_field_rev(field_id) {
+            None => {
+                tracing::warn!("Can not find the field with id: {}", field_id);
+                return Ok(None);
+            }
+            Some((_, field_rev)) => {
+                if field_rev.get_type_option_str(field_type).is_none() {
+                    operations.push(GridOperation::UpdateTypeOption {
+                        field_id: field_id.to_owned(),
+                        field_type,
+                        type_option: type_option_json_builder(&field_type),
+                    });
                 }
             }
-        })
+        }
+
+        operations.push(GridOperation::UpdateField {
+            field_id: field_id.to_owned(),
+            name: None,
+            desc: None,
+            field_type: Some(field_type),
+            frozen: None,
+            visibility: None,
+            width: None,
+            default_value: None,
+        });
+        self.apply_operations(operations)
     }
 
     pub fn update_field_rev<T: JsonDeserializer>(
@@ -165,54 +173,39 @@ impl GridRevisionPad {
         changeset: FieldChangesetParams,
         deserializer: T,
     ) -> CollaborateResult<Option<GridChangeset>> {
-        let field_id = changeset.field_id.clone();
-        self.modify_field(&field_id, |field| {
-            let mut is_changed = None;
-            if let Some(name) = changeset.name {
-                field.name = name;
-                is_changed = Some(())
-            }
-
-            if let Some(desc) = changeset.desc {
-                field.desc = desc;
-                is_changed = Some(())
-            }
-
-            if let Some(field_type) = changeset.field_type {
-                field.field_type_rev = field_type;
-                is_changed = Some(())
-            }
-
-            if let Some(frozen) = changeset.frozen {
-                field.frozen = frozen;
-                is_changed = Some(())
-            }
-
-            if let Some(visibility) = changeset.visibility {
-                field.visibility = visibility;
-                is_changed = Some(())
-            }
-
-            if let Some(width) = changeset.width {
-                field.width = width;
-                is_changed = Some(())
+        let field_type = match self.get_field_rev(&changeset.field_id) {
+            None => {
+                tracing::warn!("[GridMetaPad]: Can't find any field with id: {}", changeset.field_id);
+                return Ok(None);
             }
-
-            if let Some(type_option_data) = changeset.type_option_data {
-                match deserializer.deserialize(type_option_data) {
-                    Ok(json_str) => {
-                        let field_type = field.field_type_rev;
-                        field.insert_type_option_str(&field_type, json_str);
-                        is_changed = Some(())
-                    }
-                    Err(err) => {
-                        tracing::error!("Deserialize data to type option json failed: {}", err);
-                    }
+            Some((_, field_rev)) => changeset.field_type.unwrap_or(field_rev.field_type_rev),
+        };
+
+        let mut operations = vec![GridOperation::UpdateField {
+            field_id: changeset.field_id.clone(),
+            name: changeset.name,
+            desc: changeset.desc,
+            field_type: changeset.field_type,
+            frozen: changeset.frozen,
+            visibility: changeset.visibility,
+            width: changeset.width,
+            default_value: changeset.default_value,
+        }];
+
+        if let Some(type_option_data) = changeset.type_option_data {
+            match deserializer.deserialize(type_option_data) {
+                Ok(json_str) => operations.push(GridOperation::UpdateTypeOption {
+                    field_id: changeset.field_id,
+                    field_type,
+                    type_option: json_str,
+                }),
+                Err(err) => {
+                    tracing::error!("Deserialize data to type option json failed: {}", err);
                 }
             }
+        }
 
-            Ok(is_changed)
-        })
+        self.apply_operations(operations)
     }
 
     pub fn get_field_rev(&self, field_id: &str) -> Option<(usize, &Arc<FieldRevision>)> {
@@ -224,37 +217,30 @@ impl GridRevisionPad {
     }
 
     pub fn replace_field_rev(&mut self, field_rev: Arc<FieldRevision>) -> CollaborateResult<Option<GridChangeset>> {
-        self.modify_grid(
-            |grid_meta| match grid_meta.fields.iter().position(|field| field.id == field_rev.id) {
-                None => Ok(None),
-                Some(index) => {
-                    grid_meta.fields.remove(index);
-                    grid_meta.fields.insert(index, field_rev);
-                    Ok(Some(()))
-                }
-            },
-        )
+        self.apply_operations(vec![GridOperation::ReplaceField {
+            field: field_rev.as_ref().clone(),
+        }])
     }
 
     pub fn move_field(
         &mut self,
         field_id: &str,
-        from_index: usize,
+        _from_index: usize,
         to_index: usize,
     ) -> CollaborateResult<Option<GridChangeset>> {
-        self.modify_grid(|grid_meta| {
-            match move_vec_element(
-                &mut grid_meta.fields,
-                |field| field.id == field_id,
-                from_index,
+        if to_index >= self.grid_rev.fields.len() {
+            let msg = format!(
+                "Move element to invalid index: {}, current len: {}",
                 to_index,
-            )
-            .map_err(internal_error)?
-            {
-                true => Ok(Some(())),
-                false => Ok(None),
-            }
-        })
+                self.grid_rev.fields.len()
+            );
+            return Err(internal_error(msg));
+        }
+
+        self.apply_operations(vec![GridOperation::MoveField {
+            field_id: field_id.to_owned(),
+            to: to_index,
+        }])
     }
 
     pub fn contain_field(&self, field_id: &str) -> bool {
@@ -288,26 +274,15 @@ impl GridRevisionPad {
     }
 
     pub fn create_block_meta_rev(&mut self, block: GridBlockMetaRevision) -> CollaborateResult<Option<GridChangeset>> {
-        self.modify_grid(|grid_meta| {
-            if grid_meta.blocks.iter().any(|b| b.block_id == block.block_id) {
-                tracing::warn!("Duplicate grid block");
-                Ok(None)
-            } else {
-                match grid_meta.blocks.last() {
-                    None => grid_meta.blocks.push(Arc::new(block)),
-                    Some(last_block) => {
-                        if last_block.start_row_index > block.start_row_index
-                            && last_block.len() > block.start_row_index
-                        {
-                            let msg = "GridBlock's start_row_index should be greater than the last_block's start_row_index and its len".to_string();
-                            return Err(CollaborateError::internal().context(msg))
-                        }
-                        grid_meta.blocks.push(Arc::new(block));
-                    }
-                }
-                Ok(Some(()))
+        if let Some(last_block) = self.grid_rev.blocks.last() {
+            if last_block.start_row_index > block.start_row_index && last_block.len() > block.start_row_index {
+                let msg =
+                    "GridBlock's start_row_index should be greater than the last_block's start_row_index and its len"
+                        .to_string();
+                return Err(CollaborateError::internal().context(msg));
             }
-        })
+        }
+        self.apply_operations(vec![GridOperation::InsertBlock { block }])
     }
 
     pub fn get_block_meta_revs(&self) -> Vec<Arc<GridBlockMetaRevision>> {
@@ -318,22 +293,11 @@ impl GridRevisionPad {
         &mut self,
         changeset: GridBlockMetaRevisionChangeset,
     ) -> CollaborateResult<Option<GridChangeset>> {
-        let block_id = changeset.block_id.clone();
-        self.modify_block(&block_id, |block| {
-            let mut is_changed = None;
-
-            if let Some(row_count) = changeset.row_count {
-                block.row_count = row_count;
-                is_changed = Some(());
-            }
-
-            if let Some(start_row_index) = changeset.start_row_index {
-                block.start_row_index = start_row_index;
-                is_changed = Some(());
-            }
-
-            Ok(is_changed)
-        })
+        self.apply_operations(vec![GridOperation::UpdateBlock {
+            block_id: changeset.block_id,
+            start_row_index: changeset.start_row_index,
+            row_count: changeset.row_count,
+        }])
     }
 
     pub fn get_grid_setting_rev(&self) -> &GridSettingRevision {
@@ -374,86 +338,135 @@ impl GridRevisionPad {
         &mut self,
         changeset: GridSettingChangesetParams,
     ) -> CollaborateResult<Option<GridChangeset>> {
-        self.modify_grid(|grid_rev| {
-            let mut is_changed = None;
-            let layout_rev = changeset.layout_type;
-
-            if let Some(params) = changeset.insert_filter {
-                let filter_rev = GridFilterRevision {
+        let layout = changeset.layout_type;
+        let mut operations = vec![];
+        if let Some(params) = changeset.insert_filter {
+            operations.push(GridOperation::InsertFilter {
+                layout: layout.clone(),
+                field_type: params.field_type_rev,
+                filter: GridFilterRevision {
                     id: gen_grid_filter_id(),
-                    field_id: params.field_id.clone(),
+                    field_id: params.field_id,
                     condition: params.condition,
                     content: params.content,
-                };
-
-                grid_rev
-                    .setting
-                    .insert_filter(&layout_rev, &params.field_id, &params.field_type_rev, filter_rev);
-
-                is_changed = Some(())
-            }
-            if let Some(params) = changeset.delete_filter {
-                match grid_rev
-                    .setting
-                    .get_mut_filters(&layout_rev, &params.field_id, &params.field_type_rev)
-                {
-                    Some(filters) => {
-                        filters.retain(|filter| filter.id != params.filter_id);
-                    }
-                    None => {
-                        tracing::warn!("Can't find the filter with {:?}", layout_rev);
-                    }
-                }
-            }
-            if let Some(params) = changeset.insert_group {
-                let rev = GridGroupRevision {
+                },
+            });
+        }
+        if let Some(params) = changeset.delete_filter {
+            operations.push(GridOperation::DeleteFilter {
+                layout: layout.clone(),
+                field_id: params.field_id,
+                field_type: params.field_type_rev,
+                filter_id: params.filter_id,
+            });
+        }
+        if let Some(params) = changeset.insert_group {
+            operations.push(GridOperation::InsertGroup {
+                layout: layout.clone(),
+                group: GridGroupRevision {
                     id: gen_grid_group_id(),
                     field_id: params.field_id,
                     sub_field_id: params.sub_field_id,
-                };
+                },
+            });
+        }
+        if let Some(group_id) = changeset.delete_group {
+            operations.push(GridOperation::DeleteGroup {
+                layout: layout.clone(),
+                group_id,
+            });
+        }
+        if let Some(params) = changeset.insert_sort {
+            operations.push(GridOperation::InsertSort {
+                layout: layout.clone(),
+                sort: GridSortRevision {
+                    id: gen_grid_sort_id(),
+                    field_id: params.field_id,
+                },
+            });
+        }
+        if let Some(sort_id) = changeset.delete_sort {
+            operations.push(GridOperation::DeleteSort { layout, sort_id });
+        }
+        self.apply_operations(operations)
+    }
 
-                grid_rev
-                    .setting
-                    .groups
-                    .entry(layout_rev.clone())
-                    .or_insert_with(std::vec::Vec::new)
-                    .push(rev);
+    pub fn update_field_setting(
+        &mut self,
+        params: FieldSettingChangesetParams,
+    ) -> CollaborateResult<Option<GridChangeset>> {
+        if !self.contain_field(&params.field_id) {
+            tracing::warn!("Can't find the field with id: {}", params.field_id);
+            return Ok(None);
+        }
 
-                is_changed = Some(())
-            }
-            if let Some(delete_group_id) = changeset.delete_group {
-                match grid_rev.setting.groups.get_mut(&layout_rev) {
-                    Some(groups) => groups.retain(|group| group.id != delete_group_id),
-                    None => {
-                        tracing::warn!("Can't find the group with {:?}", layout_rev);
-                    }
-                }
-            }
-            if let Some(sort) = changeset.insert_sort {
-                let rev = GridSortRevision {
-                    id: gen_grid_sort_id(),
-                    field_id: sort.field_id,
-                };
-
-                grid_rev
-                    .setting
-                    .sorts
-                    .entry(layout_rev.clone())
-                    .or_insert_with(std::vec::Vec::new)
-                    .push(rev);
-                is_changed = Some(())
-            }
+        self.apply_operations(vec![GridOperation::SetFieldSetting {
+            layout: params.layout_type,
+            field_id: params.field_id,
+            visibility: params.visibility,
+            width: params.width,
+        }])
+    }
 
-            if let Some(delete_sort_id) = changeset.delete_sort {
-                match grid_rev.setting.sorts.get_mut(&layout_rev) {
-                    Some(sorts) => sorts.retain(|sort| sort.id != delete_sort_id),
-                    None => {
-                        tracing::warn!("Can't find the sort with {:?}", layout_rev);
-                    }
-                }
-            }
-            Ok(is_changed)
-        })
+    pub fn move_layout_field(
+        &mut self,
+        layout: &GridLayoutRevision,
+        field_id: &str,
+        from_index: usize,
+        to_index: usize,
+    ) -> CollaborateResult<Option<GridChangeset>> {
+        self.apply_operations(vec![GridOperation::MoveLayoutField {
+            layout: layout.clone(),
+            field_id: field_id.to_owned(),
+            from: from_index,
+            to: to_index,
+        }])
+    }
+
+    /// Copy the visibility, width and order of the fields into the settings of each layout that
+    /// doesn't have its own field settings.
+    pub fn init_layout_field_settings(&mut self) -> CollaborateResult<Option<GridChangeset>> {
+        let operations = GridLayoutRevision::all()
+            .into_iter()
+            .map(|layout| GridOperation::InitLayoutFieldSettings { layout })
+            .collect();
+        self.apply_operations(operations)
+    }
+
+    pub fn get_row_template_revs(&self) -> Vec<Arc<RowTemplateRevision>> {
+        self.grid_rev.row_templates.clone()
+    }
+
+    pub fn get_row_template_rev(&self, template_id: &str) -> Option<Arc<RowTemplateRevision>> {
+        self.grid_rev
+            .row_templates
+            .iter()
+            .find(|template| template.id == template_id)
+            .cloned()
+    }
+
+    pub fn create_row_template(
+        &mut self,
+        template_rev: RowTemplateRevision,
+    ) -> CollaborateResult<Option<GridChangeset>> {
+        self.apply_operations(vec![GridOperation::InsertRowTemplate { template: template_rev }])
+    }
+
+    pub fn replace_row_template(
+        &mut self,
+        template_rev: RowTemplateRevision,
+    ) -> CollaborateResult<Option<GridChangeset>> {
+        if self.get_row_template_rev(&template_rev.id).is_none() {
+            tracing::warn!("Can't find the row template with id: {}", template_rev.id);
+            return Ok(None);
+        }
+        self.apply_operations(vec![GridOperation::ReplaceRowTemplate { template: template_rev }])
+    }
+
+    pub fn delete_row_template(&mut self, template_id: &str) -> CollaborateResult<Option<GridChangeset>> {
+        self.apply_operations(vec![GridOperation::DeleteRowTemplate {
+            template_id: template_id.to_owned(),
+        }])
     }
 
     pub fn md5(&self) -> String {
@@ -472,76 +485,45 @@ impl GridRevisionPad {
         &self.grid_rev.fields
     }
 
-    fn modify_grid<F>(&mut self, f: F) -> CollaborateResult<Option<GridChangeset>>
-    where
-        F: FnOnce(&mut GridRevision) -> CollaborateResult<Option<()>>,
-    {
-        let cloned_grid = self.grid_rev.clone();
-        match f(Arc::make_mut(&mut self.grid_rev))? {
-            None => Ok(None),
-            Some(_) => {
-                let old = json_from_grid(&cloned_grid)?;
-                let new = json_from_grid(&self.grid_rev)?;
-                match cal_diff::<PlainTextAttributes>(old, new) {
-                    None => Ok(None),
-                    Some(delta) => {
-                        self.delta = self.delta.compose(&delta)?;
-                        Ok(Some(GridChangeset { delta, md5: self.md5() }))
-                    }
-                }
-            }
-        }
+    /// Returns the log that contains the snapshot of the grid only, which replaces the history of
+    /// the grid.
+    pub fn snapshot_delta(&self) -> CollaborateResult<GridRevisionDelta> {
+        make_compaction_delta::<_, GridOperation>(&GridRevisionDelta::default(), self.grid_rev.as_ref(), &[])
     }
 
-    fn modify_block<F>(&mut self, block_id: &str, f: F) -> CollaborateResult<Option<GridChangeset>>
-    where
-        F: FnOnce(&mut GridBlockMetaRevision) -> CollaborateResult<Option<()>>,
-    {
-        self.modify_grid(
-            |grid_rev| match grid_rev.blocks.iter().position(|block| block.block_id == block_id) {
-                None => {
-                    tracing::warn!("[GridMetaPad]: Can't find any block with id: {}", block_id);
-                    Ok(None)
-                }
-                Some(index) => {
-                    let block_rev = Arc::make_mut(&mut grid_rev.blocks[index]);
-                    f(block_rev)
-                }
-            },
-        )
-    }
+    fn apply_operations(&mut self, operations: Vec<GridOperation>) -> CollaborateResult<Option<GridChangeset>> {
+        // The log is compacted with the snapshot of the grid before the operations.
+        let snapshot = match count_log_lines(&self.delta) >= GRID_LOG_COMPACTION_LINES {
+            true => Some(self.grid_rev.clone()),
+            false => None,
+        };
+        let grid_rev = Arc::make_mut(&mut self.grid_rev);
+        let operations = operations
+            .into_iter()
+            .filter(|operation| operation.apply(grid_rev))
+            .collect::<Vec<_>>();
+        if operations.is_empty() {
+            return Ok(None);
+        }
 
-    fn modify_field<F>(&mut self, field_id: &str, f: F) -> CollaborateResult<Option<GridChangeset>>
-    where
-        F: FnOnce(&mut FieldRevision) -> CollaborateResult<Option<()>>,
-    {
-        self.modify_grid(
-            |grid_rev| match grid_rev.fields.iter().position(|field| field.id == field_id) {
-                None => {
-                    tracing::warn!("[GridMetaPad]: Can't find any field with id: {}", field_id);
-                    Ok(None)
-                }
-                Some(index) => {
-                    let mut_field_rev = Arc::make_mut(&mut grid_rev.fields[index]);
-                    f(mut_field_rev)
-                }
-            },
-        )
+        let delta = match snapshot {
+            None => make_append_delta(&self.delta, &operations)?,
+            Some(snapshot) => make_compaction_delta(&self.delta, snapshot.as_ref(), &operations)?,
+        };
+        self.delta = self.delta.compose(&delta)?;
+        Ok(Some(GridChangeset { delta, md5: self.md5() }))
     }
 }
 
-fn json_from_grid(grid: &Arc<GridRevision>) -> CollaborateResult<String> {
-    let json = serde_json::to_string(grid)
-        .map_err(|err| internal_error(format!("Serialize grid to json str failed. {:?}", err)))?;
-    Ok(json)
-}
-
 pub struct GridChangeset {
     pub delta: GridRevisionDelta,
     /// md5: the md5 of the grid after applying the change.
     pub md5: String,
 }
 
+/// Returns the delta that starts the grid's log with the snapshot of the grid. The grids that
+/// were saved before the [GridOperation]s consist of the snapshot only, so they are loaded as
+/// they are and the operations are appended after the snapshot.
 pub fn make_grid_delta(grid_rev: &GridRevision) -> GridRevisionDelta {
     let json = serde_json::to_string(&grid_rev).unwrap();
     PlainTextDeltaBuilder::new().insert(&json).build()
@@ -549,7 +531,7 @@ pub fn make_grid_delta(grid_rev: &GridRevision) -> GridRevisionDelta {
 
 pub fn make_grid_revisions(user_id: &str, grid_rev: &GridRevision) -> RepeatedRevision {
     let delta = make_grid_delta(grid_rev);
-    let bytes = delta.to_delta_bytes();
+    let bytes = delta.to_compact_bytes();
     let revision = Revision::initial_revision(user_id, &grid_rev.grid_id, bytes);
     revision.into()
 }
@@ -1,7 +1,9 @@
 mod grid_block_revsion_pad;
 mod grid_builder;
+mod grid_operation;
 mod grid_revision_pad;
 
 pub use grid_block_revsion_pad::*;
 pub use grid_builder::*;
+pub use grid_operation::*;
 pub use grid_revision_pad::*;
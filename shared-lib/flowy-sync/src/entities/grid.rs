@@ -36,6 +36,14 @@ pub struct CreateGridSortParams {
     pub field_id: Option<String>,
 }
 
+pub struct FieldSettingChangesetParams {
+    pub grid_id: String,
+    pub layout_type: GridLayoutRevision,
+    pub field_id: String,
+    pub visibility: Option<bool>,
+    pub width: Option<i32>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct FieldChangesetParams {
     pub field_id: String,
@@ -55,4 +63,7 @@ pub struct FieldChangesetParams {
     pub width: Option<i32>,
 
     pub type_option_data: Option<Vec<u8>>,
+
+    /// An empty string means removing the default value of the field.
+    pub default_value: Option<String>,
 }
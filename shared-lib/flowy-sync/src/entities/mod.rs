@@ -1,6 +1,7 @@
 pub mod folder;
 pub mod grid;
 pub mod parser;
+pub mod presence;
 pub mod revision;
 pub mod text_block;
 pub mod ws_data;
@@ -0,0 +1,137 @@
+use flowy_derive::{ProtoBuf, ProtoBuf_Enum};
+use lib_ot::core::Interval;
+
+/// The client sends the heartbeat of its presences at this interval, so the server knows the
+/// user is still viewing the object.
+pub const PRESENCE_HEARTBEAT_INTERVAL_IN_MILLIS: u64 = 5000;
+
+/// The server removes the presence of the user if it doesn't receive anything from the user in
+/// this duration, the other viewers receive a [PresenceWSDataType::Leave] for it.
+pub const PRESENCE_TIMEOUT_IN_MILLIS: u64 = 15000;
+
+#[derive(Debug, Clone, ProtoBuf_Enum, Eq, PartialEq, Hash)]
+pub enum PresenceWSDataType {
+    /// The user moved the selection or focused another cell.
+    Update = 0,
+    /// The user is still viewing the object, nothing changed.
+    Heartbeat = 1,
+    /// The user closed the object, or the server didn't receive the heartbeat in time.
+    Leave = 2,
+}
+
+impl std::default::Default for PresenceWSDataType {
+    fn default() -> Self {
+        PresenceWSDataType::Update
+    }
+}
+
+#[derive(ProtoBuf, Default, Debug, Clone, Eq, PartialEq)]
+pub struct PresenceSelection {
+    #[pb(index = 1)]
+    pub start: i64,
+
+    #[pb(index = 2)]
+    pub end: i64,
+}
+
+impl std::convert::From<Interval> for PresenceSelection {
+    fn from(interval: Interval) -> Self {
+        PresenceSelection {
+            start: interval.start as i64,
+            end: interval.end as i64,
+        }
+    }
+}
+
+impl std::convert::From<&PresenceSelection> for Interval {
+    fn from(selection: &PresenceSelection) -> Self {
+        let start = selection.start.max(0) as usize;
+        let end = selection.end.max(selection.start).max(0) as usize;
+        Interval::new(start, end)
+    }
+}
+
+#[derive(ProtoBuf, Default, Debug, Clone, Eq, PartialEq)]
+pub struct PresenceCell {
+    #[pb(index = 1)]
+    pub row_id: String,
+
+    #[pb(index = 2)]
+    pub field_id: String,
+}
+
+/// The ephemeral state of a user viewing a document or a grid. It's sent over the presence
+/// channel of the web socket and never saved, so it doesn't go through the revisions.
+#[derive(ProtoBuf, Default, Debug, Clone, Eq, PartialEq)]
+pub struct PresenceWSData {
+    #[pb(index = 1)]
+    pub object_id: String,
+
+    #[pb(index = 2)]
+    pub user_id: String,
+
+    #[pb(index = 3)]
+    pub ty: PresenceWSDataType,
+
+    /// The selection of the user in the document.
+    #[pb(index = 4, one_of)]
+    pub selection: Option<PresenceSelection>,
+
+    /// The cell that the user focused in the grid.
+    #[pb(index = 5, one_of)]
+    pub cell: Option<PresenceCell>,
+
+    /// The revision of the object that the user had when the selection was made, the selection
+    /// is an interval of the document at this revision.
+    #[pb(index = 6)]
+    pub rev_id: i64,
+}
+
+impl PresenceWSData {
+    pub fn selection(object_id: &str, user_id: &str, rev_id: i64, interval: Interval) -> Self {
+        Self {
+            object_id: object_id.to_owned(),
+            user_id: user_id.to_owned(),
+            ty: PresenceWSDataType::Update,
+            selection: Some(interval.into()),
+            cell: None,
+            rev_id,
+        }
+    }
+
+    pub fn cell(object_id: &str, user_id: &str, row_id: &str, field_id: &str) -> Self {
+        Self {
+            object_id: object_id.to_owned(),
+            user_id: user_id.to_owned(),
+            ty: PresenceWSDataType::Update,
+            selection: None,
+            cell: Some(PresenceCell {
+                row_id: row_id.to_owned(),
+                field_id: field_id.to_owned(),
+            }),
+            rev_id: 0,
+        }
+    }
+
+    pub fn heartbeat(object_id: &str, user_id: &str) -> Self {
+        Self {
+            object_id: object_id.to_owned(),
+            user_id: user_id.to_owned(),
+            ty: PresenceWSDataType::Heartbeat,
+            selection: None,
+            cell: None,
+            rev_id: 0,
+        }
+    }
+
+    pub fn leave(object_id: &str, user_id: &str) -> Self {
+        Self {
+            object_id: object_id.to_owned(),
+            user_id: user_id.to_owned(),
+            ty: PresenceWSDataType::Leave,
+            selection: None,
+            cell: None,
+            rev_id: 0,
+        }
+    }
+}
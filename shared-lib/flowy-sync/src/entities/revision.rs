@@ -1,6 +1,6 @@
 use bytes::Bytes;
 use flowy_derive::{ProtoBuf, ProtoBuf_Enum};
-use lib_ot::rich_text::RichTextDelta;
+use lib_ot::{core::DeltaFormat, rich_text::RichTextDelta};
 use std::{convert::TryFrom, fmt::Formatter, ops::RangeInclusive};
 
 #[derive(PartialEq, Eq, Clone, Default, ProtoBuf)]
@@ -25,6 +25,11 @@ pub struct Revision {
 
     #[pb(index = 7)]
     pub user_id: String,
+
+    /// The format of the `delta_data`. The revision that was created before the format was
+    /// recorded is in the json format.
+    #[pb(index = 8)]
+    pub format: RevisionFormat,
 }
 
 impl std::convert::From<Vec<u8>> for Revision {
@@ -62,6 +67,7 @@ impl Revision {
     ) -> Revision {
         let user_id = user_id.to_owned();
         let object_id = object_id.to_owned();
+        let format = DeltaFormat::from_bytes(&delta_data).into();
         let delta_data = delta_data.to_vec();
         let base_rev_id = base_rev_id;
         let rev_id = rev_id;
@@ -78,6 +84,7 @@ impl Revision {
             object_id,
             ty: RevType::DeprecatedLocal,
             user_id,
+            format,
         }
     }
 }
@@ -215,6 +222,27 @@ pub fn md5<T: AsRef<[u8]>>(data: T) -> String {
     md5
 }
 
+#[derive(Debug, ProtoBuf_Enum, Clone, Copy, Eq, PartialEq)]
+pub enum RevisionFormat {
+    Json = 0,
+    Compact = 1,
+}
+
+impl std::default::Default for RevisionFormat {
+    fn default() -> Self {
+        RevisionFormat::Json
+    }
+}
+
+impl std::convert::From<DeltaFormat> for RevisionFormat {
+    fn from(format: DeltaFormat) -> Self {
+        match format {
+            DeltaFormat::Json => RevisionFormat::Json,
+            DeltaFormat::Compact => RevisionFormat::Compact,
+        }
+    }
+}
+
 #[derive(Debug, ProtoBuf_Enum, Clone, Eq, PartialEq)]
 pub enum RevType {
     DeprecatedLocal = 0,
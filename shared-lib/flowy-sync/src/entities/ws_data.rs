@@ -77,6 +77,9 @@ pub enum ServerRevisionWSDataType {
     ServerPushRev = 1,
     ServerPullRev = 2,
     UserConnect = 3,
+    /// The revision that the server made by transforming its revisions against the revision sent
+    /// by the user. It's based on the document after the sent revision.
+    ServerPushTransformedRev = 4,
 }
 
 impl std::default::Default for ServerRevisionWSDataType {
@@ -108,6 +111,15 @@ impl ServerRevisionWSDataBuilder {
         }
     }
 
+    pub fn build_transformed_push_message(object_id: &str, revision: Revision) -> ServerRevisionWSData {
+        let bytes: Bytes = RepeatedRevision::new(vec![revision]).try_into().unwrap();
+        ServerRevisionWSData {
+            object_id: object_id.to_string(),
+            ty: ServerRevisionWSDataType::ServerPushTransformedRev,
+            data: bytes.to_vec(),
+        }
+    }
+
     pub fn build_pull_message(object_id: &str, range: RevisionRange) -> ServerRevisionWSData {
         let bytes: Bytes = range.try_into().unwrap();
         ServerRevisionWSData {
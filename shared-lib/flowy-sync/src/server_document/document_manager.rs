@@ -146,6 +146,22 @@ impl ServerDocumentManager {
         }
     }
 
+    /// Removes the disconnected user from the opened documents.
+    pub async fn remove_user(&self, user_id: &str) {
+        let handlers = self
+            .document_handlers
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect::<Vec<Arc<OpenDocumentHandler>>>();
+        for handler in handlers {
+            if let Err(e) = handler.remove_user(user_id).await {
+                tracing::error!("Remove the user from {} failed: {}", handler.doc_id, e);
+            }
+        }
+    }
+
     async fn get_document_handler(&self, doc_id: &str) -> Option<Arc<OpenDocumentHandler>> {
         if let Some(handler) = self.document_handlers.read().await.get(doc_id).cloned() {
             return Some(handler);
@@ -259,6 +275,17 @@ impl OpenDocumentHandler {
         result
     }
 
+    async fn remove_user(&self, user_id: &str) -> Result<(), CollaborateError> {
+        let (ret, rx) = oneshot::channel();
+        self.users.remove(user_id);
+        let msg = DocumentCommand::RemoveUser {
+            user_id: user_id.to_owned(),
+            ret,
+        };
+        let result = self.send(msg, rx).await?;
+        result
+    }
+
     async fn send<T>(&self, msg: DocumentCommand, rx: oneshot::Receiver<T>) -> CollaborateResult<T> {
         let _ = self
             .sender
@@ -291,6 +318,10 @@ enum DocumentCommand {
         repeated_revision: RepeatedRevisionPB,
         ret: oneshot::Sender<CollaborateResult<()>>,
     },
+    RemoveUser {
+        user_id: String,
+        ret: oneshot::Sender<CollaborateResult<()>>,
+    },
 }
 
 struct DocumentCommandRunner {
@@ -351,6 +382,10 @@ impl DocumentCommandRunner {
                 let result = self.synchronizer.reset(repeated_revision).await.map_err(internal_error);
                 let _ = ret.send(result);
             }
+            DocumentCommand::RemoveUser { user_id, ret } => {
+                self.synchronizer.remove_user(&user_id);
+                let _ = ret.send(Ok(()));
+            }
         }
     }
 }
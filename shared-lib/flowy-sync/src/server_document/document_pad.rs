@@ -45,4 +45,8 @@ impl RevisionSyncObject<RichTextAttributes> for ServerDocument {
     fn set_delta(&mut self, new_delta: Delta<RichTextAttributes>) {
         self.delta = new_delta;
     }
+
+    fn target_len(&self) -> usize {
+        self.delta.utf16_target_len
+    }
 }
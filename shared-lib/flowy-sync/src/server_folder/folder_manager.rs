@@ -132,6 +132,22 @@ impl ServerFolderManager {
         }
     }
 
+    /// Removes the disconnected user from the opened folders.
+    pub async fn remove_user(&self, user_id: &str) {
+        let handlers = self
+            .folder_handlers
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect::<Vec<Arc<OpenFolderHandler>>>();
+        for handler in handlers {
+            if let Err(e) = handler.remove_user(user_id).await {
+                tracing::error!("Remove the user from {} failed: {}", handler.folder_id, e);
+            }
+        }
+    }
+
     async fn get_folder_handler(&self, user_id: &str, folder_id: &str) -> Option<Arc<OpenFolderHandler>> {
         let folder_id = folder_id.to_owned();
         if let Some(handler) = self.folder_handlers.read().await.get(&folder_id).cloned() {
@@ -239,6 +255,15 @@ impl OpenFolderHandler {
         self.send(msg, rx).await?
     }
 
+    async fn remove_user(&self, user_id: &str) -> Result<(), CollaborateError> {
+        let (ret, rx) = oneshot::channel();
+        let msg = FolderCommand::RemoveUser {
+            user_id: user_id.to_owned(),
+            ret,
+        };
+        self.send(msg, rx).await?
+    }
+
     async fn send<T>(&self, msg: FolderCommand, rx: oneshot::Receiver<T>) -> CollaborateResult<T> {
         let _ = self
             .sender
@@ -266,6 +291,10 @@ enum FolderCommand {
         rev_id: i64,
         ret: oneshot::Sender<CollaborateResult<()>>,
     },
+    RemoveUser {
+        user_id: String,
+        ret: oneshot::Sender<CollaborateResult<()>>,
+    },
 }
 
 struct FolderCommandRunner {
@@ -321,6 +350,10 @@ impl FolderCommandRunner {
                 let result = self.synchronizer.pong(user, rev_id).await.map_err(internal_error);
                 let _ = ret.send(result);
             }
+            FolderCommand::RemoveUser { user_id, ret } => {
+                self.synchronizer.remove_user(&user_id);
+                let _ = ret.send(Ok(()));
+            }
         }
     }
 }
@@ -38,4 +38,8 @@ impl RevisionSyncObject<PlainTextAttributes> for ServerFolder {
     fn set_delta(&mut self, new_delta: PlainTextDelta) {
         self.delta = new_delta;
     }
+
+    fn target_len(&self) -> usize {
+        self.delta.utf16_target_len
+    }
 }
@@ -0,0 +1,281 @@
+use crate::entities::presence::{PresenceWSData, PresenceWSDataType, PRESENCE_TIMEOUT_IN_MILLIS};
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub trait PresenceUser: Send + Sync + Debug {
+    /// Identifies the connection of the user, the presences are never sent back to the
+    /// connection that sent them.
+    fn user_id(&self) -> String;
+    fn receive(&self, data: PresenceWSData);
+}
+
+struct PresenceSubscriber {
+    user: Arc<dyn PresenceUser>,
+    /// The id of the user that the client reported, it's used to build the leave message when
+    /// the subscriber times out.
+    client_user_id: String,
+    /// The last update of the subscriber, it's sent to the users that open the object later.
+    presence: Option<PresenceWSData>,
+    last_seen: Instant,
+}
+
+/// Routes the presences of the users to the other users viewing the same object. Nothing is
+/// persisted, the presences are dropped when the users leave or stop sending the heartbeat.
+pub struct ServerPresenceManager {
+    objects: RwLock<HashMap<String, HashMap<String, PresenceSubscriber>>>,
+    timeout: Duration,
+}
+
+impl std::default::Default for ServerPresenceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerPresenceManager {
+    pub fn new() -> Self {
+        Self {
+            objects: RwLock::new(HashMap::new()),
+            timeout: Duration::from_millis(PRESENCE_TIMEOUT_IN_MILLIS),
+        }
+    }
+
+    pub fn handle_client_presence(&self, user: Arc<dyn PresenceUser>, data: PresenceWSData) {
+        let user_id = user.user_id();
+        let mut messages: Vec<(Arc<dyn PresenceUser>, PresenceWSData)> = vec![];
+        {
+            let mut objects = self.objects.write();
+            match data.ty {
+                PresenceWSDataType::Leave => {
+                    if let Some(subscribers) = objects.get_mut(&data.object_id) {
+                        if subscribers.remove(&user_id).is_some() {
+                            messages.extend(broadcast(subscribers, &user_id, &data));
+                        }
+                        if subscribers.is_empty() {
+                            objects.remove(&data.object_id);
+                        }
+                    }
+                }
+                PresenceWSDataType::Update | PresenceWSDataType::Heartbeat => {
+                    let subscribers = objects.entry(data.object_id.clone()).or_default();
+                    if !subscribers.contains_key(&user_id) {
+                        // Catches up the new viewer with the presences of the others.
+                        subscribers
+                            .values()
+                            .flat_map(|subscriber| subscriber.presence.clone())
+                            .for_each(|presence| messages.push((user.clone(), presence)));
+                    }
+
+                    let subscriber = subscribers
+                        .entry(user_id.clone())
+                        .or_insert_with(|| PresenceSubscriber {
+                            user: user.clone(),
+                            client_user_id: data.user_id.clone(),
+                            presence: None,
+                            last_seen: Instant::now(),
+                        });
+                    subscriber.user = user.clone();
+                    subscriber.client_user_id = data.user_id.clone();
+                    subscriber.last_seen = Instant::now();
+                    if data.ty == PresenceWSDataType::Update {
+                        subscriber.presence = Some(data.clone());
+                        messages.extend(broadcast(subscribers, &user_id, &data));
+                    }
+                }
+            }
+        }
+        send_messages(messages);
+    }
+
+    /// Removes the users that didn't send anything in the timeout, the other users viewing the
+    /// same object receive a [PresenceWSDataType::Leave] for each of them.
+    pub fn remove_inactive_users(&self, now: Instant) {
+        let timeout = self.timeout;
+        self.remove_users(|subscriber| now.saturating_duration_since(subscriber.last_seen) > timeout);
+    }
+
+    /// Removes the user from all the objects, for example: the connection of the user was
+    /// closed.
+    pub fn remove_user(&self, user_id: &str) {
+        self.remove_users(|subscriber| subscriber.user.user_id() == user_id);
+    }
+
+    fn remove_users<F>(&self, predicate: F)
+    where
+        F: Fn(&PresenceSubscriber) -> bool,
+    {
+        let mut messages = vec![];
+        {
+            let mut objects = self.objects.write();
+            for (object_id, subscribers) in objects.iter_mut() {
+                let removed_users = subscribers
+                    .iter()
+                    .filter(|(_, subscriber)| predicate(subscriber))
+                    .map(|(user_id, _)| user_id.clone())
+                    .collect::<Vec<String>>();
+
+                for user_id in removed_users {
+                    if let Some(subscriber) = subscribers.remove(&user_id) {
+                        tracing::trace!("[ServerPresence]: {} left {}", subscriber.client_user_id, object_id);
+                        let leave = PresenceWSData::leave(object_id, &subscriber.client_user_id);
+                        messages.extend(broadcast(subscribers, &user_id, &leave));
+                    }
+                }
+            }
+            objects.retain(|_, subscribers| !subscribers.is_empty());
+        }
+        send_messages(messages);
+    }
+
+    /// Returns the ids of the users that are viewing the object.
+    pub fn viewers(&self, object_id: &str) -> Vec<String> {
+        let mut viewers = self
+            .objects
+            .read()
+            .get(object_id)
+            .map(|subscribers| {
+                subscribers
+                    .values()
+                    .map(|subscriber| subscriber.client_user_id.clone())
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        viewers.sort();
+        viewers
+    }
+}
+
+fn broadcast(
+    subscribers: &HashMap<String, PresenceSubscriber>,
+    from_user_id: &str,
+    data: &PresenceWSData,
+) -> Vec<(Arc<dyn PresenceUser>, PresenceWSData)> {
+    subscribers
+        .iter()
+        .filter(|(user_id, _)| user_id.as_str() != from_user_id)
+        .map(|(_, subscriber)| (subscriber.user.clone(), data.clone()))
+        .collect()
+}
+
+// The users receive the messages after the lock is released.
+fn send_messages(messages: Vec<(Arc<dyn PresenceUser>, PresenceWSData)>) {
+    for (user, data) in messages {
+        user.receive(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib_ot::core::Interval;
+    use parking_lot::Mutex;
+
+    #[derive(Debug, Default)]
+    struct MockPresenceUser {
+        user_id: String,
+        received: Mutex<Vec<PresenceWSData>>,
+    }
+
+    impl MockPresenceUser {
+        fn new(user_id: &str) -> Arc<Self> {
+            Arc::new(Self {
+                user_id: user_id.to_owned(),
+                received: Mutex::new(vec![]),
+            })
+        }
+
+        fn take(&self) -> Vec<PresenceWSData> {
+            std::mem::take(&mut *self.received.lock())
+        }
+    }
+
+    impl PresenceUser for MockPresenceUser {
+        fn user_id(&self) -> String {
+            self.user_id.clone()
+        }
+
+        fn receive(&self, data: PresenceWSData) {
+            self.received.lock().push(data);
+        }
+    }
+
+    #[test]
+    fn presence_routed_to_other_viewers_test() {
+        let manager = ServerPresenceManager::new();
+        let user_a = MockPresenceUser::new("a");
+        let user_b = MockPresenceUser::new("b");
+        let user_c = MockPresenceUser::new("c");
+
+        let selection = PresenceWSData::selection("doc", "a", 1, Interval::new(1, 3));
+        manager.handle_client_presence(user_a.clone(), selection.clone());
+        manager.handle_client_presence(user_b.clone(), PresenceWSData::heartbeat("doc", "b"));
+        manager.handle_client_presence(user_c.clone(), PresenceWSData::heartbeat("grid", "c"));
+        assert!(user_a.take().is_empty());
+        // The late viewer receives the presences that were sent before it joined.
+        assert_eq!(user_b.take(), vec![selection]);
+        assert!(user_c.take().is_empty());
+
+        let cell = PresenceWSData::cell("grid", "c", "row", "field");
+        manager.handle_client_presence(user_c.clone(), cell);
+        let selection = PresenceWSData::selection("doc", "b", 1, Interval::new(2, 2));
+        manager.handle_client_presence(user_b.clone(), selection.clone());
+        assert_eq!(user_a.take(), vec![selection]);
+        assert!(user_b.take().is_empty());
+        assert!(user_c.take().is_empty());
+        assert_eq!(manager.viewers("doc"), vec!["a".to_owned(), "b".to_owned()]);
+    }
+
+    #[test]
+    fn presence_leave_test() {
+        let manager = ServerPresenceManager::new();
+        let user_a = MockPresenceUser::new("a");
+        let user_b = MockPresenceUser::new("b");
+        manager.handle_client_presence(user_a.clone(), PresenceWSData::heartbeat("doc", "a"));
+        manager.handle_client_presence(user_b.clone(), PresenceWSData::heartbeat("doc", "b"));
+
+        manager.handle_client_presence(user_b.clone(), PresenceWSData::leave("doc", "b"));
+        let received = user_a.take();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].ty, PresenceWSDataType::Leave);
+        assert_eq!(received[0].user_id, "b");
+        assert_eq!(manager.viewers("doc"), vec!["a".to_owned()]);
+
+        manager.remove_user("a");
+        assert!(manager.viewers("doc").is_empty());
+        assert!(user_b.take().is_empty());
+    }
+
+    #[test]
+    fn presence_timeout_test() {
+        let manager = ServerPresenceManager::new();
+        let user_a = MockPresenceUser::new("a");
+        let user_b = MockPresenceUser::new("b");
+        manager.handle_client_presence(user_a.clone(), PresenceWSData::heartbeat("doc", "a"));
+        manager.handle_client_presence(user_b.clone(), PresenceWSData::heartbeat("doc", "b"));
+
+        manager.remove_inactive_users(Instant::now());
+        assert_eq!(manager.viewers("doc").len(), 2);
+
+        // Only b keeps sending the heartbeat.
+        let timeout = Duration::from_millis(PRESENCE_TIMEOUT_IN_MILLIS);
+        let later = Instant::now() + timeout;
+        manager
+            .objects
+            .write()
+            .get_mut("doc")
+            .unwrap()
+            .get_mut("b")
+            .unwrap()
+            .last_seen = later;
+        manager.remove_inactive_users(later + Duration::from_millis(1));
+
+        assert_eq!(manager.viewers("doc"), vec!["b".to_owned()]);
+        assert_eq!(user_b.take(), vec![PresenceWSData::leave("doc", "a")]);
+        assert!(user_a.take().is_empty());
+    }
+}
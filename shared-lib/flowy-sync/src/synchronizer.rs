@@ -1,18 +1,20 @@
 use crate::{
     entities::{
-        revision::RevisionRange,
+        revision::{Revision, RevisionRange},
         ws_data::{ServerRevisionWSData, ServerRevisionWSDataBuilder},
     },
     errors::CollaborateError,
     protobuf::{RepeatedRevision as RepeatedRevisionPB, Revision as RevisionPB},
     util::*,
 };
+use dashmap::DashMap;
 use lib_infra::future::BoxResultFuture;
-use lib_ot::core::{Attributes, Delta};
+use lib_ot::core::{Attributes, Delta, OperationTransformable};
 use parking_lot::RwLock;
 use serde::de::DeserializeOwned;
 use std::{
     cmp::Ordering,
+    convert::TryInto,
     fmt::Debug,
     sync::{
         atomic::{AtomicI64, Ordering::SeqCst},
@@ -48,6 +50,8 @@ pub trait RevisionSyncObject<T: Attributes>: Send + Sync + 'static {
     fn transform(&self, other: &Delta<T>) -> Result<(Delta<T>, Delta<T>), CollaborateError>;
     fn to_json(&self) -> String;
     fn set_delta(&mut self, new_delta: Delta<T>);
+    /// The utf16 length of the object.
+    fn target_len(&self) -> usize;
 }
 
 pub enum RevisionSyncResponse {
@@ -61,6 +65,11 @@ pub struct RevisionSynchronizer<T: Attributes> {
     rev_id: AtomicI64,
     object: Arc<RwLock<dyn RevisionSyncObject<T>>>,
     persistence: Arc<dyn RevisionSyncPersistence>,
+    users: DashMap<String, Arc<dyn RevisionUser>>,
+    /// The rev_id and md5 of the last revision of each user that was transformed before being
+    /// applied. The user sends the revision again until it receives the ack, so it's kept until
+    /// the next revision of the user is applied or the user is removed.
+    transformed_revisions: DashMap<String, (i64, String)>,
 }
 
 impl<T> RevisionSynchronizer<T>
@@ -80,6 +89,8 @@ where
             rev_id: AtomicI64::new(rev_id),
             object,
             persistence,
+            users: DashMap::new(),
+            transformed_revisions: DashMap::new(),
         }
     }
 
@@ -90,6 +101,8 @@ where
         repeated_revision: RepeatedRevisionPB,
     ) -> Result<(), CollaborateError> {
         let object_id = self.object_id.clone();
+        let user_id = user.user_id();
+        self.users.insert(user_id.clone(), user.clone());
         if repeated_revision.get_items().is_empty() {
             // Return all the revisions to client
             let revisions = self.persistence.read_revisions(&object_id, None).await?;
@@ -101,7 +114,10 @@ where
 
         let server_base_rev_id = self.rev_id.load(SeqCst);
         let first_revision = repeated_revision.get_items().first().unwrap().clone();
-        if self.is_applied_before(&first_revision, &self.persistence).await {
+        if self
+            .is_applied_before(&user_id, &first_revision, &self.persistence)
+            .await
+        {
             // Server has received this revision before, so ignore the following revisions
             return Ok(());
         }
@@ -114,7 +130,11 @@ where
                     for revision in repeated_revision.get_items() {
                         let _ = self.compose_revision(revision)?;
                     }
+                    let revisions = repeated_revision.get_items().to_vec();
                     let _ = self.persistence.save_revisions(repeated_revision).await?;
+                    let _ = self.broadcast_revisions(&user, revisions)?;
+                    // The user received the ack of its transformed revision before sending this one.
+                    self.transformed_revisions.remove(&user_id);
                 } else {
                     // The server delta is outdated, pull the missing revision from the client.
                     let range = RevisionRange {
@@ -125,17 +145,10 @@ where
                     user.receive(RevisionSyncResponse::Pull(msg));
                 }
             }
-            Ordering::Equal => {
-                // Do nothing
-                tracing::trace!("Applied {} revision rev_id is the same as cur_rev_id", self.object_id);
-            }
-            Ordering::Greater => {
-                // The client delta is outdated. Transform the client revision delta and then
-                // send the prime delta to the client. Client should compose the this prime
-                // delta.
-                let from_rev_id = first_revision.rev_id;
-                let to_rev_id = server_base_rev_id;
-                let _ = self.push_revisions_to_user(user, from_rev_id, to_rev_id).await;
+            Ordering::Equal | Ordering::Greater => {
+                // The client revisions are based on an outdated revision. Transform them against
+                // the server revisions that the client hasn't seen yet and apply them.
+                let _ = self.transform_revisions(user, repeated_revision).await?;
             }
         }
         Ok(())
@@ -144,6 +157,7 @@ where
     #[tracing::instrument(level = "trace", skip(self, user), fields(server_rev_id), err)]
     pub async fn pong(&self, user: Arc<dyn RevisionUser>, client_rev_id: i64) -> Result<(), CollaborateError> {
         let object_id = self.object_id.clone();
+        self.users.insert(user.user_id(), user.clone());
         let server_rev_id = self.rev_id();
         tracing::Span::current().record("server_rev_id", &server_rev_id);
         match server_rev_id.cmp(&client_rev_id) {
@@ -181,6 +195,12 @@ where
         self.object.read().to_json()
     }
 
+    /// Removes the user that is disconnected, the revisions are not pushed to it anymore.
+    pub fn remove_user(&self, user_id: &str) {
+        self.users.remove(user_id);
+        self.transformed_revisions.remove(user_id);
+    }
+
     fn compose_revision(&self, revision: &RevisionPB) -> Result<(), CollaborateError> {
         let delta = Delta::<T>::from_bytes(&revision.delta_data)?;
         let _ = self.compose_delta(delta)?;
@@ -188,11 +208,98 @@ where
         Ok(())
     }
 
-    #[tracing::instrument(level = "debug", skip(self, revision))]
-    fn transform_revision(&self, revision: &RevisionPB) -> Result<(Delta<T>, Delta<T>), CollaborateError> {
-        let cli_delta = Delta::<T>::from_bytes(&revision.delta_data)?;
-        let result = self.object.read().transform(&cli_delta)?;
-        Ok(result)
+    /// Transforms the client revisions against the server revisions after their base revision,
+    /// then applies and saves them as a new revision. The new revision is pushed to the other
+    /// users, and the server revisions transformed against the client revisions are pushed to
+    /// the user as a transformed revision, which brings both to the state of the server. The
+    /// transformed revision is based on the document after the client revisions, so the user
+    /// composes it without transforming it again.
+    #[tracing::instrument(level = "debug", skip(self, user, repeated_revision), err)]
+    async fn transform_revisions(
+        &self,
+        user: Arc<dyn RevisionUser>,
+        repeated_revision: RepeatedRevisionPB,
+    ) -> Result<(), CollaborateError> {
+        let client_revisions = repeated_revision.get_items().to_vec();
+        let first_revision = client_revisions.first().unwrap().clone();
+        let last_revision = client_revisions.last().unwrap().clone();
+        let server_rev_id = self.rev_id();
+        let rev_ids: Vec<i64> = (next(first_revision.base_rev_id)..=server_rev_id).collect();
+        let server_revisions = self
+            .persistence
+            .read_revisions(&self.object_id, Some(rev_ids.clone()))
+            .await?;
+        if server_revisions.len() != rev_ids.len() {
+            // The client has to transform its revisions if the server revisions are incomplete.
+            tracing::trace!("{}: can not read the revisions in range {:?}", self.object_id, rev_ids);
+            let _ = self
+                .push_revisions_to_user(user, first_revision.rev_id, server_rev_id)
+                .await;
+            return Ok(());
+        }
+
+        let server_delta = make_delta_from_revision_pb::<T>(server_revisions)?;
+        let client_delta = make_delta_from_revision_pb::<T>(client_revisions)?;
+        // The deltas omit the trailing retain, so they are completed to the length of the document
+        // at the base revision, which is the current length without the changes of the server delta.
+        let object_len = self.object.read().target_len();
+        let base_len = (object_len + server_delta.utf16_base_len)
+            .checked_sub(server_delta.utf16_target_len)
+            .ok_or_else(|| {
+                CollaborateError::revision_conflict().context(format!(
+                    "{}: the server revisions change {} of {} in total",
+                    self.object_id, server_delta.utf16_target_len, object_len
+                ))
+            })?;
+        let server_delta = complete_delta(server_delta, base_len)?;
+        let client_delta = complete_delta(client_delta, base_len)?;
+        let (server_prime, client_prime) = server_delta.transform(&client_delta)?;
+
+        let _ = self.compose_delta(client_prime.clone())?;
+        let rev_id = next(server_rev_id);
+        let md5 = md5(self.object_json());
+        let make_revision = |delta: &Delta<T>| {
+            Revision::new(
+                &self.object_id,
+                server_rev_id,
+                rev_id,
+                delta.to_compact_bytes(),
+                &last_revision.user_id,
+                md5.clone(),
+            )
+        };
+        let client_revision: RevisionPB = make_revision(&client_prime)
+            .try_into()
+            .map_err(|e| CollaborateError::internal().context(format!("{:?}", e)))?;
+        let server_revision = make_revision(&server_prime);
+
+        let _ = self
+            .persistence
+            .save_revisions(repeated_revision_pb_from_revisions(vec![client_revision.clone()]))
+            .await?;
+        let _ = self.rev_id.fetch_update(SeqCst, SeqCst, |_e| Some(rev_id));
+        self.transformed_revisions
+            .insert(user.user_id(), (first_revision.rev_id, first_revision.md5.clone()));
+
+        let _ = self.broadcast_revisions(&user, vec![client_revision])?;
+        let data = ServerRevisionWSDataBuilder::build_transformed_push_message(&self.object_id, server_revision);
+        user.receive(RevisionSyncResponse::Push(data));
+        Ok(())
+    }
+
+    /// Pushes the revisions to all the users except the one that sent them.
+    fn broadcast_revisions(
+        &self,
+        user: &Arc<dyn RevisionUser>,
+        revisions: Vec<RevisionPB>,
+    ) -> Result<(), CollaborateError> {
+        let user_id = user.user_id();
+        let repeated_revision = repeated_revision_from_revision_pbs(revisions)?;
+        for other_user in self.users.iter().filter(|other_user| other_user.key() != &user_id) {
+            let data = ServerRevisionWSDataBuilder::build_push_message(&self.object_id, repeated_revision.clone());
+            other_user.value().receive(RevisionSyncResponse::Push(data));
+        }
+        Ok(())
     }
 
     fn compose_delta(&self, delta: Delta<T>) -> Result<(), CollaborateError> {
@@ -215,9 +322,17 @@ where
 
     async fn is_applied_before(
         &self,
+        user_id: &str,
         new_revision: &RevisionPB,
         persistence: &Arc<dyn RevisionSyncPersistence>,
     ) -> bool {
+        if let Some(transformed_revision) = self.transformed_revisions.get(user_id) {
+            let (rev_id, md5) = transformed_revision.value();
+            if *rev_id == new_revision.rev_id && md5 == &new_revision.md5 {
+                return true;
+            }
+        }
+
         let rev_ids = Some(vec![new_revision.rev_id]);
         if let Ok(revisions) = persistence.read_revisions(&self.object_id, rev_ids).await {
             if let Some(revision) = revisions.first() {
@@ -262,3 +377,18 @@ where
 fn next(rev_id: i64) -> i64 {
     rev_id + 1
 }
+
+/// Appends the trailing retain that the delta omits, the delta can't be longer than the document.
+fn complete_delta<T: Attributes>(mut delta: Delta<T>, len: usize) -> Result<Delta<T>, CollaborateError> {
+    match delta.utf16_base_len.cmp(&len) {
+        Ordering::Less => {
+            delta.retain(len - delta.utf16_base_len, T::default());
+            Ok(delta)
+        }
+        Ordering::Equal => Ok(delta),
+        Ordering::Greater => Err(CollaborateError::revision_conflict().context(format!(
+            "The base length of the delta is {}, but the length of the document is {}",
+            delta.utf16_base_len, len
+        ))),
+    }
+}
@@ -11,12 +11,12 @@ use crate::{
     },
 };
 use dissimilar::Chunk;
-use lib_ot::core::{DeltaBuilder, FlowyStr};
+use lib_ot::core::{DeltaBuilder, FlowyStr, Operation, PlainTextDelta, PlainTextDeltaBuilder};
 use lib_ot::{
     core::{Attributes, Delta, OperationTransformable, NEW_LINE, WHITESPACE},
     rich_text::RichTextDelta,
 };
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
 use std::{
     convert::TryInto,
     sync::atomic::{AtomicI64, Ordering::SeqCst},
@@ -281,3 +281,164 @@ pub fn cal_diff<T: Attributes>(old: String, new: String) -> Option<Delta<T>> {
         Some(delta)
     }
 }
+
+/// An entry of an operation log. The text of the log contains one JSON value per line, which is
+/// either an operation or a snapshot of the whole object. The objects that were saved as JSON
+/// before they switched to operations start with their snapshot.
+pub(crate) enum OperationLogEntry<O, S> {
+    Operation(O),
+    Snapshot(S),
+}
+
+/// Parses the entries of the log from its last snapshot, the snapshot replaces everything that
+/// comes before it. The replicas that compact the same log concurrently insert the same snapshot,
+/// so the log is parsed from the first occurrence of the snapshot and its repeats are skipped.
+pub(crate) fn parse_operation_log<O, S>(text: &str) -> CollaborateResult<Vec<OperationLogEntry<O, S>>>
+where
+    O: DeserializeOwned,
+    S: DeserializeOwned,
+{
+    let lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<&str>>();
+    let mut operations = vec![];
+    let mut entries = vec![];
+    for (index, line) in lines.iter().enumerate().rev() {
+        match parse_operation_log_line(line)? {
+            entry @ OperationLogEntry::Operation(_) => operations.push(entry),
+            OperationLogEntry::Snapshot(snapshot) => {
+                let start = lines[..index].iter().position(|other| other == line).unwrap_or(index);
+                entries.push(OperationLogEntry::Snapshot(snapshot));
+                for other in lines[start..index].iter().skip(1).filter(|other| *other != line) {
+                    entries.push(parse_operation_log_line(other)?);
+                }
+                break;
+            }
+        }
+    }
+    entries.extend(operations.into_iter().rev());
+    Ok(entries)
+}
+
+fn parse_operation_log_line<O, S>(line: &str) -> CollaborateResult<OperationLogEntry<O, S>>
+where
+    O: DeserializeOwned,
+    S: DeserializeOwned,
+{
+    match serde_json::from_str::<O>(line) {
+        Ok(operation) => Ok(OperationLogEntry::Operation(operation)),
+        Err(e) => {
+            let snapshot = serde_json::from_str::<S>(line).map_err(|_| {
+                tracing::error!("Deserialize operation failed: {}", line);
+                CollaborateError::internal().context(format!("Deserialize operation failed: {}", e))
+            })?;
+            Ok(OperationLogEntry::Snapshot(snapshot))
+        }
+    }
+}
+
+/// Returns the text that the delta appends to the end of the log, or None if the delta changes
+/// the lines of the log, e.g. it inserts the remote operations before the local ones.
+pub(crate) fn appended_text(log: &PlainTextDelta, delta: &PlainTextDelta) -> Option<String> {
+    if delta.utf16_base_len != log.utf16_target_len {
+        return None;
+    }
+    match delta.ops.as_slice() {
+        [Operation::Insert(insert)] if log.utf16_target_len == 0 => Some(insert.s.to_string()),
+        [Operation::Retain(retain), Operation::Insert(insert)] if retain.n == log.utf16_target_len => {
+            Some(insert.s.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the delta that appends the operations to the end of the operation log. Concurrent
+/// appends are transformed into appends too, so the lines of the log never break.
+pub(crate) fn make_append_delta<O: Serialize>(
+    log: &PlainTextDelta,
+    operations: &[O],
+) -> CollaborateResult<PlainTextDelta> {
+    // The snapshot of the object that was saved as JSON doesn't end with a line break.
+    let mut lines = match log.ops.last() {
+        Some(Operation::Insert(insert)) if !insert.s.ends_with('\n') => "\n".to_owned(),
+        _ => String::new(),
+    };
+    push_log_lines(&mut lines, operations)?;
+
+    Ok(PlainTextDeltaBuilder::new()
+        .retain(log.utf16_target_len)
+        .insert(&lines)
+        .build())
+}
+
+/// Returns the delta that replaces the log with the snapshot of the object before the operations,
+/// followed by the operations. The snapshot is inserted at the start of the log, so the
+/// operations that are appended concurrently follow it after the transform.
+pub(crate) fn make_compaction_delta<S: Serialize, O: Serialize>(
+    log: &PlainTextDelta,
+    snapshot: &S,
+    operations: &[O],
+) -> CollaborateResult<PlainTextDelta> {
+    let mut lines = String::new();
+    push_log_lines(&mut lines, &[snapshot])?;
+    push_log_lines(&mut lines, operations)?;
+
+    Ok(PlainTextDeltaBuilder::new()
+        .insert(&lines)
+        .delete(log.utf16_target_len)
+        .build())
+}
+
+/// Returns the number of lines of the log.
+pub(crate) fn count_log_lines(log: &PlainTextDelta) -> usize {
+    log.ops
+        .iter()
+        .map(|op| match op {
+            Operation::Insert(insert) => insert.s.matches('\n').count(),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn push_log_lines<T: Serialize>(lines: &mut String, values: &[T]) -> CollaborateResult<()> {
+    for value in values {
+        let json = serde_json::to_string(value)
+            .map_err(|e| CollaborateError::internal().context(format!("Serialize operation failed: {}", e)))?;
+        lines.push_str(&json);
+        lines.push('\n');
+    }
+    Ok(())
+}
+
+/// Moves the element to the index `to`, which is clamped to the last index. Returns false if
+/// the element doesn't exist or is already at the index.
+pub(crate) fn move_element<T, F>(elements: &mut Vec<T>, filter: F, to: usize) -> bool
+where
+    F: FnMut(&T) -> bool,
+{
+    match elements.iter().position(filter) {
+        None => false,
+        Some(index) => {
+            let to = to.min(elements.len() - 1);
+            if index == to {
+                return false;
+            }
+            let element = elements.remove(index);
+            elements.insert(to, element);
+            true
+        }
+    }
+}
+
+/// Sets the value if `new_value` is Some and differs from the value. Returns true if the value
+/// is changed.
+pub(crate) fn set_if_changed<T: Clone + PartialEq>(value: &mut T, new_value: &Option<T>) -> bool {
+    match new_value {
+        Some(new_value) if value != new_value => {
+            *value = new_value.clone();
+            true
+        }
+        _ => false,
+    }
+}
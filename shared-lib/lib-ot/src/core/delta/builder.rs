@@ -1,4 +1,4 @@
-use crate::core::{trim, Attributes, Delta, PlainTextAttributes};
+use crate::core::{trim, Attributes, Delta, EmbedData, PlainTextAttributes};
 
 pub type PlainTextDeltaBuilder = DeltaBuilder<PlainTextAttributes>;
 
@@ -48,6 +48,16 @@ where
         self
     }
 
+    pub fn embed_with_attributes(mut self, data: EmbedData, attrs: T) -> Self {
+        self.delta.embed(data, attrs);
+        self
+    }
+
+    pub fn embed(mut self, data: EmbedData) -> Self {
+        self.delta.embed(data, T::default());
+        self
+    }
+
     pub fn trim(mut self) -> Self {
         trim(&mut self.delta);
         self
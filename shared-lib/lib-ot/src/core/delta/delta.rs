@@ -1,5 +1,5 @@
 use crate::{
-    core::{operation::*, DeltaIter, FlowyStr, Interval, OperationTransformable, MAX_IV_LEN},
+    core::{operation::*, DeltaFormat, DeltaIter, FlowyStr, Interval, OperationTransformable, MAX_IV_LEN},
     errors::{ErrorBuilder, OTError, OTErrorCode},
 };
 
@@ -85,6 +85,7 @@ where
         match op {
             Operation::Delete(i) => self.delete(i),
             Operation::Insert(i) => self.insert(&i.s, i.attributes),
+            Operation::Embed(e) => self.embed(e.data, e.attributes),
             Operation::Retain(r) => self.retain(r.n, r.attributes),
         }
     }
@@ -131,6 +132,22 @@ where
         }
     }
 
+    /// Inserts the embed that takes one utf16 code unit. The embed is never merged with the
+    /// adjacent inserts.
+    pub fn embed(&mut self, data: EmbedData, attributes: T) {
+        self.utf16_target_len += 1;
+        let new_op = OpBuilder::<T>::embed(data).attributes(attributes).build();
+        match self.ops.last_mut() {
+            Some(op_last @ Operation::<T>::Delete(_)) => {
+                // Keep the insert before the delete, same as the insert.
+                let new_last = op_last.clone();
+                *op_last = new_op;
+                self.ops.push(new_last);
+            }
+            _ => self.ops.push(new_op),
+        }
+    }
+
     pub fn retain(&mut self, n: usize, attributes: T) {
         if n == 0 {
             return;
@@ -160,22 +177,21 @@ where
                 .build());
         }
         let mut new_s = String::new();
-        let code_point_iter = &mut s.utf16_code_unit_iter();
+        let chars = &mut s.chars();
         for op in &self.ops {
             match &op {
                 Operation::Retain(retain) => {
-                    for c in code_point_iter.take(retain.n as usize) {
-                        new_s.push_str(str::from_utf8(c.0).unwrap_or(""));
-                    }
+                    new_s += &take_utf16_code_units(chars, retain.n);
                 }
                 Operation::Delete(delete) => {
-                    for _ in 0..*delete {
-                        code_point_iter.next();
-                    }
+                    let _ = take_utf16_code_units(chars, *delete);
                 }
                 Operation::Insert(insert) => {
                     new_s += &insert.s;
                 }
+                Operation::Embed(_) => {
+                    new_s += EMBED_PLACEHOLDER;
+                }
             }
         }
         Ok(new_s)
@@ -190,17 +206,16 @@ where
             match &op {
                 Operation::Retain(retain) => {
                     inverted.retain(retain.n, T::default());
-                    // TODO: use advance_by instead, but it's unstable now
-                    // chars.advance_by(retain.num)
-                    for _ in 0..retain.n {
-                        chars.next();
-                    }
+                    let _ = take_utf16_code_units(chars, retain.n);
                 }
                 Operation::Insert(insert) => {
                     inverted.delete(insert.utf16_size());
                 }
+                Operation::Embed(_) => {
+                    inverted.delete(1);
+                }
                 Operation::Delete(delete) => {
-                    inverted.insert(&chars.take(*delete as usize).collect::<String>(), op.get_attributes());
+                    inverted.insert(&take_utf16_code_units(chars, *delete), op.get_attributes());
                 }
             }
         }
@@ -270,6 +285,11 @@ where
                     composed_attrs.remove_empty();
                     new_delta.add(OpBuilder::insert(op.get_data()).attributes(composed_attrs).build())
                 }
+                (Operation::Embed(embed), Operation::Retain(other_retain)) => {
+                    let mut composed_attrs = embed.attributes.compose(&other_retain.attributes)?;
+                    composed_attrs.remove_empty();
+                    new_delta.add(OpBuilder::embed(embed.data.clone()).attributes(composed_attrs).build())
+                }
                 (Operation::Retain(_), Operation::Delete(_)) => {
                     new_delta.add(other_op);
                 }
@@ -313,10 +333,21 @@ where
                     b_prime.retain(insert.utf16_size(), insert.attributes.clone());
                     next_op1 = ops1.next();
                 }
+                (Some(Operation::Embed(embed)), _) => {
+                    a_prime.embed(embed.data.clone(), embed.attributes.clone());
+                    b_prime.retain(1, embed.attributes.clone());
+                    next_op1 = ops1.next();
+                }
+                // The text inserted by the other is kept as it is, otherwise the attributes of the
+                // insert are lost on one side and the documents diverge.
                 (_, Some(Operation::Insert(o_insert))) => {
-                    let composed_attrs = transform_op_attribute(&next_op1, &next_op2)?;
-                    a_prime.retain(o_insert.utf16_size(), composed_attrs.clone());
-                    b_prime.insert(&o_insert.s, composed_attrs);
+                    a_prime.retain(o_insert.utf16_size(), T::default());
+                    b_prime.insert(&o_insert.s, o_insert.attributes.clone());
+                    next_op2 = ops2.next();
+                }
+                (_, Some(Operation::Embed(o_embed))) => {
+                    a_prime.retain(1, T::default());
+                    b_prime.embed(o_embed.data.clone(), o_embed.attributes.clone());
                     next_op2 = ops2.next();
                 }
                 (None, _) => {
@@ -430,7 +461,7 @@ where
                     }
                     index += len;
                 }
-                Operation::Insert(_) => {
+                Operation::Insert(_) | Operation::Embed(_) => {
                     // tracing::trace!("invert insert: {} by delete {}", op, len);
                     inverted.delete(len as usize);
                 }
@@ -447,11 +478,31 @@ where
 {
     if let Some(last) = delta.ops.last() {
         if last.is_retain() && last.is_plain() {
+            let len = last.len();
             delta.ops.pop();
+            delta.utf16_base_len -= len;
+            delta.utf16_target_len -= len;
         }
     }
 }
 
+// Takes the chars that are `n` utf16 code units long, the lengths of the operations are counted
+// in the utf16 code units.
+fn take_utf16_code_units(chars: &mut str::Chars, n: usize) -> String {
+    let mut s = String::new();
+    let mut len = 0;
+    while len < n {
+        match chars.next() {
+            None => break,
+            Some(c) => {
+                len += c.len_utf16();
+                s.push(c);
+            }
+        }
+    }
+    s
+}
+
 fn invert_from_other<T: Attributes>(
     base: &mut Delta<T>,
     other: &Delta<T>,
@@ -475,7 +526,7 @@ fn invert_from_other<T: Attributes>(
             let inverted_attrs = operation.get_attributes().invert(&other_op.get_attributes());
             base.retain(other_op.len(), inverted_attrs);
         }
-        Operation::Insert(_) => {
+        Operation::Insert(_) | Operation::Embed(_) => {
             log::error!("Impossible to here. Insert operation should be treated as delete")
         }
     });
@@ -510,7 +561,11 @@ where
         Ok(delta)
     }
 
+    /// Decodes the bytes in either the compact or the json format.
     pub fn from_bytes<B: AsRef<[u8]>>(bytes: B) -> Result<Self, OTError> {
+        if DeltaFormat::from_bytes(&bytes) == DeltaFormat::Compact {
+            return Self::from_compact_bytes(bytes.as_ref());
+        }
         let json = str::from_utf8(bytes.as_ref())?.to_owned();
         let val = Self::from_delta_str(&json)?;
         Ok(val)
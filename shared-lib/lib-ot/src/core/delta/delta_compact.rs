@@ -0,0 +1,364 @@
+use crate::{
+    core::{operation::*, Delta},
+    errors::{ErrorBuilder, OTError, OTErrorCode},
+};
+use bytes::Bytes;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value};
+use std::{collections::HashMap, str};
+
+// The compact bytes start with the byte that never appears in the utf8 text, so they can't be
+// confused with the json bytes.
+const COMPACT_MAGIC: u8 = 0xFF;
+const COMPACT_VERSION: u8 = 1;
+
+// The kind of the operation is kept in the low bits of its header, the length is kept in the rest.
+const KIND_BITS: u32 = 3;
+const DELETE: u64 = 0;
+const RETAIN: u64 = 1;
+const RETAIN_WITH_ATTRIBUTES: u64 = 2;
+const INSERT: u64 = 3;
+const INSERT_WITH_ATTRIBUTES: u64 = 4;
+const EMBED: u64 = 5;
+const EMBED_WITH_ATTRIBUTES: u64 = 6;
+
+const VALUE_NULL: u8 = 0;
+const VALUE_FALSE: u8 = 1;
+const VALUE_TRUE: u8 = 2;
+const VALUE_STRING: u8 = 3;
+const VALUE_INT: u8 = 4;
+const VALUE_UINT: u8 = 5;
+const VALUE_FLOAT: u8 = 6;
+const VALUE_ARRAY: u8 = 7;
+const VALUE_OBJECT: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaFormat {
+    Json,
+    /// The versioned binary encoding. The lengths are varints, the keys of the attributes are
+    /// stored once per delta and the retain without attributes takes a single varint.
+    Compact,
+}
+
+impl DeltaFormat {
+    pub fn from_bytes<B: AsRef<[u8]>>(bytes: B) -> Self {
+        match bytes.as_ref().first() {
+            Some(&COMPACT_MAGIC) => DeltaFormat::Compact,
+            _ => DeltaFormat::Json,
+        }
+    }
+}
+
+impl<T> Delta<T>
+where
+    T: Attributes + Serialize,
+{
+    pub fn to_compact_bytes(&self) -> Bytes {
+        let mut keys = KeyTable::default();
+        let mut body = vec![];
+        write_varint(&mut body, self.ops.len() as u64);
+        for op in &self.ops {
+            let attributes = op.get_attributes();
+            let has_attributes = !attributes.is_empty();
+            match op {
+                Operation::Delete(n) => write_header(&mut body, DELETE, *n),
+                Operation::Retain(retain) => {
+                    let kind = if has_attributes { RETAIN_WITH_ATTRIBUTES } else { RETAIN };
+                    write_header(&mut body, kind, retain.n);
+                }
+                Operation::Insert(insert) => {
+                    let kind = if has_attributes { INSERT_WITH_ATTRIBUTES } else { INSERT };
+                    write_header(&mut body, kind, insert.s.len());
+                    body.extend_from_slice(insert.s.as_bytes());
+                }
+                Operation::Embed(embed) => {
+                    let kind = if has_attributes { EMBED_WITH_ATTRIBUTES } else { EMBED };
+                    write_header(&mut body, kind, 0);
+                    let data = serde_json::to_value(&embed.data).unwrap_or(Value::Null);
+                    write_value(&mut body, &mut keys, &data);
+                }
+            }
+
+            if has_attributes {
+                let attributes = serde_json::to_value(&attributes).unwrap_or(Value::Null);
+                write_value(&mut body, &mut keys, &attributes);
+            }
+        }
+
+        let mut bytes = vec![COMPACT_MAGIC, COMPACT_VERSION];
+        write_varint(&mut bytes, keys.keys.len() as u64);
+        for key in &keys.keys {
+            write_str(&mut bytes, key);
+        }
+        bytes.extend(body);
+        Bytes::from(bytes)
+    }
+}
+
+impl<T> Delta<T>
+where
+    T: Attributes + DeserializeOwned,
+{
+    pub(crate) fn from_compact_bytes(bytes: &[u8]) -> Result<Self, OTError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.read_u8()? != COMPACT_MAGIC {
+            return Err(serde_error("The bytes are not in the compact format"));
+        }
+        let version = reader.read_u8()?;
+        if version != COMPACT_VERSION {
+            return Err(serde_error(format!("Unsupported compact delta version {}", version)));
+        }
+
+        let key_count = reader.read_varint()?;
+        let mut keys = vec![];
+        for _ in 0..key_count {
+            keys.push(reader.read_str()?.to_owned());
+        }
+
+        let op_count = reader.read_varint()?;
+        let mut delta = Delta::new();
+        for _ in 0..op_count {
+            let header = reader.read_varint()?;
+            let n = (header >> KIND_BITS) as usize;
+            let kind = header & ((1 << KIND_BITS) - 1);
+            let mut op = match kind {
+                DELETE => OpBuilder::delete(n).build(),
+                RETAIN | RETAIN_WITH_ATTRIBUTES => OpBuilder::retain(n).build(),
+                INSERT | INSERT_WITH_ATTRIBUTES => OpBuilder::insert(reader.read_utf8(n)?).build(),
+                EMBED | EMBED_WITH_ATTRIBUTES => {
+                    let data = serde_json::from_value::<EmbedData>(reader.read_value(&keys)?)?;
+                    OpBuilder::embed(data).build()
+                }
+                _ => return Err(serde_error(format!("Unknown operation kind {}", kind))),
+            };
+
+            if kind == RETAIN_WITH_ATTRIBUTES || kind == INSERT_WITH_ATTRIBUTES || kind == EMBED_WITH_ATTRIBUTES {
+                let attributes = serde_json::from_value::<T>(reader.read_value(&keys)?)?;
+                op.set_attributes(attributes);
+            }
+            delta.add(op);
+        }
+
+        if reader.pos != bytes.len() {
+            return Err(serde_error("Unexpected bytes after the last operation"));
+        }
+        Ok(delta)
+    }
+}
+
+#[derive(Default)]
+struct KeyTable {
+    keys: Vec<String>,
+    indexes: HashMap<String, u64>,
+}
+
+impl KeyTable {
+    fn intern(&mut self, key: &str) -> u64 {
+        if let Some(index) = self.indexes.get(key) {
+            return *index;
+        }
+        let index = self.keys.len() as u64;
+        self.keys.push(key.to_owned());
+        self.indexes.insert(key.to_owned(), index);
+        index
+    }
+}
+
+fn write_header(bytes: &mut Vec<u8>, kind: u64, n: usize) {
+    write_varint(bytes, ((n as u64) << KIND_BITS) | kind);
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_str(bytes: &mut Vec<u8>, s: &str) {
+    write_varint(bytes, s.len() as u64);
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+fn write_value(bytes: &mut Vec<u8>, keys: &mut KeyTable, value: &Value) {
+    match value {
+        Value::Null => bytes.push(VALUE_NULL),
+        Value::Bool(false) => bytes.push(VALUE_FALSE),
+        Value::Bool(true) => bytes.push(VALUE_TRUE),
+        Value::String(s) => {
+            bytes.push(VALUE_STRING);
+            write_str(bytes, s);
+        }
+        Value::Number(number) => {
+            if let Some(n) = number.as_i64() {
+                bytes.push(VALUE_INT);
+                // Zigzag, so the small negative number is small too.
+                write_varint(bytes, ((n << 1) ^ (n >> 63)) as u64);
+            } else if let Some(n) = number.as_u64() {
+                bytes.push(VALUE_UINT);
+                write_varint(bytes, n);
+            } else {
+                bytes.push(VALUE_FLOAT);
+                bytes.extend_from_slice(&number.as_f64().unwrap_or_default().to_le_bytes());
+            }
+        }
+        Value::Array(values) => {
+            bytes.push(VALUE_ARRAY);
+            write_varint(bytes, values.len() as u64);
+            for value in values {
+                write_value(bytes, keys, value);
+            }
+        }
+        Value::Object(map) => {
+            bytes.push(VALUE_OBJECT);
+            write_varint(bytes, map.len() as u64);
+            for (key, value) in map {
+                write_varint(bytes, keys.intern(key));
+                write_value(bytes, keys, value);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_u8(&mut self) -> Result<u8, OTError> {
+        let byte = *self.bytes.get(self.pos).ok_or_else(unexpected_end)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn read_varint(&mut self) -> Result<u64, OTError> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift >= 64 {
+                return Err(serde_error("The varint is too long"));
+            }
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], OTError> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len());
+        let end = end.ok_or_else(unexpected_end)?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_utf8(&mut self, len: usize) -> Result<&'a str, OTError> {
+        let s = str::from_utf8(self.read_bytes(len)?)?;
+        Ok(s)
+    }
+
+    fn read_str(&mut self) -> Result<&'a str, OTError> {
+        let len = self.read_varint()? as usize;
+        self.read_utf8(len)
+    }
+
+    fn read_value(&mut self, keys: &[String]) -> Result<Value, OTError> {
+        let value = match self.read_u8()? {
+            VALUE_NULL => Value::Null,
+            VALUE_FALSE => Value::Bool(false),
+            VALUE_TRUE => Value::Bool(true),
+            VALUE_STRING => Value::String(self.read_str()?.to_owned()),
+            VALUE_INT => {
+                let n = self.read_varint()?;
+                Value::Number(((n >> 1) as i64 ^ -((n & 1) as i64)).into())
+            }
+            VALUE_UINT => Value::Number(self.read_varint()?.into()),
+            VALUE_FLOAT => {
+                let mut buf = [0; 8];
+                buf.copy_from_slice(self.read_bytes(8)?);
+                Number::from_f64(f64::from_le_bytes(buf)).map_or(Value::Null, Value::Number)
+            }
+            VALUE_ARRAY => {
+                let len = self.read_varint()?;
+                let mut values = vec![];
+                for _ in 0..len {
+                    values.push(self.read_value(keys)?);
+                }
+                Value::Array(values)
+            }
+            VALUE_OBJECT => {
+                let len = self.read_varint()?;
+                let mut map = Map::new();
+                for _ in 0..len {
+                    let index = self.read_varint()? as usize;
+                    let key = keys
+                        .get(index)
+                        .ok_or_else(|| serde_error(format!("Unknown key index {}", index)))?;
+                    map.insert(key.clone(), self.read_value(keys)?);
+                }
+                Value::Object(map)
+            }
+            tag => return Err(serde_error(format!("Unknown value tag {}", tag))),
+        };
+        Ok(value)
+    }
+}
+
+fn serde_error<T: Into<String>>(msg: T) -> OTError {
+    ErrorBuilder::new(OTErrorCode::SerdeError).msg(msg).build()
+}
+
+fn unexpected_end() -> OTError {
+    serde_error("Unexpected end of the compact delta")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{Delta, DeltaBuilder, DeltaFormat, EmbedData, PlainTextAttributes};
+    use crate::rich_text::{RichTextAttribute, RichTextDelta};
+
+    #[test]
+    fn compact_bytes_round_trip() {
+        let delta: RichTextDelta = DeltaBuilder::new()
+            .retain(300)
+            .retain_with_attributes(5, RichTextAttribute::Bold(true).into())
+            .insert("你好, world")
+            .insert_with_attributes("link", RichTextAttribute::Link("https://appflowy.io").into())
+            .insert_with_attributes("\n", RichTextAttribute::Header(1).into())
+            .embed(EmbedData::image("https://appflowy.io/logo.png"))
+            .delete(7)
+            .build();
+        let bytes = delta.to_compact_bytes();
+        assert_eq!(DeltaFormat::from_bytes(&bytes), DeltaFormat::Compact);
+        assert_eq!(RichTextDelta::from_bytes(&bytes).unwrap(), delta);
+        assert!(bytes.len() < delta.to_delta_bytes().len());
+    }
+
+    #[test]
+    fn compact_bytes_fallback_to_json() {
+        let delta: Delta<PlainTextAttributes> = DeltaBuilder::new().retain(2).insert("abc").delete(1).build();
+        let json = delta.to_delta_bytes();
+        assert_eq!(DeltaFormat::from_bytes(&json), DeltaFormat::Json);
+        assert_eq!(Delta::<PlainTextAttributes>::from_bytes(&json).unwrap(), delta);
+        assert_eq!(
+            Delta::<PlainTextAttributes>::from_bytes(delta.to_compact_bytes()).unwrap(),
+            delta
+        );
+    }
+
+    #[test]
+    fn compact_bytes_reject_unknown_version() {
+        let delta: RichTextDelta = DeltaBuilder::new().insert("abc").build();
+        let mut bytes = delta.to_compact_bytes().to_vec();
+        bytes[1] = 2;
+        assert!(RichTextDelta::from_bytes(&bytes).is_err());
+        bytes[1] = 1;
+        bytes.truncate(bytes.len() - 1);
+        assert!(RichTextDelta::from_bytes(&bytes).is_err());
+    }
+}
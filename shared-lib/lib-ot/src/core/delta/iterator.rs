@@ -197,6 +197,10 @@ where
                 attributes.extend_other(insert.attributes.clone());
                 length = insert.utf16_size();
             }
+            Operation::<T>::Embed(embed) => {
+                attributes.extend_other(embed.attributes.clone());
+                length = 1;
+            }
         }
 
         Some((length, attributes))
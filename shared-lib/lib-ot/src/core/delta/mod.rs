@@ -2,13 +2,17 @@
 mod builder;
 mod cursor;
 mod delta;
+mod delta_compact;
 mod delta_serde;
 mod iterator;
+mod position;
 
 pub use builder::*;
 pub use cursor::*;
 pub use delta::*;
+pub use delta_compact::*;
 pub use iterator::*;
+pub use position::*;
 
 pub const NEW_LINE: &str = "\n";
 pub const WHITESPACE: &str = " ";
@@ -0,0 +1,146 @@
+use crate::core::{Attributes, Delta, Interval, Operation};
+use std::cmp::max;
+
+/// The side of the text, that is inserted at the position, the position sticks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bias {
+    /// The position stays before the inserted text.
+    Left,
+    /// The position moves after the inserted text.
+    Right,
+}
+
+impl<T> Delta<T>
+where
+    T: Attributes,
+{
+    /// Returns the position after the delta is applied to the document. The position in the text
+    /// that is deleted moves to the start of the deletion.
+    pub fn transform_position(&self, index: usize, bias: Bias) -> usize {
+        self.transform_positions(&[(index, bias)])[0]
+    }
+
+    /// Transforms the positions in one pass over the delta, the positions are returned in the
+    /// same order.
+    pub fn transform_positions(&self, positions: &[(usize, Bias)]) -> Vec<usize> {
+        let mut sorted = (0..positions.len()).collect::<Vec<usize>>();
+        // The position that sticks to the left goes first, it stops before the insert at it.
+        sorted.sort_by_key(|i| (positions[*i].0, positions[*i].1 == Bias::Right));
+
+        let mut transformed = vec![0; positions.len()];
+        let mut ops = self.ops.iter().peekable();
+        // The offsets of the next op in the document before and after the delta is applied. The
+        // inserts at the offset may be passed already, so the offset before them is kept too.
+        let mut base = 0;
+        let mut target = 0;
+        let mut target_before_inserts = 0;
+        for i in sorted {
+            let (index, bias) = positions[i];
+            // The position is in the text that was deleted.
+            let mut index = max(index, base);
+            transformed[i] = loop {
+                if index == base && bias == Bias::Left {
+                    break target_before_inserts;
+                }
+                let op = match ops.peek() {
+                    None => break target + index - base,
+                    Some(op) => op,
+                };
+                match op {
+                    Operation::Retain(retain) => {
+                        if index < base + retain.n {
+                            break target + index - base;
+                        }
+                        base += retain.n;
+                        target += retain.n;
+                        target_before_inserts = target;
+                    }
+                    Operation::Insert(_) | Operation::Embed(_) => target += op.len(),
+                    // The inserts next to the deletion are at the same position, the text
+                    // between them is deleted.
+                    Operation::Delete(n) => {
+                        index = max(index, base + n);
+                        base += n;
+                    }
+                }
+                ops.next();
+            };
+        }
+        transformed
+    }
+
+    /// Returns the interval after the delta is applied to the document. The interval is empty if
+    /// the text in it is deleted.
+    pub fn transform_interval(&self, interval: Interval, start_bias: Bias, end_bias: Bias) -> Interval {
+        self.transform_intervals(&[interval], start_bias, end_bias)[0]
+    }
+
+    /// Transforms the intervals in one pass over the delta.
+    pub fn transform_intervals(&self, intervals: &[Interval], start_bias: Bias, end_bias: Bias) -> Vec<Interval> {
+        let positions = intervals
+            .iter()
+            .flat_map(|interval| [(interval.start, start_bias), (interval.end, end_bias)])
+            .collect::<Vec<(usize, Bias)>>();
+        self.transform_positions(&positions)
+            .chunks(2)
+            .map(|position| Interval::new(position[0], max(position[0], position[1])))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{Bias, DeltaBuilder, Interval};
+    use crate::rich_text::RichTextDelta;
+
+    #[test]
+    fn transform_position_with_insert() {
+        let delta: RichTextDelta = DeltaBuilder::new().retain(2).insert("abc").build();
+        assert_eq!(delta.transform_position(1, Bias::Left), 1);
+        assert_eq!(delta.transform_position(2, Bias::Left), 2);
+        assert_eq!(delta.transform_position(2, Bias::Right), 5);
+        assert_eq!(delta.transform_position(4, Bias::Left), 7);
+    }
+
+    #[test]
+    fn transform_position_with_delete() {
+        let delta: RichTextDelta = DeltaBuilder::new().retain(2).delete(3).insert("a").build();
+        assert_eq!(delta.transform_position(2, Bias::Right), 3);
+        assert_eq!(delta.transform_position(3, Bias::Left), 2);
+        assert_eq!(delta.transform_position(3, Bias::Right), 3);
+        assert_eq!(delta.transform_position(5, Bias::Left), 2);
+        assert_eq!(delta.transform_position(6, Bias::Left), 4);
+    }
+
+    #[test]
+    fn transform_positions_in_one_pass() {
+        let delta: RichTextDelta = DeltaBuilder::new().insert("ab").retain(3).delete(2).insert("c").build();
+        let positions = vec![
+            (9, Bias::Left),
+            (0, Bias::Left),
+            (6, Bias::Right),
+            (0, Bias::Right),
+            (3, Bias::Left),
+        ];
+        assert_eq!(delta.transform_positions(&positions), vec![10, 0, 7, 2, 5]);
+
+        // The positions in the deleted text are moved to the same position, the bias decides
+        // which side of the insert they go.
+        let delta: RichTextDelta = DeltaBuilder::new().delete(3).insert("x").retain(5).build();
+        let positions = vec![(1, Bias::Right), (2, Bias::Left)];
+        assert_eq!(delta.transform_positions(&positions), vec![1, 0]);
+    }
+
+    #[test]
+    fn transform_interval_with_delete_spanning_it() {
+        let delta: RichTextDelta = DeltaBuilder::new().retain(1).delete(10).build();
+        let interval = delta.transform_interval(Interval::new(3, 6), Bias::Right, Bias::Left);
+        assert_eq!(interval, Interval::new(1, 1));
+
+        let delta: RichTextDelta = DeltaBuilder::new().retain(3).insert("ab").build();
+        let interval = delta.transform_interval(Interval::new(3, 3), Bias::Right, Bias::Left);
+        assert_eq!(interval, Interval::new(5, 5));
+        let interval = delta.transform_interval(Interval::new(1, 3), Bias::Right, Bias::Right);
+        assert_eq!(interval, Interval::new(1, 5));
+    }
+}
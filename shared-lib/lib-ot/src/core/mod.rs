@@ -1,13 +1,17 @@
 mod delta;
 mod flowy_str;
 mod interval;
+mod node;
 mod operation;
+mod rope;
 
 use crate::errors::OTError;
 pub use delta::*;
 pub use flowy_str::*;
 pub use interval::*;
+pub use node::*;
 pub use operation::*;
+pub use rope::*;
 
 pub trait OperationTransformable {
     /// Merges the operation with `other` into one operation while preserving
@@ -0,0 +1,12 @@
+#![allow(clippy::module_inception)]
+mod node;
+mod node_tree;
+mod operation;
+mod path;
+mod transaction;
+
+pub use node::*;
+pub use node_tree::*;
+pub use operation::*;
+pub use path::*;
+pub use transaction::*;
@@ -0,0 +1,52 @@
+use crate::rich_text::RichTextDelta;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub type NodeAttributes = BTreeMap<String, String>;
+
+/// The changes of the attributes of a node. A `None` value removes the attribute.
+pub type NodeAttributesChangeset = BTreeMap<String, Option<String>>;
+
+/// A node of the [NodeTree](crate::core::NodeTree), e.g. a paragraph, a toggle list or a
+/// column. The text content of the node, if any, is a [RichTextDelta] that is edited with
+/// [NodeOperation::EditText](crate::core::NodeOperation::EditText).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Node {
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: NodeAttributes,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta: Option<RichTextDelta>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn new(node_type: &str) -> Self {
+        Self {
+            node_type: node_type.to_owned(),
+            attributes: NodeAttributes::new(),
+            delta: None,
+            children: vec![],
+        }
+    }
+
+    pub fn with_attribute(mut self, key: &str, value: &str) -> Self {
+        self.attributes.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    pub fn with_delta(mut self, delta: RichTextDelta) -> Self {
+        self.delta = Some(delta);
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<Node>) -> Self {
+        self.children = children;
+        self
+    }
+}
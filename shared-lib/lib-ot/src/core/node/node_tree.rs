@@ -0,0 +1,256 @@
+use crate::core::{Node, NodeAttributesChangeset, NodeOperation, OperationTransformable, Path, Transaction};
+use crate::errors::{ErrorBuilder, OTError, OTErrorCode};
+use crate::rich_text::RichTextDelta;
+use serde::{Deserialize, Serialize};
+
+/// A document made of nested [Node]s. The tree is changed by applying [NodeOperation]s, each of
+/// which returns the operation that undoes it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct NodeTree {
+    nodes: Vec<Node>,
+}
+
+impl NodeTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_nodes(nodes: Vec<Node>) -> Self {
+        Self { nodes }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, OTError> {
+        let tree = serde_json::from_str(json)?;
+        Ok(tree)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "".to_owned())
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn node(&self, path: &Path) -> Option<&Node> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.nodes.get(*first)?;
+        for index in rest {
+            node = node.children.get(*index)?;
+        }
+        Some(node)
+    }
+
+    /// The text content of the node at `path`, or an empty delta if the node has no text.
+    pub fn text(&self, path: &Path) -> Option<RichTextDelta> {
+        let node = self.node(path)?;
+        Some(node.delta.clone().unwrap_or_default())
+    }
+
+    /// Applies the operation and returns the operation that undoes it.
+    pub fn apply(&mut self, operation: &NodeOperation) -> Result<NodeOperation, OTError> {
+        match operation {
+            NodeOperation::Insert { path, node } => {
+                self.insert(path, node.clone())?;
+                Ok(NodeOperation::Delete { path: path.clone() })
+            }
+            NodeOperation::Delete { path } => {
+                let node = self.remove(path)?;
+                Ok(NodeOperation::Insert {
+                    path: path.clone(),
+                    node,
+                })
+            }
+            NodeOperation::Move { from, to } => {
+                let node = self.remove(from)?;
+                if let Err(err) = self.insert(to, node.clone()) {
+                    // Puts the node back so that a failed operation leaves the tree untouched.
+                    self.insert(from, node)?;
+                    return Err(err);
+                }
+                Ok(NodeOperation::Move {
+                    from: to.clone(),
+                    to: from.clone(),
+                })
+            }
+            NodeOperation::UpdateAttributes { path, attributes } => {
+                let node = self.node_mut(path)?;
+                let mut inverted = NodeAttributesChangeset::new();
+                for (key, value) in attributes {
+                    let old_value = match value {
+                        None => node.attributes.remove(key),
+                        Some(value) => node.attributes.insert(key.clone(), value.clone()),
+                    };
+                    inverted.insert(key.clone(), old_value);
+                }
+                Ok(NodeOperation::UpdateAttributes {
+                    path: path.clone(),
+                    attributes: inverted,
+                })
+            }
+            NodeOperation::EditText { path, delta } => {
+                let node = self.node_mut(path)?;
+                let text = node.delta.take().unwrap_or_default();
+                let new_text = text.compose(delta)?;
+                let inverted = delta.invert(&text);
+                node.delta = Some(new_text);
+                Ok(NodeOperation::EditText {
+                    path: path.clone(),
+                    delta: inverted,
+                })
+            }
+        }
+    }
+
+    /// Applies the operations of the transaction one by one and returns the transaction that
+    /// undoes them. The tree is left untouched if any of the operations fails.
+    pub fn apply_transaction(&mut self, transaction: &Transaction) -> Result<Transaction, OTError> {
+        let mut tree = self.clone();
+        let mut inverted = vec![];
+        for operation in &transaction.operations {
+            inverted.push(tree.apply(operation)?);
+        }
+        inverted.reverse();
+        *self = tree;
+        Ok(Transaction::from_operations(inverted))
+    }
+
+    fn node_mut(&mut self, path: &Path) -> Result<&mut Node, OTError> {
+        let (siblings, index) = self.siblings_mut(path)?;
+        siblings.get_mut(index).ok_or_else(|| path_not_found(path))
+    }
+
+    fn insert(&mut self, path: &Path, node: Node) -> Result<(), OTError> {
+        let (siblings, index) = self.siblings_mut(path)?;
+        if index > siblings.len() {
+            return Err(path_not_found(path));
+        }
+        siblings.insert(index, node);
+        Ok(())
+    }
+
+    fn remove(&mut self, path: &Path) -> Result<Node, OTError> {
+        let (siblings, index) = self.siblings_mut(path)?;
+        if index >= siblings.len() {
+            return Err(path_not_found(path));
+        }
+        Ok(siblings.remove(index))
+    }
+
+    /// Returns the children of the parent of `path` and the index of `path` in them.
+    fn siblings_mut(&mut self, path: &Path) -> Result<(&mut Vec<Node>, usize), OTError> {
+        let (index, parent) = path.split_last().ok_or_else(|| path_not_found(path))?;
+        let mut siblings = &mut self.nodes;
+        for i in parent {
+            siblings = &mut siblings.get_mut(*i).ok_or_else(|| path_not_found(path))?.children;
+        }
+        Ok((siblings, *index))
+    }
+}
+
+fn path_not_found(path: &Path) -> OTError {
+    ErrorBuilder::new(OTErrorCode::PathNotFound)
+        .msg(format!("There is no node at {}", path))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{Node, NodeOperation, NodeTree, Path, Transaction};
+    use crate::rich_text::RichTextDeltaBuilder;
+
+    fn tree() -> NodeTree {
+        NodeTree::from_nodes(vec![
+            Node::new("text").with_delta(RichTextDeltaBuilder::new().insert("title").build()),
+            Node::new("toggle_list").with_children(vec![Node::new("text"), Node::new("text")]),
+        ])
+    }
+
+    #[test]
+    fn node_tree_apply_and_invert() {
+        let origin = tree();
+        let mut tree = origin.clone();
+        let transaction = Transaction::from_operations(vec![
+            NodeOperation::Insert {
+                path: vec![1, 1].into(),
+                node: Node::new("callout"),
+            },
+            NodeOperation::Move {
+                from: vec![0].into(),
+                to: vec![0, 1, 0].into(),
+            },
+            NodeOperation::UpdateAttributes {
+                path: vec![0].into(),
+                attributes: vec![("collapsed".to_owned(), Some("true".to_owned()))]
+                    .into_iter()
+                    .collect(),
+            },
+            NodeOperation::EditText {
+                path: vec![0, 1, 0].into(),
+                delta: RichTextDeltaBuilder::new().retain(5).insert("!").build(),
+            },
+            NodeOperation::Delete {
+                path: vec![0, 0].into(),
+            },
+        ]);
+        let undo = tree.apply_transaction(&transaction).unwrap();
+
+        let toggle_list = tree.node(&Path(vec![0])).unwrap();
+        assert_eq!(toggle_list.attributes.get("collapsed").unwrap(), "true");
+        assert_eq!(toggle_list.children.len(), 2);
+        assert_eq!(toggle_list.children[0].node_type, "callout");
+        let text = tree.text(&Path(vec![0, 0, 0])).unwrap();
+        assert_eq!(text.to_str().unwrap(), "title!");
+
+        tree.apply_transaction(&undo).unwrap();
+        assert_eq!(tree, origin);
+    }
+
+    #[test]
+    fn node_tree_apply_invalid_path() {
+        let mut tree = tree();
+        let transaction = Transaction::from_operations(vec![
+            NodeOperation::Delete { path: vec![0].into() },
+            NodeOperation::Move {
+                from: vec![0].into(),
+                to: vec![3].into(),
+            },
+        ]);
+        assert!(tree.apply_transaction(&transaction).is_err());
+        assert_eq!(tree, self::tree());
+        assert!(tree
+            .apply(&NodeOperation::Delete {
+                path: vec![1, 2].into()
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn node_tree_serde() {
+        let tree = tree();
+        let json = tree.to_json();
+        assert_eq!(
+            json,
+            r#"[{"type":"text","delta":[{"insert":"title"}]},{"type":"toggle_list","children":[{"type":"text"},{"type":"text"}]}]"#
+        );
+        assert_eq!(NodeTree::from_json(&json).unwrap(), tree);
+
+        let transaction = Transaction::from_operations(vec![
+            NodeOperation::Move {
+                from: vec![0].into(),
+                to: vec![1, 0].into(),
+            },
+            NodeOperation::UpdateAttributes {
+                path: vec![1].into(),
+                attributes: vec![("collapsed".to_owned(), None)].into_iter().collect(),
+            },
+        ]);
+        let json = transaction.to_json();
+        assert_eq!(
+            json,
+            r#"{"operations":[{"op":"move","from":[0],"to":[1,0]},{"op":"update_attributes","path":[1],"attributes":{"collapsed":null}}]}"#
+        );
+        assert_eq!(Transaction::from_json(&json).unwrap(), transaction);
+    }
+}
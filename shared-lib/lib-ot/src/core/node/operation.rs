@@ -0,0 +1,63 @@
+use crate::core::{Node, NodeAttributesChangeset, Path};
+use crate::rich_text::RichTextDelta;
+use serde::{Deserialize, Serialize};
+
+/// An operation on the [NodeTree](crate::core::NodeTree). Every operation addresses the nodes
+/// by [Path] and acts on exactly one node (and its subtree).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum NodeOperation {
+    /// Inserts the node in front of the node at `path`, or appends it if `path` points right
+    /// after the last child.
+    Insert { path: Path, node: Node },
+
+    /// Deletes the node at `path` with its children.
+    Delete { path: Path },
+
+    /// Moves the node at `from` to `to`. `to` is an insertion point in the tree after the node
+    /// has been removed from `from`.
+    Move { from: Path, to: Path },
+
+    UpdateAttributes {
+        path: Path,
+        attributes: NodeAttributesChangeset,
+    },
+
+    /// Edits the text content of the node. The delta is applied to the whole text of the node.
+    EditText { path: Path, delta: RichTextDelta },
+}
+
+impl NodeOperation {
+    /// Returns true if the operation changes the structure of the tree, which means it changes
+    /// the path of other nodes.
+    pub fn is_structural(&self) -> bool {
+        matches!(
+            self,
+            NodeOperation::Insert { .. } | NodeOperation::Delete { .. } | NodeOperation::Move { .. }
+        )
+    }
+
+    /// The path of the node the operation acts on. It's the source path for
+    /// [NodeOperation::Move].
+    pub fn path(&self) -> &Path {
+        match self {
+            NodeOperation::Insert { path, .. } => path,
+            NodeOperation::Delete { path } => path,
+            NodeOperation::Move { from, .. } => from,
+            NodeOperation::UpdateAttributes { path, .. } => path,
+            NodeOperation::EditText { path, .. } => path,
+        }
+    }
+
+    pub(crate) fn with_path(&self, path: Path) -> NodeOperation {
+        let mut operation = self.clone();
+        match &mut operation {
+            NodeOperation::Insert { path: p, .. } => *p = path,
+            NodeOperation::Delete { path: p } => *p = path,
+            NodeOperation::Move { from, .. } => *from = path,
+            NodeOperation::UpdateAttributes { path: p, .. } => *p = path,
+            NodeOperation::EditText { path: p, .. } => *p = path,
+        }
+        operation
+    }
+}
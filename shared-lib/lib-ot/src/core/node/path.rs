@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+
+/// The address of a node in the [NodeTree](crate::core::NodeTree). Each element is the index
+/// of the node in the children of its parent, so `[1, 0]` is the first child of the second
+/// top-level node.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Path(pub Vec<usize>);
+
+impl Path {
+    /// Returns true if `other` is this path or lies inside the subtree of this path.
+    pub fn is_prefix_of(&self, other: &Path) -> bool {
+        other.0.starts_with(&self.0)
+    }
+
+    /// Returns true if `other` lies inside the subtree of this path.
+    pub fn is_ancestor_of(&self, other: &Path) -> bool {
+        self.len() < other.len() && self.is_prefix_of(other)
+    }
+
+    pub fn parent(&self) -> Path {
+        Path(self.0[..self.len().saturating_sub(1)].to_vec())
+    }
+
+    /// Replaces the `prefix` of the path with `new_prefix`. The path must start with `prefix`.
+    pub(crate) fn rebase(&self, prefix: &Path, new_prefix: &Path) -> Path {
+        let mut path = new_prefix.clone();
+        path.extend_from_slice(&self.0[prefix.len()..]);
+        path
+    }
+
+    /// Transforms the path against the insertion of a node at `at`.
+    ///
+    /// A node address always shifts when a node is inserted in front of it. An insertion point
+    /// at the same position as `at` only shifts if `shift_equal` is true, which decides which
+    /// of two concurrent inserts goes first.
+    pub(crate) fn transform_insert(&self, at: &Path, shift_equal: bool) -> Path {
+        let mut path = self.clone();
+        if let Some((depth, index)) = sibling_index(self, at) {
+            let at_index = at[depth];
+            if index > at_index || (index == at_index && (shift_equal || self.len() > at.len())) {
+                path[depth] += 1;
+            }
+        }
+        path
+    }
+
+    /// Transforms the path against the deletion of the node at `at`. Returns None if the path
+    /// lies inside the deleted subtree.
+    ///
+    /// An insertion point at the position of the deleted node stays where it is, because it
+    /// inserts in front of the node.
+    pub(crate) fn transform_delete(&self, at: &Path, is_insertion: bool) -> Option<Path> {
+        let mut path = self.clone();
+        if let Some((depth, index)) = sibling_index(self, at) {
+            let at_index = at[depth];
+            if index == at_index {
+                if !is_insertion || self.len() > at.len() {
+                    return None;
+                }
+            } else if index > at_index {
+                path[depth] -= 1;
+            }
+        }
+        Some(path)
+    }
+
+    /// Transforms a node address against moving the node at `from` to `to`.
+    pub(crate) fn transform_move(&self, from: &Path, to: &Path) -> Path {
+        if from.is_prefix_of(self) {
+            return self.rebase(from, to);
+        }
+        // The path is not inside the moved subtree, so it is never removed.
+        let path = self.transform_delete(from, false).unwrap_or_else(|| self.clone());
+        path.transform_insert(to, true)
+    }
+
+    /// Transforms an insertion point against moving the node at `from` to `to`.
+    pub(crate) fn transform_move_insertion(&self, from: &Path, to: &Path, shift_equal: bool) -> Path {
+        if from.is_ancestor_of(self) {
+            return self.rebase(from, to);
+        }
+        let path = self.transform_delete(from, true).unwrap_or_else(|| self.clone());
+        path.transform_insert(to, shift_equal)
+    }
+}
+
+/// Returns the depth of the last element of `at` and the index of `path` at that depth if
+/// `path` runs through the same parent as `at`.
+fn sibling_index(path: &Path, at: &Path) -> Option<(usize, usize)> {
+    if at.is_empty() || path.len() < at.len() {
+        return None;
+    }
+    let depth = at.len() - 1;
+    if path[..depth] != at[..depth] {
+        return None;
+    }
+    Some((depth, path[depth]))
+}
+
+impl Deref for Path {
+    type Target = Vec<usize>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Path {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl std::convert::From<Vec<usize>> for Path {
+    fn from(path: Vec<usize>) -> Self {
+        Path(path)
+    }
+}
+
+impl std::convert::From<&[usize]> for Path {
+    fn from(path: &[usize]) -> Self {
+        Path(path.to_vec())
+    }
+}
+
+impl std::fmt::Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
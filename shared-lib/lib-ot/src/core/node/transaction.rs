@@ -0,0 +1,551 @@
+use crate::core::{NodeOperation, OperationTransformable, Path};
+use crate::errors::OTError;
+use serde::{Deserialize, Serialize};
+
+/// A list of [NodeOperation]s that are applied to the [NodeTree](crate::core::NodeTree) one
+/// after another. Each operation addresses the tree as left by the previous one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub operations: Vec<NodeOperation>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_operations(operations: Vec<NodeOperation>) -> Self {
+        Self { operations }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, OTError> {
+        let transaction = serde_json::from_str(json)?;
+        Ok(transaction)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "".to_owned())
+    }
+
+    pub fn push(&mut self, operation: NodeOperation) {
+        self.operations.push(operation);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn compose(&self, other: &Transaction) -> Transaction {
+        let mut operations = self.operations.clone();
+        operations.extend(other.operations.iter().cloned());
+        Transaction { operations }
+    }
+
+    /// Transforms two transactions a and b that happened concurrently on the same tree and
+    /// produces a' and b', such that applying a then b' gives the same tree as applying b then a'.
+    ///  (a', b') = a.transform(b)
+    ///
+    /// `self` takes priority: its inserts go first when both insert at the same place, its
+    /// attributes win when both update the same key, and its move wins when both move the same
+    /// (or a nested) node. Deleting a node always wins over moving it or moving a node into it,
+    /// the moved node is deleted as well.
+    pub fn transform(&self, other: &Transaction) -> Result<(Transaction, Transaction), OTError> {
+        let (a_prime, b_prime) = transform_operations(&self.operations, &other.operations, true)?;
+        Ok((
+            Transaction::from_operations(a_prime),
+            Transaction::from_operations(b_prime),
+        ))
+    }
+}
+
+type TransformedOperations = (Vec<NodeOperation>, Vec<NodeOperation>);
+
+fn transform_operations(
+    a: &[NodeOperation],
+    b: &[NodeOperation],
+    a_priority: bool,
+) -> Result<TransformedOperations, OTError> {
+    match (a, b) {
+        ([], _) | (_, []) => Ok((a.to_vec(), b.to_vec())),
+        ([a], [b]) => transform_operation(a, b, a_priority),
+        ([a_first, a_rest @ ..], _) if !a_rest.is_empty() => {
+            let (a_first_prime, b_prime) = transform_operations(std::slice::from_ref(a_first), b, a_priority)?;
+            let (mut a_rest_prime, b_prime) = transform_operations(a_rest, &b_prime, a_priority)?;
+            let mut a_prime = a_first_prime;
+            a_prime.append(&mut a_rest_prime);
+            Ok((a_prime, b_prime))
+        }
+        (_, [b_first, b_rest @ ..]) => {
+            let (a_prime, b_first_prime) = transform_operations(a, std::slice::from_ref(b_first), a_priority)?;
+            let (a_prime, mut b_rest_prime) = transform_operations(&a_prime, b_rest, a_priority)?;
+            let mut b_prime = b_first_prime;
+            b_prime.append(&mut b_rest_prime);
+            Ok((a_prime, b_prime))
+        }
+    }
+}
+
+fn transform_operation(
+    a: &NodeOperation,
+    b: &NodeOperation,
+    a_priority: bool,
+) -> Result<TransformedOperations, OTError> {
+    if !a.is_structural() && !b.is_structural() {
+        return transform_content(a, b, a_priority);
+    }
+    if !a.is_structural() {
+        return Ok((transform_path(a, b).into_iter().collect(), vec![b.clone()]));
+    }
+    if !b.is_structural() {
+        return Ok((vec![a.clone()], transform_path(b, a).into_iter().collect()));
+    }
+
+    let transformed = match (a, b) {
+        (NodeOperation::Insert { path: a_path, .. }, NodeOperation::Insert { path: b_path, .. }) => (
+            vec![a.with_path(a_path.transform_insert(b_path, !a_priority))],
+            vec![b.with_path(b_path.transform_insert(a_path, a_priority))],
+        ),
+        (NodeOperation::Insert { path: a_path, .. }, NodeOperation::Delete { path: b_path }) => {
+            match a_path.transform_delete(b_path, true) {
+                // The node is inserted inside the deleted subtree.
+                None => (vec![], vec![b.clone()]),
+                Some(path) => (
+                    vec![a.with_path(path)],
+                    vec![b.with_path(b_path.transform_insert(a_path, true))],
+                ),
+            }
+        }
+        (NodeOperation::Insert { path, .. }, NodeOperation::Move { from, to }) => {
+            let a_path = path.transform_move_insertion(from, to, !a_priority);
+            let b_to = if from.is_ancestor_of(path) {
+                to.clone()
+            } else {
+                let path = path.transform_delete(from, true).unwrap_or_else(|| path.clone());
+                to.transform_insert(&path, a_priority)
+            };
+            let b_prime = NodeOperation::Move {
+                from: from.transform_insert(path, true),
+                to: b_to,
+            };
+            (vec![a.with_path(a_path)], vec![b_prime])
+        }
+        (NodeOperation::Delete { path: a_path }, NodeOperation::Delete { path: b_path }) => {
+            let a_prime = a_path.transform_delete(b_path, false).map(|path| a.with_path(path));
+            let b_prime = b_path.transform_delete(a_path, false).map(|path| b.with_path(path));
+            (a_prime.into_iter().collect(), b_prime.into_iter().collect())
+        }
+        (NodeOperation::Delete { path }, NodeOperation::Move { from, to }) => {
+            if path.is_prefix_of(from) {
+                // The moved node is deleted wherever it's moved to.
+                let mut a_prime = vec![NodeOperation::Delete { path: to.clone() }];
+                if let Some(path) = path.transform_delete(from, false) {
+                    a_prime.push(a.with_path(path));
+                }
+                (a_prime, vec![])
+            } else if from.is_ancestor_of(path) {
+                (vec![a.with_path(path.transform_move(from, to))], vec![b.clone()])
+            } else {
+                let deleted = path.transform_delete(from, false).unwrap_or_else(|| path.clone());
+                let from_prime = from.transform_delete(path, false).unwrap_or_else(|| from.clone());
+                let b_prime = match to.transform_delete(&deleted, true) {
+                    // The node is moved into the deleted subtree, so it's deleted too.
+                    None => NodeOperation::Delete { path: from_prime },
+                    Some(to) => NodeOperation::Move { from: from_prime, to },
+                };
+                (vec![a.with_path(path.transform_move(from, to))], vec![b_prime])
+            }
+        }
+        (NodeOperation::Move { from: a_from, to: a_to }, NodeOperation::Move { from: b_from, to: b_to }) => {
+            match transform_moves((a_from, a_to), (b_from, b_to), a_priority) {
+                Some((a_prime, b_prime)) => (vec![a_prime], vec![b_prime]),
+                None if a_priority => (vec![reverse_move(b), a.clone()], vec![]),
+                None => (vec![], vec![reverse_move(a), b.clone()]),
+            }
+        }
+        _ => {
+            let (b_prime, a_prime) = transform_operation(b, a, !a_priority)?;
+            (a_prime, b_prime)
+        }
+    };
+    Ok(transformed)
+}
+
+/// Transforms two concurrent moves. Returns None if they conflict: if both move the same node or
+/// nested nodes, or if each moves its node into the node of the other.
+fn transform_moves(
+    (a_from, a_to): (&Path, &Path),
+    (b_from, b_to): (&Path, &Path),
+    a_priority: bool,
+) -> Option<(NodeOperation, NodeOperation)> {
+    if a_from.is_prefix_of(b_from) || b_from.is_prefix_of(a_from) {
+        return None;
+    }
+    let a_to_prime = transform_move_destination((a_from, a_to), (b_from, b_to), !a_priority)?;
+    let b_to_prime = transform_move_destination((b_from, b_to), (a_from, a_to), a_priority)?;
+    Some((
+        NodeOperation::Move {
+            from: a_from.transform_move(b_from, b_to),
+            to: a_to_prime,
+        },
+        NodeOperation::Move {
+            from: b_from.transform_move(a_from, a_to),
+            to: b_to_prime,
+        },
+    ))
+}
+
+/// Transforms the destination of the move a against the concurrent move b of another node.
+fn transform_move_destination(
+    (a_from, a_to): (&Path, &Path),
+    (b_from, b_to): (&Path, &Path),
+    shift_equal: bool,
+) -> Option<Path> {
+    // The destination of a is addressed in the tree without the node of a, so b is expressed in
+    // that tree too.
+    let b_from_prime = b_from.transform_delete(a_from, false)?;
+    let a_from_prime = a_from.transform_delete(b_from, false)?;
+    match b_to.transform_delete(&a_from_prime, true) {
+        Some(b_to_prime) => Some(a_to.transform_move_insertion(&b_from_prime, &b_to_prime, shift_equal)),
+        // b moves its node into the node of a, so the node of b just disappears from that tree.
+        // If a moves its node into the node of b as well, the moves form a cycle.
+        None => a_to.transform_delete(&b_from_prime, true),
+    }
+}
+
+fn transform_content(a: &NodeOperation, b: &NodeOperation, a_priority: bool) -> Result<TransformedOperations, OTError> {
+    let transformed = match (a, b) {
+        (
+            NodeOperation::UpdateAttributes {
+                path: a_path,
+                attributes: a_attributes,
+            },
+            NodeOperation::UpdateAttributes {
+                path: b_path,
+                attributes: b_attributes,
+            },
+        ) if a_path == b_path => {
+            // The attributes that are changed by both operations are taken from the one with the
+            // priority.
+            let (mut a_attributes, mut b_attributes) = (a_attributes.clone(), b_attributes.clone());
+            if a_priority {
+                b_attributes.retain(|key, _| !a_attributes.contains_key(key));
+            } else {
+                a_attributes.retain(|key, _| !b_attributes.contains_key(key));
+            }
+            let update = |path: &Path, attributes| match attributes {
+                attributes if attributes == Default::default() => vec![],
+                attributes => vec![NodeOperation::UpdateAttributes {
+                    path: path.clone(),
+                    attributes,
+                }],
+            };
+            (update(a_path, a_attributes), update(b_path, b_attributes))
+        }
+        (
+            NodeOperation::EditText {
+                path: a_path,
+                delta: a_delta,
+            },
+            NodeOperation::EditText {
+                path: b_path,
+                delta: b_delta,
+            },
+        ) if a_path == b_path => {
+            let (a_delta, b_delta) = if a_priority {
+                a_delta.transform(b_delta)?
+            } else {
+                let (b_delta, a_delta) = b_delta.transform(a_delta)?;
+                (a_delta, b_delta)
+            };
+            (
+                vec![NodeOperation::EditText {
+                    path: a_path.clone(),
+                    delta: a_delta,
+                }],
+                vec![NodeOperation::EditText {
+                    path: b_path.clone(),
+                    delta: b_delta,
+                }],
+            )
+        }
+        _ => (vec![a.clone()], vec![b.clone()]),
+    };
+    Ok(transformed)
+}
+
+/// Transforms the path of a non-structural operation against a structural one. Returns None if
+/// the node of the operation is deleted.
+fn transform_path(operation: &NodeOperation, other: &NodeOperation) -> Option<NodeOperation> {
+    let path = operation.path();
+    let path = match other {
+        NodeOperation::Insert { path: at, .. } => path.transform_insert(at, true),
+        NodeOperation::Delete { path: at } => path.transform_delete(at, false)?,
+        NodeOperation::Move { from, to } => path.transform_move(from, to),
+        _ => path.clone(),
+    };
+    Some(operation.with_path(path))
+}
+
+/// Returns the move that undoes the move.
+fn reverse_move(operation: &NodeOperation) -> NodeOperation {
+    match operation {
+        NodeOperation::Move { from, to } => NodeOperation::Move {
+            from: to.clone(),
+            to: from.clone(),
+        },
+        _ => operation.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{Node, NodeOperation, NodeTree, Path, Transaction};
+    use crate::rich_text::{RichTextDelta, RichTextDeltaBuilder};
+
+    fn text(s: &str) -> Node {
+        Node::new("text").with_delta(RichTextDeltaBuilder::new().insert(s).build())
+    }
+
+    fn tree() -> NodeTree {
+        NodeTree::from_nodes(vec![
+            text("a"),
+            Node::new("toggle_list").with_children(vec![text("b"), text("c")]),
+            text("d"),
+        ])
+    }
+
+    fn assert_converge(tree: &NodeTree, a: &Transaction, b: &Transaction) -> NodeTree {
+        let (a_prime, b_prime) = a.transform(b).unwrap();
+        let mut left = tree.clone();
+        left.apply_transaction(a).unwrap();
+        left.apply_transaction(&b_prime).unwrap();
+        let mut right = tree.clone();
+        right.apply_transaction(b).unwrap();
+        right.apply_transaction(&a_prime).unwrap();
+        assert_eq!(left, right, "a: {:?}, b: {:?}", a, b);
+        left
+    }
+
+    fn types(tree: &NodeTree, path: &[usize]) -> Vec<String> {
+        let nodes = match path.is_empty() {
+            true => tree.nodes(),
+            false => &tree.node(&Path(path.to_vec())).unwrap().children,
+        };
+        nodes
+            .iter()
+            .map(|node| match &node.delta {
+                Some(delta) => delta.to_str().unwrap(),
+                None => node.node_type.clone(),
+            })
+            .collect()
+    }
+
+    fn transaction(operation: NodeOperation) -> Transaction {
+        Transaction::from_operations(vec![operation])
+    }
+
+    #[test]
+    fn transform_concurrent_inserts() {
+        let a = transaction(NodeOperation::Insert {
+            path: vec![1, 1].into(),
+            node: text("x"),
+        });
+        let b = transaction(NodeOperation::Insert {
+            path: vec![1, 1].into(),
+            node: text("y"),
+        });
+        let tree = assert_converge(&tree(), &a, &b);
+        assert_eq!(types(&tree, &[1]), vec!["b", "x", "y", "c"]);
+    }
+
+    #[test]
+    fn transform_delete_wins_over_move() {
+        // Moves a node out of the deleted toggle list.
+        let a = transaction(NodeOperation::Delete { path: vec![1].into() });
+        let b = transaction(NodeOperation::Move {
+            from: vec![1, 0].into(),
+            to: vec![0].into(),
+        });
+        let tree = assert_converge(&tree(), &a, &b);
+        assert_eq!(types(&tree, &[]), vec!["a", "d"]);
+
+        // Moves a node into the deleted toggle list.
+        let b = transaction(NodeOperation::Move {
+            from: vec![2].into(),
+            to: vec![1, 0].into(),
+        });
+        let tree = assert_converge(&self::tree(), &b, &a);
+        assert_eq!(types(&tree, &[]), vec!["a"]);
+    }
+
+    #[test]
+    fn transform_conflicting_moves() {
+        // Both move the same node, the move with priority wins.
+        let a = transaction(NodeOperation::Move {
+            from: vec![0].into(),
+            to: vec![0, 2].into(),
+        });
+        let b = transaction(NodeOperation::Move {
+            from: vec![0].into(),
+            to: vec![2].into(),
+        });
+        let tree = assert_converge(&tree(), &a, &b);
+        assert_eq!(types(&tree, &[]), vec!["toggle_list", "d"]);
+        assert_eq!(types(&tree, &[0]), vec!["b", "c", "a"]);
+
+        // Each moves its node into the other one.
+        let a = transaction(NodeOperation::Move {
+            from: vec![0].into(),
+            to: vec![0, 0].into(),
+        });
+        let b = transaction(NodeOperation::Move {
+            from: vec![1].into(),
+            to: vec![0, 0].into(),
+        });
+        let mut tree = self::tree();
+        tree.apply(&NodeOperation::Insert {
+            path: vec![0].into(),
+            node: Node::new("column"),
+        })
+        .unwrap();
+        let tree = assert_converge(&tree, &b, &a);
+        assert_eq!(types(&tree, &[]), vec!["column", "toggle_list", "d"]);
+        assert_eq!(types(&tree, &[0]), vec!["a"]);
+    }
+
+    #[test]
+    fn transform_update_attributes_and_edit_text() {
+        let attributes = |value: &str| vec![("color".to_owned(), Some(value.to_owned()))].into_iter().collect();
+        let a = Transaction::from_operations(vec![
+            NodeOperation::UpdateAttributes {
+                path: vec![1, 1].into(),
+                attributes: attributes("red"),
+            },
+            NodeOperation::EditText {
+                path: vec![1, 1].into(),
+                delta: RichTextDeltaBuilder::new().insert("x").retain(1).build(),
+            },
+        ]);
+        let b = Transaction::from_operations(vec![
+            NodeOperation::Move {
+                from: vec![1, 1].into(),
+                to: vec![0].into(),
+            },
+            NodeOperation::UpdateAttributes {
+                path: vec![0].into(),
+                attributes: attributes("blue"),
+            },
+            NodeOperation::EditText {
+                path: vec![0].into(),
+                delta: RichTextDeltaBuilder::new().retain(1).insert("y").build(),
+            },
+        ]);
+        let tree = assert_converge(&tree(), &a, &b);
+        let node = tree.node(&Path(vec![0])).unwrap();
+        assert_eq!(node.attributes.get("color").unwrap(), "red");
+        assert_eq!(node.delta.as_ref().unwrap().to_str().unwrap(), "xcy");
+    }
+
+    /// A small linear congruential generator to keep the random tests reproducible.
+    struct Rng(u64);
+
+    impl Rng {
+        fn gen(&mut self, n: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % n.max(1)
+        }
+    }
+
+    fn node_paths(nodes: &[Node], parent: &Path, paths: &mut Vec<Path>) {
+        for (index, node) in nodes.iter().enumerate() {
+            let mut path = parent.clone();
+            path.push(index);
+            node_paths(&node.children, &path, paths);
+            paths.push(path);
+        }
+    }
+
+    fn insertion_paths(nodes: &[Node], parent: &Path, paths: &mut Vec<Path>) {
+        for index in 0..=nodes.len() {
+            let mut path = parent.clone();
+            path.push(index);
+            if let Some(node) = nodes.get(index) {
+                insertion_paths(&node.children, &path, paths);
+            }
+            paths.push(path);
+        }
+    }
+
+    fn random_operation(rng: &mut Rng, tree: &NodeTree) -> NodeOperation {
+        let mut nodes = vec![];
+        node_paths(tree.nodes(), &Path::default(), &mut nodes);
+        let mut insertions = vec![];
+        insertion_paths(tree.nodes(), &Path::default(), &mut insertions);
+        let kind = if nodes.is_empty() { 0 } else { rng.gen(5) };
+        let path = nodes.get(rng.gen(nodes.len())).cloned().unwrap_or_default();
+        match kind {
+            0 => NodeOperation::Insert {
+                path: insertions[rng.gen(insertions.len())].clone(),
+                node: text(&rng.gen(10).to_string()),
+            },
+            1 => NodeOperation::Delete { path },
+            2 => {
+                let mut tree = tree.clone();
+                tree.apply(&NodeOperation::Delete { path: path.clone() }).unwrap();
+                let mut insertions = vec![];
+                insertion_paths(tree.nodes(), &Path::default(), &mut insertions);
+                NodeOperation::Move {
+                    from: path,
+                    to: insertions[rng.gen(insertions.len())].clone(),
+                }
+            }
+            3 => {
+                let value = [None, Some("1".to_owned()), Some("2".to_owned())][rng.gen(3)].clone();
+                let key = ["k1", "k2"][rng.gen(2)].to_owned();
+                NodeOperation::UpdateAttributes {
+                    path,
+                    attributes: vec![(key, value)].into_iter().collect(),
+                }
+            }
+            _ => {
+                let len = tree.text(&path).unwrap().utf16_target_len;
+                let index = rng.gen(len + 1);
+                let delta: RichTextDelta = RichTextDeltaBuilder::new()
+                    .retain(index)
+                    .insert(&rng.gen(10).to_string())
+                    .retain(len - index)
+                    .build();
+                NodeOperation::EditText { path, delta }
+            }
+        }
+    }
+
+    fn random_transaction(rng: &mut Rng, tree: &NodeTree) -> Transaction {
+        let mut tree = tree.clone();
+        let mut transaction = Transaction::new();
+        for _ in 0..rng.gen(3) + 1 {
+            let operation = random_operation(rng, &tree);
+            tree.apply(&operation).unwrap();
+            transaction.push(operation);
+        }
+        transaction
+    }
+
+    #[test]
+    fn transform_random_transactions_converge() {
+        let mut rng = Rng(7);
+        for _ in 0..3000 {
+            let mut tree = tree();
+            for _ in 0..rng.gen(4) {
+                let transaction = random_transaction(&mut rng, &tree);
+                tree.apply_transaction(&transaction).unwrap();
+            }
+            let a = random_transaction(&mut rng, &tree);
+            let b = random_transaction(&mut rng, &tree);
+            assert_converge(&tree, &a, &b);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    core::{Attributes, Operation, PlainTextAttributes},
+    core::{Attributes, EmbedData, Operation, PlainTextAttributes},
     rich_text::RichTextAttributes,
 };
 
@@ -34,6 +34,10 @@ where
         OpBuilder::new(Operation::Insert(s.into()))
     }
 
+    pub fn embed(data: EmbedData) -> OpBuilder<T> {
+        OpBuilder::new(Operation::Embed(data.into()))
+    }
+
     pub fn attributes(mut self, attrs: T) -> OpBuilder<T> {
         self.attrs = attrs;
         self
@@ -45,6 +49,7 @@ where
             Operation::Delete(_) => {}
             Operation::Retain(retain) => retain.attributes = self.attrs,
             Operation::Insert(insert) => insert.attributes = self.attrs,
+            Operation::Embed(embed) => embed.attributes = self.attrs,
         }
         operation
     }
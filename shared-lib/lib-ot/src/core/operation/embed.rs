@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The embed takes one utf16 code unit in the document. The placeholder is used wherever the
+/// embed is treated as text, for example: `Delta::apply` or `Operation::get_data`.
+pub const EMBED_PLACEHOLDER: &str = "\u{FFFC}";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EmbedData {
+    Image(ImageEmbed),
+    Divider,
+    PageMention(String),
+    /// The timestamp of the date in seconds.
+    DateMention(i64),
+}
+
+impl EmbedData {
+    pub fn image(source: &str) -> Self {
+        EmbedData::Image(ImageEmbed {
+            source: source.to_owned(),
+            width: None,
+            height: None,
+        })
+    }
+
+    /// The block embed takes the whole line, for example: the image or the divider.
+    pub fn is_block(&self) -> bool {
+        matches!(self, EmbedData::Image(_) | EmbedData::Divider)
+    }
+}
+
+impl fmt::Display for EmbedData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmbedData::Image(image) => f.write_fmt(format_args!("image: {}", image.source)),
+            EmbedData::Divider => f.write_str("divider"),
+            EmbedData::PageMention(page_id) => f.write_fmt(format_args!("page: {}", page_id)),
+            EmbedData::DateMention(timestamp) => f.write_fmt(format_args!("date: {}", timestamp)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ImageEmbed {
+    pub source: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<usize>,
+}
@@ -1,5 +1,5 @@
 use crate::{
-    core::{FlowyStr, Interval, OpBuilder, OperationTransformable},
+    core::{EmbedData, FlowyStr, Interval, OpBuilder, OperationTransformable, EMBED_PLACEHOLDER},
     errors::OTError,
 };
 use serde::{Deserialize, Serialize, __private::Formatter};
@@ -24,6 +24,7 @@ pub enum Operation<T: Attributes> {
     Delete(usize),
     Retain(Retain<T>),
     Insert(Insert<T>),
+    Embed(Embed<T>),
 }
 
 impl<T> Operation<T>
@@ -35,6 +36,7 @@ where
             Operation::Delete(_) => "",
             Operation::Retain(_) => "",
             Operation::Insert(insert) => &insert.s,
+            Operation::Embed(_) => EMBED_PLACEHOLDER,
         }
     }
 
@@ -43,6 +45,7 @@ where
             Operation::Delete(_) => T::default(),
             Operation::Retain(retain) => retain.attributes.clone(),
             Operation::Insert(insert) => insert.attributes.clone(),
+            Operation::Embed(embed) => embed.attributes.clone(),
         }
     }
 
@@ -51,6 +54,7 @@ where
             Operation::Delete(_) => log::error!("Delete should not contains attributes"),
             Operation::Retain(retain) => retain.attributes = attributes,
             Operation::Insert(insert) => insert.attributes = attributes,
+            Operation::Embed(embed) => embed.attributes = attributes,
         }
     }
 
@@ -63,6 +67,7 @@ where
             Operation::Delete(n) => *n,
             Operation::Retain(r) => r.n,
             Operation::Insert(i) => i.utf16_size(),
+            Operation::Embed(_) => 1,
         }
     }
 
@@ -97,6 +102,10 @@ where
                         .build(),
                 );
             }
+            Operation::Embed(_) => {
+                left = None;
+                right = Some(self.clone());
+            }
         }
 
         (left, right)
@@ -116,6 +125,15 @@ where
                     OpBuilder::insert(&s).attributes(insert.attributes.clone()).build()
                 }
             }
+            Operation::Embed(embed) => {
+                // The embed can't be split, it's either kept or dropped as a whole.
+                if interval.start > 0 || interval.is_empty() {
+                    return None;
+                }
+                OpBuilder::embed(embed.data.clone())
+                    .attributes(embed.attributes.clone())
+                    .build()
+            }
         };
 
         match op.is_empty() {
@@ -131,13 +149,25 @@ where
         false
     }
 
+    /// Returns true if the operation inserts the text or the embed.
     pub fn is_insert(&self) -> bool {
-        if let Operation::Insert(_) = self {
+        matches!(self, Operation::Insert(_) | Operation::Embed(_))
+    }
+
+    pub fn is_embed(&self) -> bool {
+        if let Operation::Embed(_) = self {
             return true;
         }
         false
     }
 
+    pub fn get_embed(&self) -> Option<&EmbedData> {
+        match self {
+            Operation::Embed(embed) => Some(&embed.data),
+            _ => None,
+        }
+    }
+
     pub fn is_retain(&self) -> bool {
         if let Operation::Retain(_) = self {
             return true;
@@ -150,6 +180,7 @@ where
             Operation::Delete(_) => true,
             Operation::Retain(retain) => retain.is_plain(),
             Operation::Insert(insert) => insert.is_plain(),
+            Operation::Embed(embed) => embed.attributes.is_empty(),
         }
     }
 }
@@ -170,6 +201,9 @@ where
             Operation::Insert(i) => {
                 f.write_fmt(format_args!("{}", i))?;
             }
+            Operation::Embed(e) => {
+                f.write_fmt(format_args!("{}", e))?;
+            }
         }
         f.write_str("}")?;
         Ok(())
@@ -338,6 +372,37 @@ where
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Embed<T: Attributes> {
+    pub data: EmbedData,
+    pub attributes: T,
+}
+
+impl<T> fmt::Display for Embed<T>
+where
+    T: Attributes,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.attributes.is_empty() {
+            f.write_fmt(format_args!("embed: {}", self.data))
+        } else {
+            f.write_fmt(format_args!("embed: {}, attributes: {}", self.data, self.attributes))
+        }
+    }
+}
+
+impl<T> std::convert::From<EmbedData> for Embed<T>
+where
+    T: Attributes,
+{
+    fn from(data: EmbedData) -> Self {
+        Embed {
+            data,
+            attributes: T::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct PlainTextAttributes();
 impl fmt::Display for PlainTextAttributes {
@@ -1,4 +1,4 @@
-use crate::core::{Attributes, FlowyStr, Insert, Operation, Retain};
+use crate::core::{Attributes, Embed, EmbedData, FlowyStr, ImageEmbed, Insert, Operation, Retain};
 use serde::{
     de,
     de::{MapAccess, SeqAccess, Visitor},
@@ -23,6 +23,7 @@ where
                 map.end()
             }
             Operation::Insert(insert) => insert.serialize(serializer),
+            Operation::Embed(embed) => embed.serialize(serializer),
         }
     }
 }
@@ -73,8 +74,11 @@ where
                             if operation.is_some() {
                                 return Err(de::Error::duplicate_field("operation"));
                             }
-                            let i: String = map.next_value()?;
-                            operation = Some(Operation::<T>::Insert(i.into()));
+                            // The embed is inserted as an object, for example: {"insert":{"divider":"hr"}}
+                            operation = match map.next_value::<InsertValue>()? {
+                                InsertValue::Text(s) => Some(Operation::<T>::Insert(s.into())),
+                                InsertValue::Embed(data) => Some(Operation::<T>::Embed(data.into())),
+                            };
                         }
                         "attributes" => {
                             if attributes.is_some() {
@@ -307,3 +311,87 @@ where
         serde::Deserializer::deserialize_struct(deserializer, "Insert", FIELDS, InsertVisitor(PhantomData))
     }
 }
+
+impl<T> Serialize for Embed<T>
+where
+    T: Attributes + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = false as usize + 1 + if self.attributes.is_empty() { 0 } else { 1 };
+        let mut serde_state = serializer.serialize_struct("Embed", len)?;
+        let _ = serde::ser::SerializeStruct::serialize_field(&mut serde_state, "insert", &self.data)?;
+        if !self.attributes.is_empty() {
+            let _ = serde::ser::SerializeStruct::serialize_field(&mut serde_state, "attributes", &self.attributes)?;
+        }
+        serde::ser::SerializeStruct::end(serde_state)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InsertValue {
+    Text(String),
+    Embed(EmbedData),
+}
+
+// Same as Quill, the embed is serialized as the object with single key, for example:
+// {"image":"https://appflowy.io/logo.png"}, {"divider":"hr"} or {"mention":{"page":"page_id"}}.
+// The image with size is serialized as {"image":{"source":"...","width":100,"height":100}}.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum EmbedValue {
+    Image(ImageValue),
+    Divider(serde_json::Value),
+    Mention(MentionValue),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ImageValue {
+    Source(String),
+    Sized(ImageEmbed),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MentionValue {
+    Page(String),
+    Date(i64),
+}
+
+impl Serialize for EmbedData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = match self.clone() {
+            EmbedData::Image(image) => match (image.width, image.height) {
+                (None, None) => EmbedValue::Image(ImageValue::Source(image.source)),
+                _ => EmbedValue::Image(ImageValue::Sized(image)),
+            },
+            EmbedData::Divider => EmbedValue::Divider(serde_json::Value::String("hr".to_owned())),
+            EmbedData::PageMention(page_id) => EmbedValue::Mention(MentionValue::Page(page_id)),
+            EmbedData::DateMention(timestamp) => EmbedValue::Mention(MentionValue::Date(timestamp)),
+        };
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EmbedData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = match EmbedValue::deserialize(deserializer)? {
+            EmbedValue::Image(ImageValue::Source(source)) => EmbedData::image(&source),
+            EmbedValue::Image(ImageValue::Sized(image)) => EmbedData::Image(image),
+            EmbedValue::Divider(_) => EmbedData::Divider,
+            EmbedValue::Mention(MentionValue::Page(page_id)) => EmbedData::PageMention(page_id),
+            EmbedValue::Mention(MentionValue::Date(timestamp)) => EmbedData::DateMention(timestamp),
+        };
+        Ok(data)
+    }
+}
@@ -0,0 +1,657 @@
+use crate::{
+    core::{Attributes, Delta, Interval, OpBuilder, Operation},
+    errors::{ErrorBuilder, OTError, OTErrorCode},
+};
+use std::cmp::{max, min};
+
+const MAX_CHILDREN: usize = 16;
+const MAX_LEAF_OPS: usize = 32;
+/// The inserts that are longer than it are split, so the cost of editing the leaf is bounded.
+const MAX_INSERT_LEN: usize = 512;
+
+/// The document stored in a B-tree that is indexed by the utf16 offsets. The leaves keep the
+/// insert and the embed operations of the document, and each node caches its length, so the
+/// insert, the delete, the format and the slice are O(log n) no matter how long the document is.
+///
+/// The delta is still the format that is sent and saved, use `from_delta` and `to_delta` to
+/// convert between them.
+#[derive(Debug, Clone)]
+pub struct Rope<T: Attributes> {
+    root: Node<T>,
+}
+
+impl<T> Default for Rope<T>
+where
+    T: Attributes,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Rope<T>
+where
+    T: Attributes,
+{
+    pub fn new() -> Self {
+        Self {
+            root: Node::leaf(vec![]),
+        }
+    }
+
+    pub fn from_delta(delta: &Delta<T>) -> Self {
+        let mut rope = Self::new();
+        let ops = delta
+            .ops
+            .iter()
+            .filter(|op| op.is_insert() || op.is_embed())
+            .cloned()
+            .collect();
+        rope.splice(0, 0, ops);
+        rope
+    }
+
+    pub fn to_delta(&self) -> Delta<T> {
+        self.slice(Interval::new(0, self.len()))
+    }
+
+    /// The utf16 length of the document.
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the operations of the text in the interval.
+    pub fn slice(&self, interval: Interval) -> Delta<T> {
+        let mut ops = vec![];
+        let end = min(interval.end, self.len());
+        if interval.start < end {
+            self.root.slice(interval.start, end, &mut ops);
+        }
+
+        let mut delta = Delta::new();
+        ops.into_iter().for_each(|op| delta.add(op));
+        delta
+    }
+
+    pub fn insert(&mut self, index: usize, s: &str, attributes: T) {
+        let op = OpBuilder::insert(s).attributes(attributes).build();
+        self.splice(index, index, vec![op]);
+    }
+
+    pub fn delete(&mut self, interval: Interval) {
+        self.splice(interval.start, interval.end, vec![]);
+    }
+
+    /// Composes the attributes with the attributes of the text in the interval, the attribute
+    /// with the empty value removes the attribute.
+    pub fn format(&mut self, interval: Interval, attributes: &T) -> Result<(), OTError> {
+        let mut ops = self.slice(interval).ops;
+        for op in ops.iter_mut() {
+            let mut composed_attrs = op.get_attributes().compose(attributes)?;
+            composed_attrs.remove_empty();
+            op.set_attributes(composed_attrs);
+        }
+        self.splice(interval.start, interval.end, ops);
+        Ok(())
+    }
+
+    /// Applies the changeset to the document, it's the same as composing the changeset with the
+    /// delta of the document but only the parts that the changeset touches are visited.
+    pub fn apply(&mut self, changeset: &Delta<T>) -> Result<(), OTError> {
+        if changeset.utf16_base_len > self.len() {
+            return Err(ErrorBuilder::new(OTErrorCode::IncompatibleLength)
+                .msg(format!(
+                    "Expected: {}, received: {}",
+                    self.len(),
+                    changeset.utf16_base_len
+                ))
+                .build());
+        }
+
+        let mut index = 0;
+        for op in &changeset.ops {
+            match op {
+                Operation::Retain(retain) => {
+                    if !retain.is_plain() {
+                        let _ = self.format(Interval::new(index, index + retain.n), &retain.attributes)?;
+                    }
+                    index += retain.n;
+                }
+                Operation::Insert(_) | Operation::Embed(_) => {
+                    self.splice(index, index, vec![op.clone()]);
+                    index += op.len();
+                }
+                Operation::Delete(n) => self.splice(index, index + n, vec![]),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the changeset that reverts the `changeset` after it's applied to the document.
+    /// It's the same as `changeset.invert(&self.to_delta())`.
+    pub fn invert(&self, changeset: &Delta<T>) -> Delta<T> {
+        let mut inverted = Delta::default();
+        if self.is_empty() {
+            return inverted;
+        }
+
+        let mut index = 0;
+        for op in &changeset.ops {
+            let len = op.len();
+            match op {
+                Operation::Delete(n) => {
+                    self.slice(Interval::new(index, index + n))
+                        .ops
+                        .into_iter()
+                        .for_each(|other_op| inverted.add(other_op));
+                    index += len;
+                }
+                Operation::Retain(retain) => {
+                    if retain.is_plain() {
+                        inverted.retain(len, retain.attributes.clone());
+                    } else {
+                        for other_op in self.slice(Interval::new(index, index + len)).ops {
+                            let inverted_attrs = retain.attributes.invert(&other_op.get_attributes());
+                            inverted.retain(other_op.len(), inverted_attrs);
+                        }
+                    }
+                    index += len;
+                }
+                Operation::Insert(_) | Operation::Embed(_) => inverted.delete(len),
+            }
+        }
+        inverted
+    }
+
+    /// Returns the offset of the start of the line that contains the index.
+    pub fn line_start(&self, index: usize) -> usize {
+        self.root
+            .rfind_newline(min(index, self.len()))
+            .map_or(0, |offset| offset + 1)
+    }
+
+    /// Returns the offset after the newline of the line that contains the index, or the length of
+    /// the document if the line doesn't end with a newline.
+    pub fn line_end(&self, index: usize) -> usize {
+        if index >= self.len() {
+            return self.len();
+        }
+        self.root.find_newline(index).map_or(self.len(), |offset| offset + 1)
+    }
+
+    // Replaces the text between start and end with the ops.
+    fn splice(&mut self, start: usize, end: usize, ops: Vec<Operation<T>>) {
+        let end = min(end, self.len());
+        let start = min(start, end);
+        let overflow = self.root.splice(start, end, Some(ops));
+        if !overflow.is_empty() {
+            let root = std::mem::replace(&mut self.root, Node::leaf(vec![]));
+            let mut nodes = vec![root];
+            nodes.extend(overflow);
+            while nodes.len() > 1 {
+                nodes = group_nodes(nodes);
+            }
+            self.root = nodes.pop().unwrap();
+        }
+
+        // The root that only has one child is replaced by the child, the tree is one level lower.
+        loop {
+            match &mut self.root {
+                Node::Internal { children, .. } if children.len() <= 1 => {
+                    self.root = children.pop().unwrap_or_else(|| Node::leaf(vec![]));
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node<T: Attributes> {
+    Leaf { ops: Vec<Operation<T>>, len: usize },
+    Internal { children: Vec<Node<T>>, len: usize },
+}
+
+impl<T> Node<T>
+where
+    T: Attributes,
+{
+    fn leaf(ops: Vec<Operation<T>>) -> Self {
+        let len = ops.iter().map(|op| op.len()).sum();
+        Node::Leaf { ops, len }
+    }
+
+    fn internal(children: Vec<Node<T>>) -> Self {
+        let len = children.iter().map(|child| child.len()).sum();
+        Node::Internal { children, len }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf { len, .. } => *len,
+            Node::Internal { len, .. } => *len,
+        }
+    }
+
+    fn slice(&self, start: usize, end: usize, output: &mut Vec<Operation<T>>) {
+        match self {
+            Node::Leaf { ops, .. } => {
+                let mut offset = 0;
+                for op in ops {
+                    let (op_start, op_end) = (offset, offset + op.len());
+                    offset = op_end;
+                    if op_end <= start {
+                        continue;
+                    }
+                    if op_start >= end {
+                        break;
+                    }
+                    let interval = Interval::new(max(start, op_start) - op_start, min(end, op_end) - op_start);
+                    if let Some(op) = op.shrink(interval) {
+                        output.push(op);
+                    }
+                }
+            }
+            Node::Internal { children, .. } => {
+                let mut offset = 0;
+                for child in children {
+                    let (child_start, child_end) = (offset, offset + child.len());
+                    offset = child_end;
+                    if child_end <= start {
+                        continue;
+                    }
+                    if child_start >= end {
+                        break;
+                    }
+                    child.slice(
+                        max(start, child_start) - child_start,
+                        min(end, child_end) - child_start,
+                        output,
+                    );
+                }
+            }
+        }
+    }
+
+    // Replaces the text between start and end with the ops, returns the nodes that don't fit in
+    // this node, they are the siblings that follow this node.
+    fn splice(&mut self, start: usize, end: usize, insert: Option<Vec<Operation<T>>>) -> Vec<Node<T>> {
+        match self {
+            Node::Leaf { ops, len } => {
+                let mut new_ops = Vec::with_capacity(ops.len());
+                let mut suffix = vec![];
+                let mut offset = 0;
+                for op in ops.drain(..) {
+                    let (op_start, op_end) = (offset, offset + op.len());
+                    offset = op_end;
+                    if op_start < start {
+                        if let Some(op) = op.shrink(Interval::new(0, min(start, op_end) - op_start)) {
+                            push_op(&mut new_ops, op);
+                        }
+                    }
+                    if op_end > end {
+                        if let Some(op) = op.shrink(Interval::new(max(end, op_start) - op_start, op_end - op_start)) {
+                            suffix.push(op);
+                        }
+                    }
+                }
+                insert
+                    .into_iter()
+                    .flatten()
+                    .chain(suffix)
+                    .for_each(|op| push_op(&mut new_ops, op));
+
+                *len = new_ops.iter().map(|op| op.len()).sum();
+                if new_ops.len() <= MAX_LEAF_OPS {
+                    *ops = new_ops;
+                    return vec![];
+                }
+
+                let mut leaves = split_into(new_ops, MAX_LEAF_OPS / 2, Node::leaf);
+                let overflow = leaves.split_off(1);
+                *self = leaves.pop().unwrap();
+                overflow
+            }
+            Node::Internal { children, len } => {
+                let mut insert = insert;
+                let mut offset = 0;
+                let mut index = 0;
+                while index < children.len() {
+                    let (child_start, child_end) = (offset, offset + children[index].len());
+                    offset = child_end;
+                    // The ops are inserted into the first child that contains the start, the
+                    // other children that overlap the interval only delete the text.
+                    let contains_start = insert.is_some() && start >= child_start && start <= child_end;
+                    if contains_start || (start < child_end && end > child_start) {
+                        let ops = if contains_start { insert.take() } else { None };
+                        let overflow = children[index].splice(
+                            max(start, child_start) - child_start,
+                            min(end, child_end) - child_start,
+                            ops,
+                        );
+                        let overflow_len = overflow.len();
+                        children.splice(index + 1..index + 1, overflow);
+                        index += overflow_len;
+                    }
+                    index += 1;
+                }
+
+                let mut new_children: Vec<Node<T>> = Vec::with_capacity(children.len());
+                for child in children.drain(..).filter(|child| child.len() > 0) {
+                    match new_children.last_mut() {
+                        Some(last) => {
+                            if let Some(child) = last.merge(child) {
+                                new_children.push(child);
+                            }
+                        }
+                        None => new_children.push(child),
+                    }
+                }
+
+                *len = new_children.iter().map(|child| child.len()).sum();
+                if new_children.len() <= MAX_CHILDREN {
+                    *children = new_children;
+                    return vec![];
+                }
+
+                let mut nodes = split_into(new_children, MAX_CHILDREN / 2, Node::internal);
+                let overflow = nodes.split_off(1);
+                *self = nodes.pop().unwrap();
+                overflow
+            }
+        }
+    }
+
+    // Merges the node with the next sibling if they fit in one node, otherwise returns the
+    // sibling back.
+    fn merge(&mut self, other: Node<T>) -> Option<Node<T>> {
+        match (self, other) {
+            (
+                Node::Leaf { ops, len },
+                Node::Leaf {
+                    ops: other_ops,
+                    len: other_len,
+                },
+            ) => {
+                if ops.len() + other_ops.len() > MAX_LEAF_OPS {
+                    return Some(Node::Leaf {
+                        ops: other_ops,
+                        len: other_len,
+                    });
+                }
+                other_ops.into_iter().for_each(|op| push_op(ops, op));
+                *len += other_len;
+                None
+            }
+            (
+                Node::Internal { children, len },
+                Node::Internal {
+                    children: other_children,
+                    len: other_len,
+                },
+            ) => {
+                if children.len() + other_children.len() > MAX_CHILDREN {
+                    return Some(Node::Internal {
+                        children: other_children,
+                        len: other_len,
+                    });
+                }
+                children.extend(other_children);
+                *len += other_len;
+                None
+            }
+            (_, other) => Some(other),
+        }
+    }
+
+    // Returns the offset of the last newline before the end.
+    fn rfind_newline(&self, end: usize) -> Option<usize> {
+        match self {
+            Node::Leaf { ops, .. } => {
+                let mut found = None;
+                let mut offset = 0;
+                for op in ops {
+                    if offset >= end {
+                        break;
+                    }
+                    if let Operation::Insert(insert) = op {
+                        found = newline_offsets(&insert.s)
+                            .take_while(|newline| offset + newline < end)
+                            .last()
+                            .map(|newline| offset + newline)
+                            .or(found);
+                    }
+                    offset += op.len();
+                }
+                found
+            }
+            Node::Internal { children, .. } => {
+                let mut child_end = 0;
+                let mut starts = Vec::with_capacity(children.len());
+                for child in children {
+                    if child_end >= end {
+                        break;
+                    }
+                    starts.push(child_end);
+                    child_end += child.len();
+                }
+                children.iter().zip(starts).rev().find_map(|(child, child_start)| {
+                    child
+                        .rfind_newline(min(end - child_start, child.len()))
+                        .map(|offset| child_start + offset)
+                })
+            }
+        }
+    }
+
+    // Returns the offset of the first newline at or after the start.
+    fn find_newline(&self, start: usize) -> Option<usize> {
+        match self {
+            Node::Leaf { ops, .. } => {
+                let mut offset = 0;
+                for op in ops {
+                    let op_end = offset + op.len();
+                    if let (Operation::Insert(insert), true) = (op, op_end > start) {
+                        let newline = newline_offsets(&insert.s).find(|newline| offset + newline >= start);
+                        if let Some(newline) = newline {
+                            return Some(offset + newline);
+                        }
+                    }
+                    offset = op_end;
+                }
+                None
+            }
+            Node::Internal { children, .. } => {
+                let mut offset = 0;
+                for child in children {
+                    let child_start = offset;
+                    offset += child.len();
+                    if offset <= start {
+                        continue;
+                    }
+                    if let Some(newline) = child.find_newline(start.saturating_sub(child_start)) {
+                        return Some(child_start + newline);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+// Appends the op to the ops, the insert is merged into the last insert if they have the same
+// attributes, and the long insert is split.
+fn push_op<T: Attributes>(ops: &mut Vec<Operation<T>>, op: Operation<T>) {
+    let op = match op {
+        Operation::Insert(insert) if insert.utf16_size() > MAX_INSERT_LEN => {
+            split_str(&insert.s, MAX_INSERT_LEN)
+                .into_iter()
+                .for_each(|s| push_op(ops, OpBuilder::insert(&s).attributes(insert.attributes.clone()).build()));
+            return;
+        }
+        op => op,
+    };
+
+    if let (Some(Operation::Insert(last)), Operation::Insert(insert)) = (ops.last_mut(), &op) {
+        if last.attributes == insert.attributes && last.utf16_size() + insert.utf16_size() <= MAX_INSERT_LEN {
+            last.s += &insert.s;
+            return;
+        }
+    }
+    ops.push(op);
+}
+
+// Splits the string into the parts that are at most `len` utf16 code units long, the char is
+// never split.
+fn split_str(s: &str, len: usize) -> Vec<String> {
+    let mut parts = vec![];
+    let mut part = String::new();
+    let mut part_len = 0;
+    for c in s.chars() {
+        if part_len + c.len_utf16() > len {
+            parts.push(std::mem::take(&mut part));
+            part_len = 0;
+        }
+        part.push(c);
+        part_len += c.len_utf16();
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+fn split_into<I, N, F>(items: Vec<I>, size: usize, f: F) -> Vec<N>
+where
+    F: Fn(Vec<I>) -> N,
+{
+    let mut nodes = vec![];
+    let mut items = items.into_iter().peekable();
+    while items.peek().is_some() {
+        nodes.push(f(items.by_ref().take(size).collect()));
+    }
+    nodes
+}
+
+fn group_nodes<T: Attributes>(nodes: Vec<Node<T>>) -> Vec<Node<T>> {
+    split_into(nodes, MAX_CHILDREN, Node::internal)
+}
+
+// Returns the utf16 offsets of the newlines in the string.
+fn newline_offsets(s: &str) -> impl Iterator<Item = usize> + '_ {
+    s.chars()
+        .scan(0, |offset, c| {
+            let current = *offset;
+            *offset += c.len_utf16();
+            Some((current, c))
+        })
+        .filter(|(_, c)| *c == '\n')
+        .map(|(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{Attributes, Delta, DeltaBuilder, DeltaIter, Interval, OperationTransformable, Rope},
+        rich_text::{RichTextAttribute, RichTextAttributes, RichTextDelta},
+    };
+
+    fn long_document(lines: usize) -> RichTextDelta {
+        let mut delta = RichTextDelta::new();
+        for i in 0..lines {
+            let mut attributes = RichTextAttributes::new();
+            if i % 3 == 0 {
+                attributes.add(RichTextAttribute::Bold(true));
+            }
+            delta.insert(&format!("line {} 👋 你好", i), attributes);
+            delta.insert("\n", RichTextAttributes::new());
+        }
+        delta
+    }
+
+    fn assert_apply<T: Attributes>(rope: &mut Rope<T>, delta: &Delta<T>, changeset: &Delta<T>) -> Delta<T> {
+        let inverted = rope.invert(changeset);
+        assert_eq!(inverted, changeset.invert(delta));
+        rope.apply(changeset).unwrap();
+        let composed = delta.compose(changeset).unwrap();
+        assert_eq!(rope.to_delta(), composed);
+        assert_eq!(rope.len(), composed.utf16_target_len);
+        composed
+    }
+
+    #[test]
+    fn rope_from_delta_test() {
+        let delta = long_document(500);
+        let rope = Rope::from_delta(&delta);
+        assert_eq!(rope.len(), delta.utf16_target_len);
+        assert_eq!(rope.to_delta(), delta);
+        let mut sliced = RichTextDelta::new();
+        DeltaIter::from_interval(&delta, Interval::new(5, 12))
+            .ops()
+            .into_iter()
+            .for_each(|op| sliced.add(op));
+        assert_eq!(rope.slice(Interval::new(5, 12)), sliced);
+    }
+
+    #[test]
+    fn rope_apply_test() {
+        let mut delta = long_document(300);
+        let mut rope = Rope::from_delta(&delta);
+        let len = delta.utf16_target_len;
+
+        let changeset = DeltaBuilder::new().retain(len / 2).insert("abc\n").build();
+        delta = assert_apply(&mut rope, &delta, &changeset);
+
+        let changeset = DeltaBuilder::new().retain(100).delete(len / 3).build();
+        delta = assert_apply(&mut rope, &delta, &changeset);
+
+        let changeset = DeltaBuilder::new()
+            .retain(7)
+            .retain_with_attributes(2000, RichTextAttribute::Italic(true).into())
+            .build();
+        delta = assert_apply(&mut rope, &delta, &changeset);
+
+        let changeset = DeltaBuilder::new()
+            .retain(3)
+            .retain_with_attributes(1000, RichTextAttribute::Bold(false).into())
+            .insert(&"x".repeat(5000))
+            .delete(10)
+            .build();
+        delta = assert_apply(&mut rope, &delta, &changeset);
+
+        let changeset = DeltaBuilder::new().delete(delta.utf16_target_len).build();
+        let _ = assert_apply(&mut rope, &delta, &changeset);
+        assert!(rope.is_empty());
+    }
+
+    #[test]
+    fn rope_apply_out_of_bound_test() {
+        let mut rope = Rope::from_delta(&long_document(1));
+        let changeset: RichTextDelta = DeltaBuilder::new().retain(100).insert("abc").build();
+        assert!(rope.apply(&changeset).is_err());
+    }
+
+    #[test]
+    fn rope_line_test() {
+        let mut rope = Rope::<RichTextAttributes>::new();
+        rope.insert(0, "123\n👋56\n", RichTextAttributes::new());
+        rope.insert(9, "789", RichTextAttributes::new());
+        assert_eq!(rope.line_start(0), 0);
+        assert_eq!(rope.line_start(3), 0);
+        assert_eq!(rope.line_start(4), 4);
+        assert_eq!(rope.line_start(8), 4);
+        assert_eq!(rope.line_end(0), 4);
+        assert_eq!(rope.line_end(4), 9);
+        assert_eq!(rope.line_end(10), 12);
+
+        let rope = Rope::from_delta(&long_document(200));
+        let start = rope.line_start(4000);
+        assert_eq!(rope.slice(Interval::new(start - 1, start)).ops[0].get_data(), "\n");
+        let end = rope.line_end(4000);
+        assert_eq!(rope.slice(Interval::new(end - 1, end)).ops[0].get_data(), "\n");
+    }
+}
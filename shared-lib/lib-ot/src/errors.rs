@@ -40,6 +40,7 @@ impl OTError {
     static_ot_error!(duplicate_revision, OTErrorCode::DuplicatedRevision);
     static_ot_error!(revision_id_conflict, OTErrorCode::RevisionIDConflict);
     static_ot_error!(internal, OTErrorCode::Internal);
+    static_ot_error!(path_not_found, OTErrorCode::PathNotFound);
 }
 
 impl fmt::Display for OTError {
@@ -68,6 +69,7 @@ pub enum OTErrorCode {
     ApplyFormatFail,
     ComposeOperationFail,
     IntervalOutOfBound,
+    PathNotFound,
     UndoFail,
     RedoFail,
     SerdeError,
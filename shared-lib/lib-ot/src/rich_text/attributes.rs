@@ -2,7 +2,7 @@
 use crate::{
     block_attribute,
     core::{Attributes, Operation, OperationTransformable},
-    errors::OTError,
+    errors::{ErrorBuilder, OTError, OTErrorCode},
     ignore_attribute, inline_attribute, list_attribute,
 };
 use lazy_static::lazy_static;
@@ -11,6 +11,8 @@ use std::{
     fmt,
     fmt::Formatter,
     iter::FromIterator,
+    str::FromStr,
+    sync::RwLock,
 };
 use strum_macros::Display;
 
@@ -214,6 +216,7 @@ impl RichTextAttribute {
     inline_attribute!(Underline, bool);
     inline_attribute!(StrikeThrough, bool);
     inline_attribute!(Link, &str);
+    inline_attribute!(PageLink, &str);
     inline_attribute!(Color, String);
     inline_attribute!(Font, usize);
     inline_attribute!(Size, usize);
@@ -227,6 +230,8 @@ impl RichTextAttribute {
     block_attribute!(List, &str);
     block_attribute!(CodeBlock, bool);
     block_attribute!(BlockQuote, bool);
+    block_attribute!(TableRow, &str);
+    block_attribute!(TableColumn, &str);
 
     // ignore
     ignore_attribute!(Width, usize);
@@ -238,6 +243,26 @@ impl RichTextAttribute {
     list_attribute!(Checked, "checked");
     list_attribute!(UnChecked, "unchecked");
 
+    /// Returns the custom attribute with the key that was registered by
+    /// `register_custom_attribute`, the scope of the attribute is the declared scope.
+    pub fn custom<T: Into<RichTextAttributeValue>>(key: &str, value: T) -> Result<Self, OTError> {
+        let spec = custom_attribute_spec(key)
+            .ok_or_else(|| attribute_error(format!("The custom attribute {} is not registered", key)))?;
+        let value = value.into();
+        if !spec.value_type.is_valid(&value) {
+            return Err(attribute_error(format!(
+                "The value {:?} of {} is not {:?}",
+                value.0, key, spec.value_type
+            )));
+        }
+
+        Ok(Self {
+            key: RichTextAttributeKey::Custom(key.to_owned()),
+            value,
+            scope: spec.scope,
+        })
+    }
+
     pub fn to_json(&self) -> String {
         match serde_json::to_string(self) {
             Ok(json) => json,
@@ -264,46 +289,98 @@ impl std::convert::From<RichTextAttribute> for RichTextAttributes {
     }
 }
 
-#[derive(Clone, Debug, Display, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
-// serde.rs/variant-attrs.html
-// #[serde(rename_all = "snake_case")]
+#[derive(Clone, Debug, Display, Hash, Eq, PartialEq)]
 pub enum RichTextAttributeKey {
-    #[serde(rename = "bold")]
     Bold,
-    #[serde(rename = "italic")]
     Italic,
-    #[serde(rename = "underline")]
     Underline,
-    #[serde(rename = "strike")]
     StrikeThrough,
-    #[serde(rename = "font")]
     Font,
-    #[serde(rename = "size")]
     Size,
-    #[serde(rename = "link")]
     Link,
-    #[serde(rename = "color")]
+    /// The text links to the view, the value is the id of the view.
+    PageLink,
     Color,
-    #[serde(rename = "background")]
     Background,
-    #[serde(rename = "indent")]
     Indent,
-    #[serde(rename = "align")]
     Align,
-    #[serde(rename = "code_block")]
     CodeBlock,
-    #[serde(rename = "code")]
     InlineCode,
-    #[serde(rename = "list")]
     List,
-    #[serde(rename = "blockquote")]
     BlockQuote,
-    #[serde(rename = "width")]
     Width,
-    #[serde(rename = "height")]
     Height,
-    #[serde(rename = "header")]
     Header,
+    /// The line is in the cell of the table, the value is the id of the row.
+    TableRow,
+    /// The line is in the cell of the table, the value is the id of the column.
+    TableColumn,
+    /// The attribute that is not built in, the key is namespaced, for example: `acme:highlight`.
+    /// It's kept even if it's not registered, so the document that is edited by the other
+    /// client doesn't lose it.
+    Custom(String),
+}
+
+impl RichTextAttributeKey {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RichTextAttributeKey::Bold => "bold",
+            RichTextAttributeKey::Italic => "italic",
+            RichTextAttributeKey::Underline => "underline",
+            RichTextAttributeKey::StrikeThrough => "strike",
+            RichTextAttributeKey::Font => "font",
+            RichTextAttributeKey::Size => "size",
+            RichTextAttributeKey::Link => "link",
+            RichTextAttributeKey::PageLink => "page_link",
+            RichTextAttributeKey::Color => "color",
+            RichTextAttributeKey::Background => "background",
+            RichTextAttributeKey::Indent => "indent",
+            RichTextAttributeKey::Align => "align",
+            RichTextAttributeKey::CodeBlock => "code_block",
+            RichTextAttributeKey::InlineCode => "code",
+            RichTextAttributeKey::List => "list",
+            RichTextAttributeKey::BlockQuote => "blockquote",
+            RichTextAttributeKey::Width => "width",
+            RichTextAttributeKey::Height => "height",
+            RichTextAttributeKey::Header => "header",
+            RichTextAttributeKey::TableRow => "table_row",
+            RichTextAttributeKey::TableColumn => "table_column",
+            RichTextAttributeKey::Custom(key) => key,
+        }
+    }
+}
+
+impl FromStr for RichTextAttributeKey {
+    type Err = OTError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = match s {
+            "bold" => RichTextAttributeKey::Bold,
+            "italic" => RichTextAttributeKey::Italic,
+            "underline" => RichTextAttributeKey::Underline,
+            "strike" => RichTextAttributeKey::StrikeThrough,
+            "font" => RichTextAttributeKey::Font,
+            "size" => RichTextAttributeKey::Size,
+            "link" => RichTextAttributeKey::Link,
+            "page_link" => RichTextAttributeKey::PageLink,
+            "color" => RichTextAttributeKey::Color,
+            "background" => RichTextAttributeKey::Background,
+            "indent" => RichTextAttributeKey::Indent,
+            "align" => RichTextAttributeKey::Align,
+            "code_block" => RichTextAttributeKey::CodeBlock,
+            "code" => RichTextAttributeKey::InlineCode,
+            "list" => RichTextAttributeKey::List,
+            "blockquote" => RichTextAttributeKey::BlockQuote,
+            "width" => RichTextAttributeKey::Width,
+            "height" => RichTextAttributeKey::Height,
+            "header" => RichTextAttributeKey::Header,
+            "table_row" => RichTextAttributeKey::TableRow,
+            "table_column" => RichTextAttributeKey::TableColumn,
+            _ if is_custom_key(s) => RichTextAttributeKey::Custom(s.to_owned()),
+            _ => return Err(attribute_error(format!("Unknown attribute key {}", s))),
+        };
+        Ok(key)
+    }
 }
 
 // pub trait AttributeValueData<'a>: Serialize + Deserialize<'a> {}
@@ -359,10 +436,89 @@ impl std::convert::From<bool> for RichTextAttributeValue {
 }
 
 pub fn is_block_except_header(k: &RichTextAttributeKey) -> bool {
-    if k == &RichTextAttributeKey::Header {
-        return false;
+    match k {
+        RichTextAttributeKey::Header => false,
+        RichTextAttributeKey::Custom(key) => {
+            custom_attribute_spec(key).map(|spec| spec.scope) == Some(AttributeScope::Block)
+        }
+        _ => BLOCK_KEYS.contains(k),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeValueType {
+    /// The attribute is removed if the value is false.
+    Bool,
+    Integer,
+    String,
+}
+
+impl AttributeValueType {
+    fn is_valid(&self, value: &RichTextAttributeValue) -> bool {
+        match (&value.0, self) {
+            (None, _) => true,
+            (Some(s), AttributeValueType::Bool) => s.parse::<bool>().is_ok(),
+            (Some(s), AttributeValueType::Integer) => s.parse::<i64>().is_ok(),
+            (Some(_), AttributeValueType::String) => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomAttributeSpec {
+    pub scope: AttributeScope,
+    pub value_type: AttributeValueType,
+}
+
+/// Declares the scope and the type of the value of the custom attribute. The key is namespaced,
+/// the namespace and the name are separated by the colon, for example: `acme:footnote`.
+pub fn register_custom_attribute(
+    key: &str,
+    scope: AttributeScope,
+    value_type: AttributeValueType,
+) -> Result<(), OTError> {
+    if !is_custom_key(key) {
+        return Err(attribute_error(format!(
+            "The custom attribute key {} is not namespaced",
+            key
+        )));
+    }
+    if scope != AttributeScope::Inline && scope != AttributeScope::Block {
+        return Err(attribute_error(format!(
+            "The custom attribute {} should be inline or block",
+            key
+        )));
+    }
+
+    let spec = CustomAttributeSpec { scope, value_type };
+    let mut custom_attributes = CUSTOM_ATTRIBUTES.write().unwrap();
+    match custom_attributes.get(key) {
+        Some(registered) if registered != &spec => Err(attribute_error(format!(
+            "The custom attribute {} was registered as {:?}",
+            key, registered
+        ))),
+        _ => {
+            custom_attributes.insert(key.to_owned(), spec);
+            Ok(())
+        }
     }
-    BLOCK_KEYS.contains(k)
+}
+
+pub fn custom_attribute_spec(key: &str) -> Option<CustomAttributeSpec> {
+    CUSTOM_ATTRIBUTES.read().unwrap().get(key).cloned()
+}
+
+fn is_custom_key(key: &str) -> bool {
+    let is_valid_part =
+        |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    match key.split_once(':') {
+        None => false,
+        Some((namespace, name)) => is_valid_part(namespace) && is_valid_part(name),
+    }
+}
+
+fn attribute_error<T: Into<String>>(msg: T) -> OTError {
+    ErrorBuilder::new(OTErrorCode::SerdeError).msg(msg).build()
 }
 
 lazy_static! {
@@ -373,6 +529,8 @@ lazy_static! {
         RichTextAttributeKey::CodeBlock,
         RichTextAttributeKey::List,
         RichTextAttributeKey::BlockQuote,
+        RichTextAttributeKey::TableRow,
+        RichTextAttributeKey::TableColumn,
     ]);
     static ref INLINE_KEYS: HashSet<RichTextAttributeKey> = HashSet::from_iter(vec![
         RichTextAttributeKey::Bold,
@@ -380,6 +538,7 @@ lazy_static! {
         RichTextAttributeKey::Underline,
         RichTextAttributeKey::StrikeThrough,
         RichTextAttributeKey::Link,
+        RichTextAttributeKey::PageLink,
         RichTextAttributeKey::Color,
         RichTextAttributeKey::Font,
         RichTextAttributeKey::Size,
@@ -388,12 +547,74 @@ lazy_static! {
     ]);
     static ref INGORE_KEYS: HashSet<RichTextAttributeKey> =
         HashSet::from_iter(vec![RichTextAttributeKey::Width, RichTextAttributeKey::Height,]);
+    static ref CUSTOM_ATTRIBUTES: RwLock<HashMap<String, CustomAttributeSpec>> = RwLock::new(HashMap::new());
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum AttributeScope {
     Inline,
     Block,
     Embeds,
     Ignore,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::OperationTransformable;
+    use crate::rich_text::{
+        register_custom_attribute, AttributeScope, AttributeValueType, RichTextAttribute, RichTextAttributeKey,
+        RichTextAttributes,
+    };
+
+    #[test]
+    fn custom_attribute_register() {
+        register_custom_attribute("test:footnote", AttributeScope::Inline, AttributeValueType::Integer).unwrap();
+        register_custom_attribute("test:footnote", AttributeScope::Inline, AttributeValueType::Integer).unwrap();
+        assert!(
+            register_custom_attribute("test:footnote", AttributeScope::Block, AttributeValueType::Integer).is_err()
+        );
+        assert!(register_custom_attribute("footnote", AttributeScope::Inline, AttributeValueType::Integer).is_err());
+        assert!(register_custom_attribute("test:embed", AttributeScope::Embeds, AttributeValueType::Bool).is_err());
+
+        let attribute = RichTextAttribute::custom("test:footnote", "12").unwrap();
+        assert_eq!(attribute.scope, AttributeScope::Inline);
+        assert!(RichTextAttribute::custom("test:footnote", "first").is_err());
+        assert!(RichTextAttribute::custom("test:unregistered", "12").is_err());
+    }
+
+    #[test]
+    fn custom_attribute_serde() {
+        register_custom_attribute("test:collapsed", AttributeScope::Block, AttributeValueType::Bool).unwrap();
+        let mut attributes: RichTextAttributes = RichTextAttribute::custom("test:collapsed", true).unwrap().into();
+        attributes.add(RichTextAttribute::Bold(true));
+        let json = serde_json::to_string(&attributes).unwrap();
+        assert!(json.contains(r#""test:collapsed":true"#));
+        assert_eq!(serde_json::from_str::<RichTextAttributes>(&json).unwrap(), attributes);
+
+        // The custom attribute that is not registered is kept too.
+        let attributes = serde_json::from_str::<RichTextAttributes>(r#"{"other:tag":"a"}"#).unwrap();
+        let key = RichTextAttributeKey::Custom("other:tag".to_owned());
+        assert_eq!(attributes.get(&key).unwrap().0.as_deref(), Some("a"));
+        assert_eq!(serde_json::to_string(&attributes).unwrap(), r#"{"other:tag":"a"}"#);
+        assert!(serde_json::from_str::<RichTextAttributes>(r#"{"tag":"a"}"#).is_err());
+    }
+
+    #[test]
+    fn custom_attribute_compose_transform_invert() {
+        register_custom_attribute("test:highlight", AttributeScope::Inline, AttributeValueType::String).unwrap();
+        let highlight: RichTextAttributes = RichTextAttribute::custom("test:highlight", "yellow").unwrap().into();
+        let bold: RichTextAttributes = RichTextAttribute::Bold(true).into();
+
+        let composed = bold.compose(&highlight).unwrap();
+        assert_eq!(composed.len(), 2);
+
+        let (a, b) = highlight.transform(&bold).unwrap();
+        assert_eq!(a, highlight);
+        assert_eq!(b, bold);
+
+        let base = RichTextAttributes::new();
+        let inverted = highlight.invert(&base);
+        let key = RichTextAttributeKey::Custom("test:highlight".to_owned());
+        assert_eq!(inverted.get(&key).unwrap().0, None);
+    }
+}
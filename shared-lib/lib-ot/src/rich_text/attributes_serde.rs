@@ -1,5 +1,5 @@
 #[rustfmt::skip]
-use crate::rich_text::{RichTextAttribute, RichTextAttributeKey, RichTextAttributes, RichTextAttributeValue};
+use crate::rich_text::{custom_attribute_spec, AttributeValueType, RichTextAttribute, RichTextAttributeKey, RichTextAttributes, RichTextAttributeValue};
 use serde::{
     de,
     de::{MapAccess, Visitor},
@@ -69,12 +69,30 @@ where
             },
 
             RichTextAttributeKey::Link
+            | RichTextAttributeKey::PageLink
             | RichTextAttributeKey::Color
             | RichTextAttributeKey::Background
             | RichTextAttributeKey::Align
-            | RichTextAttributeKey::List => {
+            | RichTextAttributeKey::List
+            | RichTextAttributeKey::TableRow
+            | RichTextAttributeKey::TableColumn => {
                 map_serializer.serialize_entry(&key, v)?;
             }
+
+            // The value of the custom attribute that is not registered is kept as the string.
+            RichTextAttributeKey::Custom(custom_key) => {
+                match custom_attribute_spec(custom_key).map(|spec| spec.value_type) {
+                    Some(AttributeValueType::Bool) => match &v.parse::<bool>() {
+                        Ok(value) => map_serializer.serialize_entry(&key, value)?,
+                        Err(e) => log::error!("Serial {:?} failed. {:?}", &key, e),
+                    },
+                    Some(AttributeValueType::Integer) => match &v.parse::<i64>() {
+                        Ok(value) => map_serializer.serialize_entry(&key, value)?,
+                        Err(e) => log::error!("Serial {:?} failed. {:?}", &key, e),
+                    },
+                    Some(AttributeValueType::String) | None => map_serializer.serialize_entry(&key, v)?,
+                }
+            }
         }
     } else {
         map_serializer.serialize_entry(&key, "")?;
@@ -82,6 +100,38 @@ where
     Ok(())
 }
 
+impl Serialize for RichTextAttributeKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RichTextAttributeKey {
+    fn deserialize<D>(deserializer: D) -> Result<RichTextAttributeKey, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AttributeKeyVisitor;
+        impl<'de> Visitor<'de> for AttributeKeyVisitor {
+            type Value = RichTextAttributeKey;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("the built-in or the namespaced attribute key")
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                s.parse::<RichTextAttributeKey>().map_err(|e| de::Error::custom(e.msg))
+            }
+        }
+        deserializer.deserialize_str(AttributeKeyVisitor)
+    }
+}
+
 impl<'de> Deserialize<'de> for RichTextAttributes {
     fn deserialize<D>(deserializer: D) -> Result<RichTextAttributes, D::Error>
     where
@@ -1,7 +1,8 @@
 use crate::{
-    core::{Delta, DeltaBuilder},
+    core::{Delta, DeltaBuilder, Rope},
     rich_text::RichTextAttributes,
 };
 
 pub type RichTextDelta = Delta<RichTextAttributes>;
 pub type RichTextDeltaBuilder = DeltaBuilder<RichTextAttributes>;
+pub type RichTextRope = Rope<RichTextAttributes>;
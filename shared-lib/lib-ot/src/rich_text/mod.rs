@@ -5,7 +5,9 @@ mod builder;
 #[macro_use]
 mod macros;
 mod delta;
+mod table;
 
 pub use attributes::*;
 pub use builder::*;
 pub use delta::*;
+pub use table::*;
@@ -0,0 +1,408 @@
+use crate::{
+    core::{count_utf16_code_units, Interval, Operation, NEW_LINE},
+    errors::{ErrorBuilder, OTError, OTErrorCode},
+    rich_text::{plain_attributes, RichTextAttribute, RichTextAttributeKey, RichTextAttributes, RichTextDelta},
+};
+
+/// The cell of the table. The cell is made of the consecutive lines whose newlines have the same
+/// `table_row` and `table_column` attributes, so the text of the cell can be more than one line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCell {
+    pub column_id: String,
+    /// The interval of the lines of the cell, including the last newline.
+    pub interval: Interval,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRow {
+    pub id: String,
+    pub cells: Vec<TableCell>,
+}
+
+impl TableRow {
+    pub fn cell(&self, column_id: &str) -> Option<&TableCell> {
+        self.cells.iter().find(|cell| cell.column_id == column_id)
+    }
+}
+
+/// The table block of the document. The table is stored as the consecutive lines that have the
+/// `table_row` and `table_column` block attributes, each cell is one or more lines. The row and
+/// the column are identified by the id instead of the index, so inserting or deleting the row or
+/// the column is the plain insert or delete of the lines and it composes and transforms the same
+/// as the other changes.
+///
+/// The concurrent edits may leave the table irregular. The row may miss some cells, for example,
+/// the row was inserted while the other collaborator inserted a column, and the missing cell is
+/// treated as empty. The cells of the row may also be apart, so the cells are grouped by the id of
+/// the row instead of the position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub interval: Interval,
+    /// The ids of the columns in order.
+    pub columns: Vec<String>,
+    pub rows: Vec<TableRow>,
+}
+
+impl Table {
+    /// Returns the delta that inserts the row before the row at the index. The row is appended
+    /// if the index is equal to the number of the rows.
+    pub fn insert_row(&self, index: usize, row_id: &str) -> Result<RichTextDelta, OTError> {
+        let position = match self.rows.get(index) {
+            Some(row) => row.cells[0].interval.start,
+            None if index == self.rows.len() => self.interval.end,
+            None => return Err(out_of_bound(index, self.rows.len())),
+        };
+
+        let mut delta = RichTextDelta::default();
+        delta.retain(position, plain_attributes());
+        for column_id in &self.columns {
+            delta.insert(NEW_LINE, table_cell_attributes(row_id, column_id));
+        }
+        Ok(delta)
+    }
+
+    pub fn delete_row(&self, index: usize) -> Result<RichTextDelta, OTError> {
+        let row = self
+            .rows
+            .get(index)
+            .ok_or_else(|| out_of_bound(index, self.rows.len()))?;
+        Ok(delete_intervals(row.cells.iter().map(|cell| cell.interval).collect()))
+    }
+
+    /// Returns the delta that inserts the cell of the new column into each row. The column is
+    /// inserted before the column at the index, or appended if the index is equal to the number of
+    /// the columns.
+    pub fn insert_column(&self, index: usize, column_id: &str) -> Result<RichTextDelta, OTError> {
+        if index > self.columns.len() {
+            return Err(out_of_bound(index, self.columns.len()));
+        }
+
+        let mut inserts = self
+            .rows
+            .iter()
+            .map(|row| {
+                // The cell is inserted before the first cell that is not in the columns on the
+                // left, or after the last cell of the row.
+                let position = row
+                    .cells
+                    .iter()
+                    .find(|cell| !self.columns[..index].contains(&cell.column_id))
+                    .or_else(|| row.cells.last())
+                    .map(|cell| match self.columns[..index].contains(&cell.column_id) {
+                        true => cell.interval.end,
+                        false => cell.interval.start,
+                    })
+                    .unwrap_or(self.interval.end);
+                (position, &row.id)
+            })
+            .collect::<Vec<_>>();
+        inserts.sort_by_key(|(position, _)| *position);
+
+        let mut delta = RichTextDelta::default();
+        let mut offset = 0;
+        for (position, row_id) in inserts {
+            delta.retain(position - offset, plain_attributes());
+            delta.insert(NEW_LINE, table_cell_attributes(row_id, column_id));
+            offset = position;
+        }
+        Ok(delta)
+    }
+
+    pub fn delete_column(&self, index: usize) -> Result<RichTextDelta, OTError> {
+        let column_id = self
+            .columns
+            .get(index)
+            .ok_or_else(|| out_of_bound(index, self.columns.len()))?;
+
+        let intervals = self
+            .rows
+            .iter()
+            .flat_map(|row| row.cells.iter())
+            .filter(|cell| &cell.column_id == column_id)
+            .map(|cell| cell.interval)
+            .collect();
+        Ok(delete_intervals(intervals))
+    }
+
+    /// Returns the row and the cell that contains the offset.
+    pub fn cell_at(&self, offset: usize) -> Option<(&TableRow, &TableCell)> {
+        self.rows.iter().find_map(|row| {
+            row.cells
+                .iter()
+                .find(|cell| cell.interval.contains(offset))
+                .map(|cell| (row, cell))
+        })
+    }
+}
+
+/// Returns the delta that inserts the empty table at the index, the index should be the start of
+/// the line.
+pub fn insert_table(index: usize, row_ids: &[String], column_ids: &[String]) -> RichTextDelta {
+    let mut delta = RichTextDelta::default();
+    delta.retain(index, plain_attributes());
+    for row_id in row_ids {
+        for column_id in column_ids {
+            delta.insert(NEW_LINE, table_cell_attributes(row_id, column_id));
+        }
+    }
+    delta
+}
+
+/// Returns the ids of the row and the column if the attributes of the newline are in the cell.
+pub fn table_cell_of(attributes: &RichTextAttributes) -> Option<(&str, &str)> {
+    let value = |key: &RichTextAttributeKey| attributes.get(key).and_then(|value| value.0.as_deref());
+    Some((
+        value(&RichTextAttributeKey::TableRow)?,
+        value(&RichTextAttributeKey::TableColumn)?,
+    ))
+}
+
+pub fn table_cell_attributes(row_id: &str, column_id: &str) -> RichTextAttributes {
+    let mut attributes = RichTextAttributes::new();
+    attributes.add(RichTextAttribute::TableRow(row_id));
+    attributes.add(RichTextAttribute::TableColumn(column_id));
+    attributes
+}
+
+/// Returns the tables of the document, the delta should be the composed delta of the document.
+pub fn tables_from_delta(delta: &RichTextDelta) -> Vec<Table> {
+    let mut tables: Vec<Table> = vec![];
+    let mut is_in_table = false;
+    let mut offset = 0;
+    for (interval, attributes) in lines_of(delta) {
+        let (row_id, column_id) = match table_cell_of(&attributes) {
+            None => {
+                is_in_table = false;
+                continue;
+            }
+            Some(cell) => cell,
+        };
+
+        if !is_in_table || offset != interval.start {
+            tables.push(Table {
+                interval,
+                columns: vec![],
+                rows: vec![],
+            });
+            is_in_table = true;
+        }
+        offset = interval.end;
+
+        let table = tables.last_mut().unwrap();
+        table.interval.end = interval.end;
+        match table.rows.iter_mut().find(|row| row.id == row_id) {
+            Some(row) => match row.cells.last_mut() {
+                Some(cell) if cell.column_id == column_id && cell.interval.end == interval.start => {
+                    cell.interval.end = interval.end
+                }
+                _ => row.cells.push(TableCell {
+                    column_id: column_id.to_owned(),
+                    interval,
+                }),
+            },
+            None => table.rows.push(TableRow {
+                id: row_id.to_owned(),
+                cells: vec![TableCell {
+                    column_id: column_id.to_owned(),
+                    interval,
+                }],
+            }),
+        }
+    }
+
+    tables.iter_mut().for_each(|table| {
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| row.cells.iter().map(|cell| cell.column_id.as_str()));
+        table.columns = table_columns(rows);
+    });
+    tables
+}
+
+/// Returns the table that contains the offset.
+pub fn table_at(delta: &RichTextDelta, offset: usize) -> Option<Table> {
+    tables_from_delta(delta)
+        .into_iter()
+        .find(|table| table.interval.contains(offset))
+}
+
+/// Returns the ids of the columns in order, the rows are the ids of the columns of the cells in
+/// each row. The order of the columns is the order of the cells in the rows, and the column that
+/// is missing in the previous rows is placed after the column of the cell on its left.
+pub fn table_columns<'a, R, C>(rows: R) -> Vec<String>
+where
+    R: IntoIterator<Item = C>,
+    C: IntoIterator<Item = &'a str>,
+{
+    let mut columns: Vec<String> = vec![];
+    for row in rows {
+        let mut next = 0;
+        for column_id in row {
+            match columns.iter().position(|id| id == column_id) {
+                Some(position) => next = position + 1,
+                None => {
+                    columns.insert(next, column_id.to_owned());
+                    next += 1;
+                }
+            }
+        }
+    }
+    columns
+}
+
+// Returns the interval of each line, including the newline, and the attributes of the newline.
+fn lines_of(delta: &RichTextDelta) -> Vec<(Interval, RichTextAttributes)> {
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut offset = 0;
+    for op in &delta.ops {
+        match op {
+            Operation::Embed(_) => offset += 1,
+            Operation::Insert(_) => {
+                let mut s = op.get_data();
+                while let Some(index) = s.find(NEW_LINE) {
+                    offset += count_utf16_code_units(&s[..index]) + 1;
+                    lines.push((Interval::new(start, offset), op.get_attributes()));
+                    start = offset;
+                    s = &s[index + 1..];
+                }
+                offset += count_utf16_code_units(s);
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+// The intervals are sorted and don't overlap.
+fn delete_intervals(intervals: Vec<Interval>) -> RichTextDelta {
+    let mut delta = RichTextDelta::default();
+    let mut offset = 0;
+    for interval in intervals {
+        delta.retain(interval.start - offset, plain_attributes());
+        delta.delete(interval.size());
+        offset = interval.end;
+    }
+    delta
+}
+
+fn out_of_bound(index: usize, len: usize) -> OTError {
+    ErrorBuilder::new(OTErrorCode::IntervalOutOfBound)
+        .msg(format!("The index {} is out of bound {}", index, len))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{Interval, OperationTransformable};
+    use crate::rich_text::{insert_table, table_at, tables_from_delta, RichTextDelta};
+
+    fn ids(prefix: &str, n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("{}{}", prefix, i)).collect()
+    }
+
+    // The deltas are transformed against each other, so the rest of the document is retained.
+    fn retain_all(mut delta: RichTextDelta, document: &RichTextDelta) -> RichTextDelta {
+        let len = document.utf16_target_len - delta.utf16_base_len;
+        delta.retain(len, Default::default());
+        delta
+    }
+
+    fn document_with_table() -> RichTextDelta {
+        let mut document = RichTextDelta::default();
+        document.insert("title\n", Default::default());
+        let document = document.compose(&insert_table(6, &ids("r", 2), &ids("c", 2))).unwrap();
+        let mut text = RichTextDelta::default();
+        text.retain(6, Default::default());
+        text.insert("a", Default::default());
+        document.compose(&text).unwrap()
+    }
+
+    #[test]
+    fn table_from_delta() {
+        let document = document_with_table();
+        let tables = tables_from_delta(&document);
+        assert_eq!(tables.len(), 1);
+
+        let table = &tables[0];
+        assert_eq!(table.interval, Interval::new(6, 11));
+        assert_eq!(table.columns, ids("c", 2));
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].cells[0].interval, Interval::new(6, 8));
+        assert_eq!(table.rows[1].cells[1].interval, Interval::new(10, 11));
+
+        let (row, cell) = table.cell_at(7).unwrap();
+        assert_eq!((row.id.as_str(), cell.column_id.as_str()), ("r0", "c0"));
+        assert!(table_at(&document, 3).is_none());
+    }
+
+    #[test]
+    fn table_insert_and_delete() {
+        let document = document_with_table();
+        let table = table_at(&document, 6).unwrap();
+
+        let document = document.compose(&table.insert_column(1, "c2").unwrap()).unwrap();
+        let table = table_at(&document, 6).unwrap();
+        assert_eq!(table.columns, vec!["c0", "c2", "c1"]);
+        assert_eq!(table.rows[1].cells.len(), 3);
+
+        let document = document.compose(&table.insert_row(2, "r2").unwrap()).unwrap();
+        let table = table_at(&document, 6).unwrap();
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(table.rows[2].cells.len(), 3);
+
+        let document = document.compose(&table.delete_column(0).unwrap()).unwrap();
+        let table = table_at(&document, 6).unwrap();
+        assert_eq!(table.columns, vec!["c2", "c1"]);
+
+        let document = document.compose(&table.delete_row(0).unwrap()).unwrap();
+        let table = table_at(&document, 6).unwrap();
+        assert_eq!(table.rows.len(), 2);
+        assert!(table.delete_row(2).is_err());
+    }
+
+    #[test]
+    fn table_concurrent_insert_row_and_column() {
+        let document = document_with_table();
+        let table = table_at(&document, 6).unwrap();
+        let a = table.insert_row(1, "r2").unwrap();
+        let a = retain_all(a, &document);
+        let b = table.insert_column(2, "c2").unwrap();
+        let b = retain_all(b, &document);
+
+        let (a_prime, b_prime) = a.transform(&b).unwrap();
+        let document_a = document.compose(&a).unwrap().compose(&b_prime).unwrap();
+        let document_b = document.compose(&b).unwrap().compose(&a_prime).unwrap();
+        assert_eq!(document_a, document_b);
+
+        let table = table_at(&document_a, 6).unwrap();
+        assert_eq!(table.columns, ids("c", 3));
+        assert_eq!(table.rows.len(), 3);
+        // The row that was inserted concurrently misses the cell of the new column.
+        assert_eq!(table.rows[1].cells.len(), 2);
+        assert!(table.rows[1].cell("c2").is_none());
+    }
+
+    #[test]
+    fn table_concurrent_delete_row_and_insert_column() {
+        let document = document_with_table();
+        let table = table_at(&document, 6).unwrap();
+        let a = table.delete_row(0).unwrap();
+        let a = retain_all(a, &document);
+        let b = table.insert_column(0, "c2").unwrap();
+        let b = retain_all(b, &document);
+
+        let (a_prime, b_prime) = a.transform(&b).unwrap();
+        let document_a = document.compose(&a).unwrap().compose(&b_prime).unwrap();
+        let document_b = document.compose(&b).unwrap().compose(&a_prime).unwrap();
+        assert_eq!(document_a, document_b);
+
+        // The cell that was inserted into the deleted row concurrently is kept.
+        let table = table_at(&document_a, 6).unwrap();
+        assert_eq!(table.columns, vec!["c2", "c0", "c1"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].cells.len(), 1);
+        assert_eq!(table.rows[1].cells.len(), 3);
+    }
+}
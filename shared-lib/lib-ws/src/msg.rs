@@ -18,6 +18,7 @@ pub enum WSChannel {
     Document = 0,
     Folder = 1,
     Grid = 2,
+    Presence = 3,
 }
 
 impl std::default::Default for WSChannel {
@@ -32,6 +33,7 @@ impl ToString for WSChannel {
             WSChannel::Document => "0".to_string(),
             WSChannel::Folder => "1".to_string(),
             WSChannel::Grid => "2".to_string(),
+            WSChannel::Presence => "3".to_string(),
         }
     }
 }